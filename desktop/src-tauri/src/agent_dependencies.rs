@@ -0,0 +1,233 @@
+// Local services agents depend on.
+//
+// An agent can list what it needs running on this machine: a port that must
+// be listening (postgres on 5432), a process (ollama), or an HTTP endpoint
+// that must answer. They are checked before the bridge is started by hand and
+// by `test_agent`, a cancellable task, so a missing database is reported up
+// front instead of surfacing as a confusing tool error halfway through a
+// conversation.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use tokio::net::TcpStream;
+use tokio::process::Command;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::tasks::{spawn_task, TaskHandle};
+use crate::{http, i18n};
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+// Exactly one of `port`, `process` and `url` says what to check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceDependency {
+    // Shown in reports, e.g. "postgres"
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    process: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyCheck {
+    agent_id: String,
+    name: String,
+    ok: bool,
+    detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTestReport {
+    agent_id: String,
+    ok: bool,
+    checks: Vec<DependencyCheck>,
+}
+
+impl std::fmt::Display for DependencyCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (agent {}): {}", self.name, self.agent_id, self.detail)
+    }
+}
+
+pub(crate) fn validate(dependencies: &[ServiceDependency]) -> CommandResult<()> {
+    for dependency in dependencies {
+        if dependency.name.trim().is_empty() {
+            return Err(Error::Invalid("Every dependency needs a name".to_string()));
+        }
+        let kinds = [dependency.port.is_some(), dependency.process.is_some(), dependency.url.is_some()];
+        if kinds.iter().filter(|k| **k).count() != 1 {
+            return Err(Error::Invalid(format!(
+                "Dependency '{}' must set exactly one of port, process or url",
+                dependency.name
+            )));
+        }
+        if let Some(ref url) = dependency.url {
+            reqwest::Url::parse(url).map_err(|e| Error::Invalid(format!("Invalid URL for '{}': {}", dependency.name, e)))?;
+        }
+    }
+    Ok(())
+}
+
+async fn port_open(host: &str, port: u16) -> Result<String, String> {
+    match tokio::time::timeout(CHECK_TIMEOUT, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(format!("Listening on {}:{}", host, port)),
+        Ok(Err(e)) => Err(format!("Nothing listening on {}:{} ({})", host, port, e)),
+        Err(_) => Err(format!("{}:{} did not answer in time", host, port)),
+    }
+}
+
+async fn process_running(name: &str) -> Result<String, String> {
+    // tasklist succeeds either way and prints "INFO: No tasks..." when nothing matches
+    #[cfg(windows)]
+    let found = {
+        let image = format!("{}.exe", name.trim_end_matches(".exe"));
+        let output = Command::new("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {}", image), "/NH"])
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+        String::from_utf8_lossy(&output.stdout).to_lowercase().contains(&image.to_lowercase())
+    };
+    #[cfg(not(windows))]
+    let found = Command::new("pgrep")
+        .args(["-x", name])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to list processes: {}", e))?
+        .status
+        .success();
+
+    if found {
+        Ok(format!("{} is running", name))
+    } else {
+        Err(format!("{} is not running", name))
+    }
+}
+
+async fn url_answers(url: &str) -> Result<String, String> {
+    let response = http::client()
+        .get(url)
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("{} is not reachable: {}", url, e))?;
+    if response.status().is_server_error() {
+        return Err(format!("{} returned {}", url, response.status()));
+    }
+    Ok(format!("{} answered with {}", url, response.status()))
+}
+
+async fn check(agent_id: &str, dependency: &ServiceDependency) -> DependencyCheck {
+    let result = if let Some(port) = dependency.port {
+        port_open(dependency.host.as_deref().unwrap_or(DEFAULT_HOST), port).await
+    } else if let Some(ref process) = dependency.process {
+        process_running(process).await
+    } else if let Some(ref url) = dependency.url {
+        url_answers(url).await
+    } else {
+        Err("Nothing to check".to_string())
+    };
+    let (ok, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    DependencyCheck {
+        agent_id: agent_id.to_string(),
+        name: dependency.name.clone(),
+        ok,
+        detail,
+    }
+}
+
+fn agent_dependencies(agent: &serde_json::Value) -> Vec<ServiceDependency> {
+    agent
+        .get("dependencies")
+        .and_then(|d| serde_json::from_value(d.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn agents(config: &serde_json::Value) -> Vec<serde_json::Value> {
+    config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+async fn check_agent(agent: &serde_json::Value) -> Vec<DependencyCheck> {
+    let id = agent.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+    let mut checks = Vec::new();
+    for dependency in agent_dependencies(agent) {
+        checks.push(check(id, &dependency).await);
+    }
+    checks
+}
+
+// Unmet dependencies of every agent in `config`
+pub(crate) async fn unmet(config: &serde_json::Value) -> Vec<DependencyCheck> {
+    let mut unmet = Vec::new();
+    for agent in agents(config) {
+        unmet.extend(check_agent(&agent).await.into_iter().filter(|c| !c.ok));
+    }
+    unmet
+}
+
+async fn run_test(task: TaskHandle, id: String, agent: serde_json::Value) -> AgentTestReport {
+    let workspace = agent.get("workspace").and_then(|v| v.as_str()).unwrap_or_default();
+    let workspace_ok = !workspace.is_empty() && expand_home(workspace).is_dir();
+    let mut checks = vec![DependencyCheck {
+        agent_id: id.clone(),
+        name: "Workspace".to_string(),
+        ok: workspace_ok,
+        detail: if workspace_ok {
+            format!("{} exists", workspace)
+        } else {
+            format!("{} is not a directory", workspace)
+        },
+    }];
+    let dependencies = agent_dependencies(&agent);
+    for (i, dependency) in dependencies.iter().enumerate() {
+        task.progress(
+            Some(i as f64 / dependencies.len() as f64),
+            format!("Checking {}", dependency.name),
+        );
+        checks.push(check(&id, dependency).await);
+    }
+
+    AgentTestReport {
+        ok: checks.iter().all(|c| c.ok),
+        agent_id: id,
+        checks,
+    }
+}
+
+// Workspace and dependencies of one agent, checked as a task; returns the task id
+#[tauri::command]
+pub(crate) fn test_agent(app: AppHandle, store: State<'_, ConfigStore>, id: String) -> CommandResult<String> {
+    let config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    let agent = agents(&config)
+        .into_iter()
+        .find(|a| a.get("id").and_then(|v| v.as_str()) == Some(id.as_str()))
+        .ok_or_else(|| Error::Invalid(i18n::tr("agent.not_found", &[("id", &id)])))?;
+
+    let label = format!("Test agent {}", id);
+    let task_id = spawn_task(&app, "agent_test", label, move |task| async move {
+        let report = run_test(task, id, agent).await;
+        serde_json::to_value(report).map_err(|e| e.to_string())
+    })?;
+    Ok(task_id)
+}
@@ -0,0 +1,81 @@
+// Built-in agent presets.
+//
+// A new agent can start from one of these instead of an empty form: each
+// preset fills in the model, permission mode, tool allowlist and blocklist
+// and a system prompt for a common role. The agent editor asks for the id and
+// workspace, which a preset can't know, and saves the result with `add_agent`.
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::agents::AgentConfig;
+use crate::error::CommandResult;
+use crate::i18n;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTemplate {
+    id: String,
+    name: String,
+    description: String,
+    // With an empty id and workspace
+    agent: AgentConfig,
+}
+
+fn code_reviewer() -> serde_json::Value {
+    json!({
+        "model": "sonnet",
+        "permissionMode": "default",
+        "maxTurns": 30,
+        "allowedTools": ["Read", "Grep", "Glob", "Bash(git diff:*)", "Bash(git log:*)", "Bash(git show:*)", "Bash(git blame:*)"],
+        "disallowedTools": ["Write", "Edit", "NotebookEdit"],
+        "systemPrompt": "You are a code reviewer. When asked to review a change, read the diff and the code around it, then report bugs, security problems, missing error handling and missing tests, most severe first, each with the file and line. Point out what is done well only briefly. Do not modify any files; suggest fixes as snippets instead.",
+    })
+}
+
+fn docs_writer() -> serde_json::Value {
+    json!({
+        "model": "sonnet",
+        "permissionMode": "acceptEdits",
+        "maxTurns": 40,
+        "allowedTools": ["Read", "Grep", "Glob", "Write", "Edit", "WebFetch"],
+        "disallowedTools": ["Bash"],
+        "systemPrompt": "You are a technical writer for this project. Keep the README, guides and code comments accurate and easy to follow. Read the code before describing it, match the existing tone and structure of the docs, prefer short sentences and working examples, and never document behavior you haven't confirmed in the source.",
+    })
+}
+
+fn devops() -> serde_json::Value {
+    json!({
+        "model": "sonnet",
+        "permissionMode": "default",
+        "maxTurns": 30,
+        "allowedTools": [
+            "Read", "Grep", "Glob",
+            "Bash(git status:*)", "Bash(docker ps:*)", "Bash(docker logs:*)",
+            "Bash(kubectl get:*)", "Bash(kubectl describe:*)", "Bash(kubectl logs:*)",
+            "Bash(systemctl status:*)", "Bash(journalctl:*)", "Bash(df:*)", "Bash(free:*)"
+        ],
+        "disallowedTools": ["Bash(rm -rf:*)", "Bash(kubectl delete:*)", "Bash(terraform destroy:*)", "Bash(docker system prune:*)"],
+        "systemPrompt": "You are a DevOps assistant. Help investigate deployments, CI pipelines, containers and servers. Start with read-only commands to gather facts, explain what you found before proposing a fix, and ask for confirmation before anything that restarts, deletes or changes infrastructure.",
+    })
+}
+
+#[tauri::command]
+pub(crate) fn get_agent_templates() -> CommandResult<Vec<AgentTemplate>> {
+    let presets = [("code-reviewer", "code_reviewer", code_reviewer()), ("docs-writer", "docs_writer", docs_writer()), ("devops", "devops", devops())];
+    presets
+        .into_iter()
+        .map(|(id, key, mut agent)| {
+            let name = i18n::t(&format!("template.{}.name", key));
+            agent["id"] = json!("");
+            agent["name"] = json!(name);
+            agent["workspace"] = json!("");
+            Ok(AgentTemplate {
+                id: id.to_string(),
+                name,
+                description: i18n::t(&format!("template.{}.description", key)),
+                agent: serde_json::from_value(agent).map_err(|e| e.to_string())?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,319 @@
+// Agents configured in config.json.
+//
+// Each agent is a Claude Code setup (workspace, model, tools, MCP servers)
+// that bots route chats to. Agents live under `agents.list` in the config.
+// New ones can start from a built-in preset (see agent_templates) or as a
+// copy of an existing agent.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::{agent_dependencies, config_validation, i18n, pairing_policy, scheduler};
+
+fn get_plugins_path() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not find home directory");
+    home.join(".claude").join("plugins").join("installed_plugins.json")
+}
+
+#[tauri::command]
+pub(crate) fn get_installed_plugins() -> CommandResult<Vec<InstalledPlugin>> {
+    let plugins_path = get_plugins_path();
+
+    if !plugins_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&plugins_path)
+        .map_err(|e| format!("Failed to read plugins file: {}", e))?;
+
+    let plugins_file: InstalledPluginsFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse plugins file: {}", e))?;
+
+    let mut installed_plugins: Vec<InstalledPlugin> = Vec::new();
+
+    for (name, entries) in plugins_file.plugins {
+        // Take the first entry (most recent) for each plugin
+        if let Some(entry) = entries.first() {
+            installed_plugins.push(InstalledPlugin {
+                name: name.clone(),
+                path: entry.install_path.clone(),
+                version: entry.version.clone(),
+            });
+        }
+    }
+
+    // Sort by name for consistent ordering
+    installed_plugins.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(installed_plugins)
+}
+
+// Agent configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentConfig {
+    id: String,
+    name: String,
+    workspace: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_turns: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    permission_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disallowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skills: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plugins: Option<Vec<PluginConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mcp_servers: Option<Vec<McpServerConfig>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule: Option<AgentSchedule>,
+    // High-sensitivity agents need an extra confirmation to pair with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensitivity: Option<pairing_policy::Sensitivity>,
+    // Local services checked before the bridge starts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependencies: Option<Vec<agent_dependencies::ServiceDependency>>,
+}
+
+// Working hours; outside them the bridge replies with `away_message` instead
+// of running the agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSchedule {
+    // "HH:MM" in `timezone`; an end before the start wraps past midnight
+    start: String,
+    end: String,
+    // 0 = Sunday ... 6 = Saturday; empty means every day
+    #[serde(default)]
+    days: Vec<u8>,
+    // IANA name such as "Europe/Berlin"
+    timezone: String,
+    away_message: String,
+}
+
+fn validate_schedule(schedule: &AgentSchedule) -> CommandResult<()> {
+    let parse = |t: &str| scheduler::parse_time(t).map_err(Error::Invalid);
+    if parse(&schedule.start)? == parse(&schedule.end)? {
+        return Err(Error::Invalid("Working hours must not start and end at the same time".to_string()));
+    }
+    if let Some(day) = schedule.days.iter().find(|d| **d > 6) {
+        return Err(Error::Invalid(format!("Invalid weekday {}, expected 0 (Sunday) to 6 (Saturday)", day)));
+    }
+    scheduler::parse_timezone(&schedule.timezone).map_err(Error::Invalid)?;
+    if schedule.away_message.trim().is_empty() {
+        return Err(Error::Invalid("An away message is required".to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    #[serde(rename = "type")]
+    plugin_type: String,
+    path: String,
+}
+
+// Installed plugin from ~/.claude/plugins/installed_plugins.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    name: String,
+    path: String,
+    version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct InstalledPluginEntry {
+    #[serde(rename = "installPath")]
+    install_path: String,
+    version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct InstalledPluginsFile {
+    plugins: std::collections::HashMap<String, Vec<InstalledPluginEntry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerConfig {
+    pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) args: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) env: Option<std::collections::HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "type")]
+    pub(crate) server_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+}
+
+#[tauri::command]
+pub(crate) fn get_agents(store: State<'_, ConfigStore>) -> CommandResult<Vec<AgentConfig>> {
+    let Some(config) = store.read()? else {
+        return Ok(vec![]);
+    };
+
+    let agents = config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| serde_json::from_value(v.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(agents)
+}
+
+#[tauri::command]
+pub(crate) fn add_agent(store: State<'_, ConfigStore>, agent: AgentConfig) -> CommandResult<bool> {
+    if let Some(ref schedule) = agent.schedule {
+        validate_schedule(schedule)?;
+    }
+    if let Some(ref dependencies) = agent.dependencies {
+        agent_dependencies::validate(dependencies)?;
+    }
+
+    let mut config = store.read()?.unwrap_or_else(|| {
+        serde_json::json!({
+            "agents": { "list": [] },
+            "channels": {}
+        })
+    });
+
+    // Get or create agents list
+    let agents_list = config
+        .get_mut("agents")
+        .and_then(|a| a.get_mut("list"))
+        .and_then(|l| l.as_array_mut())
+        .ok_or_else(|| Error::Config(i18n::t("config.invalid")))?;
+
+    // Check if agent already exists
+    if agents_list.iter().any(|a| a.get("id").and_then(|v| v.as_str()) == Some(&agent.id)) {
+        return Err(Error::Invalid(i18n::tr("agent.exists", &[("id", &agent.id)])));
+    }
+
+    // Add new agent
+    let agent_value = serde_json::to_value(&agent).map_err(|e| e.to_string())?;
+    agents_list.push(agent_value);
+    let scope = format!("agents.list[{}]", agents_list.len() - 1);
+
+    config_validation::ensure_valid_in(&config, &[scope])?;
+    store.write(&config)?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn update_agent(store: State<'_, ConfigStore>, agent: AgentConfig) -> CommandResult<bool> {
+    if let Some(ref schedule) = agent.schedule {
+        validate_schedule(schedule)?;
+    }
+    if let Some(ref dependencies) = agent.dependencies {
+        agent_dependencies::validate(dependencies)?;
+    }
+
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+
+    let agents_list = config
+        .get_mut("agents")
+        .and_then(|a| a.get_mut("list"))
+        .and_then(|l| l.as_array_mut())
+        .ok_or_else(|| Error::Config(i18n::t("config.invalid")))?;
+
+    // Find and update agent
+    let index = agents_list
+        .iter()
+        .position(|a| a.get("id").and_then(|v| v.as_str()) == Some(&agent.id))
+        .ok_or_else(|| Error::Invalid(i18n::tr("agent.not_found", &[("id", &agent.id)])))?;
+    agents_list[index] = serde_json::to_value(&agent).map_err(|e| e.to_string())?;
+
+    config_validation::ensure_valid_in(&config, &[format!("agents.list[{}]", index)])?;
+    store.write(&config)?;
+
+    Ok(true)
+}
+
+// A copy of agent `id` under `new_id`; bots and bindings stay with the original
+#[tauri::command]
+pub(crate) fn duplicate_agent(store: State<'_, ConfigStore>, id: String, new_id: String) -> CommandResult<AgentConfig> {
+    let new_id = new_id.trim().to_string();
+    if new_id.is_empty() {
+        return Err(Error::Invalid("An id for the copy is required".to_string()));
+    }
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+
+    let agents_list = config
+        .get_mut("agents")
+        .and_then(|a| a.get_mut("list"))
+        .and_then(|l| l.as_array_mut())
+        .ok_or_else(|| Error::Config(i18n::t("config.invalid")))?;
+
+    if agents_list.iter().any(|a| a.get("id").and_then(|v| v.as_str()) == Some(&new_id)) {
+        return Err(Error::Invalid(i18n::tr("agent.exists", &[("id", &new_id)])));
+    }
+    // Copied as stored, so fields the app doesn't know about come along
+    let mut copy = agents_list
+        .iter()
+        .find(|a| a.get("id").and_then(|v| v.as_str()) == Some(&id))
+        .cloned()
+        .ok_or_else(|| Error::Invalid(i18n::tr("agent.not_found", &[("id", &id)])))?;
+    let name = copy.get("name").and_then(|n| n.as_str()).unwrap_or(&id).to_string();
+    copy["id"] = serde_json::Value::String(new_id);
+    copy["name"] = serde_json::Value::String(i18n::tr("agent.copy_name", &[("name", &name)]));
+    agents_list.push(copy.clone());
+    let scope = format!("agents.list[{}]", agents_list.len() - 1);
+
+    config_validation::ensure_valid_in(&config, &[scope])?;
+    store.write(&config)?;
+
+    Ok(serde_json::from_value(copy).map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+pub(crate) fn remove_agent(store: State<'_, ConfigStore>, id: String) -> CommandResult<bool> {
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+
+    let agents_list = config
+        .get_mut("agents")
+        .and_then(|a| a.get_mut("list"))
+        .and_then(|l| l.as_array_mut())
+        .ok_or_else(|| Error::Config(i18n::t("config.invalid")))?;
+
+    // Don't allow removing last agent
+    if agents_list.len() <= 1 {
+        return Err(Error::Invalid(i18n::t("agent.last")));
+    }
+
+    // Remove agent
+    let original_len = agents_list.len();
+    agents_list.retain(|a| a.get("id").and_then(|v| v.as_str()) != Some(&id));
+
+    if agents_list.len() == original_len {
+        return Err(Error::Invalid(i18n::tr("agent.not_found", &[("id", &id)])));
+    }
+
+    // Write config
+    store.write(&config)?;
+
+    Ok(true)
+}
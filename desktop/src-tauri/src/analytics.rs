@@ -0,0 +1,205 @@
+// Opt-in local usage analytics.
+//
+// When enabled, the app counts which commands get used and which errors occur,
+// per day, in a sqlite database in the desktop's data dir. Only command names
+// and error kinds are stored, never arguments or message content, and nothing
+// leaves the machine: the data exists solely for the in-app insights page.
+
+use chrono::{Duration, Local};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::config::get_desktop_data_dir;
+use crate::local_db::LocalDb;
+use crate::log_forwarding;
+
+// Opened on first use and closed when analytics is turned off
+static DATABASE: LocalDb = LocalDb::new(
+    "analytics.db",
+    "analytics",
+    "CREATE TABLE IF NOT EXISTS events (
+        day TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        name TEXT NOT NULL,
+        count INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (day, kind, name)
+    )",
+);
+// Read once; every IPC command checks it
+static SETTINGS: RwLock<Option<AnalyticsSettings>> = RwLock::new(None);
+
+const KIND_COMMAND: &str = "command";
+const KIND_ERROR: &str = "error";
+const MAX_DAYS: u32 = 365;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AnalyticsSettings {
+    enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCount {
+    name: String,
+    count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyActivity {
+    date: String,
+    commands: u64,
+    errors: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalAnalytics {
+    enabled: bool,
+    days: u32,
+    top_commands: Vec<EventCount>,
+    top_errors: Vec<EventCount>,
+    by_day: Vec<DailyActivity>,
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("analytics.json")
+}
+
+fn load_settings() -> AnalyticsSettings {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn is_enabled() -> bool {
+    if let Some(settings) = SETTINGS.read().ok().and_then(|s| s.clone()) {
+        return settings.enabled;
+    }
+    let settings = load_settings();
+    if let Ok(mut cached) = SETTINGS.write() {
+        *cached = Some(settings.clone());
+    }
+    settings.enabled
+}
+
+fn record(kind: &str, name: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let recorded = DATABASE.with(|conn| {
+        conn.execute(
+            "INSERT INTO events (day, kind, name, count) VALUES (?1, ?2, ?3, 1)
+             ON CONFLICT (day, kind, name) DO UPDATE SET count = count + 1",
+            params![Local::now().format("%Y-%m-%d").to_string(), kind, name],
+        )
+    });
+    if let Err(e) = recorded {
+        log_forwarding::desktop_error(e);
+    }
+}
+
+// Wrap the app's invoke handler so every IPC command is counted by name
+pub(crate) fn track_commands<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        record(KIND_COMMAND, invoke.message.command());
+        handler(invoke)
+    }
+}
+
+// Count an error by a short, content-free kind such as "start_failed"
+pub(crate) fn record_error(kind: &str) {
+    record(KIND_ERROR, kind);
+}
+
+fn top_events(conn: &Connection, kind: &str, since: &str) -> rusqlite::Result<Vec<EventCount>> {
+    let mut stmt = conn.prepare(
+        "SELECT name, SUM(count) AS total FROM events WHERE kind = ?1 AND day >= ?2
+         GROUP BY name ORDER BY total DESC LIMIT 20",
+    )?;
+    let rows = stmt.query_map(params![kind, since], |row| {
+        Ok(EventCount {
+            name: row.get(0)?,
+            count: row.get::<_, i64>(1)? as u64,
+        })
+    })?;
+    rows.collect()
+}
+
+fn daily_activity(conn: &Connection, since: &str) -> rusqlite::Result<Vec<DailyActivity>> {
+    let mut stmt = conn.prepare(
+        "SELECT day,
+                SUM(CASE WHEN kind = ?1 THEN count ELSE 0 END),
+                SUM(CASE WHEN kind = ?2 THEN count ELSE 0 END)
+         FROM events WHERE day >= ?3 GROUP BY day ORDER BY day",
+    )?;
+    let rows = stmt.query_map(params![KIND_COMMAND, KIND_ERROR, since], |row| {
+        Ok(DailyActivity {
+            date: row.get(0)?,
+            commands: row.get::<_, i64>(1)? as u64,
+            errors: row.get::<_, i64>(2)? as u64,
+        })
+    })?;
+    rows.collect()
+}
+
+#[tauri::command]
+pub(crate) fn set_analytics_enabled(enabled: bool) -> Result<bool, String> {
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let settings = AnalyticsSettings { enabled };
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write analytics settings: {}", e))?;
+    if let Ok(mut cached) = SETTINGS.write() {
+        *cached = Some(settings);
+    }
+
+    if !enabled {
+        // Nothing more is recorded; the file stays until purged
+        DATABASE.close()?;
+    }
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub(crate) fn get_local_analytics(days: Option<u32>) -> Result<LocalAnalytics, String> {
+    let days = days.unwrap_or(30).clamp(1, MAX_DAYS);
+    let enabled = is_enabled();
+    let mut analytics = LocalAnalytics {
+        enabled,
+        days,
+        top_commands: vec![],
+        top_errors: vec![],
+        by_day: vec![],
+    };
+    if !DATABASE.path().exists() {
+        return Ok(analytics);
+    }
+
+    let since = (Local::now() - Duration::days(days as i64 - 1)).format("%Y-%m-%d").to_string();
+    (analytics.top_commands, analytics.top_errors, analytics.by_day) = DATABASE.with(|conn| {
+        Ok((
+            top_events(conn, KIND_COMMAND, &since)?,
+            top_events(conn, KIND_ERROR, &since)?,
+            daily_activity(conn, &since)?,
+        ))
+    })?;
+    Ok(analytics)
+}
+
+#[tauri::command]
+pub(crate) fn purge_analytics() -> Result<bool, String> {
+    DATABASE.delete()?;
+    Ok(true)
+}
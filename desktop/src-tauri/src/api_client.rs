@@ -0,0 +1,372 @@
+// Talking to the bridge's Control API.
+//
+// The bridge serves a small HTTP API, on localhost unless another endpoint is
+// active. `BridgeApiClient` is held in managed state and shares one pooled
+// connection per endpoint across commands, with typed methods for the common
+// calls and `ApiError` for everything that can go wrong. Also here: the API's
+// wire types as the desktop uses them, and the commands that stream sessions
+// and transcripts through to the frontend.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::app_settings::{api_url, BridgeInstance};
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::{bridge_endpoints, identities, json_stream, rate_limits};
+
+// Bridge status from the Control API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeStatus {
+    pub(crate) running: bool,
+    pub(crate) uptime: u64,
+    pub(crate) channels: Vec<ChannelStatus>,
+    pub(crate) sessions: SessionStats,
+    pub(crate) pairings: PairingStats,
+    // SHA-256 of the config file the bridge loaded; older bridges omit it
+    #[serde(rename = "configHash", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) config_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelStatus {
+    pub(crate) name: String,
+    pub(crate) enabled: bool,
+    pub(crate) connected: bool,
+    #[serde(rename = "botCount")]
+    pub(crate) bot_count: u32,
+    pub(crate) bots: Vec<BotInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotInfo {
+    pub(crate) id: String,
+    pub(crate) username: Option<String>,
+    #[serde(rename = "agentId")]
+    pub(crate) agent_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub(crate) active: u32,
+    pub(crate) total: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairingStats {
+    pub(crate) pending: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairingRequest {
+    pub(crate) code: String,
+    #[serde(rename = "chatKey")]
+    pub(crate) chat_key: String,
+    #[serde(rename = "userInfo")]
+    pub(crate) user_info: UserInfo,
+    #[serde(rename = "createdAt")]
+    pub(crate) created_at: String,
+    #[serde(rename = "expiresAt")]
+    pub(crate) expires_at: String,
+}
+
+// A code the operator hands out ahead of time; whoever redeems it is paired
+// with the bot (and agent, if given) without waiting for approval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingInvite {
+    pub(crate) code: String,
+    pub(crate) bot_id: String,
+    #[serde(default)]
+    pub(crate) agent_id: Option<String>,
+    // t.me/<bot>?start=<code> on Telegram; Discord has no equivalent
+    #[serde(default)]
+    pub(crate) link: Option<String>,
+    pub(crate) expires_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub(crate) id: String,
+    pub(crate) username: Option<String>,
+    #[serde(rename = "displayName")]
+    pub(crate) display_name: Option<String>,
+    pub(crate) channel: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingsResponse {
+    pub(crate) pairings: Vec<PairingRequest>,
+}
+
+// Session summary from the Control API's /sessions list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub(crate) id: String,
+    pub(crate) chat_key: String,
+    pub(crate) session_name: Option<String>,
+    pub(crate) agent_id: Option<String>,
+    pub(crate) status: String,
+    pub(crate) created_at: String,
+    pub(crate) last_active: String,
+    // Present when rate limiting is enabled in the bridge
+    #[serde(default)]
+    pub(crate) throttle: Option<rate_limits::ThrottleState>,
+    // Claude Code's own session id, as found in its transcripts
+    #[serde(default)]
+    pub(crate) sdk_session_id: Option<String>,
+    // Newer bridges count the session's messages
+    #[serde(default)]
+    pub(crate) message_count: Option<u32>,
+    // Name of the linked identity, filled in by the desktop
+    #[serde(default)]
+    pub(crate) identity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptBatch {
+    session_id: String,
+    messages: Vec<serde_json::Value>,
+}
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Attempts for requests that are safe to repeat, with doubling delays between
+const RETRY_ATTEMPTS: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+#[derive(Debug)]
+pub(crate) enum ApiError {
+    // Nothing listening on the Control API port, or the connection dropped
+    Unreachable(String),
+    Timeout,
+    // This bridge version has no such endpoint, e.g. "DELETE:/outbox/1"
+    Unsupported(String),
+    // The endpoint exists but the thing asked for doesn't, e.g. an expired pairing
+    NotFound(String),
+    Status(u16, String),
+    Decode(String),
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::Unreachable(e) => write!(f, "Bridge is not reachable: {}", e),
+            ApiError::Timeout => f.write_str("Bridge did not answer in time"),
+            ApiError::Unsupported(route) => write!(f, "This bridge version doesn't support {}", route),
+            ApiError::NotFound(message) => f.write_str(message),
+            ApiError::Status(code, message) if message.is_empty() => write!(f, "Bridge returned {}", code),
+            ApiError::Status(code, message) => write!(f, "Bridge returned {}: {}", code, message),
+            ApiError::Decode(e) => write!(f, "Unexpected response from the bridge: {}", e),
+        }
+    }
+}
+
+// Rejections reach the frontend as their message, like every other command error
+impl Serialize for ApiError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ApiError::Timeout
+        } else if e.is_decode() {
+            ApiError::Decode(e.to_string())
+        } else {
+            ApiError::Unreachable(e.to_string())
+        }
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(e: ApiError) -> Self {
+        Error::Bridge(e.to_string())
+    }
+}
+
+impl From<ApiError> for String {
+    fn from(e: ApiError) -> Self {
+        e.to_string()
+    }
+}
+
+// Managed state; cheap to clone. Talks to the active endpoint unless made
+// for a specific one
+#[derive(Clone, Default)]
+pub(crate) struct BridgeApiClient {
+    // (base URL, client) of a specific bridge
+    target: Option<(String, reqwest::Client)>,
+}
+
+impl BridgeApiClient {
+    pub(crate) fn for_instance(instance: &BridgeInstance, client: reqwest::Client) -> Self {
+        Self {
+            target: Some((instance.url.trim_end_matches('/').to_string(), client)),
+        }
+    }
+
+    // The pooled client, for requests without a typed method (e.g. streaming)
+    pub(crate) fn http(&self) -> reqwest::Client {
+        match self.target {
+            Some((_, ref client)) => client.clone(),
+            None => bridge_endpoints::client(),
+        }
+    }
+
+    // URL of `path` on the active endpoint
+    pub(crate) fn url(path: &str) -> String {
+        format!("{}{}", api_url(), path)
+    }
+
+    fn target_url(&self, path: &str) -> String {
+        match self.target {
+            Some((ref base, _)) => format!("{}{}", base, path),
+            None => Self::url(path),
+        }
+    }
+
+    // Streams set their own (or no) timeout, so it's applied per request
+    fn request(&self, client: &reqwest::Client, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        client.request(method, self.target_url(path)).timeout(REQUEST_TIMEOUT)
+    }
+
+    // Map a non-success response to an error; Fastify answers unknown routes
+    // with "Route GET:/... not found"
+    pub(crate) async fn check(response: reqwest::Response) -> Result<reqwest::Response, ApiError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let message = body
+            .get("message")
+            .or_else(|| body.get("error"))
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Err(match status {
+            reqwest::StatusCode::NOT_FOUND if message.starts_with("Route ") => {
+                let route = message.trim_start_matches("Route ").trim_end_matches(" not found");
+                ApiError::Unsupported(route.to_string())
+            }
+            reqwest::StatusCode::NOT_FOUND => ApiError::NotFound(message),
+            status => ApiError::Status(status.as_u16(), message),
+        })
+    }
+
+    // Send once per attempt; `build` is called again for each retry. Only
+    // failures to connect are retried, so a request is never applied twice
+    async fn send(
+        &self,
+        attempts: u32,
+        build: impl Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, ApiError> {
+        let client = self.http();
+        let mut delay = RETRY_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            match build(&client).send().await {
+                Ok(response) => return Self::check(response).await,
+                Err(e) if e.is_connect() && attempt < attempts => {
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, ApiError> {
+        let response = self.send(RETRY_ATTEMPTS, |c| self.request(c, reqwest::Method::GET, path)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub(crate) async fn post(&self, path: &str, body: Option<&serde_json::Value>) -> Result<reqwest::Response, ApiError> {
+        self.send(RETRY_ATTEMPTS, |c| {
+            let request = self.request(c, reqwest::Method::POST, path);
+            match body {
+                Some(body) => request.json(body),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    pub(crate) async fn delete(&self, path: &str) -> Result<reqwest::Response, ApiError> {
+        self.send(RETRY_ATTEMPTS, |c| self.request(c, reqwest::Method::DELETE, path)).await
+    }
+
+    // Polled continuously, so a single attempt: the next poll is the retry
+    pub(crate) async fn status(&self) -> Result<BridgeStatus, ApiError> {
+        let response = self.send(1, |c| self.request(c, reqwest::Method::GET, "/status")).await?;
+        Ok(response.json().await?)
+    }
+
+    pub(crate) async fn pairings(&self) -> Result<Vec<PairingRequest>, ApiError> {
+        let response = self.send(1, |c| self.request(c, reqwest::Method::GET, "/pairings")).await?;
+        Ok(response.json::<PairingsResponse>().await?.pairings)
+    }
+
+    pub(crate) async fn approve(&self, code: &str) -> Result<(), ApiError> {
+        self.post(&format!("/pairings/{}/approve", code), None).await.map(|_| ())
+    }
+
+    pub(crate) async fn deny(&self, code: &str) -> Result<(), ApiError> {
+        self.post(&format!("/pairings/{}/deny", code), None).await.map(|_| ())
+    }
+
+    pub(crate) async fn create_invite(&self, body: &serde_json::Value) -> Result<PairingInvite, ApiError> {
+        let response = self.post("/pairings/invites", Some(body)).await?;
+        Ok(response.json().await?)
+    }
+
+    pub(crate) async fn stop(&self) -> Result<(), ApiError> {
+        self.post("/stop", None).await.map(|_| ())
+    }
+}
+
+// Sessions and transcripts can be large, so they are streamed to the frontend
+// in batches as events; the command resolves with the total count once done.
+#[tauri::command]
+pub(crate) async fn stream_sessions(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+) -> CommandResult<usize> {
+    let config = store.read()?.unwrap_or_default();
+    let count =
+        json_stream::fetch_array(&api.http(), &BridgeApiClient::url("/sessions"), "sessions", |mut batch: Vec<SessionSummary>| {
+            for session in batch.iter_mut() {
+                session.identity = identities::label_for_chat(&config, &session.chat_key);
+            }
+            let _ = app.emit("sessions://batch", batch);
+        })
+        .await
+        .map_err(Error::Bridge)?;
+    Ok(count)
+}
+
+#[tauri::command]
+pub(crate) async fn stream_transcript(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    session_id: String,
+) -> CommandResult<usize> {
+    let url = BridgeApiClient::url(&format!("/sessions/{}/messages", session_id));
+    json_stream::fetch_array(&api.http(), &url, "messages", |messages| {
+        let _ = app.emit(
+            "transcript://batch",
+            TranscriptBatch { session_id: session_id.clone(), messages },
+        );
+    })
+    .await
+    .map_err(Error::Bridge)
+}
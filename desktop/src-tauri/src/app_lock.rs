@@ -0,0 +1,109 @@
+// Optional app lock for sensitive approvals.
+//
+// A PIN the operator sets on this machine. While one is set, approving a
+// pairing with a high-sensitivity agent takes the PIN on top of the native
+// confirmation, so someone at an unlocked laptop can't let people in with two
+// clicks. Only a salted, stretched hash is kept, in a file of its own rather
+// than the app settings the webview reads and writes.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::get_desktop_data_dir;
+use crate::error::{CommandResult, Error};
+use crate::i18n;
+
+const HASH_ROUNDS: u32 = 100_000;
+const MIN_PIN_LEN: usize = 4;
+// Slows down guessing from the webview
+const FAILURE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AppLock {
+    // Hex
+    salt: String,
+    hash: String,
+}
+
+fn get_lock_path() -> PathBuf {
+    get_desktop_data_dir().join("app-lock.json")
+}
+
+fn load_lock() -> Option<AppLock> {
+    fs::read_to_string(get_lock_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = Sha256::new().chain_update(salt).chain_update(pin).finalize();
+    for _ in 1..HASH_ROUNDS {
+        digest = Sha256::new().chain_update(digest).chain_update(pin).finalize();
+    }
+    to_hex(&digest)
+}
+
+fn matches(lock: &AppLock, pin: &str) -> bool {
+    hash_pin(&lock.salt, pin) == lock.hash
+}
+
+pub(crate) fn is_set() -> bool {
+    load_lock().is_some()
+}
+
+// Ok when no lock is set or `pin` opens it
+pub(crate) async fn verify(pin: Option<&str>) -> CommandResult<()> {
+    let Some(lock) = load_lock() else {
+        return Ok(());
+    };
+    let Some(pin) = pin else {
+        return Err(Error::Invalid(i18n::t("app_lock.required")));
+    };
+    if matches(&lock, pin) {
+        return Ok(());
+    }
+    tokio::time::sleep(FAILURE_DELAY).await;
+    Err(Error::Invalid(i18n::t("app_lock.wrong_pin")))
+}
+
+#[tauri::command]
+pub(crate) fn get_app_lock_status() -> bool {
+    is_set()
+}
+
+// Set, change or (with no `pin`) remove the lock; changing it takes the current PIN
+#[tauri::command]
+pub(crate) async fn set_app_lock(current_pin: Option<String>, pin: Option<String>) -> CommandResult<bool> {
+    verify(current_pin.as_deref()).await?;
+    let path = get_lock_path();
+    let Some(pin) = pin else {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove app lock: {}", e))?;
+        }
+        return Ok(false);
+    };
+    if pin.chars().count() < MIN_PIN_LEN {
+        return Err(Error::Invalid(format!("The PIN needs at least {} characters", MIN_PIN_LEN)));
+    }
+
+    let mut salt = [0u8; 16];
+    getrandom::fill(&mut salt).map_err(|e| format!("Failed to generate a salt: {}", e))?;
+    let salt = to_hex(&salt);
+    let lock = AppLock {
+        hash: hash_pin(&salt, &pin),
+        salt,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&lock).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save app lock: {}", e))?;
+    Ok(true)
+}
@@ -0,0 +1,264 @@
+// Desktop app settings.
+//
+// Where the bridge's Control API lives. It defaults to the bridge's own
+// default of 127.0.0.1:38792; users running the bridge on another port point
+// the app at it here, and a local bridge started from the app is told to
+// listen on the configured port. Further bridges, e.g. on a home server, can
+// be listed with their URL, API key and TLS settings; their status shows next
+// to this one's, and any of them can be made the bridge the app manages.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::State;
+
+use crate::config::{expand_home, get_desktop_data_dir};
+use crate::{demo, node_runtime};
+use crate::poller::StatusCache;
+
+pub(crate) const DEFAULT_API_HOST: &str = "127.0.0.1";
+pub(crate) const DEFAULT_API_PORT: u16 = 38792;
+// Id of the main bridge among the instances
+pub(crate) const DEFAULT_INSTANCE: &str = "default";
+const DEFAULT_LOG_BUFFER_LINES: usize = 500;
+const MAX_LOG_BUFFER_LINES: usize = 50_000;
+const MIN_LOG_BUFFER_BYTES: usize = 64 * 1024;
+const MAX_LOG_BUFFER_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    api_host: String,
+    api_port: u16,
+    // Keep bot tokens in the OS keychain instead of config.json
+    #[serde(default)]
+    pub(crate) use_keychain: bool,
+    #[serde(default)]
+    pub(crate) instances: Vec<BridgeInstance>,
+    #[serde(default)]
+    pub(crate) mute_pairing_notifications: bool,
+    // Start the bridge whenever the app launches, e.g. at login
+    #[serde(default)]
+    pub(crate) start_bridge_on_launch: bool,
+    // Service log entries kept in memory
+    #[serde(default = "default_log_buffer_lines")]
+    pub(crate) log_buffer_lines: usize,
+    // Cap on the message bytes those entries hold; lines alone when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) log_buffer_bytes: Option<usize>,
+    // Node version to put first on the PATH, e.g. "20" or "20.11.1"; the
+    // newest install when unset
+    #[serde(default)]
+    pub(crate) preferred_node_version: Option<String>,
+    // Id of the instance the app manages; the local bridge when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) active_instance: Option<String>,
+    // Leave a bridge started from the app running when the app quits
+    #[serde(default)]
+    pub(crate) keep_bridge_on_quit: bool,
+    // Profile whose config is in config.json; "default" when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) active_profile: Option<String>,
+    // Which desktop app releases the updater offers
+    #[serde(default)]
+    pub(crate) update_channel: UpdateChannel,
+}
+
+fn default_log_buffer_lines() -> usize {
+    DEFAULT_LOG_BUFFER_LINES
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    // Pre-releases, published ahead of stable ones
+    Beta,
+}
+
+// Another bridge, e.g. on a home server, watched alongside the main one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeInstance {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    // Control API base URL, e.g. https://server.lan:38792
+    pub(crate) url: String,
+    // Sent as a bearer token, e.g. to an authenticating reverse proxy; a
+    // keychain reference when the keychain is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) api_key: Option<String>,
+    // PEM file with the CA that signed the bridge's certificate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) ca_cert: Option<String>,
+    // For self-signed certificates without a CA file
+    #[serde(default)]
+    pub(crate) accept_invalid_certs: bool,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            api_host: DEFAULT_API_HOST.to_string(),
+            api_port: DEFAULT_API_PORT,
+            use_keychain: false,
+            instances: vec![],
+            mute_pairing_notifications: false,
+            start_bridge_on_launch: false,
+            log_buffer_lines: DEFAULT_LOG_BUFFER_LINES,
+            log_buffer_bytes: None,
+            preferred_node_version: None,
+            active_instance: None,
+            keep_bridge_on_quit: false,
+            active_profile: None,
+            update_channel: UpdateChannel::Stable,
+        }
+    }
+}
+
+// Read on every Control API request, so kept in memory once loaded
+static SETTINGS: RwLock<Option<AppSettings>> = RwLock::new(None);
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("settings.json")
+}
+
+fn load_settings() -> AppSettings {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn current() -> AppSettings {
+    if let Some(settings) = SETTINGS.read().ok().and_then(|s| s.clone()) {
+        return settings;
+    }
+    let settings = load_settings();
+    if let Ok(mut cached) = SETTINGS.write() {
+        *cached = Some(settings.clone());
+    }
+    settings
+}
+
+// The instance the app manages, when that isn't the local bridge
+pub(crate) fn active_instance() -> Option<BridgeInstance> {
+    // The demo bridge stands in for all of them
+    if demo::is_enabled() {
+        return None;
+    }
+    let settings = current();
+    let id = settings.active_instance?;
+    settings.instances.into_iter().find(|instance| instance.id == id)
+}
+
+// Base URL of the managed bridge's Control API, e.g. http://127.0.0.1:38792
+pub(crate) fn api_url() -> String {
+    match active_instance() {
+        Some(instance) => instance.url.trim_end_matches('/').to_string(),
+        None => local_api_url(),
+    }
+}
+
+// Base URL of the local bridge's Control API
+pub(crate) fn local_api_url() -> String {
+    if let Some(url) = demo::api_url() {
+        return url;
+    }
+    let settings = current();
+    if settings.api_host.contains(':') {
+        format!("http://[{}]:{}", settings.api_host, settings.api_port)
+    } else {
+        format!("http://{}:{}", settings.api_host, settings.api_port)
+    }
+}
+
+// Env vars for `ccb start`: the bridge listens on CCB_CONTROL_API_PORT
+pub(crate) fn start_env() -> Vec<(String, String)> {
+    vec![("CCB_CONTROL_API_PORT".to_string(), current().api_port.to_string())]
+}
+
+fn validate(settings: &AppSettings) -> Result<(), String> {
+    let host = settings.api_host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err("Control API host is required".to_string());
+    }
+    if host.contains('/') || host.contains(char::is_whitespace) {
+        return Err(format!("'{}' is not a host name or address", settings.api_host));
+    }
+    if settings.api_port == 0 {
+        return Err("Control API port must be between 1 and 65535".to_string());
+    }
+    if !(50..=MAX_LOG_BUFFER_LINES).contains(&settings.log_buffer_lines) {
+        return Err(format!("Log buffer must hold between 50 and {} lines", MAX_LOG_BUFFER_LINES));
+    }
+    if settings.log_buffer_bytes.is_some_and(|bytes| !(MIN_LOG_BUFFER_BYTES..=MAX_LOG_BUFFER_BYTES).contains(&bytes)) {
+        return Err(format!(
+            "Log buffer size must be between {} KB and {} MB",
+            MIN_LOG_BUFFER_BYTES / 1024,
+            MAX_LOG_BUFFER_BYTES / (1024 * 1024)
+        ));
+    }
+    if let Some(ref version) = settings.preferred_node_version {
+        if node_runtime::parse_version(version).is_none() {
+            return Err(format!("'{}' is not a Node version such as 20 or 20.11.1", version));
+        }
+    }
+    for (i, instance) in settings.instances.iter().enumerate() {
+        if instance.id.is_empty() || instance.id == DEFAULT_INSTANCE {
+            return Err(format!("'{}' can't be used as a bridge id", instance.id));
+        }
+        if settings.instances[..i].iter().any(|other| other.id == instance.id) {
+            return Err(format!("Bridge id '{}' is used twice", instance.id));
+        }
+        let url = reqwest::Url::parse(&instance.url)
+            .map_err(|e| format!("Invalid URL for bridge '{}': {}", instance.id, e))?;
+        if !["http", "https"].contains(&url.scheme()) {
+            return Err(format!("URL for bridge '{}' must start with http:// or https://", instance.id));
+        }
+        if let Some(ref ca_cert) = instance.ca_cert {
+            if !expand_home(ca_cert).is_file() {
+                return Err(format!("CA certificate for bridge '{}' not found: {}", instance.id, ca_cert));
+            }
+        }
+    }
+    if let Some(ref id) = settings.active_instance {
+        if !settings.instances.iter().any(|instance| &instance.id == id) {
+            return Err(format!("No bridge with id '{}'", id));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_app_settings() -> AppSettings {
+    current()
+}
+
+pub(crate) fn save(settings: AppSettings) -> Result<(), String> {
+    validate(&settings)?;
+    let settings = AppSettings {
+        api_host: settings.api_host.trim_start_matches('[').trim_end_matches(']').to_string(),
+        ..settings
+    };
+
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    *SETTINGS.write().map_err(|e| e.to_string())? = Some(settings);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn set_app_settings(cache: State<'_, StatusCache>, settings: AppSettings) -> Result<bool, String> {
+    save(settings)?;
+    // Look for the bridge at its new address right away
+    cache.request_refresh();
+    Ok(true)
+}
@@ -0,0 +1,144 @@
+// Updating the desktop app itself.
+//
+// Desktop releases on GitHub carry a signed updater manifest, one per release
+// channel: stable follows the latest release, beta the manifest kept under the
+// `desktop-beta` tag. Nothing is checked or installed until `plugins.updater`
+// in tauri.conf.json has the public key releases are signed with; until then
+// the commands say updates aren't set up. `check_for_app_update` asks the
+// channel picked in the app settings whether there's a newer app, and
+// `install_app_update` downloads it as a task. Once the download is verified,
+// a bridge the app started is stopped the same way quitting stops it, the
+// update is installed and the app relaunches into the new version.
+
+use serde::Serialize;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::app_settings::{self, UpdateChannel};
+use crate::error::{CommandResult, Error};
+use crate::poller::StatusCache;
+use crate::service::{self, AppState, StartLock};
+use crate::tasks::{spawn_task, TaskHandle};
+use crate::{http, i18n, log_forwarding, shutdown};
+
+const STABLE_MANIFEST_URL: &str = "https://github.com/misbahsy/cc-bridge/releases/latest/download/latest.json";
+const BETA_MANIFEST_URL: &str = "https://github.com/misbahsy/cc-bridge/releases/download/desktop-beta/latest.json";
+const CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdate {
+    version: String,
+    current_version: String,
+    // Release notes from the manifest
+    notes: Option<String>,
+    channel: UpdateChannel,
+}
+
+// A public key in tauri.conf.json, without which no update can be verified
+pub(crate) fn is_configured(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.trim().is_empty())
+}
+
+fn ensure_configured(app: &AppHandle) -> CommandResult<()> {
+    if is_configured(app) {
+        Ok(())
+    } else {
+        Err(Error::Config(i18n::t("updates.not_configured")))
+    }
+}
+
+fn manifest_url(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => STABLE_MANIFEST_URL,
+        UpdateChannel::Beta => BETA_MANIFEST_URL,
+    }
+}
+
+async fn find_update(app: &AppHandle, channel: UpdateChannel) -> Result<Option<Update>, String> {
+    let url = reqwest::Url::parse(manifest_url(channel)).map_err(|e| e.to_string())?;
+    let mut builder = app
+        .updater_builder()
+        .endpoints(vec![url])
+        .map_err(|e| format!("Failed to set up the updater: {}", e))?
+        .timeout(CHECK_TIMEOUT);
+    if let Some(proxy) = http::proxy_url() {
+        builder = builder.proxy(proxy);
+    }
+    let updater = builder.build().map_err(|e| format!("Failed to set up the updater: {}", e))?;
+    updater.check().await.map_err(|e| format!("Checking for app updates failed: {}", e))
+}
+
+// None when the app is up to date
+#[tauri::command]
+pub(crate) async fn check_for_app_update(app: AppHandle) -> CommandResult<Option<AppUpdate>> {
+    ensure_configured(&app)?;
+    let channel = app_settings::current().update_channel;
+    let update = find_update(&app, channel).await?;
+    Ok(update.map(|update| AppUpdate {
+        version: update.version,
+        current_version: update.current_version,
+        notes: update.body,
+        channel,
+    }))
+}
+
+async fn install(app: AppHandle, task: TaskHandle) -> Result<serde_json::Value, String> {
+    let channel = app_settings::current().update_channel;
+    let update = find_update(&app, channel).await?.ok_or("CCB is already up to date")?;
+
+    let mut downloaded: u64 = 0;
+    let mut reported = None;
+    let bytes = update
+        .download(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let Some(total) = total.filter(|total| *total > 0) else {
+                    return;
+                };
+                // One event per percent is plenty for a progress bar
+                let percent = downloaded * 100 / total;
+                if reported != Some(percent) {
+                    reported = Some(percent);
+                    task.progress(Some(downloaded as f64 / total as f64), format!("Downloading CCB {}", update.version));
+                }
+            },
+            || task.progress(Some(1.0), format!("Installing CCB {}", update.version)),
+        )
+        .await
+        .map_err(|e| format!("Failed to download the update: {}", e))?;
+
+    let stopped = shutdown::before_relaunch(&app).await;
+    if let Err(e) = update.install(&bytes) {
+        // Leave things as they were
+        if stopped {
+            let launched = service::launch(
+                &app,
+                &app.state::<AppState>(),
+                &app.state::<StatusCache>(),
+                &app.state::<StartLock>(),
+            )
+            .await;
+            if let Err(failure) = launched {
+                log_forwarding::desktop_error(format!("Failed to restart the bridge: {}", failure.message));
+            }
+        }
+        return Err(format!("Failed to install the update: {}", e));
+    }
+    app.restart()
+}
+
+// Download and install the update as a task, then relaunch; returns the task id
+#[tauri::command]
+pub(crate) fn install_app_update(app: AppHandle) -> CommandResult<String> {
+    ensure_configured(&app)?;
+    let handle = app.clone();
+    Ok(spawn_task(&app, "app_update", "Update CCB", move |task| install(handle, task))?)
+}
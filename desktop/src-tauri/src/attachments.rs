@@ -0,0 +1,233 @@
+// Files received from chats and artifacts handed back by agents.
+//
+// The bridge stores attachments under the directory configured in the
+// `attachments` section of config.json, organized as
+// `<directory>/<agentId>/<chatKey>/<file>`. The desktop lists that tree, copies
+// files out on request, and enforces the retention policy in the background.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::{expand_home, ConfigStore};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentSettings {
+    directory: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retention_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_total_mb: Option<u64>,
+}
+
+impl Default for AttachmentSettings {
+    fn default() -> Self {
+        Self {
+            directory: "~/.ccb/files".to_string(),
+            retention_days: None,
+            max_total_mb: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedFile {
+    // Path relative to the attachments directory
+    id: String,
+    name: String,
+    agent_id: Option<String>,
+    chat_key: Option<String>,
+    size: u64,
+    received_at: String,
+    #[serde(skip)]
+    path: PathBuf,
+    #[serde(skip)]
+    modified: SystemTime,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupReport {
+    dry_run: bool,
+    removed: Vec<String>,
+    freed_bytes: u64,
+}
+
+fn read_settings(config: &serde_json::Value) -> AttachmentSettings {
+    config
+        .get("attachments")
+        .and_then(|a| serde_json::from_value(a.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<ReceivedFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_files(root, &path, files);
+            continue;
+        }
+
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        // <agentId>/<chatKey>/<file>; anything shallower is unsorted
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        let (agent_id, chat_key) = match parts.as_slice() {
+            [agent, chat, ..] if parts.len() >= 3 => (Some(agent.clone()), Some(chat.clone())),
+            [agent, _] => (Some(agent.clone()), None),
+            _ => (None, None),
+        };
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+        files.push(ReceivedFile {
+            id: relative.to_string_lossy().to_string(),
+            name: entry.file_name().to_string_lossy().to_string(),
+            agent_id,
+            chat_key,
+            size: metadata.len(),
+            received_at: DateTime::<Local>::from(modified).to_rfc3339(),
+            path,
+            modified,
+        });
+    }
+}
+
+fn list_files(settings: &AttachmentSettings) -> Vec<ReceivedFile> {
+    let root = expand_home(&settings.directory);
+    let mut files = Vec::new();
+    collect_files(&root, &root, &mut files);
+    // Newest first
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified));
+    files
+}
+
+fn cleanup(settings: &AttachmentSettings, dry_run: bool) -> CleanupReport {
+    let mut files = list_files(settings);
+    let mut doomed: Vec<ReceivedFile> = Vec::new();
+
+    if let Some(days) = settings.retention_days {
+        let cutoff = SystemTime::now() - Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+        let (old, keep): (Vec<_>, Vec<_>) = files.into_iter().partition(|f| f.modified < cutoff);
+        doomed.extend(old);
+        files = keep;
+    }
+
+    if let Some(max_mb) = settings.max_total_mb {
+        let max_bytes = max_mb * 1024 * 1024;
+        let mut total: u64 = files.iter().map(|f| f.size).sum();
+        // Files are newest first, so evict from the back
+        while total > max_bytes {
+            let Some(oldest) = files.pop() else {
+                break;
+            };
+            total -= oldest.size;
+            doomed.push(oldest);
+        }
+    }
+
+    let mut report = CleanupReport {
+        dry_run,
+        removed: Vec::new(),
+        freed_bytes: 0,
+    };
+    for file in doomed {
+        if dry_run || fs::remove_file(&file.path).is_ok() {
+            report.freed_bytes += file.size;
+            report.removed.push(file.id);
+        }
+    }
+    report
+}
+
+pub(crate) async fn run_cleanup(app: AppHandle) {
+    loop {
+        let settings = app
+            .state::<ConfigStore>()
+            .read()
+            .ok()
+            .flatten()
+            .map(|c| read_settings(&c));
+        if let Some(settings) = settings {
+            if settings.retention_days.is_some() || settings.max_total_mb.is_some() {
+                let _ = tauri::async_runtime::spawn_blocking(move || cleanup(&settings, false)).await;
+            }
+        }
+        tokio::time::sleep(CLEANUP_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_attachment_settings(store: State<'_, ConfigStore>) -> Result<AttachmentSettings, String> {
+    Ok(store.read()?.map(|c| read_settings(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn set_attachment_settings(store: State<'_, ConfigStore>, settings: AttachmentSettings) -> Result<bool, String> {
+    if settings.directory.trim().is_empty() {
+        return Err("Attachments directory cannot be empty".to_string());
+    }
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    config["attachments"] = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn list_received_files(
+    store: State<'_, ConfigStore>,
+    agent_id: Option<String>,
+    chat_key: Option<String>,
+) -> Result<Vec<ReceivedFile>, String> {
+    let settings = store.read()?.map(|c| read_settings(&c)).unwrap_or_default();
+    Ok(list_files(&settings)
+        .into_iter()
+        .filter(|f| agent_id.is_none() || f.agent_id == agent_id)
+        .filter(|f| chat_key.is_none() || f.chat_key == chat_key)
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) fn save_file_to(store: State<'_, ConfigStore>, id: String, path: String) -> Result<String, String> {
+    // Ids are relative paths; refuse anything that could escape the directory
+    let relative = Path::new(&id);
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(format!("Invalid file id '{}'", id));
+    }
+
+    let settings = store.read()?.map(|c| read_settings(&c)).unwrap_or_default();
+    let source = expand_home(&settings.directory).join(relative);
+    if !source.is_file() {
+        return Err(format!("File '{}' not found", id));
+    }
+
+    let mut destination = expand_home(&path);
+    if destination.is_dir() {
+        destination = destination.join(source.file_name().unwrap_or_default());
+    }
+    fs::copy(&source, &destination).map_err(|e| format!("Failed to save file: {}", e))?;
+
+    Ok(destination.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub(crate) fn cleanup_received_files(store: State<'_, ConfigStore>, dry_run: bool) -> Result<CleanupReport, String> {
+    let settings = store.read()?.map(|c| read_settings(&c)).unwrap_or_default();
+    Ok(cleanup(&settings, dry_run))
+}
@@ -0,0 +1,49 @@
+// Launch at login.
+//
+// The autostart plugin registers the app as a LaunchAgent on macOS, in the
+// Run registry key on Windows and as an XDG autostart entry on Linux. With
+// `startBridgeOnLaunch` on in the app settings the bridge is started as soon
+// as the app comes up, so together they keep the bridge running whenever the
+// user is logged in.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::poller::StatusCache;
+use crate::service::{start_service, AppState, StartLock};
+use crate::{app_settings, log_forwarding};
+
+// Passed to the app when the OS starts it at login
+pub(crate) const LAUNCH_ARG: &str = "--autostart";
+
+pub(crate) async fn start_bridge_on_launch(app: AppHandle) {
+    if !app_settings::current().start_bridge_on_launch {
+        return;
+    }
+    let result = start_service(
+        app.clone(),
+        app.state::<AppState>(),
+        app.state::<StatusCache>(),
+        app.state::<StartLock>(),
+        None,
+    )
+    .await;
+    if let Err(failure) = result {
+        log_forwarding::desktop_error(format!("Failed to start the bridge on launch: {}", failure.message));
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_autostart(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to read launch at login: {}", e))
+}
+
+#[tauri::command]
+pub(crate) fn set_autostart(app: AppHandle, enabled: bool) -> Result<bool, String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled { autolaunch.enable() } else { autolaunch.disable() };
+    result.map_err(|e| format!("Failed to update launch at login: {}", e))?;
+    Ok(enabled)
+}
@@ -0,0 +1,327 @@
+// Config backups.
+//
+// Copies config.json into a backup folder (by default ~/.ccb/backups, or any
+// folder such as an external disk or a synced directory) on demand and on a
+// daily or weekly schedule, keeping only the newest N copies. Settings and the
+// outcome of the last run live in the desktop's data dir.
+//
+// Backups are written with their secrets redacted unless that's turned off;
+// restoring one takes the secrets from the current config.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::State;
+
+use crate::config::{expand_home, get_ccb_dir, get_config_path, get_desktop_data_dir, ConfigStore};
+use crate::secrets;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const BACKUP_PREFIX: &str = "config-";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%d-%H%M%S-%3f";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupSchedule {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    schedule: BackupSchedule,
+    keep_last: u32,
+    // None uses ~/.ccb/backups
+    destination: Option<String>,
+    // Replace tokens and keys with a placeholder in the copies; only turned
+    // off on purpose, for a folder nobody else can read
+    #[serde(default = "default_true")]
+    redact_secrets: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            schedule: BackupSchedule::Off,
+            keep_last: 10,
+            destination: None,
+            redact_secrets: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupStatus {
+    last_success_at: Option<String>,
+    last_backup: Option<String>,
+    last_error: Option<String>,
+    last_error_at: Option<String>,
+    next_run_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupState {
+    #[serde(default)]
+    settings: BackupSettings,
+    #[serde(default)]
+    status: BackupStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupFile {
+    name: String,
+    path: String,
+    size: u64,
+    created_at: String,
+}
+
+fn get_state_path() -> PathBuf {
+    get_desktop_data_dir().join("backups.json")
+}
+
+fn load_state() -> BackupState {
+    fs::read_to_string(get_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &BackupState) -> Result<(), String> {
+    let path = get_state_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save backup settings: {}", e))
+}
+
+fn backup_dir(settings: &BackupSettings) -> PathBuf {
+    match settings.destination {
+        Some(ref dir) if !dir.trim().is_empty() => expand_home(dir),
+        _ => get_ccb_dir().join("backups"),
+    }
+}
+
+fn next_run(settings: &BackupSettings, status: &BackupStatus) -> Option<DateTime<Local>> {
+    let period = match settings.schedule {
+        BackupSchedule::Off => return None,
+        BackupSchedule::Daily => chrono::Duration::days(1),
+        BackupSchedule::Weekly => chrono::Duration::weeks(1),
+    };
+    let last = status
+        .last_success_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Local));
+    Some(last.map(|t| t + period).unwrap_or_else(Local::now))
+}
+
+// Sort key of a name this app gives its backups: config-<date>-<time>.json,
+// with milliseconds and a counter for backups taken in the same millisecond.
+// Older backups have no milliseconds. Any other file is not ours
+fn backup_key(name: &str) -> Option<(String, u32)> {
+    let stamp = name.strip_prefix(BACKUP_PREFIX)?.strip_suffix(".json")?;
+    let parts: Vec<&str> = stamp.split('-').collect();
+    let digits = |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    let (date, time, millis, counter) = match parts.as_slice() {
+        [date, time] => (*date, *time, "000", None),
+        [date, time, millis] => (*date, *time, *millis, None),
+        [date, time, millis, counter] => (*date, *time, *millis, Some(*counter)),
+        _ => return None,
+    };
+    if !digits(date, 8) || !digits(time, 6) || !digits(millis, 3) {
+        return None;
+    }
+    let counter = match counter {
+        Some(counter) if counter.bytes().all(|b| b.is_ascii_digit()) => counter.parse().ok()?,
+        Some(_) => return None,
+        None => 0,
+    };
+    Some((format!("{}{}{}", date, time, millis), counter))
+}
+
+fn list_files(settings: &BackupSettings) -> Vec<BackupFile> {
+    let Ok(entries) = fs::read_dir(backup_dir(settings)) else {
+        return vec![];
+    };
+    let mut files: Vec<((String, u32), BackupFile)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let key = backup_key(&name)?;
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            let created: DateTime<Local> = metadata.modified().ok()?.into();
+            let file = BackupFile {
+                path: entry.path().to_string_lossy().to_string(),
+                name,
+                size: metadata.len(),
+                created_at: created.to_rfc3339(),
+            };
+            Some((key, file))
+        })
+        .collect();
+    // Names embed the timestamp, so newest first
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files.into_iter().map(|(_, file)| file).collect()
+}
+
+// Only ever touches files named like our own backups, so a shared
+// destination folder keeps everything else
+fn prune(settings: &BackupSettings) {
+    for file in list_files(settings).into_iter().skip(settings.keep_last.max(1) as usize) {
+        let _ = fs::remove_file(file.path);
+    }
+}
+
+fn create_backup(settings: &BackupSettings) -> Result<PathBuf, String> {
+    let config_path = get_config_path();
+    if !config_path.exists() {
+        return Err("Config file not found".to_string());
+    }
+    let dir = backup_dir(settings);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup folder {}: {}", dir.display(), e))?;
+
+    let content = if settings.redact_secrets {
+        let content = fs::read_to_string(&config_path).map_err(|e| format!("Failed to read config: {}", e))?;
+        let mut config: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| format!("Config is not valid JSON: {}", e))?;
+        secrets::redact_config(&mut config);
+        serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?.into_bytes()
+    } else {
+        fs::read(&config_path).map_err(|e| format!("Failed to read config: {}", e))?
+    };
+
+    // Never overwrite an earlier backup, even one from the same millisecond
+    let stamp = Local::now().format(BACKUP_TIME_FORMAT).to_string();
+    let mut counter = 0;
+    let (dest, mut file) = loop {
+        let name = match counter {
+            0 => format!("{}{}.json", BACKUP_PREFIX, stamp),
+            n => format!("{}{}-{}.json", BACKUP_PREFIX, stamp, n),
+        };
+        let dest = dir.join(name);
+        match OpenOptions::new().write(true).create_new(true).open(&dest) {
+            Ok(file) => break (dest, file),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => counter += 1,
+            Err(e) => return Err(format!("Failed to write backup: {}", e)),
+        }
+    };
+    file.write_all(&content).map_err(|e| format!("Failed to write backup: {}", e))?;
+    prune(settings);
+    Ok(dest)
+}
+
+// Back up now and record the outcome
+fn run_backup() -> Result<BackupStatus, String> {
+    let mut state = load_state();
+    let now = Local::now().to_rfc3339();
+    let result = create_backup(&state.settings);
+    match result {
+        Ok(ref path) => {
+            state.status.last_success_at = Some(now);
+            state.status.last_backup = Some(path.to_string_lossy().to_string());
+            state.status.last_error = None;
+        }
+        Err(ref e) => {
+            state.status.last_error = Some(e.clone());
+            state.status.last_error_at = Some(now);
+        }
+    }
+    state.status.next_run_at = next_run(&state.settings, &state.status).map(|t| t.to_rfc3339());
+    save_state(&state)?;
+    result.map(|_| state.status)
+}
+
+pub(crate) async fn run() {
+    loop {
+        let state = load_state();
+        let due = next_run(&state.settings, &state.status).is_some_and(|t| t <= Local::now());
+        if due {
+            // A destination on an unplugged disk fails; we retry on the next check
+            let _ = tauri::async_runtime::spawn_blocking(run_backup).await;
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_backup_settings() -> BackupSettings {
+    load_state().settings
+}
+
+#[tauri::command]
+pub(crate) fn set_backup_settings(settings: BackupSettings) -> Result<BackupStatus, String> {
+    if settings.keep_last == 0 {
+        return Err("Keep at least one backup".to_string());
+    }
+    let mut state = load_state();
+    state.settings = settings;
+    state.status.next_run_at = next_run(&state.settings, &state.status).map(|t| t.to_rfc3339());
+    save_state(&state)?;
+    prune(&state.settings);
+    Ok(state.status)
+}
+
+#[tauri::command]
+pub(crate) fn get_backup_status() -> BackupStatus {
+    load_state().status
+}
+
+#[tauri::command]
+pub(crate) async fn backup_config_now() -> Result<BackupStatus, String> {
+    tauri::async_runtime::spawn_blocking(run_backup)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub(crate) fn list_config_backups() -> Vec<BackupFile> {
+    list_files(&load_state().settings)
+}
+
+// Takes a backup of the current config first so a restore can be undone
+#[tauri::command]
+pub(crate) fn restore_config_backup(store: State<'_, ConfigStore>, name: String) -> Result<bool, String> {
+    let settings = load_state().settings;
+    let file = list_files(&settings)
+        .into_iter()
+        .find(|f| f.name == name)
+        .ok_or(format!("Backup '{}' not found", name))?;
+    let content = fs::read_to_string(&file.path).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let mut config: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("Backup is not valid JSON: {}", e))?;
+
+    // A redacted backup gets its secrets back from the config it replaces
+    if content.contains(secrets::REDACTED) {
+        let current = store.read()?.unwrap_or_default();
+        let missing = secrets::restore_redacted(&mut config, &current);
+        if !missing.is_empty() {
+            return Err(format!(
+                "Backup has redacted secrets the current config can't fill in: {}",
+                missing.join(", ")
+            ));
+        }
+    }
+
+    if get_config_path().exists() {
+        create_backup(&settings)?;
+    }
+    store.write(&config)?;
+    Ok(true)
+}
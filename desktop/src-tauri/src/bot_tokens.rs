@@ -0,0 +1,142 @@
+// Checking bot tokens with their platform.
+//
+// A pasted token can be mistyped, revoked, or belong to a different bot than
+// intended, and the bridge only finds out when it connects. Asking Telegram
+// (`getMe`) or Discord (`/users/@me`) first rejects dead tokens before they
+// are saved and tells the config UI which bot a token belongs to, avatar
+// included. Telegram's file URLs contain the token, so its avatar is fetched
+// here and handed over as a data URL instead.
+
+use base64::Engine;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::error::{CommandResult, Error};
+use crate::{http, keychain, secrets};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// Telegram's smallest profile photo size is 160px; anything bigger is skipped
+const MAX_AVATAR_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotIdentity {
+    channel: String,
+    id: String,
+    pub(crate) username: String,
+    display_name: String,
+    // Image URL or data URL; None when the bot has no picture
+    avatar: Option<String>,
+}
+
+// Telegram's URLs contain the token, so reqwest errors are stripped of theirs
+async fn telegram_get(client: &reqwest::Client, url: String) -> Result<reqwest::Response, String> {
+    client
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Telegram: {}", e.without_url()))
+}
+
+async fn telegram_call(client: &reqwest::Client, token: &str, method: &str) -> Result<serde_json::Value, String> {
+    let response = telegram_get(client, format!("https://api.telegram.org/bot{}/{}", token, method)).await?;
+    let body: serde_json::Value = response.json().await.map_err(|e| e.without_url().to_string())?;
+    if body["ok"].as_bool() != Some(true) {
+        return Err(format!(
+            "Telegram rejected the token: {}",
+            body["description"].as_str().unwrap_or("unknown error")
+        ));
+    }
+    Ok(body["result"].clone())
+}
+
+// The bot's current profile photo, smallest size
+async fn telegram_avatar(client: &reqwest::Client, token: &str, user_id: &str) -> Option<String> {
+    let photos = telegram_call(client, token, &format!("getUserProfilePhotos?user_id={}&limit=1", user_id))
+        .await
+        .ok()?;
+    let file_id = photos["photos"][0][0]["file_id"].as_str()?.to_string();
+    let file = telegram_call(client, token, &format!("getFile?file_id={}", file_id)).await.ok()?;
+    let path = file["file_path"].as_str()?;
+    let response = telegram_get(client, format!("https://api.telegram.org/file/bot{}/{}", token, path))
+        .await
+        .ok()?;
+    let bytes = response.bytes().await.ok()?;
+    if bytes.len() > MAX_AVATAR_BYTES {
+        return None;
+    }
+    Some(format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(&bytes)
+    ))
+}
+
+pub(crate) async fn telegram_identity(client: &reqwest::Client, token: &str) -> Result<BotIdentity, String> {
+    let me = telegram_call(client, token, "getMe").await?;
+    Ok(BotIdentity {
+        channel: "telegram".to_string(),
+        id: me["id"].as_i64().map(|id| id.to_string()).unwrap_or_default(),
+        username: format!("@{}", me["username"].as_str().unwrap_or_default()),
+        display_name: me["first_name"].as_str().unwrap_or_default().to_string(),
+        avatar: None,
+    })
+}
+
+pub(crate) async fn discord_identity(client: &reqwest::Client, token: &str) -> Result<BotIdentity, String> {
+    let response = client
+        .get("https://discord.com/api/v10/users/@me")
+        .header("Authorization", format!("Bot {}", token))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Discord: {}", e))?;
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err("Discord rejected the token".to_string());
+    }
+    if !response.status().is_success() {
+        return Err(format!("Discord returned {}", response.status()));
+    }
+    let me: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    let id = me["id"].as_str().unwrap_or_default().to_string();
+    let avatar = me["avatar"]
+        .as_str()
+        .map(|hash| format!("https://cdn.discordapp.com/avatars/{}/{}.png?size=128", id, hash));
+    Ok(BotIdentity {
+        channel: "discord".to_string(),
+        username: me["username"].as_str().unwrap_or_default().to_string(),
+        display_name: me["global_name"].as_str().or(me["username"].as_str()).unwrap_or_default().to_string(),
+        id,
+        avatar,
+    })
+}
+
+// Which bot a token belongs to; fails for malformed or dead tokens. Accepts
+// keychain references as written in config.json
+#[tauri::command]
+pub(crate) async fn validate_bot_token(channel: String, token: String) -> CommandResult<BotIdentity> {
+    let token = keychain::resolve(token.trim());
+    if token.is_empty() {
+        return Err(Error::Invalid("Token is empty".to_string()));
+    }
+    let client = http::client();
+    match channel.as_str() {
+        "telegram" => {
+            if !secrets::is_telegram_token(&token) {
+                return Err(Error::Invalid(
+                    "That doesn't look like a Telegram bot token (expected 123456789:ABC...)".to_string(),
+                ));
+            }
+            let mut identity = telegram_identity(&client, &token).await?;
+            identity.avatar = telegram_avatar(&client, &token, &identity.id).await;
+            Ok(identity)
+        }
+        "discord" => {
+            if !secrets::is_discord_token(&token) {
+                return Err(Error::Invalid("That doesn't look like a Discord bot token".to_string()));
+            }
+            Ok(discord_identity(&client, &token).await?)
+        }
+        _ => Err(Error::Invalid(format!("Unknown channel '{}'", channel))),
+    }
+}
@@ -0,0 +1,256 @@
+// Bridge endpoints.
+//
+// Besides the local bridge, the app can talk to bridges elsewhere, e.g. one
+// on a home server managed from a laptop. Each endpoint has a URL, an
+// optional API key sent as a bearer token (the Control API itself has no
+// auth, so remote ones sit behind a reverse proxy that checks it) and TLS
+// settings for self-hosted certificates. One endpoint is active: every
+// Control API request the app makes goes there, through a client built for
+// it and rebuilt when its settings change. The others can still be queried
+// for status, pairings and sessions by id.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::RwLock;
+use std::time::Duration;
+use tauri::State;
+
+use crate::api_client::{BridgeApiClient, BridgeStatus, PairingRequest, SessionSummary};
+use crate::app_settings::{self, BridgeInstance, DEFAULT_INSTANCE};
+use crate::config::expand_home;
+use crate::error::{CommandResult, Error};
+use crate::keychain;
+use crate::poller::StatusCache;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+// Remote bridges are further away than loopback
+const REMOTE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct SharedClient {
+    // What the client was built for; None for the local bridge
+    instance: Option<BridgeInstance>,
+    client: reqwest::Client,
+}
+
+static CLIENT: RwLock<Option<SharedClient>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointInfo {
+    id: String,
+    name: String,
+    url: String,
+    has_api_key: bool,
+    ca_cert: Option<String>,
+    accept_invalid_certs: bool,
+    active: bool,
+}
+
+// An endpoint as edited in the UI; `api_key` is left out to keep the saved
+// one and empty to remove it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointInput {
+    id: String,
+    name: String,
+    url: String,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    ca_cert: Option<String>,
+    #[serde(default)]
+    accept_invalid_certs: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SessionsResponse {
+    sessions: Vec<SessionSummary>,
+}
+
+// A client for `instance`, or for the local bridge when None
+pub(crate) fn build(instance: Option<&BridgeInstance>) -> Result<reqwest::Client, String> {
+    // Bridges are reached directly; a system proxy must never see these requests
+    let builder = reqwest::Client::builder().no_proxy();
+    let Some(instance) = instance else {
+        return builder
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e));
+    };
+
+    let mut builder = builder
+        .connect_timeout(REMOTE_CONNECT_TIMEOUT)
+        .danger_accept_invalid_certs(instance.accept_invalid_certs);
+    if let Some(key) = instance.api_key.as_deref().map(keychain::resolve).filter(|k| !k.is_empty()) {
+        let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", key))
+            .map_err(|_| format!("API key for bridge '{}' contains invalid characters", instance.id))?;
+        value.set_sensitive(true);
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+        builder = builder.default_headers(headers);
+    }
+    if let Some(ref path) = instance.ca_cert {
+        let pem = fs::read(expand_home(path)).map_err(|e| format!("Failed to read CA certificate: {}", e))?;
+        let cert = reqwest::Certificate::from_pem(&pem).map_err(|e| format!("Invalid CA certificate: {}", e))?;
+        builder = builder.add_root_certificate(cert);
+    }
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// The client for the active endpoint; cheap to clone, clones share the pool
+pub(crate) fn client() -> reqwest::Client {
+    let instance = app_settings::active_instance();
+    if let Some(shared) = CLIENT.read().ok().as_ref().and_then(|c| c.as_ref()) {
+        if shared.instance == instance {
+            return shared.client.clone();
+        }
+    }
+    // Settings were validated when saved, so this only fails if e.g. the CA
+    // file has since gone; requests then fail with the TLS error
+    let client = build(instance.as_ref()).unwrap_or_default();
+    if let Ok(mut shared) = CLIENT.write() {
+        *shared = Some(SharedClient {
+            instance,
+            client: client.clone(),
+        });
+    }
+    client
+}
+
+fn instance(id: &str) -> CommandResult<BridgeInstance> {
+    app_settings::current()
+        .instances
+        .into_iter()
+        .find(|instance| instance.id == id)
+        .ok_or_else(|| Error::Invalid(format!("No bridge with id '{}'", id)))
+}
+
+// A client for the endpoint `id`; "default" is the local bridge
+pub(crate) fn api_for(id: &str) -> CommandResult<BridgeApiClient> {
+    if id == DEFAULT_INSTANCE {
+        let local = BridgeInstance {
+            id: DEFAULT_INSTANCE.to_string(),
+            name: String::new(),
+            url: app_settings::local_api_url(),
+            api_key: None,
+            ca_cert: None,
+            accept_invalid_certs: false,
+        };
+        return Ok(BridgeApiClient::for_instance(&local, build(None)?));
+    }
+    let instance = instance(id)?;
+    let client = build(Some(&instance))?;
+    Ok(BridgeApiClient::for_instance(&instance, client))
+}
+
+// Keep the API key in the keychain when it's on; returns what to save
+fn store_api_key(id: &str, key: &str) -> Result<String, String> {
+    if !app_settings::current().use_keychain {
+        return Ok(key.to_string());
+    }
+    let name = format!("BRIDGE_{}", id)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect::<String>();
+    keychain::store(&name, key)
+}
+
+#[tauri::command]
+pub(crate) fn get_bridge_endpoints() -> Vec<EndpointInfo> {
+    let settings = app_settings::current();
+    let local = EndpointInfo {
+        id: DEFAULT_INSTANCE.to_string(),
+        name: "Local".to_string(),
+        url: app_settings::local_api_url(),
+        has_api_key: false,
+        ca_cert: None,
+        accept_invalid_certs: false,
+        active: settings.active_instance.is_none(),
+    };
+    let active = settings.active_instance.clone();
+    std::iter::once(local)
+        .chain(settings.instances.into_iter().map(|instance| EndpointInfo {
+            active: active.as_deref() == Some(instance.id.as_str()),
+            has_api_key: instance.api_key.as_deref().is_some_and(|k| !k.is_empty()),
+            id: instance.id,
+            name: instance.name,
+            url: instance.url,
+            ca_cert: instance.ca_cert,
+            accept_invalid_certs: instance.accept_invalid_certs,
+        }))
+        .collect()
+}
+
+// Adds the endpoint, or replaces the one with the same id
+#[tauri::command]
+pub(crate) fn save_bridge_endpoint(cache: State<'_, StatusCache>, endpoint: EndpointInput) -> CommandResult<bool> {
+    let mut settings = app_settings::current();
+    let existing = settings.instances.iter().position(|instance| instance.id == endpoint.id);
+    let api_key = match endpoint.api_key.as_deref().map(str::trim) {
+        None => existing.and_then(|i| settings.instances[i].api_key.clone()),
+        Some("") => None,
+        Some(key) => Some(store_api_key(&endpoint.id, key)?),
+    };
+    let instance = BridgeInstance {
+        id: endpoint.id,
+        name: endpoint.name,
+        url: endpoint.url.trim().to_string(),
+        api_key,
+        ca_cert: endpoint.ca_cert.filter(|path| !path.trim().is_empty()),
+        accept_invalid_certs: endpoint.accept_invalid_certs,
+    };
+    build(Some(&instance)).map_err(Error::Invalid)?;
+    match existing {
+        Some(i) => settings.instances[i] = instance,
+        None => settings.instances.push(instance),
+    }
+    app_settings::save(settings).map_err(Error::Invalid)?;
+    cache.request_refresh();
+    Ok(true)
+}
+
+// The local bridge becomes active again if this one was
+#[tauri::command]
+pub(crate) fn remove_bridge_endpoint(cache: State<'_, StatusCache>, id: String) -> CommandResult<bool> {
+    let mut settings = app_settings::current();
+    let before = settings.instances.len();
+    settings.instances.retain(|instance| instance.id != id);
+    if settings.instances.len() == before {
+        return Ok(false);
+    }
+    if settings.active_instance.as_deref() == Some(id.as_str()) {
+        settings.active_instance = None;
+    }
+    app_settings::save(settings).map_err(Error::Invalid)?;
+    cache.request_refresh();
+    Ok(true)
+}
+
+// Manage the bridge at endpoint `id` from now on; "default" for the local one
+#[tauri::command]
+pub(crate) fn set_active_endpoint(cache: State<'_, StatusCache>, id: String) -> CommandResult<bool> {
+    let mut settings = app_settings::current();
+    settings.active_instance = if id == DEFAULT_INSTANCE {
+        None
+    } else {
+        Some(instance(&id)?.id)
+    };
+    app_settings::save(settings).map_err(Error::Invalid)?;
+    cache.request_refresh();
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn get_endpoint_status(id: String) -> CommandResult<BridgeStatus> {
+    Ok(api_for(&id)?.status().await?)
+}
+
+#[tauri::command]
+pub(crate) async fn get_endpoint_pairings(id: String) -> CommandResult<Vec<PairingRequest>> {
+    Ok(api_for(&id)?.pairings().await?)
+}
+
+#[tauri::command]
+pub(crate) async fn get_endpoint_sessions(id: String) -> CommandResult<Vec<SessionSummary>> {
+    Ok(api_for(&id)?.get::<SessionsResponse>("/sessions").await?.sessions)
+}
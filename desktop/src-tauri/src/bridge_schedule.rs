@@ -0,0 +1,274 @@
+// Scheduled bridge start and stop, e.g. quiet hours.
+//
+// Rules such as "stop at 23:00, start at 08:00 on weekdays" are saved in the
+// desktop's data dir and enforced by a background task, which starts or stops
+// the local bridge the same way the buttons do. Times are wall-clock times in
+// the schedule's time zone. A transition missed while the machine slept is
+// applied on waking, the latest one winning; transitions from before the app
+// launched are left alone.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::get_desktop_data_dir;
+use crate::error::{CommandResult, Error};
+use crate::poller::StatusCache;
+use crate::service::{self, AppState};
+use crate::{app_settings, i18n, log_forwarding, scheduler};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+// The last transition the task applied
+static LAST: Mutex<Option<AppliedTransition>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+}
+
+impl ScheduleAction {
+    fn name(self) -> &'static str {
+        match self {
+            ScheduleAction::Start => "start",
+            ScheduleAction::Stop => "stop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRule {
+    action: ScheduleAction,
+    // "HH:MM"
+    time: String,
+    // 0 = Sunday ... 6 = Saturday; empty means every day
+    #[serde(default)]
+    days: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeSchedule {
+    rules: Vec<ScheduleRule>,
+    // IANA name such as "Europe/Berlin"; the system time zone when unset
+    #[serde(default)]
+    timezone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTransition {
+    action: ScheduleAction,
+    // RFC 3339
+    at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedTransition {
+    #[serde(flatten)]
+    transition: ScheduledTransition,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleStatus {
+    rules: Vec<ScheduleRule>,
+    timezone: Option<String>,
+    next: Option<ScheduledTransition>,
+    last: Option<AppliedTransition>,
+}
+
+fn get_schedule_path() -> PathBuf {
+    get_desktop_data_dir().join("bridge-schedule.json")
+}
+
+fn load_schedule() -> BridgeSchedule {
+    fs::read_to_string(get_schedule_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_schedule(schedule: &BridgeSchedule) -> Result<(), String> {
+    let path = get_schedule_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(schedule).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save bridge schedule: {}", e))
+}
+
+fn timezone(schedule: &BridgeSchedule) -> Option<Tz> {
+    match schedule.timezone.as_deref() {
+        Some(name) => scheduler::parse_timezone(name).ok(),
+        None => None,
+    }
+}
+
+// Times `rule` fires within a day either side of `from` and `to`, in UTC
+fn rule_times(rule: &ScheduleRule, tz: Option<Tz>, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let Ok(time) = scheduler::parse_time(&rule.time) else {
+        return vec![];
+    };
+    let (first, last) = match tz {
+        Some(tz) => (from.with_timezone(&tz).date_naive(), to.with_timezone(&tz).date_naive()),
+        None => (from.with_timezone(&chrono::Local).date_naive(), to.with_timezone(&chrono::Local).date_naive()),
+    };
+    let mut times = Vec::new();
+    let mut day = first - ChronoDuration::days(1);
+    while day <= last + ChronoDuration::days(1) {
+        let weekday = day.weekday().num_days_from_sunday() as u8;
+        if rule.days.is_empty() || rule.days.contains(&weekday) {
+            let local = day.and_time(time);
+            let at = match tz {
+                Some(tz) => scheduler::resolve_local(&tz, local).map(|t| t.with_timezone(&Utc)),
+                None => scheduler::resolve_local(&chrono::Local, local).map(|t| t.with_timezone(&Utc)),
+            };
+            times.extend(at);
+        }
+        day += ChronoDuration::days(1);
+    }
+    times
+}
+
+// All transitions in (from, to], oldest first
+fn transitions(schedule: &BridgeSchedule, from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<(DateTime<Utc>, ScheduleAction)> {
+    let tz = timezone(schedule);
+    let mut found: Vec<(DateTime<Utc>, ScheduleAction)> = schedule
+        .rules
+        .iter()
+        .flat_map(|rule| {
+            rule_times(rule, tz, from, to)
+                .into_iter()
+                .filter(|at| *at > from && *at <= to)
+                .map(|at| (at, rule.action))
+        })
+        .collect();
+    found.sort_by_key(|(at, _)| *at);
+    found
+}
+
+fn next_transition(schedule: &BridgeSchedule) -> Option<ScheduledTransition> {
+    let now = Utc::now();
+    let tz = timezone(schedule);
+    // Every rule fires at least once a week
+    let (at, action) = transitions(schedule, now, now + ChronoDuration::days(8)).into_iter().next()?;
+    let at = match tz {
+        Some(tz) => at.with_timezone(&tz).to_rfc3339(),
+        None => at.with_timezone(&chrono::Local).to_rfc3339(),
+    };
+    Some(ScheduledTransition { action, at })
+}
+
+fn validate(schedule: &BridgeSchedule) -> CommandResult<()> {
+    let mut times = Vec::with_capacity(schedule.rules.len());
+    for rule in &schedule.rules {
+        times.push(scheduler::parse_time(&rule.time).map_err(Error::Invalid)?);
+        if let Some(day) = rule.days.iter().find(|d| **d > 6) {
+            return Err(Error::Invalid(format!("Invalid weekday {}, expected 0 (Sunday) to 6 (Saturday)", day)));
+        }
+    }
+    // Compared as times, so "9:00" and "09:00" clash too
+    for (i, rule) in schedule.rules.iter().enumerate() {
+        let clash = schedule.rules[..i].iter().zip(&times).any(|(other, time)| {
+            *time == times[i]
+                && other.action != rule.action
+                && (other.days.is_empty() || rule.days.is_empty() || other.days.iter().any(|d| rule.days.contains(d)))
+        });
+        if clash {
+            return Err(Error::Invalid(format!("The bridge can't be both started and stopped at {}", rule.time)));
+        }
+    }
+    if let Some(ref name) = schedule.timezone {
+        scheduler::parse_timezone(name).map_err(Error::Invalid)?;
+    }
+    Ok(())
+}
+
+async fn apply(app: &AppHandle, action: ScheduleAction) -> Result<(), String> {
+    // Only the local bridge is started and stopped from here
+    if app_settings::active_instance().is_some() {
+        return Ok(());
+    }
+    let state = app.state::<AppState>();
+    let running = state.lock().map(|s| s.is_running).unwrap_or(false);
+    let note = |key: &str| {
+        if let Ok(mut service) = state.lock() {
+            service.logs.info(i18n::t(key));
+        }
+    };
+    match action {
+        ScheduleAction::Start if !running => {
+            note("schedule.starting");
+            service::start_service(app.clone(), app.state(), app.state(), app.state(), None)
+                .await
+                .map(|_| ())
+                .map_err(|failure| failure.message)
+        }
+        ScheduleAction::Stop if running || app.state::<StatusCache>().status().is_some() => {
+            note("schedule.stopping");
+            service::stop_service(app.state(), app.state(), app.state()).await.map(|_| ()).map_err(String::from)
+        }
+        _ => Ok(()),
+    }
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    let mut checked = Utc::now();
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        let now = Utc::now();
+        let due = transitions(&load_schedule(), checked, now).pop();
+        checked = now;
+        let Some((at, action)) = due else {
+            continue;
+        };
+
+        let error = apply(&app, action).await.err();
+        if let Some(ref e) = error {
+            log_forwarding::desktop_error(format!("Scheduled bridge {} failed: {}", action.name(), e));
+        }
+        if let Ok(mut last) = LAST.lock() {
+            *last = Some(AppliedTransition {
+                transition: ScheduledTransition {
+                    action,
+                    at: at.with_timezone(&chrono::Local).to_rfc3339(),
+                },
+                error,
+            });
+        }
+    }
+}
+
+// Replace the rules; an empty list turns the schedule off
+#[tauri::command]
+pub(crate) fn set_schedule(rules: Vec<ScheduleRule>, timezone: Option<String>) -> CommandResult<ScheduleStatus> {
+    let schedule = BridgeSchedule {
+        rules,
+        timezone: timezone.map(|tz| tz.trim().to_string()).filter(|tz| !tz.is_empty()),
+    };
+    validate(&schedule)?;
+    save_schedule(&schedule)?;
+    get_schedule_status()
+}
+
+#[tauri::command]
+pub(crate) fn get_schedule_status() -> CommandResult<ScheduleStatus> {
+    let schedule = load_schedule();
+    Ok(ScheduleStatus {
+        next: next_transition(&schedule),
+        last: LAST.lock().map_err(|e| e.to_string())?.clone(),
+        rules: schedule.rules,
+        timezone: schedule.timezone,
+    })
+}
@@ -0,0 +1,143 @@
+// Announcements to every paired chat.
+//
+// Broadcasting is a two-step operation: the first call resolves the recipients
+// from the bridge's allowlist and returns them with a confirmation token; only a
+// second call carrying that token sends anything, and it sends to exactly the
+// recipients that were previewed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::scheduled_messages::send_chat_message;
+
+const CONFIRMATION_TTL: Duration = Duration::from_secs(5 * 60);
+// Spacing between sends to stay clear of platform rate limits
+const SEND_SPACING: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AllowlistEntry {
+    chat_key: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AllowlistResponse {
+    allowlist: Vec<AllowlistEntry>,
+}
+
+struct PendingBroadcast {
+    text: String,
+    recipients: Vec<String>,
+    created: Instant,
+}
+
+#[derive(Default)]
+pub(crate) struct PendingBroadcasts(Mutex<HashMap<String, PendingBroadcast>>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryResult {
+    chat_key: String,
+    delivered: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "stage")]
+pub enum BroadcastOutcome {
+    // Nothing sent yet; call again with the token to deliver
+    Preview {
+        confirmation_token: String,
+        recipients: Vec<String>,
+    },
+    Delivered {
+        results: Vec<DeliveryResult>,
+    },
+}
+
+// Chat keys are "<channel>:..." (e.g. telegram:bot1:12345)
+fn chat_channel(chat_key: &str) -> &str {
+    chat_key.split(':').next().unwrap_or_default()
+}
+
+pub(crate) async fn fetch_paired_chats(api: &BridgeApiClient) -> Result<Vec<String>, String> {
+    match api.get::<AllowlistResponse>("/allowlist").await {
+        Ok(data) => Ok(data.allowlist.into_iter().map(|e| e.chat_key).collect()),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't list paired chats".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn broadcast_message(
+    api: State<'_, BridgeApiClient>,
+    pending: State<'_, PendingBroadcasts>,
+    text: String,
+    channel_filter: Option<Vec<String>>,
+    confirmation_token: Option<String>,
+) -> Result<BroadcastOutcome, String> {
+    if text.trim().is_empty() {
+        return Err("Broadcast text cannot be empty".to_string());
+    }
+
+    let Some(token) = confirmation_token else {
+        let recipients: Vec<String> = fetch_paired_chats(&api)
+            .await?
+            .into_iter()
+            .filter(|key| {
+                channel_filter
+                    .as_ref()
+                    .map(|channels| channels.iter().any(|c| c == chat_channel(key)))
+                    .unwrap_or(true)
+            })
+            .collect();
+        if recipients.is_empty() {
+            return Err("No paired chats match this broadcast".to_string());
+        }
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let token = format!("{:x}", nanos);
+        let mut pending = pending.0.lock().map_err(|e| e.to_string())?;
+        pending.retain(|_, p| p.created.elapsed() < CONFIRMATION_TTL);
+        pending.insert(
+            token.clone(),
+            PendingBroadcast {
+                text,
+                recipients: recipients.clone(),
+                created: Instant::now(),
+            },
+        );
+        return Ok(BroadcastOutcome::Preview {
+            confirmation_token: token,
+            recipients,
+        });
+    };
+
+    let broadcast = pending
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&token)
+        .filter(|p| p.created.elapsed() < CONFIRMATION_TTL)
+        .ok_or("Broadcast confirmation expired, please review it again")?;
+    if broadcast.text != text {
+        return Err("Broadcast text changed after confirmation, please review it again".to_string());
+    }
+
+    let mut results = Vec::new();
+    for chat_key in broadcast.recipients {
+        let result = send_chat_message(&api, &chat_key, &broadcast.text).await;
+        results.push(DeliveryResult {
+            chat_key,
+            delivered: result.is_ok(),
+            error: result.err(),
+        });
+        tokio::time::sleep(SEND_SPACING).await;
+    }
+
+    Ok(BroadcastOutcome::Delivered { results })
+}
@@ -0,0 +1,240 @@
+// Cost alerts.
+//
+// Compares the estimated spend from the usage rollups against an optional
+// per-session and per-day limit. Crossing a limit notifies once, emits
+// `usage://budget-exceeded`, and with `pauseSessions` on asks the bridge to
+// pause the sessions responsible so an agent stuck in a loop stops spending.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api_client::{ApiError, BridgeApiClient, SessionSummary};
+use crate::config::{get_desktop_data_dir, ConfigStore};
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+use crate::log_forwarding;
+use crate::transcripts::agent_workspaces;
+use crate::usage::{cost_snapshot, CostSnapshot};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub(crate) const BUDGET_EXCEEDED_EVENT: &str = "usage://budget-exceeded";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetSettings {
+    per_session_usd: Option<f64>,
+    per_day_usd: Option<f64>,
+    // Ask the bridge to pause sessions over budget, not just notify
+    pause_sessions: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BudgetState {
+    #[serde(default)]
+    settings: BudgetSettings,
+    // Alerts already raised, so each one fires once
+    #[serde(default)]
+    alerted_day: Option<String>,
+    // Only sessions active today are kept, so this doesn't grow forever
+    #[serde(default)]
+    alerted_sessions: HashSet<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BudgetScope {
+    Session,
+    Day,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BudgetExceeded {
+    scope: BudgetScope,
+    session_id: Option<String>,
+    agent_id: Option<String>,
+    cost_usd: f64,
+    limit_usd: f64,
+    // Bridge sessions that were paused
+    paused: Vec<String>,
+    pause_error: Option<String>,
+}
+
+fn get_state_path() -> PathBuf {
+    get_desktop_data_dir().join("budgets.json")
+}
+
+fn load_state() -> BudgetState {
+    fs::read_to_string(get_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &BudgetState) -> Result<(), String> {
+    let path = get_state_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save budget settings: {}", e))
+}
+
+// Pause the bridge sessions backed by these Claude Code sessions; returns the
+// bridge session ids paused
+async fn pause_sessions(api: &BridgeApiClient, sdk_session_ids: &[String]) -> Result<Vec<String>, String> {
+    let body: serde_json::Value = api.get("/sessions").await?;
+    let sessions: Vec<SessionSummary> = serde_json::from_value(body["sessions"].clone()).unwrap_or_default();
+
+    let mut paused = Vec::new();
+    for session in sessions {
+        let over = session.sdk_session_id.as_ref().is_some_and(|id| sdk_session_ids.contains(id));
+        if !over || session.status == "paused" {
+            continue;
+        }
+        match api.post(&format!("/sessions/{}/pause", session.id), None).await {
+            Ok(_) => paused.push(session.id),
+            Err(ApiError::Unsupported(_)) => return Err("This bridge version can't pause sessions".to_string()),
+            Err(e) => return Err(format!("Failed to pause session {}: {}", session.id, e)),
+        }
+    }
+    Ok(paused)
+}
+
+async fn raise(app: &AppHandle, settings: &BudgetSettings, mut alert: BudgetExceeded, sdk_session_ids: Vec<String>) {
+    if settings.pause_sessions {
+        match pause_sessions(&app.state::<BridgeApiClient>(), &sdk_session_ids).await {
+            Ok(paused) => alert.paused = paused,
+            Err(e) => alert.pause_error = Some(e),
+        }
+    }
+
+    let cost = format!("${:.2}", alert.cost_usd);
+    let limit = format!("${:.2}", alert.limit_usd);
+    let body = match alert.scope {
+        BudgetScope::Session => i18n::tr(
+            "budget.session_body",
+            &[
+                ("agent", alert.agent_id.as_deref().unwrap_or("?")),
+                ("cost", &cost),
+                ("limit", &limit),
+            ],
+        ),
+        BudgetScope::Day => i18n::tr("budget.day_body", &[("cost", &cost), ("limit", &limit)]),
+    };
+    let body = if alert.paused.is_empty() {
+        body
+    } else {
+        format!("{} {}", body, i18n::tr("budget.paused", &[("count", &alert.paused.len().to_string())]))
+    };
+    notifications::notify(app, NotificationCategory::Budget, &i18n::t("budget.title"), &body);
+    let _ = app.emit(BUDGET_EXCEEDED_EVENT, alert);
+}
+
+async fn check(app: &AppHandle) -> Result<(), String> {
+    let mut state = load_state();
+    let settings = state.settings.clone();
+    if settings.per_session_usd.is_none() && settings.per_day_usd.is_none() {
+        return Ok(());
+    }
+
+    let workspaces = app
+        .state::<ConfigStore>()
+        .read()?
+        .map(|c| agent_workspaces(&c))
+        .unwrap_or_default();
+    let CostSnapshot {
+        today_usd,
+        today_sessions,
+        sessions,
+    } = tauri::async_runtime::spawn_blocking(move || cost_snapshot(&workspaces))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let alerted = state.alerted_sessions.len();
+    state.alerted_sessions.retain(|id| today_sessions.contains(id));
+    let pruned = state.alerted_sessions.len() != alerted;
+
+    let mut alerts = Vec::new();
+    if let Some(limit) = settings.per_session_usd {
+        // Only sessions still in use; old ones over a new limit aren't news
+        for (session_id, (agent_id, cost)) in sessions {
+            let active = today_sessions.contains(&session_id);
+            if active && cost >= limit && state.alerted_sessions.insert(session_id.clone()) {
+                let alert = BudgetExceeded {
+                    scope: BudgetScope::Session,
+                    session_id: Some(session_id.clone()),
+                    agent_id,
+                    cost_usd: cost,
+                    limit_usd: limit,
+                    paused: vec![],
+                    pause_error: None,
+                };
+                alerts.push((alert, vec![session_id]));
+            }
+        }
+    }
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    if let Some(limit) = settings.per_day_usd {
+        if today_usd >= limit && state.alerted_day.as_deref() != Some(today.as_str()) {
+            state.alerted_day = Some(today);
+            let alert = BudgetExceeded {
+                scope: BudgetScope::Day,
+                session_id: None,
+                agent_id: None,
+                cost_usd: today_usd,
+                limit_usd: limit,
+                paused: vec![],
+                pause_error: None,
+            };
+            // Every session that spent today shares the blame
+            alerts.push((alert, today_sessions));
+        }
+    }
+
+    if alerts.is_empty() {
+        return if pruned { save_state(&state) } else { Ok(()) };
+    }
+    // Record first so a failing pause doesn't alert again on every check
+    save_state(&state)?;
+    for (alert, sdk_session_ids) in alerts {
+        raise(app, &settings, alert, sdk_session_ids).await;
+    }
+    Ok(())
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    loop {
+        if let Err(e) = check(&app).await {
+            log_forwarding::desktop_error(format!("Budget check failed: {}", e));
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_budget_settings() -> BudgetSettings {
+    load_state().settings
+}
+
+#[tauri::command]
+pub(crate) fn set_budget_settings(settings: BudgetSettings) -> Result<bool, String> {
+    let invalid = |limit: Option<f64>| limit.is_some_and(|l| !l.is_finite() || l <= 0.0);
+    if invalid(settings.per_session_usd) || invalid(settings.per_day_usd) {
+        return Err("Budget limits must be positive amounts".to_string());
+    }
+    let mut state = load_state();
+    // New limits get a fresh look at sessions that were already over the old ones
+    state.alerted_sessions.clear();
+    state.alerted_day = None;
+    state.settings = settings;
+    save_state(&state)?;
+    Ok(true)
+}
@@ -0,0 +1,183 @@
+// Installing and updating the ccb package.
+//
+// Without ccb on the PATH the app can't start anything, so rather than only
+// telling people to run npm themselves it runs `npm install -g` for them, with
+// the extended PATH and proxy settings the bridge gets. npm's output goes to
+// the log buffer line by line as it arrives, so a slow install shows progress
+// in the logs view, and the installed version is reported once it's done.
+// Installs and updates run as cancellable tasks; cancelling one kills npm.
+
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+
+use crate::error::{CommandResult, Error};
+use crate::runtime_info::{find_executable, probe_output};
+use crate::logs::{self, LogLevel};
+use crate::service::{get_extended_path, AppState};
+use crate::tasks::spawn_task;
+use crate::{ccb_updates, crash_reports, http};
+
+pub(crate) const CCB_PACKAGE: &str = "claude-code-bridge";
+const INSTALL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+// Lines of npm's output included when an install fails
+const INSTALL_ERROR_LINES: usize = 10;
+
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+// Clears INSTALLING however the install ends, including a cancelled task
+struct InstallGuard;
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        INSTALLING.store(false, Ordering::Relaxed);
+    }
+}
+
+fn log_line(state: &AppState, line: String) {
+    if let Ok(mut service) = state.lock() {
+        service.logs.info(line);
+    }
+}
+
+// npm's warnings and errors ("npm warn ...", "npm error ...") go to stderr
+fn log_npm_line(state: &AppState, line: &str, stderr: bool) {
+    let Ok(mut service) = state.lock() else {
+        return;
+    };
+    let message = format!("npm: {}", line);
+    match stderr.then(|| logs::detect_level(line)) {
+        Some(LogLevel::Error) => service.logs.error(message),
+        Some(LogLevel::Warn) => service.logs.warn(message),
+        _ => service.logs.info(message),
+    }
+}
+
+async fn forward_lines<R: AsyncRead + Unpin>(pipe: R, stderr: bool, tx: mpsc::UnboundedSender<(bool, String)>) {
+    let mut lines = BufReader::new(pipe).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if !line.trim().is_empty() && tx.send((stderr, line)).is_err() {
+            return;
+        }
+    }
+}
+
+// Run npm to completion, copying its output to the logs; returns the last few
+// lines, which is where npm puts its error
+async fn run_npm(state: &AppState, args: &[&str]) -> CommandResult<(bool, Vec<String>)> {
+    let npm = find_executable("npm").ok_or_else(|| Error::Invalid("npm not found. Install Node.js first.".to_string()))?;
+    let mut child = Command::new(npm)
+        .args(args)
+        .env("PATH", get_extended_path())
+        .envs(http::proxy_env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("Failed to run npm: {}", e))?;
+
+    // Both pipes feed one channel, which closes when npm closes them
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        tauri::async_runtime::spawn(forward_lines(stdout, false, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tauri::async_runtime::spawn(forward_lines(stderr, true, tx));
+    }
+
+    let mut tail = VecDeque::new();
+    while let Some((stderr, line)) = rx.recv().await {
+        log_npm_line(state, &line, stderr);
+        tail.push_back(line);
+        if tail.len() > INSTALL_ERROR_LINES {
+            tail.pop_front();
+        }
+    }
+
+    let status = child.wait().await.map_err(|e| format!("Failed to run npm: {}", e))?;
+    Ok((status.success(), tail.into()))
+}
+
+// `npm install -g <package>`, logged as it goes
+pub(crate) async fn npm_install_global(state: &AppState, package: &str) -> CommandResult<String> {
+    if INSTALLING.swap(true, Ordering::Relaxed) {
+        return Err(Error::Invalid("An install is already running".to_string()));
+    }
+    let _guard = InstallGuard;
+    log_line(state, format!("Installing {}", package));
+    let result = tokio::time::timeout(INSTALL_TIMEOUT, run_npm(state, &["install", "-g", package])).await;
+
+    let (success, tail) = result.map_err(|_| format!("Installing {} timed out", package))??;
+    if !success {
+        if let Ok(mut service) = state.lock() {
+            service.logs.error(format!("Installing {} failed", package));
+        }
+        return Err(Error::Other(format!("npm install failed:\n{}", tail.join("\n"))));
+    }
+    log_line(state, format!("Installed {}", package));
+    Ok(format!("Installed {}", package))
+}
+
+// The installed version, for reporting after an install or update
+async fn installed_version(state: &AppState) -> CommandResult<String> {
+    crash_reports::detect_ccb_version().await;
+    let version = get_ccb_version()
+        .await
+        .ok_or_else(|| Error::Other("ccb was installed but isn't on the PATH".to_string()))?;
+    log_line(state, format!("ccb {} is installed", version));
+    ccb_updates::set_installed(&version);
+    Ok(version)
+}
+
+#[tauri::command]
+pub(crate) async fn get_ccb_version() -> Option<String> {
+    let ccb = find_executable("ccb")?;
+    probe_output(&ccb, &["--version"]).await
+}
+
+async fn install(state: AppState) -> CommandResult<String> {
+    npm_install_global(&state, CCB_PACKAGE).await?;
+    installed_version(&state).await
+}
+
+// A running bridge keeps the old version until it's restarted
+async fn update(state: AppState) -> CommandResult<String> {
+    let before = get_ccb_version().await;
+    npm_install_global(&state, &format!("{}@latest", CCB_PACKAGE)).await?;
+    let version = installed_version(&state).await?;
+    let running = state.lock().map(|s| s.is_running).unwrap_or(false);
+    if running && before.as_deref() != Some(version.as_str()) {
+        log_line(&state, "Restart the bridge to run the new version".to_string());
+    }
+    Ok(version)
+}
+
+// Install as a task whose result is the installed version; returns the task id
+#[tauri::command]
+pub(crate) fn install_ccb(app: AppHandle) -> CommandResult<String> {
+    let state = app.state::<AppState>().inner().clone();
+    let task_id = spawn_task(&app, "ccb_install", "Install ccb", move |task| async move {
+        task.progress(None, format!("Installing {}", CCB_PACKAGE));
+        let version = install(state).await.map_err(|e| e.to_string())?;
+        Ok(serde_json::Value::String(version))
+    })?;
+    Ok(task_id)
+}
+
+// Update as a task whose result is the version now installed; returns the task id
+#[tauri::command]
+pub(crate) fn update_ccb(app: AppHandle) -> CommandResult<String> {
+    let state = app.state::<AppState>().inner().clone();
+    let task_id = spawn_task(&app, "ccb_update", "Update ccb", move |task| async move {
+        task.progress(None, format!("Updating {}", CCB_PACKAGE));
+        let version = update(state).await.map_err(|e| e.to_string())?;
+        Ok(serde_json::Value::String(version))
+    })?;
+    Ok(task_id)
+}
@@ -0,0 +1,171 @@
+// Checking for newer ccb releases.
+//
+// Every few hours the npm registry is asked for the latest claude-code-bridge
+// release. When it's newer than the installed ccb, `ccb://update-available`
+// is emitted once per release and the tray icon gets a badge until ccb is
+// updated, which `update_ccb` does in one go. `get_bridge_version` reports the
+// installed version, the one the running bridge reports where it can, and the
+// latest release.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::ccb_install::{get_ccb_version, CCB_PACKAGE};
+use crate::error::CommandResult;
+use crate::{http, log_forwarding, node_runtime, tray};
+
+pub(crate) const CCB_UPDATE_AVAILABLE_EVENT: &str = "ccb://update-available";
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+// Let startup settle before the first check
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+const REGISTRY_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct UpdateState {
+    installed: Option<String>,
+    latest: Option<String>,
+    checked_at: Option<DateTime<Local>>,
+    // Release the event was last emitted for
+    notified: Option<String>,
+}
+
+static STATE: Mutex<UpdateState> = Mutex::new(UpdateState {
+    installed: None,
+    latest: None,
+    checked_at: None,
+    notified: None,
+});
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeVersion {
+    // `ccb --version`; None when ccb isn't installed
+    installed: Option<String>,
+    // What the running bridge reports; None if it isn't running or can't say
+    running: Option<String>,
+    // Latest release on npm, as of `checked_at`
+    latest: Option<String>,
+    update_available: bool,
+    checked_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RegistryRelease {
+    version: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+// Whether `latest` is a higher x.y.z than `installed`
+fn is_newer(latest: &str, installed: &str) -> bool {
+    let parse = |v: &str| node_runtime::parse_version(v.split(['-', '+']).next().unwrap_or(v));
+    match (parse(latest), parse(installed)) {
+        (Some(latest), Some(installed)) => latest > installed,
+        _ => false,
+    }
+}
+
+// The newer release, if there is one
+pub(crate) fn update_available() -> Option<String> {
+    let state = STATE.lock().ok()?;
+    let (latest, installed) = (state.latest.as_deref()?, state.installed.as_deref()?);
+    is_newer(latest, installed).then(|| latest.to_string())
+}
+
+// Called after ccb was installed or updated from the app
+pub(crate) fn set_installed(version: &str) {
+    if let Ok(mut state) = STATE.lock() {
+        state.installed = Some(version.to_string());
+    }
+}
+
+async fn fetch_latest() -> Result<String, String> {
+    let response = http::client()
+        .get(format!("https://registry.npmjs.org/{}/latest", CCB_PACKAGE))
+        .timeout(REGISTRY_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("npm registry is not reachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("npm registry returned {}", response.status()));
+    }
+    let release: RegistryRelease = response.json().await.map_err(|e| e.to_string())?;
+    Ok(release.version)
+}
+
+async fn check(app: &AppHandle) -> Result<(), String> {
+    let installed = get_ccb_version().await;
+    let latest = fetch_latest().await?;
+
+    let notify = {
+        let mut state = STATE.lock().map_err(|e| e.to_string())?;
+        state.installed = installed;
+        state.latest = Some(latest.clone());
+        state.checked_at = Some(Local::now());
+        let newer = state.installed.as_deref().is_some_and(|installed| is_newer(&latest, installed));
+        let notify = newer && state.notified.as_deref() != Some(latest.as_str());
+        if notify {
+            state.notified = Some(latest.clone());
+        }
+        notify
+    };
+    if notify {
+        let _ = app.emit(CCB_UPDATE_AVAILABLE_EVENT, version_info(None));
+    }
+    tray::update_tray_status(app);
+    Ok(())
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    tokio::time::sleep(FIRST_CHECK_DELAY).await;
+    loop {
+        if let Err(e) = check(&app).await {
+            log_forwarding::desktop_error(format!("Checking for ccb updates failed: {}", e));
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+fn version_info(running: Option<String>) -> BridgeVersion {
+    let state = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    BridgeVersion {
+        installed: state.installed.clone(),
+        running,
+        latest: state.latest.clone(),
+        update_available: match (state.latest.as_deref(), state.installed.as_deref()) {
+            (Some(latest), Some(installed)) => is_newer(latest, installed),
+            _ => false,
+        },
+        checked_at: state.checked_at.map(|t| t.to_rfc3339()),
+    }
+}
+
+// With `check`, asks the registry now rather than using the last result
+#[tauri::command]
+pub(crate) async fn get_bridge_version(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    check: Option<bool>,
+) -> CommandResult<BridgeVersion> {
+    if check == Some(true) {
+        self::check(&app).await?;
+    } else {
+        let installed = get_ccb_version().await;
+        if let Ok(mut state) = STATE.lock() {
+            state.installed = installed;
+        }
+    }
+    let running = match api.get::<VersionResponse>("/version").await {
+        Ok(response) => Some(response.version),
+        Err(ApiError::Unsupported(_)) | Err(ApiError::Unreachable(_)) | Err(ApiError::Timeout) => None,
+        Err(e) => return Err(e.into()),
+    };
+    Ok(version_info(running))
+}
@@ -0,0 +1,471 @@
+// The bridge's config.json.
+//
+// All reads and writes go through `ConfigStore`, which caches the parsed file
+// and applies the desktop's invariants on every write. The bot settings screen
+// edits the file through `read_config`/`save_config`, which keep bot tokens in
+// the keychain when the user opted in. A bot can be switched off with
+// `set_bot_enabled` without losing its token; the running bridge is told right
+// away when it supports that, else the change applies on its next start.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use tauri::{AppHandle, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::error::{CommandResult, Error};
+use crate::poller::StatusCache;
+use crate::{config_reload, config_validation, demo, http, i18n, keychain, pairing_policy};
+
+// ~/.ccb, or the demo's temp dir in demo mode
+pub(crate) fn get_ccb_dir() -> PathBuf {
+    if let Some(dir) = demo::ccb_dir() {
+        return dir;
+    }
+    let home = dirs::home_dir().expect("Could not find home directory");
+    home.join(".ccb")
+}
+
+pub(crate) fn get_config_path() -> PathBuf {
+    get_ccb_dir().join("config.json")
+}
+
+// Parsed config.json shared by all config commands. Readers are served from
+// memory; the cache is dropped on every write and whenever the file's mtime
+// changes underneath us (e.g. the user edits it by hand or `ccb setup` runs).
+#[derive(Default)]
+pub(crate) struct ConfigStore {
+    cache: Mutex<Option<CachedConfig>>,
+    // Set once the app is up, so every write reaches the running bridge
+    app: OnceLock<AppHandle>,
+}
+
+struct CachedConfig {
+    // None when config.json does not exist
+    value: Option<serde_json::Value>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigStore {
+    pub(crate) fn read(&self) -> CommandResult<Option<serde_json::Value>> {
+        let config_path = get_config_path();
+        let modified = fs::metadata(&config_path).and_then(|m| m.modified()).ok();
+
+        let mut cache = self.cache.lock().map_err(|e| e.to_string())?;
+        if let Some(ref cached) = *cache {
+            if cached.modified == modified {
+                return Ok(cached.value.clone());
+            }
+        }
+
+        let value = if config_path.exists() {
+            let content = fs::read_to_string(&config_path)
+                .map_err(|e| Error::Config(format!("Failed to read config: {}", e)))?;
+            let config: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| Error::Config(format!("Failed to parse config: {}", e)))?;
+            Some(config)
+        } else {
+            None
+        };
+
+        *cache = Some(CachedConfig { value: value.clone(), modified });
+        Ok(value)
+    }
+
+    pub(crate) fn write(&self, config: &serde_json::Value) -> CommandResult<()> {
+        let config_path = get_config_path();
+        // Every write goes through here, so sensitive agents can't be opened up by accident
+        pairing_policy::check(config).map_err(Error::Invalid)?;
+        let config_str = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+        fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))?;
+        self.invalidate();
+        // Any write may have touched the proxy section
+        http::configure(config);
+        if let Some(app) = self.app.get() {
+            config_reload::after_write(app);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn attach(&self, app: &AppHandle) {
+        let _ = self.app.set(app.clone());
+    }
+
+    pub(crate) fn invalidate(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            *cache = None;
+        }
+    }
+}
+
+// Expand a leading `~` so paths typed by users resolve like they would in a shell
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') || rest.starts_with('\\') => {
+            home.join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+// Data owned by the desktop app itself (rollups, caches), kept apart from the
+// bridge's own files in ~/.ccb
+pub(crate) fn get_desktop_data_dir() -> PathBuf {
+    get_ccb_dir().join("desktop")
+}
+
+#[tauri::command]
+pub(crate) fn check_config(store: State<'_, ConfigStore>) -> bool {
+    matches!(store.read(), Ok(Some(_)))
+}
+
+// Response structure for reading config
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigResponse {
+    telegram_bots: Vec<BotConfig>,
+    discord_bots: Vec<BotConfig>,
+}
+
+#[tauri::command]
+pub(crate) fn read_config(store: State<'_, ConfigStore>) -> CommandResult<ConfigResponse> {
+    let Some(config) = store.read()? else {
+        return Ok(ConfigResponse::default());
+    };
+
+    let mut response = ConfigResponse::default();
+
+    // Extract Telegram bots
+    if let Some(telegram) = config.get("channels").and_then(|c| c.get("telegram")) {
+        if let Some(bots) = telegram.get("bots").and_then(|b| b.as_array()) {
+            for bot in bots {
+                let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main").to_string();
+                let token = keychain::resolve(bot.get("botToken").and_then(|v| v.as_str()).unwrap_or(""));
+                let agent_id = bot.get("agentId").and_then(|v| v.as_str()).map(|s| s.to_string());
+                let enabled = Some(bot.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true));
+                response.telegram_bots.push(BotConfig { id, token, agent_id, enabled });
+            }
+        }
+    }
+
+    // Extract Discord bots (Discord uses "token" not "botToken")
+    if let Some(discord) = config.get("channels").and_then(|c| c.get("discord")) {
+        if let Some(bots) = discord.get("bots").and_then(|b| b.as_array()) {
+            for bot in bots {
+                let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main").to_string();
+                let token = keychain::resolve(bot.get("token").and_then(|v| v.as_str()).unwrap_or(""));
+                let agent_id = bot.get("agentId").and_then(|v| v.as_str()).map(|s| s.to_string());
+                let enabled = Some(bot.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true));
+                response.discord_bots.push(BotConfig { id, token, agent_id, enabled });
+            }
+        }
+    }
+
+    Ok(response)
+}
+
+// Bot configuration for multi-bot support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotConfig {
+    pub(crate) id: String,
+    pub(crate) token: String,
+    #[serde(rename = "agentId")]
+    pub(crate) agent_id: Option<String>,
+    // None keeps what config.json has
+    #[serde(default)]
+    pub(crate) enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotToggle {
+    channel: String,
+    bot_id: String,
+    enabled: bool,
+    // The running bridge switched the bot; otherwise it happens on the next start
+    applied: bool,
+    // Why the bridge couldn't apply it
+    detail: Option<String>,
+}
+
+fn find_bot<'a>(config: &'a serde_json::Value, channel: &str, id: &str) -> Option<&'a serde_json::Value> {
+    config
+        .pointer(&format!("/channels/{}/bots", channel))
+        .and_then(|b| b.as_array())?
+        .iter()
+        .find(|b| b.get("id").and_then(|v| v.as_str()) == Some(id))
+}
+
+// A bot saved from the bots screen keeps its on/off state unless the screen sets it
+fn bot_enabled(config: &serde_json::Value, channel: &str, bot: &BotConfig) -> bool {
+    bot.enabled.unwrap_or_else(|| {
+        find_bot(config, channel, &bot.id)
+            .and_then(|b| b.get("enabled"))
+            .and_then(|e| e.as_bool())
+            .unwrap_or(true)
+    })
+}
+
+// Point a channel section at `bots`, keeping its other keys such as webhook
+// mode; the legacy single token gives way to the list
+fn set_channel_bots(config: &mut serde_json::Value, channel: &str, legacy_key: &str, bots: Vec<serde_json::Value>) {
+    if bots.is_empty() {
+        if let Some(channels) = config["channels"].as_object_mut() {
+            channels.remove(channel);
+        }
+        return;
+    }
+    let section = &mut config["channels"][channel];
+    if !section.is_object() {
+        *section = serde_json::json!({});
+    }
+    section["enabled"] = serde_json::json!(true);
+    section["bots"] = serde_json::Value::Array(bots);
+    if let Some(section) = section.as_object_mut() {
+        section.remove(legacy_key);
+    }
+}
+
+// A legacy single token is the bot "main"
+fn legacy_bots(token: Option<String>) -> Vec<BotConfig> {
+    token
+        .map(|token| BotConfig {
+            id: "main".to_string(),
+            token,
+            agent_id: None,
+            enabled: None,
+        })
+        .into_iter()
+        .collect()
+}
+
+// The bots screen's edits, each merged into the bot of the same id so keys
+// the screen doesn't show, such as Discord scopes, are kept
+fn edited_bots(config: &serde_json::Value, channel: &str, token_key: &str, bots: &[BotConfig]) -> Vec<serde_json::Value> {
+    bots.iter()
+        .filter(|b| !b.token.is_empty())
+        .map(|b| {
+            let mut bot = find_bot(config, channel, &b.id)
+                .and_then(|existing| existing.as_object())
+                .cloned()
+                .unwrap_or_else(|| {
+                    let mut bot = serde_json::Map::new();
+                    bot.insert("id".to_string(), serde_json::json!(b.id));
+                    bot.insert("dmPolicy".to_string(), serde_json::json!("pairing"));
+                    bot
+                });
+            bot.insert(token_key.to_string(), serde_json::json!(b.token));
+            if bot_enabled(config, channel, b) {
+                bot.remove("enabled");
+            } else {
+                bot.insert("enabled".to_string(), serde_json::json!(false));
+            }
+            match b.agent_id.as_deref().filter(|id| !id.is_empty()) {
+                Some(agent_id) => bot.insert("agentId".to_string(), serde_json::json!(agent_id)),
+                None => bot.remove("agentId"),
+            };
+            serde_json::Value::Object(bot)
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub(crate) fn save_config(
+    store: State<'_, ConfigStore>,
+    telegram_bots: Option<Vec<BotConfig>>,
+    discord_bots: Option<Vec<BotConfig>>,
+    // Legacy single-token support for backward compatibility
+    telegram_token: Option<String>,
+    discord_token: Option<String>,
+) -> CommandResult<bool> {
+    let config_path = get_config_path();
+    let config_dir = config_path.parent().unwrap();
+
+    // Create directory if it doesn't exist
+    fs::create_dir_all(config_dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+
+    // Load existing config or create default
+    let mut config = match store.read()? {
+        Some(config) => config,
+        None => {
+            let home_dir = dirs::home_dir().unwrap().to_string_lossy().to_string();
+            serde_json::json!({
+                "agents": {
+                    "default": "claude",
+                    "list": [{
+                        "id": "claude",
+                        "name": "Claude",
+                        "workspace": home_dir
+                    }]
+                },
+                "channels": {}
+            })
+        }
+    };
+
+    // Ensure channels object exists
+    if config.get("channels").is_none() {
+        config["channels"] = serde_json::json!({});
+    }
+
+    // Handle Telegram bots - only update if provided
+    if telegram_bots.is_some() || telegram_token.is_some() {
+        let bots = telegram_bots.unwrap_or_else(|| legacy_bots(telegram_token));
+        let tg_bots = edited_bots(&config, "telegram", "botToken", &bots);
+        set_channel_bots(&mut config, "telegram", "botToken", tg_bots);
+    }
+
+    // Handle Discord bots - only update if provided
+    if discord_bots.is_some() || discord_token.is_some() {
+        let bots = discord_bots.unwrap_or_else(|| legacy_bots(discord_token));
+        let dc_bots = edited_bots(&config, "discord", "token", &bots);
+        set_channel_bots(&mut config, "discord", "token", dc_bots);
+    }
+
+    // Ensure agents exist with at least a default
+    if config.get("agents").is_none() || config["agents"].get("list").map(|l| l.as_array().map(|a| a.is_empty()).unwrap_or(true)).unwrap_or(true) {
+        let home_dir = dirs::home_dir().unwrap().to_string_lossy().to_string();
+        config["agents"] = serde_json::json!({
+            "default": "claude",
+            "list": [{
+                "id": "claude",
+                "name": "Claude",
+                "workspace": home_dir
+            }]
+        });
+    }
+
+    // Before the tokens move to the keychain, so their format can be checked;
+    // only the bot sections are edited here
+    let scopes = ["channels.telegram", "channels.discord"].map(String::from);
+    config_validation::ensure_valid_in(&config, &scopes)?;
+    keychain::protect_tokens(&mut config)?;
+
+    // Write config file
+    store.write(&config)?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn set_bot_enabled(
+    store: State<'_, ConfigStore>,
+    api: State<'_, BridgeApiClient>,
+    cache: State<'_, StatusCache>,
+    channel: String,
+    bot_id: String,
+    enabled: bool,
+) -> CommandResult<BotToggle> {
+    let token_key = match channel.as_str() {
+        "telegram" => "botToken",
+        "discord" => "token",
+        _ => return Err(Error::Invalid(format!("Unknown channel '{}'", channel))),
+    };
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    let section = config
+        .get_mut("channels")
+        .and_then(|c| c.get_mut(&channel))
+        .and_then(|s| s.as_object_mut())
+        .ok_or_else(|| Error::Invalid(format!("No {} bots are configured", channel)))?;
+
+    // A legacy single-token setup becomes the bot "main" so it can carry the flag
+    if !section.contains_key("bots") {
+        if let Some(token) = section.remove(token_key) {
+            section.insert("bots".to_string(), serde_json::json!([{ "id": "main", token_key: token }]));
+        }
+    }
+    let bot = section
+        .get_mut("bots")
+        .and_then(|b| b.as_array_mut())
+        .and_then(|bots| bots.iter_mut().find(|b| b.get("id").and_then(|v| v.as_str()) == Some(&bot_id)))
+        .and_then(|b| b.as_object_mut())
+        .ok_or_else(|| Error::Invalid(format!("Bot '{}' not found on {}", bot_id, channel)))?;
+    if enabled {
+        bot.remove("enabled");
+    } else {
+        bot.insert("enabled".to_string(), serde_json::json!(false));
+    }
+    store.write(&config)?;
+
+    let mut toggle = BotToggle {
+        channel: channel.clone(),
+        bot_id: bot_id.clone(),
+        enabled,
+        applied: false,
+        detail: None,
+    };
+    if cache.status().is_none() {
+        return Ok(toggle);
+    }
+    let action = if enabled { "enable" } else { "disable" };
+    match api.post(&format!("/bots/{}/{}/{}", channel, bot_id, action), None).await {
+        Ok(_) => {
+            toggle.applied = true;
+            cache.request_refresh();
+        }
+        Err(ApiError::Unsupported(_)) => {
+            toggle.detail = Some("This bridge version can't switch bots while running; restart it to apply".to_string())
+        }
+        Err(e) => toggle.detail = Some(format!("Failed to switch the bot in the running bridge: {}", e)),
+    }
+    Ok(toggle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn bot(id: &str, token: &str, agent_id: Option<&str>) -> BotConfig {
+        BotConfig {
+            id: id.to_string(),
+            token: token.to_string(),
+            agent_id: agent_id.map(String::from),
+            enabled: None,
+        }
+    }
+
+    #[test]
+    fn saved_bots_keep_keys_the_screen_does_not_edit() {
+        let config = json!({"channels": {"discord": {"bots": [
+            {"id": "main", "token": "old", "dmPolicy": "open", "scope": {"guilds": ["1"], "channels": []}}
+        ]}}});
+        let bots = edited_bots(&config, "discord", "token", &[bot("main", "new", Some("coder")), bot("second", "t2", None)]);
+        assert_eq!(
+            bots,
+            vec![
+                json!({"id": "main", "token": "new", "dmPolicy": "open", "agentId": "coder", "scope": {"guilds": ["1"], "channels": []}}),
+                json!({"id": "second", "token": "t2", "dmPolicy": "pairing"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn saving_bots_keeps_group_access() {
+        let groups = json!({"policy": "allowlist", "allow": ["-100"], "deny": []});
+        let mut config = json!({"channels": {"telegram": {
+            "groups": groups,
+            "bots": [{"id": "main", "botToken": "t", "groups": groups}]
+        }}});
+        let bots = edited_bots(&config, "telegram", "botToken", &[bot("main", "t2", None)]);
+        set_channel_bots(&mut config, "telegram", "botToken", bots);
+        assert_eq!(config["channels"]["telegram"]["groups"], groups);
+        assert_eq!(config["channels"]["telegram"]["bots"][0]["groups"], groups);
+        assert_eq!(config["channels"]["telegram"]["bots"][0]["botToken"], "t2");
+    }
+
+    #[test]
+    fn saved_bots_apply_cleared_fields() {
+        let config = json!({"channels": {"telegram": {"bots": [
+            {"id": "main", "botToken": "t", "agentId": "coder", "enabled": false}
+        ]}}});
+        let mut edit = bot("main", "t", None);
+        edit.enabled = Some(true);
+        assert_eq!(
+            edited_bots(&config, "telegram", "botToken", &[edit, bot("empty", "", None)]),
+            vec![json!({"id": "main", "botToken": "t"})]
+        );
+    }
+}
@@ -0,0 +1,120 @@
+// Drift between the running bridge's config and config.json on disk.
+//
+// The bridge reads its config once at startup, so desktop edits only apply
+// after a restart or a reload (see config_reload). Bridges that report `configHash` in /status (SHA-256 of the
+// file as loaded) are compared against the file directly; otherwise we fall
+// back to the hash recorded when the desktop itself launched the bridge. When
+// the two start to differ a `bridge://config-drift` event prompts a restart.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter};
+
+use crate::api_client::BridgeStatus;
+use crate::config::get_config_path;
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+
+pub(crate) const CONFIG_DRIFT_EVENT: &str = "bridge://config-drift";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashSource {
+    // Reported by the bridge in /status
+    Bridge,
+    // Recorded when the desktop started the bridge
+    Launch,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDrift {
+    config_drift: bool,
+    running_hash: Option<String>,
+    disk_hash: Option<String>,
+    source: Option<HashSource>,
+}
+
+// Hash of the config the desktop last launched the bridge with
+static LAUNCH_HASH: Mutex<Option<String>> = Mutex::new(None);
+// (modified time, hash) so the file is only re-hashed when it changes
+static DISK_HASH: Mutex<Option<(Option<SystemTime>, Option<String>)>> = Mutex::new(None);
+static LAST: Mutex<Option<ConfigDrift>> = Mutex::new(None);
+
+fn disk_hash() -> Option<String> {
+    let path = get_config_path();
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+    let mut cached = DISK_HASH.lock().ok()?;
+    if let Some((cached_modified, ref hash)) = *cached {
+        if cached_modified == modified {
+            return hash.clone();
+        }
+    }
+    let hash = fs::read(&path).ok().map(|bytes| format!("{:x}", Sha256::digest(bytes)));
+    *cached = Some((modified, hash.clone()));
+    hash
+}
+
+// Called right before the desktop spawns the bridge, and once it has reloaded
+// the config
+pub(crate) fn remember_launch_config() {
+    if let Ok(mut launch) = LAUNCH_HASH.lock() {
+        *launch = disk_hash();
+    }
+}
+
+// Whether the bridge runs with config.json as it is on disk; None for bridges
+// that don't report a hash
+pub(crate) fn running_matches_disk(status: &BridgeStatus) -> Option<bool> {
+    let hash = status.config_hash.as_ref()?;
+    Some(disk_hash().is_some_and(|disk| disk == hash.to_lowercase()))
+}
+
+fn compute(status: Option<&BridgeStatus>) -> ConfigDrift {
+    let Some(status) = status else {
+        return ConfigDrift::default();
+    };
+    let (running_hash, source) = match status.config_hash {
+        Some(ref hash) => (Some(hash.to_lowercase()), Some(HashSource::Bridge)),
+        None => match LAUNCH_HASH.lock().ok().and_then(|h| h.clone()) {
+            Some(hash) => (Some(hash), Some(HashSource::Launch)),
+            None => (None, None),
+        },
+    };
+    let disk_hash = disk_hash();
+    ConfigDrift {
+        config_drift: running_hash.is_some() && disk_hash.is_some() && running_hash != disk_hash,
+        running_hash,
+        disk_hash,
+        source,
+    }
+}
+
+// Called by the poller after every poll
+pub(crate) fn check(app: &AppHandle, status: Option<&BridgeStatus>) {
+    let drift = compute(status);
+    let Ok(mut last) = LAST.lock() else {
+        return;
+    };
+    let was_drifting = last.as_ref().is_some_and(|d| d.config_drift);
+    if drift.config_drift != was_drifting {
+        if drift.config_drift {
+            notifications::record(
+                app,
+                NotificationCategory::Config,
+                &i18n::t("notify.config_drift_title"),
+                &i18n::t("notify.config_drift_body"),
+            );
+        }
+        let _ = app.emit(CONFIG_DRIFT_EVENT, &drift);
+    }
+    *last = Some(drift);
+}
+
+#[tauri::command]
+pub(crate) fn get_config_drift() -> Result<ConfigDrift, String> {
+    Ok(LAST.lock().map_err(|e| e.to_string())?.clone().unwrap_or_default())
+}
@@ -0,0 +1,162 @@
+// Applying config.json to the running bridge.
+//
+// The bridge reads its config at startup, so edits made in the app used to
+// wait for a restart. After every write through `ConfigStore` the running
+// bridge is asked to reload: through POST /reload on the Control API, or, for
+// bridges without that route, with SIGHUP to the child the app started. Node
+// exits on a SIGHUP it doesn't handle, so the signal is only sent when the
+// process is seen catching it, which only Linux lets us check. The outcome
+// goes out as `bridge://config-reloaded`, including whether some of the
+// changes still need a full restart.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::error::{CommandResult, Error};
+use crate::poller::StatusCache;
+use crate::service::AppState;
+use crate::{app_settings, config_drift};
+
+pub(crate) const CONFIG_RELOADED_EVENT: &str = "bridge://config-reloaded";
+
+// Time a signalled bridge gets to re-read its config before it's checked
+const SIGNAL_SETTLE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReloadMethod {
+    Api,
+    Signal,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadResult {
+    method: ReloadMethod,
+    reloaded: bool,
+    // Some changes, such as bot tokens, only take effect on a restart
+    restart_required: bool,
+    detail: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReloadResponse {
+    #[serde(default)]
+    restart_required: bool,
+}
+
+impl ReloadResult {
+    fn failed(method: ReloadMethod, detail: impl Into<String>) -> Self {
+        Self {
+            method,
+            reloaded: false,
+            restart_required: true,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+// Whether `pid` has a SIGHUP handler, from the caught-signals mask in /proc
+#[cfg(target_os = "linux")]
+fn handles_sighup(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/status", pid))
+        .ok()
+        .and_then(|status| {
+            let mask = status.lines().find_map(|line| line.strip_prefix("SigCgt:"))?;
+            u64::from_str_radix(mask.trim(), 16).ok()
+        })
+        .is_some_and(|mask| mask & (1 << (libc::SIGHUP - 1)) != 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn handles_sighup(_pid: u32) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn send_sighup(pid: u32) -> bool {
+    unsafe { libc::kill(pid as libc::pid_t, libc::SIGHUP) == 0 }
+}
+
+#[cfg(not(unix))]
+fn send_sighup(_pid: u32) -> bool {
+    false
+}
+
+async fn signal(app: &AppHandle) -> ReloadResult {
+    let pid = app
+        .state::<AppState>()
+        .lock()
+        .ok()
+        .and_then(|s| s.process.as_ref().and_then(|child| child.id()));
+    let Some(pid) = pid.filter(|pid| handles_sighup(*pid)) else {
+        return ReloadResult::failed(ReloadMethod::None, "This bridge version can't reload its config; restart it to apply the changes");
+    };
+    if !send_sighup(pid) {
+        return ReloadResult::failed(ReloadMethod::Signal, "Failed to signal the bridge; restart it to apply the changes");
+    }
+
+    tokio::time::sleep(SIGNAL_SETTLE).await;
+    match app.state::<BridgeApiClient>().status().await {
+        Ok(status) => ReloadResult {
+            method: ReloadMethod::Signal,
+            reloaded: true,
+            // Only bridges that report their config hash can be checked
+            restart_required: config_drift::running_matches_disk(&status) == Some(false),
+            detail: None,
+        },
+        Err(e) => ReloadResult::failed(ReloadMethod::Signal, format!("The bridge stopped answering after SIGHUP: {}", e)),
+    }
+}
+
+async fn reload(app: &AppHandle) -> ReloadResult {
+    let result = match app.state::<BridgeApiClient>().post("/reload", None).await {
+        Ok(response) => {
+            let body: ReloadResponse = response.json().await.unwrap_or_default();
+            ReloadResult {
+                method: ReloadMethod::Api,
+                reloaded: true,
+                restart_required: body.restart_required,
+                detail: None,
+            }
+        }
+        Err(ApiError::Unsupported(_)) => signal(app).await,
+        Err(e) => ReloadResult::failed(ReloadMethod::Api, format!("Failed to reload the bridge's config: {}", e)),
+    };
+    // The running bridge now has what's on disk, as far as drift is concerned
+    if result.reloaded && !result.restart_required {
+        config_drift::remember_launch_config();
+    }
+    app.state::<StatusCache>().request_refresh();
+    result
+}
+
+// Called by `ConfigStore::write`. A stopped bridge reads the config when it
+// starts, and a remote one has a config of its own
+pub(crate) fn after_write(app: &AppHandle) {
+    if app_settings::active_instance().is_some() || app.state::<StatusCache>().status().is_none() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = reload(&app).await;
+        let _ = app.emit(CONFIG_RELOADED_EVENT, &result);
+    });
+}
+
+#[tauri::command]
+pub(crate) async fn reload_bridge_config(app: AppHandle) -> CommandResult<ReloadResult> {
+    if let Some(instance) = app_settings::active_instance() {
+        return Err(Error::Invalid(format!("{} is a remote bridge and reads its own config", instance.name)));
+    }
+    if app.state::<StatusCache>().status().is_none() {
+        return Err(Error::Bridge("The bridge isn't running".to_string()));
+    }
+    let result = reload(&app).await;
+    let _ = app.emit(CONFIG_RELOADED_EVENT, &result);
+    Ok(result)
+}
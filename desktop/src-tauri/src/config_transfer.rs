@@ -0,0 +1,303 @@
+// Moving a setup between machines.
+//
+// `export_config` writes config.json to a file of the user's choosing. Tokens
+// and keys are replaced with a placeholder unless the user asks to include
+// them, in which case keychain references are resolved too, since the other
+// machine's keychain won't have them, and only the user can read the file.
+// `import_config` reads such a file and either merges it into
+// ~/.ccb/config.json (agents and bots matched by id, everything else key by
+// key) or replaces the config outright. Placeholders are filled from the
+// current config. A dry run reports the changes without writing anything; a
+// real import validates the result and first saves the config it replaces,
+// secrets included, to a private file outside the pruned backups.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::config::{expand_home, get_config_path, ConfigStore};
+use crate::config_validation::{self, FieldError};
+use crate::error::{CommandResult, Error};
+use crate::secrets::{self, id_of, keyed, SecretFinding, REDACTED};
+use crate::keychain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    // Add the file's agents and bots, overwriting those with the same id
+    Merge,
+    Replace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    // e.g. agents.list[main].model; list entries go by id
+    path: String,
+    kind: ChangeKind,
+    // Secrets shown as the placeholder
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExport {
+    path: String,
+    // Secrets left out of the file
+    redacted: Vec<SecretFinding>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    changes: Vec<ConfigChange>,
+    // Placeholders the current config has no secret for
+    missing_secrets: Vec<String>,
+    // Problems with the resulting config; the import is refused while any remain
+    errors: Vec<FieldError>,
+    applied: bool,
+    // Copy of the replaced config, when there was one
+    backup: Option<String>,
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+// Keychain references swapped for their secret, or for the placeholder;
+// returns the paths of those that were swapped
+fn replace_references(value: &mut Value, path: &str, resolve: bool, found: &mut Vec<String>) {
+    match value {
+        Value::String(text) if keychain::is_reference(text) => {
+            *text = if resolve { keychain::resolve(text) } else { REDACTED.to_string() };
+            found.push(path.to_string());
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                replace_references(item, &format!("{}[{}]", path, i), resolve, found);
+            }
+        }
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                replace_references(child, &child_path(path, key), resolve, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Deep merge of `incoming` into `current`; a placeholder keeps the current value
+fn merge(current: &mut Value, incoming: &Value) {
+    match incoming {
+        Value::Object(incoming) if current.is_object() => {
+            let Some(current) = current.as_object_mut() else {
+                return;
+            };
+            for (key, value) in incoming {
+                match current.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        current.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        Value::Array(incoming) if current.as_array().is_some_and(|c| keyed(c)) && keyed(incoming) => {
+            let Some(current) = current.as_array_mut() else {
+                return;
+            };
+            for item in incoming {
+                match current.iter_mut().find(|c| id_of(c) == id_of(item)) {
+                    Some(existing) => merge(existing, item),
+                    None => current.push(item.clone()),
+                }
+            }
+        }
+        Value::String(text) if text.contains(REDACTED) && current.as_str().is_some_and(|c| !c.contains(REDACTED)) => {}
+        _ => *current = incoming.clone(),
+    }
+}
+
+// A value as it may be shown: with any secret under `key` or inside it redacted
+fn shown(key: &str, value: &Value) -> Value {
+    let mut wrapped = serde_json::json!({ key: value });
+    secrets::redact_config(&mut wrapped);
+    wrapped[key].take()
+}
+
+fn compare(path: String, key: &str, before: Option<&Value>, after: Option<&Value>, changes: &mut Vec<ConfigChange>) {
+    match (before, after) {
+        (Some(Value::Object(b)), Some(Value::Object(a))) => {
+            let keys: BTreeSet<&String> = b.keys().chain(a.keys()).collect();
+            for k in keys {
+                compare(child_path(&path, k), k, b.get(k), a.get(k), changes);
+            }
+        }
+        (Some(Value::Array(b)), Some(Value::Array(a))) if keyed(b) && keyed(a) => {
+            let mut ids: Vec<&str> = b.iter().filter_map(id_of).collect();
+            ids.extend(a.iter().filter_map(id_of).filter(|id| !b.iter().any(|item| id_of(item) == Some(id))));
+            for id in ids {
+                let find = |items: &'_ [Value]| items.iter().find(|item| id_of(item) == Some(id)).cloned();
+                compare(format!("{}[{}]", path, id), key, find(b).as_ref(), find(a).as_ref(), changes);
+            }
+        }
+        (b, a) if b == a => {}
+        (b, a) => changes.push(ConfigChange {
+            path,
+            kind: match (b, a) {
+                (None, _) => ChangeKind::Added,
+                (_, None) => ChangeKind::Removed,
+                _ => ChangeKind::Changed,
+            },
+            before: b.map(|v| shown(key, v)),
+            after: a.map(|v| shown(key, v)),
+        }),
+    }
+}
+
+fn read_import(path: &str) -> CommandResult<Value> {
+    let content = fs::read_to_string(expand_home(path)).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let config: Value =
+        serde_json::from_str(&content).map_err(|e| Error::Invalid(format!("{} is not valid JSON: {}", path, e)))?;
+    if !config.is_object() {
+        return Err(Error::Invalid(format!("{} is not a ccb config", path)));
+    }
+    Ok(config)
+}
+
+// An export with secrets in it is readable by the owner only, also when it
+// replaces an existing file
+fn write_export(dest: &Path, content: &str, private: bool) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(dest)?;
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    file.write_all(content.as_bytes())
+}
+
+#[tauri::command]
+pub(crate) fn export_config(store: State<'_, ConfigStore>, path: String, include_secrets: bool) -> CommandResult<ConfigExport> {
+    let mut config = store.read()?.ok_or_else(|| Error::Config(crate::i18n::t("config.not_found")))?;
+
+    let mut references = Vec::new();
+    replace_references(&mut config, "", include_secrets, &mut references);
+    let mut redacted = Vec::new();
+    if !include_secrets {
+        redacted = secrets::redact_config(&mut config);
+        redacted.extend(references.into_iter().map(|location| SecretFinding {
+            location,
+            kind: "Keychain secret".to_string(),
+        }));
+    }
+
+    let dest = expand_home(&path);
+    if let Some(dir) = dest.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_export(&dest, &content, include_secrets).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(ConfigExport {
+        path: dest.to_string_lossy().to_string(),
+        redacted,
+    })
+}
+
+// The config an import replaces, with keychain secrets resolved since the
+// import may overwrite them. Kept apart from the scheduled backups, which are
+// redacted and pruned
+fn save_pre_import_copy(config: &Value) -> Result<PathBuf, String> {
+    let mut copy = config.clone();
+    replace_references(&mut copy, "", true, &mut Vec::new());
+    let dir = get_config_path()
+        .parent()
+        .ok_or("Invalid config path")?
+        .join("backups")
+        .join("pre-import");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let dest = dir.join(format!("config-{}.json", Local::now().format("%Y%m%d-%H%M%S-%3f")));
+    let content = serde_json::to_string_pretty(&copy).map_err(|e| e.to_string())?;
+    write_export(&dest, &content, true).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(dest)
+}
+
+// With `dry_run`, only reports what the import would change
+#[tauri::command]
+pub(crate) fn import_config(
+    store: State<'_, ConfigStore>,
+    path: String,
+    merge_strategy: MergeStrategy,
+    dry_run: Option<bool>,
+) -> CommandResult<ImportPreview> {
+    let incoming = read_import(&path)?;
+    let current = store.read()?;
+    let before = current.clone().unwrap_or_else(|| serde_json::json!({}));
+
+    let mut after = match merge_strategy {
+        MergeStrategy::Merge => {
+            let mut merged = before.clone();
+            merge(&mut merged, &incoming);
+            merged
+        }
+        MergeStrategy::Replace => incoming,
+    };
+    let missing_secrets = secrets::restore_redacted(&mut after, &before);
+
+    let mut changes = Vec::new();
+    compare(String::new(), "", Some(&before), Some(&after), &mut changes);
+    let mut preview = ImportPreview {
+        changes,
+        missing_secrets,
+        errors: config_validation::validate(&after),
+        applied: false,
+        backup: None,
+    };
+    if dry_run.unwrap_or(false) || preview.changes.is_empty() {
+        return Ok(preview);
+    }
+
+    if !preview.missing_secrets.is_empty() {
+        return Err(Error::Invalid(format!(
+            "The file has redacted secrets the current config can't fill in: {}",
+            preview.missing_secrets.join(", ")
+        )));
+    }
+    config_validation::ensure_valid(&after)?;
+    if let Some(current) = &current {
+        preview.backup = Some(save_pre_import_copy(current)?.to_string_lossy().to_string());
+    } else if let Some(dir) = get_config_path().parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    keychain::protect_tokens(&mut after)?;
+    store.write(&after)?;
+    preview.applied = true;
+    Ok(preview)
+}
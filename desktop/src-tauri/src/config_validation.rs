@@ -0,0 +1,259 @@
+// Checking config.json before it's written.
+//
+// The bridge validates its config only when it starts, and a bad value (a
+// mistyped permissionMode, a pasted token with a stray character) then stops
+// it with a schema error in the log. The same rules are applied here, plus a
+// few the bridge can't know about such as workspaces existing on this machine,
+// and reported per field so the UI can point at the offending input.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::State;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::secrets;
+
+const PERMISSION_MODES: &[&str] = &["default", "acceptEdits", "plan", "bypassPermissions"];
+const DM_POLICIES: &[&str] = &["pairing", "allowlist", "open"];
+const MCP_TYPES: &[&str] = &["stdio", "sse"];
+// Claude Code's model aliases; full model ids start with "claude-", or
+// "anthropic.claude-" with an optional region prefix on Bedrock
+const MODEL_ALIASES: &[&str] = &["default", "sonnet", "opus", "haiku", "opusplan", "sonnet[1m]"];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    // e.g. agents.list[0].permissionMode
+    pub(crate) path: String,
+    pub(crate) message: String,
+}
+
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+    fn add(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(FieldError {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn required_str<'a>(&mut self, value: &'a serde_json::Value, path: &str, key: &str) -> Option<&'a str> {
+        match value.get(key).and_then(|v| v.as_str()).filter(|s| !s.trim().is_empty()) {
+            Some(s) => Some(s),
+            None => {
+                self.add(format!("{}.{}", path, key), format!("{} is required", key));
+                None
+            }
+        }
+    }
+
+    fn one_of(&mut self, value: &serde_json::Value, path: &str, key: &str, allowed: &[&str]) {
+        let Some(found) = value.get(key) else {
+            return;
+        };
+        if !found.as_str().is_some_and(|s| allowed.contains(&s)) {
+            self.add(format!("{}.{}", path, key), format!("Must be one of {}", allowed.join(", ")));
+        }
+    }
+}
+
+// Vertex ids (claude-...@20250514) start with "claude-" too; Bedrock has
+// anthropic.claude-..., us.anthropic.claude-... and inference profile ARNs
+fn is_valid_model(model: &str) -> bool {
+    let bedrock = model.split_once("anthropic.claude-").is_some_and(|(prefix, _)| {
+        prefix.is_empty()
+            || prefix
+                .strip_suffix('.')
+                .is_some_and(|region| !region.is_empty() && region.chars().all(|c| c.is_ascii_lowercase()))
+    });
+    MODEL_ALIASES.contains(&model) || model.starts_with("claude-") || bedrock || model.starts_with("arn:aws:bedrock:")
+}
+
+// `${VAR}` references are resolved by the bridge, so only literal tokens are checked
+fn check_token(errors: &mut Errors, path: String, channel: &str, token: &str) {
+    if token.starts_with("${") && token.ends_with('}') {
+        return;
+    }
+    let valid = match channel {
+        "telegram" => secrets::is_telegram_token(token),
+        _ => secrets::is_discord_token(token),
+    };
+    if !valid {
+        let expected = match channel {
+            "telegram" => "a Telegram bot token such as 123456789:AA...",
+            _ => "a Discord bot token (three parts separated by dots)",
+        };
+        errors.add(path, format!("Doesn't look like {}", expected));
+    }
+}
+
+fn check_agents(errors: &mut Errors, config: &serde_json::Value) -> HashSet<String> {
+    let mut ids = HashSet::new();
+    let Some(list) = config.get("agents").and_then(|a| a.get("list")).and_then(|l| l.as_array()) else {
+        errors.add("agents.list", "At least one agent is required");
+        return ids;
+    };
+    if list.is_empty() {
+        errors.add("agents.list", "At least one agent is required");
+    }
+
+    for (i, agent) in list.iter().enumerate() {
+        let path = format!("agents.list[{}]", i);
+        if let Some(id) = errors.required_str(agent, &path, "id") {
+            if !ids.insert(id.to_string()) {
+                errors.add(format!("{}.id", path), format!("Agent id '{}' is used more than once", id));
+            }
+        }
+        errors.required_str(agent, &path, "name");
+        if let Some(workspace) = errors.required_str(agent, &path, "workspace") {
+            if !expand_home(workspace).is_dir() {
+                errors.add(format!("{}.workspace", path), format!("{} is not a directory", workspace));
+            }
+        }
+        if let Some(model) = agent.get("model") {
+            if !model.as_str().is_some_and(is_valid_model) {
+                errors.add(
+                    format!("{}.model", path),
+                    format!(
+                        "Use one of {} or a full model id such as claude-..., or us.anthropic.claude-... on Bedrock",
+                        MODEL_ALIASES.join(", ")
+                    ),
+                );
+            }
+        }
+        errors.one_of(agent, &path, "permissionMode", PERMISSION_MODES);
+        if let Some(max_turns) = agent.get("maxTurns") {
+            if !max_turns.as_f64().is_some_and(|n| n > 0.0) {
+                errors.add(format!("{}.maxTurns", path), "Must be a positive number");
+            }
+        }
+
+        let servers = agent.get("mcpServers").and_then(|s| s.as_array()).into_iter().flatten();
+        for (j, server) in servers.enumerate() {
+            let server_path = format!("{}.mcpServers[{}]", path, j);
+            errors.required_str(server, &server_path, "name");
+            errors.one_of(server, &server_path, "type", MCP_TYPES);
+            if server.get("type").and_then(|t| t.as_str()) == Some("sse") {
+                errors.required_str(server, &server_path, "url");
+            } else {
+                errors.required_str(server, &server_path, "command");
+            }
+        }
+    }
+    ids
+}
+
+fn check_channel(errors: &mut Errors, config: &serde_json::Value, channel: &str, agent_ids: &HashSet<String>) {
+    let Some(section) = config.get("channels").and_then(|c| c.get(channel)) else {
+        return;
+    };
+    let path = format!("channels.{}", channel);
+    let token_key = if channel == "discord" { "token" } else { "botToken" };
+    errors.one_of(section, &path, "dmPolicy", DM_POLICIES);
+
+    let single = section.get(token_key).and_then(|t| t.as_str()).filter(|t| !t.is_empty());
+    let bots = section.get("bots").and_then(|b| b.as_array()).cloned().unwrap_or_default();
+    let enabled = section.get("enabled").and_then(|e| e.as_bool()).unwrap_or(false);
+    if enabled && single.is_none() && bots.is_empty() {
+        errors.add(format!("{}.{}", path, token_key), "Add at least one bot or disable the channel");
+    }
+    if let Some(token) = single {
+        check_token(errors, format!("{}.{}", path, token_key), channel, token);
+    }
+
+    let mut bot_ids = HashSet::new();
+    for (i, bot) in bots.iter().enumerate() {
+        let bot_path = format!("{}.bots[{}]", path, i);
+        if let Some(id) = errors.required_str(bot, &bot_path, "id") {
+            if !bot_ids.insert(id.to_string()) {
+                errors.add(format!("{}.id", bot_path), format!("Bot id '{}' is used more than once", id));
+            }
+        }
+        if let Some(token) = errors.required_str(bot, &bot_path, token_key) {
+            check_token(errors, format!("{}.{}", bot_path, token_key), channel, token);
+        }
+        errors.one_of(bot, &bot_path, "dmPolicy", DM_POLICIES);
+        if let Some(agent_id) = bot.get("agentId").and_then(|a| a.as_str()) {
+            if !agent_ids.contains(agent_id) {
+                errors.add(format!("{}.agentId", bot_path), format!("No agent with id '{}'", agent_id));
+            }
+        }
+    }
+}
+
+// Field-level problems with `config`; empty when it's fine to write
+pub(crate) fn validate(config: &serde_json::Value) -> Vec<FieldError> {
+    let mut errors = Errors(Vec::new());
+    let agent_ids = check_agents(&mut errors, config);
+    for channel in ["telegram", "discord"] {
+        check_channel(&mut errors, config, channel, &agent_ids);
+    }
+
+    if let Some(whatsapp) = config.get("channels").and_then(|c| c.get("whatsapp")) {
+        errors.one_of(whatsapp, "channels.whatsapp", "dmPolicy", DM_POLICIES);
+        if let Some(agent_id) = whatsapp.get("agentId").and_then(|a| a.as_str()) {
+            if !agent_ids.contains(agent_id) {
+                errors.add("channels.whatsapp.agentId", format!("No agent with id '{}'", agent_id));
+            }
+        }
+    }
+
+    let bindings = config.get("bindings").and_then(|b| b.as_array()).into_iter().flatten();
+    for (i, binding) in bindings.enumerate() {
+        let path = format!("bindings[{}]", i);
+        if let Some(agent_id) = errors.required_str(binding, &path, "agentId") {
+            if !agent_ids.contains(agent_id) {
+                errors.add(format!("{}.agentId", path), format!("No agent with id '{}'", agent_id));
+            }
+        }
+    }
+
+    if let Some(hooks) = config.get("hooks") {
+        let enabled = hooks.get("enabled").and_then(|e| e.as_bool()).unwrap_or(false);
+        if enabled {
+            errors.required_str(hooks, "hooks", "token");
+        }
+    }
+    errors.0
+}
+
+fn to_error(errors: Vec<FieldError>) -> CommandResult<()> {
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.path, e.message)).collect();
+    Err(Error::Invalid(format!("Invalid config: {}", details.join("; "))))
+}
+
+// For writers replacing the whole config: the problems as one `Error::Invalid`
+pub(crate) fn ensure_valid(config: &serde_json::Value) -> CommandResult<()> {
+    to_error(validate(config))
+}
+
+// For writers changing part of the config, e.g. "agents.list[2]" or
+// "channels.telegram": only problems under those paths count, so a workspace
+// gone missing elsewhere doesn't block an unrelated edit
+pub(crate) fn ensure_valid_in(config: &serde_json::Value, scopes: &[String]) -> CommandResult<()> {
+    let in_scope = |path: &str| {
+        scopes.iter().any(|scope| {
+            path.strip_prefix(scope.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+        })
+    };
+    to_error(validate(config).into_iter().filter(|e| in_scope(&e.path)).collect())
+}
+
+// Checks `config`, or config.json when none is given
+#[tauri::command]
+pub(crate) fn validate_config(
+    store: State<'_, ConfigStore>,
+    config: Option<serde_json::Value>,
+) -> CommandResult<Vec<FieldError>> {
+    let config = match config {
+        Some(config) => config,
+        None => store.read()?.ok_or_else(|| Error::Config(crate::i18n::t("config.not_found")))?,
+    };
+    Ok(validate(&config))
+}
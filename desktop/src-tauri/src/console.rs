@@ -0,0 +1,144 @@
+// In-app chat console.
+//
+// Opens a desktop-originated session with the bridge so an agent can be tried
+// out locally, going through the same routing, session handling and tools a
+// Telegram or Discord user would get. Replies are streamed as newline-delimited
+// JSON and forwarded to the frontend as `console://event` events. One console
+// session is open at a time.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+
+pub(crate) const CONSOLE_EVENT: &str = "console://event";
+
+// A whole agent turn, tools included
+const REPLY_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleSession {
+    session_id: String,
+    agent_id: String,
+    chat_key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsoleEvent {
+    session_id: String,
+    // Bridge event ("text", "tool_use", "tool_result", "done", "error", ...)
+    // with its payload passed through as-is
+    #[serde(flatten)]
+    event: serde_json::Value,
+}
+
+// Managed state holding the open console session
+#[derive(Default)]
+pub(crate) struct Console(Mutex<Option<ConsoleSession>>);
+
+fn unsupported() -> String {
+    "This bridge version doesn't support the console".to_string()
+}
+
+fn current(console: &Console) -> Result<ConsoleSession, String> {
+    console
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("No console session is open".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn open_console_session(
+    api: State<'_, BridgeApiClient>,
+    console: State<'_, Console>,
+    agent_id: String,
+) -> Result<ConsoleSession, String> {
+    let response = match api.post("/console/sessions", Some(&serde_json::json!({ "agentId": agent_id }))).await {
+        Ok(response) => response,
+        Err(ApiError::Unsupported(_)) => return Err(unsupported()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let session: ConsoleSession = response.json().await.map_err(ApiError::from)?;
+    *console.0.lock().map_err(|e| e.to_string())? = Some(session.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+pub(crate) fn get_console_session(console: State<'_, Console>) -> Result<Option<ConsoleSession>, String> {
+    Ok(console.0.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// Resolves once the reply has finished streaming
+#[tauri::command]
+pub(crate) async fn console_send(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    console: State<'_, Console>,
+    text: String,
+) -> Result<bool, String> {
+    if text.trim().is_empty() {
+        return Err("Message is empty".to_string());
+    }
+    let session = current(&console)?;
+
+    let response = api
+        .http()
+        .post(BridgeApiClient::url(&format!("/console/sessions/{}/messages", session.session_id)))
+        .json(&serde_json::json!({ "text": text }))
+        .timeout(REPLY_TIMEOUT)
+        .send()
+        .await
+        .map_err(ApiError::from)?;
+    let mut response = match BridgeApiClient::check(response).await {
+        Ok(response) => response,
+        Err(ApiError::NotFound(_)) => return Err("Console session has expired; open a new one".to_string()),
+        Err(ApiError::Unsupported(_)) => return Err(unsupported()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let emit = |line: &[u8]| {
+        let line = String::from_utf8_lossy(line);
+        if line.trim().is_empty() {
+            return;
+        }
+        let event = serde_json::from_str(&line).unwrap_or_else(|_| serde_json::json!({ "type": "text", "text": line }));
+        let _ = app.emit(
+            CONSOLE_EVENT,
+            ConsoleEvent {
+                session_id: session.session_id.clone(),
+                event,
+            },
+        );
+    };
+
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        buf.extend_from_slice(&chunk);
+        while let Some(newline) = buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=newline).collect();
+            emit(&line);
+        }
+    }
+    emit(&buf);
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn close_console_session(
+    api: State<'_, BridgeApiClient>,
+    console: State<'_, Console>,
+) -> Result<bool, String> {
+    let Some(session) = console.0.lock().map_err(|e| e.to_string())?.take() else {
+        return Ok(false);
+    };
+    // Best effort; the bridge expires idle console sessions on its own
+    let _ = api.delete(&format!("/console/sessions/{}", session.session_id)).await;
+    Ok(true)
+}
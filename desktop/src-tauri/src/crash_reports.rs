@@ -0,0 +1,301 @@
+// Structured crash reports.
+//
+// A panic hook and a bridge exit watcher write JSON reports to ~/.ccb/crashes/
+// with the backtrace or log tail, platform, app and ccb versions, and the last
+// few commands the UI invoked. A bridge crash also captures its last 500 log
+// lines and the config it ran with, secrets redacted. Nothing is sent
+// anywhere: submitting a report builds a prefilled GitHub issue for the user
+// to review, and exporting one writes it to a file to attach, with any tokens
+// in the message or logs redacted either way.
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+use tokio::process::Command;
+
+use crate::config::{expand_home, get_config_path};
+use crate::log_forwarding;
+use crate::secrets;
+use crate::service::get_extended_path;
+
+const RECENT_COMMANDS: usize = 20;
+// Bridge log lines kept with a bridge crash
+pub(crate) const CRASH_LOG_LINES: usize = 500;
+const ISSUES_URL: &str = "https://github.com/misbahsy/cc-bridge/issues/new";
+// Keep prefilled issue URLs under what browsers and GitHub accept
+const MAX_ISSUE_BODY: usize = 6000;
+
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CCB_VERSION: OnceLock<String> = OnceLock::new();
+static OS_VERSION: OnceLock<Option<String>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrashKind {
+    // The desktop app itself panicked
+    Panic,
+    // The bridge process exited on its own with a failure status
+    BridgeExit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    id: String,
+    kind: CrashKind,
+    timestamp: String,
+    message: String,
+    location: Option<String>,
+    backtrace: Option<String>,
+    exit_code: Option<i32>,
+    log_excerpt: Vec<String>,
+    os: String,
+    #[serde(default)]
+    os_version: Option<String>,
+    arch: String,
+    app_version: String,
+    ccb_version: Option<String>,
+    recent_commands: Vec<String>,
+    // config.json at the time, secrets redacted; bridge crashes only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    config: Option<serde_json::Value>,
+}
+
+fn get_crashes_dir() -> PathBuf {
+    dirs::home_dir()
+        .map(|h| h.join(".ccb").join("crashes"))
+        .unwrap_or_else(|| PathBuf::from(".ccb/crashes"))
+}
+
+// Where earlier versions wrote reports; still listed
+fn get_legacy_crashes_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".ccb").join("desktop-crashes"))
+}
+
+// Read from files only, so the panic hook can call it
+fn detect_os_version() -> Option<String> {
+    if cfg!(target_os = "linux") {
+        let release = fs::read_to_string("/etc/os-release").ok()?;
+        let name = release.lines().find_map(|line| line.strip_prefix("PRETTY_NAME="))?;
+        Some(name.trim_matches('"').to_string())
+    } else if cfg!(target_os = "macos") {
+        let plist = fs::read_to_string("/System/Library/CoreServices/SystemVersion.plist").ok()?;
+        let (_, rest) = plist.split_once("<key>ProductVersion</key>")?;
+        let version = rest.split_once("<string>")?.1.split_once("</string>")?.0;
+        Some(format!("macOS {}", version.trim()))
+    } else {
+        None
+    }
+}
+
+fn new_report(kind: CrashKind, message: String) -> CrashReport {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    CrashReport {
+        id: format!("crash-{}", millis),
+        kind,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        message,
+        location: None,
+        backtrace: None,
+        exit_code: None,
+        log_excerpt: vec![],
+        os: std::env::consts::OS.to_string(),
+        os_version: OS_VERSION.get_or_init(detect_os_version).clone(),
+        arch: std::env::consts::ARCH.to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        ccb_version: CCB_VERSION.get().cloned(),
+        // try_lock: the panic may have happened while holding this lock
+        recent_commands: RECENT.try_lock().map(|r| r.iter().cloned().collect()).unwrap_or_default(),
+        config: None,
+    }
+}
+
+fn write_report(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = get_crashes_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create crash dir: {}", e))?;
+    let path = dir.join(format!("{}.json", report.id));
+    let content = serde_json::to_string_pretty(report).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path)
+}
+
+// Chain onto the default hook so panics are still printed as usual
+pub(crate) fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic with non-string payload".to_string());
+
+        let mut report = new_report(CrashKind::Panic, message);
+        report.location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report.backtrace = Some(Backtrace::force_capture().to_string());
+        let _ = write_report(&report);
+
+        default_hook(info);
+    }));
+}
+
+// Look up the ccb version once so the panic hook never has to spawn processes
+pub(crate) async fn detect_ccb_version() {
+    let output = tokio::time::timeout(
+        Duration::from_secs(10),
+        Command::new("ccb")
+            .arg("--version")
+            .env("PATH", get_extended_path())
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await;
+    if let Ok(Ok(output)) = output {
+        if output.status.success() {
+            let _ = CCB_VERSION.set(String::from_utf8_lossy(&output.stdout).trim().to_string());
+        }
+    }
+}
+
+// Wrap the app's invoke handler to remember the most recent command names
+pub(crate) fn remember_commands<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if let Ok(mut recent) = RECENT.lock() {
+            if recent.len() == RECENT_COMMANDS {
+                recent.pop_front();
+            }
+            recent.push_back(invoke.message.command().to_string());
+        }
+        handler(invoke)
+    }
+}
+
+// Called when the bridge exits without being asked to stop
+pub(crate) fn record_bridge_exit(exit_code: Option<i32>, logs: &[String]) {
+    let message = match exit_code {
+        Some(code) => format!("Bridge exited with code {}", code),
+        None => "Bridge was terminated by a signal".to_string(),
+    };
+    let mut report = new_report(CrashKind::BridgeExit, message);
+    report.exit_code = exit_code;
+    report.log_excerpt = logs.to_vec();
+    report.config = fs::read_to_string(get_config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .map(|mut config| {
+            secrets::redact_config(&mut config);
+            config
+        });
+    if let Err(e) = write_report(&report) {
+        log_forwarding::desktop_error(e);
+    }
+}
+
+fn report_dirs() -> Vec<PathBuf> {
+    std::iter::once(get_crashes_dir()).chain(get_legacy_crashes_dir()).collect()
+}
+
+fn report_path(id: &str) -> Result<PathBuf, String> {
+    if id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("Invalid crash report id '{}'", id));
+    }
+    report_dirs()
+        .into_iter()
+        .map(|dir| dir.join(format!("{}.json", id)))
+        .find(|path| path.is_file())
+        .ok_or(format!("Crash report '{}' not found", id))
+}
+
+fn load_report(id: &str) -> Result<CrashReport, String> {
+    let content = fs::read_to_string(report_path(id)?).map_err(|e| format!("Failed to read crash report: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse crash report: {}", e))
+}
+
+#[tauri::command]
+pub(crate) fn list_crash_reports() -> Result<Vec<CrashReport>, String> {
+    let mut reports: Vec<CrashReport> = report_dirs()
+        .into_iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    reports.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(reports)
+}
+
+#[tauri::command]
+pub(crate) fn delete_crash_report(id: String) -> Result<bool, String> {
+    fs::remove_file(report_path(&id)?).map_err(|e| format!("Failed to delete crash report: {}", e))?;
+    Ok(true)
+}
+
+// Write report `id` to `path` for attaching to an issue; returns the path
+#[tauri::command]
+pub(crate) fn export_crash_report(id: String, path: String) -> Result<String, String> {
+    let report = load_report(&id)?;
+    let mut json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    // Paths name the user; tokens can turn up in log lines
+    if let Some(home) = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).filter(|h| h.len() > 1) {
+        let escaped = serde_json::to_string(&home).unwrap_or_default();
+        json = json.replace(escaped.trim_matches('"'), "~");
+    }
+    let json = secrets::redact_text(&json);
+
+    let path = expand_home(&path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, json).map_err(|e| format!("Failed to write crash report: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// Build a prefilled GitHub issue URL for the frontend to open; the user sees
+// and edits everything before anything is actually sent
+#[tauri::command]
+pub(crate) fn submit_crash_report(id: String) -> Result<String, String> {
+    let report = load_report(&id)?;
+
+    let mut body = format!(
+        "**Kind:** {:?}\n**When:** {}\n**App:** {} ({} {})\n**ccb:** {}\n\n**Message:** {}\n",
+        report.kind,
+        report.timestamp,
+        report.app_version,
+        report.os_version.as_deref().unwrap_or(&report.os),
+        report.arch,
+        report.ccb_version.as_deref().unwrap_or("unknown"),
+        report.message,
+    );
+    if let Some(ref location) = report.location {
+        body.push_str(&format!("**Location:** {}\n", location));
+    }
+    if !report.recent_commands.is_empty() {
+        body.push_str(&format!("**Recent commands:** {}\n", report.recent_commands.join(", ")));
+    }
+    let details = report.backtrace.clone().unwrap_or_else(|| report.log_excerpt.join("\n"));
+    if !details.is_empty() {
+        body.push_str(&format!("\n```\n{}\n```\n", details));
+    }
+    let mut body = secrets::redact_text(&body);
+    if body.len() > MAX_ISSUE_BODY {
+        let cut = (0..=MAX_ISSUE_BODY).rev().find(|&i| body.is_char_boundary(i)).unwrap_or(0);
+        body.truncate(cut);
+        body.push_str("\n```\n(truncated)\n");
+    }
+
+    let title = secrets::redact_text(&format!("Crash report: {}", report.message));
+    let url = reqwest::Url::parse_with_params(ISSUES_URL, &[("title", title.as_str()), ("body", body.as_str())])
+        .map_err(|e| e.to_string())?;
+    Ok(url.to_string())
+}
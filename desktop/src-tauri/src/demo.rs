@@ -0,0 +1,449 @@
+// Demo mode.
+//
+// Started with `--demo`, the app needs no node, ccb or bot tokens: a fake
+// Control API is served in-process on a free loopback port and every request
+// meant for the bridge goes there instead. It answers like a bridge would,
+// with a canned status, a few sessions and pairing requests that trickle in
+// while it's "running", and Start/Stop drive it instead of a ccb process,
+// printing scripted log output. Routes it doesn't fake answer the way an
+// older bridge does, so the app's fallbacks apply. The config and the
+// desktop's data live in a temp dir standing in for ~/.ccb, seeded with a
+// config that matches the fake bridge and reset on every launch, so the real
+// setup is never read or touched. Meant for working on the frontend and for
+// screenshots.
+
+use chrono::{Duration as ChronoDuration, Utc};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::api_client::{BotInfo, BridgeStatus, ChannelStatus, PairingRequest, PairingStats, SessionStats, SessionSummary, UserInfo};
+use crate::log_forwarding;
+use crate::service::AppState;
+
+const DEMO_FLAG: &str = "--demo";
+// Largest request head or body the fake reads
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+const LOG_INTERVAL: Duration = Duration::from_secs(8);
+// A new pairing request this often while fewer than two are pending
+const PAIRING_INTERVAL: Duration = Duration::from_secs(60);
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+static URL: OnceLock<String> = OnceLock::new();
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+static BRIDGE: Mutex<Option<FakeBridge>> = Mutex::new(None);
+// Bumped on every start so the log script of a stopped run ends
+static RUN: AtomicU64 = AtomicU64::new(0);
+
+const STARTUP_LOG: &[&str] = &[
+    "[ccb] cc-bridge (demo) starting",
+    "[ccb] Loaded config with 2 agents",
+    "[ccb] Control API listening",
+    "[telegram] Bot @demo_assistant_bot connected",
+    "[telegram] Bot @demo_reviewer_bot connected",
+    "[discord] Bot DemoHelper#0042 connected",
+    "[ccb] Bridge ready",
+];
+
+const ACTIVITY_LOG: &[&str] = &[
+    "[telegram] Message from @alice (assistant): \"Can you summarize today's commits?\"",
+    "[session] demo-1 resumed for telegram:1001",
+    "[claude] demo-1: running git log --since=midnight",
+    "[telegram] Reply sent to @alice (1 message)",
+    "[discord] Message from bob in #dev (reviewer): \"Review PR 42 please\"",
+    "[claude] demo-2: reading src/api/routes.ts",
+    "[claude] demo-2: reading src/api/handlers.ts",
+    "[discord] Reply sent to bob (2 messages)",
+];
+
+const PAIRING_USERS: &[(&str, &str, &str)] = &[
+    ("telegram", "carol_dev", "Carol"),
+    ("discord", "dave", "Dave"),
+    ("telegram", "erin", "Erin Park"),
+    ("discord", "frank.ops", "Frank"),
+];
+
+struct FakeBridge {
+    started_at: Option<Instant>,
+    pairings: Vec<PairingRequest>,
+    sessions: Vec<SessionSummary>,
+    allowlist: Vec<serde_json::Value>,
+    last_pairing: Instant,
+    pairings_made: usize,
+}
+
+fn session(id: &str, chat_key: &str, name: &str, agent: &str, status: &str, age_minutes: i64) -> SessionSummary {
+    let now = Utc::now();
+    SessionSummary {
+        id: id.to_string(),
+        chat_key: chat_key.to_string(),
+        session_name: Some(name.to_string()),
+        agent_id: Some(agent.to_string()),
+        status: status.to_string(),
+        created_at: (now - ChronoDuration::minutes(age_minutes * 10)).to_rfc3339(),
+        last_active: (now - ChronoDuration::minutes(age_minutes)).to_rfc3339(),
+        throttle: None,
+        sdk_session_id: None,
+        message_count: Some(12 + age_minutes as u32),
+        identity: None,
+    }
+}
+
+fn pairing(index: usize) -> PairingRequest {
+    let (channel, username, display_name) = PAIRING_USERS[index % PAIRING_USERS.len()];
+    let id = (2001 + index).to_string();
+    let now = Utc::now();
+    PairingRequest {
+        code: format!("DEMO{:02}", index + 1),
+        chat_key: format!("{}:{}", channel, id),
+        user_info: UserInfo {
+            id,
+            username: Some(username.to_string()),
+            display_name: Some(display_name.to_string()),
+            channel: channel.to_string(),
+        },
+        created_at: now.to_rfc3339(),
+        expires_at: (now + ChronoDuration::minutes(10)).to_rfc3339(),
+    }
+}
+
+impl FakeBridge {
+    fn new() -> Self {
+        Self {
+            started_at: None,
+            pairings: vec![pairing(0)],
+            sessions: vec![
+                session("demo-1", "telegram:1001", "Daily summary", "assistant", "active", 2),
+                session("demo-2", "discord:1002", "PR reviews", "reviewer", "active", 5),
+                session("demo-3", "telegram:1003", "Trip planning", "assistant", "idle", 180),
+            ],
+            allowlist: vec![
+                json!({ "chatKey": "telegram:1001", "userInfo": { "id": "1001", "username": "alice", "displayName": "Alice", "channel": "telegram" } }),
+                json!({ "chatKey": "discord:1002", "userInfo": { "id": "1002", "username": "bob", "displayName": "Bob", "channel": "discord" } }),
+            ],
+            last_pairing: Instant::now(),
+            pairings_made: 1,
+        }
+    }
+
+    // Expire old requests and let a new one arrive now and then
+    fn tick(&mut self) {
+        let now = Utc::now().to_rfc3339();
+        self.pairings.retain(|p| p.expires_at > now);
+        if self.pairings.len() < 2 && self.last_pairing.elapsed() >= PAIRING_INTERVAL {
+            self.pairings.push(pairing(self.pairings_made));
+            self.pairings_made += 1;
+            self.last_pairing = Instant::now();
+        }
+    }
+
+    fn status(&self) -> BridgeStatus {
+        let bot = |id: &str, username: &str, agent: &str| BotInfo {
+            id: id.to_string(),
+            username: Some(username.to_string()),
+            agent_id: Some(agent.to_string()),
+        };
+        BridgeStatus {
+            running: true,
+            uptime: self.started_at.map(|t| t.elapsed().as_millis() as u64).unwrap_or_default(),
+            channels: vec![
+                ChannelStatus {
+                    name: "telegram".to_string(),
+                    enabled: true,
+                    connected: true,
+                    bot_count: 2,
+                    bots: vec![
+                        bot("assistant", "demo_assistant_bot", "assistant"),
+                        bot("reviewer", "demo_reviewer_bot", "reviewer"),
+                    ],
+                },
+                ChannelStatus {
+                    name: "discord".to_string(),
+                    enabled: true,
+                    connected: true,
+                    bot_count: 1,
+                    bots: vec![bot("helper", "DemoHelper#0042", "reviewer")],
+                },
+            ],
+            sessions: SessionStats {
+                active: self.sessions.iter().filter(|s| s.status == "active").count() as u32,
+                total: self.sessions.len() as u32,
+            },
+            pairings: PairingStats {
+                pending: self.pairings.len() as u32,
+            },
+            config_hash: None,
+        }
+    }
+
+    fn messages(&self, session_id: &str) -> Option<serde_json::Value> {
+        let session = self.sessions.iter().find(|s| s.id == session_id)?;
+        let now = Utc::now();
+        let at = |minutes: i64| (now - ChronoDuration::minutes(minutes)).to_rfc3339();
+        let topic = session.session_name.clone().unwrap_or_default();
+        Some(json!({
+            "messages": [
+                { "role": "user", "content": format!("Let's pick up \"{}\" where we left off.", topic), "timestamp": at(12) },
+                { "role": "assistant", "content": "Sure. Here's where things stand and what I'd do next.", "timestamp": at(11) },
+                { "role": "user", "content": "Sounds good, go ahead.", "timestamp": at(3) },
+                { "role": "assistant", "content": "Done. I've left a short summary of the changes above.", "timestamp": at(2) },
+            ]
+        }))
+    }
+
+    // (status code, body) for a request
+    fn handle(&mut self, method: &str, path: &str) -> (u16, serde_json::Value) {
+        self.tick();
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match (method, segments.as_slice()) {
+            ("GET", ["health"]) => (200, json!({ "ok": true })),
+            ("GET", ["status"]) => (200, serde_json::to_value(self.status()).unwrap_or_default()),
+            ("GET", ["pairings"]) => (200, json!({ "pairings": self.pairings })),
+            ("POST", ["pairings", code, action @ ("approve" | "deny")]) => {
+                let Some(index) = self.pairings.iter().position(|p| p.code == *code) else {
+                    return (404, json!({ "error": "Pairing not found or expired" }));
+                };
+                let request = self.pairings.remove(index);
+                if *action == "deny" {
+                    return (200, json!({ "success": true }));
+                }
+                self.allowlist.push(json!({ "chatKey": request.chat_key, "userInfo": request.user_info }));
+                (200, json!({ "success": true, "chatKey": request.chat_key }))
+            }
+            ("GET", ["sessions"]) => (200, json!({ "sessions": self.sessions })),
+            ("GET", ["sessions", id, "messages"]) => match self.messages(id) {
+                Some(messages) => (200, messages),
+                None => (404, json!({ "error": format!("Session {} not found", id) })),
+            },
+            ("GET", ["allowlist"]) => (200, json!({ "allowlist": self.allowlist })),
+            ("DELETE", ["allowlist", chat_key]) => {
+                let chat_key = chat_key.replace("%3A", ":");
+                self.allowlist.retain(|entry| entry["chatKey"] != chat_key.as_str());
+                (200, json!({ "success": true }))
+            }
+            ("POST", ["stop"]) => {
+                self.started_at = None;
+                (200, json!({ "success": true, "message": "Shutting down..." }))
+            }
+            _ => (
+                404,
+                json!({
+                    "message": format!("Route {}:{} not found", method, path),
+                    "error": "Not Found",
+                    "statusCode": 404,
+                }),
+            ),
+        }
+    }
+}
+
+pub(crate) fn is_enabled() -> bool {
+    *ENABLED.get_or_init(|| std::env::args().any(|a| a == DEMO_FLAG))
+}
+
+// The bots and agents the fake bridge reports; the tokens only have to look real
+fn seed_config(dir: &Path) -> serde_json::Value {
+    let workspace = dir.join("workspace").to_string_lossy().to_string();
+    json!({
+        "agents": {
+            "default": "assistant",
+            "list": [
+                { "id": "assistant", "name": "Assistant", "workspace": workspace },
+                { "id": "reviewer", "name": "Reviewer", "workspace": workspace }
+            ]
+        },
+        "channels": {
+            "telegram": {
+                "enabled": true,
+                "bots": [
+                    { "id": "assistant", "botToken": "100000001:DEMOdemoDEMOdemoDEMOdemoDEMOdemo1", "agentId": "assistant", "dmPolicy": "pairing" },
+                    { "id": "reviewer", "botToken": "100000002:DEMOdemoDEMOdemoDEMOdemoDEMOdemo2", "agentId": "reviewer", "dmPolicy": "pairing" }
+                ]
+            },
+            "discord": {
+                "enabled": true,
+                "bots": [
+                    { "id": "helper", "token": "DEMOdemoDEMOdemoDEMOdemo.DEMOde.DEMOdemoDEMOdemoDEMOdemo", "agentId": "reviewer", "dmPolicy": "pairing" }
+                ]
+            }
+        }
+    })
+}
+
+fn seed(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).map_err(|e| format!("Failed to reset {}: {}", dir.display(), e))?;
+    }
+    fs::create_dir_all(dir.join("workspace")).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let config = serde_json::to_string_pretty(&seed_config(dir)).map_err(|e| e.to_string())?;
+    fs::write(dir.join("config.json"), config).map_err(|e| format!("Failed to write the demo config: {}", e))
+}
+
+// Stands in for ~/.ccb in demo mode
+pub(crate) fn ccb_dir() -> Option<PathBuf> {
+    if !is_enabled() {
+        return None;
+    }
+    let dir = DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join("ccb-desktop-demo");
+        if let Err(e) = seed(&dir) {
+            log_forwarding::desktop_error(e);
+        }
+        dir
+    });
+    Some(dir.clone())
+}
+
+// Base URL of the fake Control API, in demo mode
+pub(crate) fn api_url() -> Option<String> {
+    URL.get().cloned()
+}
+
+fn with_bridge<T>(f: impl FnOnce(&mut FakeBridge) -> T) -> Option<T> {
+    let mut bridge = BRIDGE.lock().ok()?;
+    Some(f(bridge.get_or_insert_with(FakeBridge::new)))
+}
+
+fn is_running() -> bool {
+    with_bridge(|b| b.started_at.is_some()).unwrap_or(false)
+}
+
+// Read one request; returns the method and path, the body is ignored
+async fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return None;
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+    let head = String::from_utf8_lossy(&buf[..head_end]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_BYTES);
+    // Drain the body so the client doesn't see a reset
+    let mut remaining = content_length.saturating_sub(buf.len() - head_end);
+    while remaining > 0 {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        remaining = remaining.saturating_sub(read);
+    }
+
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.split('?').next()?.to_string();
+    Some((method, path))
+}
+
+async fn serve_connection(mut stream: TcpStream) {
+    // A stopped bridge isn't listening; dropping the connection reads the same
+    if !is_running() {
+        return;
+    }
+    let Some((method, path)) = read_request(&mut stream).await else {
+        return;
+    };
+    let Some((code, body)) = with_bridge(|b| b.handle(&method, &path)) else {
+        return;
+    };
+    let body = body.to_string();
+    let reason = match code {
+        200 => "OK",
+        404 => "Not Found",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve(listener: std::net::TcpListener) {
+    let listener = match listener.set_nonblocking(true).and_then(|_| TcpListener::from_std(listener)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log_forwarding::desktop_error(format!("Failed to start the demo Control API: {}", e));
+            return;
+        }
+    };
+    while let Ok((stream, _)) = listener.accept().await {
+        tauri::async_runtime::spawn(serve_connection(stream));
+    }
+}
+
+async fn play_log(state: AppState, run: u64) {
+    let mut lines = ACTIVITY_LOG.iter().cycle();
+    loop {
+        tokio::time::sleep(LOG_INTERVAL).await;
+        if RUN.load(Ordering::SeqCst) != run || !is_running() {
+            return;
+        }
+        if let (Some(line), Ok(mut service)) = (lines.next(), state.lock()) {
+            service.logs.extend_bridge(&[line.to_string()]);
+        }
+    }
+}
+
+// Stands in for launching ccb
+pub(crate) fn start(state: &AppState) -> BridgeStatus {
+    let status = with_bridge(|b| {
+        if b.started_at.is_none() {
+            b.started_at = Some(Instant::now());
+        }
+        b.status()
+    })
+    .unwrap_or_else(|| FakeBridge::new().status());
+    if let Ok(mut service) = state.lock() {
+        service.logs.clear();
+        service.logs.extend_bridge(&STARTUP_LOG.iter().map(|l| l.to_string()).collect::<Vec<_>>());
+        service.is_running = true;
+        service.started_at = Some(Instant::now());
+    }
+    let run = RUN.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(play_log(state.clone(), run));
+    status
+}
+
+pub(crate) fn stop(state: &AppState) {
+    with_bridge(|b| b.started_at = None);
+    if let Ok(mut service) = state.lock() {
+        service.is_running = false;
+        service.logs.extend_bridge(&["[ccb] Shutting down".to_string()]);
+    }
+}
+
+// Serve the fake Control API and bring the demo bridge up
+pub(crate) fn init(app: &AppHandle) -> Result<(), String> {
+    let listener =
+        std::net::TcpListener::bind("127.0.0.1:0").map_err(|e| format!("Failed to start the demo Control API: {}", e))?;
+    let address = listener.local_addr().map_err(|e| e.to_string())?;
+    let _ = URL.set(format!("http://{}", address));
+    tauri::async_runtime::spawn(serve(listener));
+    start(&app.state::<AppState>());
+    Ok(())
+}
@@ -0,0 +1,383 @@
+// Diagnostics.
+//
+// One pass over everything a working bridge depends on: the ccb binary, Node,
+// the config, the Control API, each enabled channel, the Claude CLI's login
+// and free disk space where agents work. Every check runs on its own, so one
+// failure doesn't hide the rest, and says what's wrong and, where there's an
+// obvious fix, what to do. The report renders as a diagnostics page and can
+// be exported as JSON for bug reports, with tokens redacted and the home
+// directory shortened to `~`.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::api_client::{BridgeApiClient, BridgeStatus};
+use crate::config::{expand_home, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::runtime_info::{claude_logged_in, find_executable, probe_output};
+use crate::{config_validation, http, node_runtime, secrets};
+
+// The bridge's `engines.node`
+const MIN_NODE_MAJOR: u64 = 20;
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+const DISK_ERROR_BYTES: u64 = 500 * 1024 * 1024;
+const DISK_WARNING_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    // Couldn't be checked because something it needs is missing
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCheck {
+    // e.g. "ccb", "node", "channel:telegram", "disk"
+    component: String,
+    label: String,
+    status: CheckStatus,
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    generated_at: String,
+    app_version: String,
+    os: String,
+    arch: String,
+    // No check ended in an error
+    healthy: bool,
+    checks: Vec<DiagnosticCheck>,
+}
+
+struct Checks(Vec<DiagnosticCheck>);
+
+impl Checks {
+    fn add(&mut self, component: impl Into<String>, label: impl Into<String>, started: Instant, outcome: Outcome) {
+        self.0.push(DiagnosticCheck {
+            component: component.into(),
+            label: label.into(),
+            status: outcome.status,
+            detail: outcome.detail,
+            hint: outcome.hint,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+struct Outcome {
+    status: CheckStatus,
+    detail: String,
+    hint: Option<String>,
+}
+
+impl Outcome {
+    fn ok(detail: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Ok, detail, None)
+    }
+
+    fn new(status: CheckStatus, detail: impl Into<String>, hint: Option<&str>) -> Self {
+        Self {
+            status,
+            detail: detail.into(),
+            hint: hint.map(String::from),
+        }
+    }
+}
+
+async fn check_ccb() -> Outcome {
+    match find_executable("ccb") {
+        Some(ccb) => match probe_output(&ccb, &["--version"]).await {
+            Some(version) => Outcome::ok(format!("ccb {} at {}", version, ccb.display())),
+            None => Outcome::new(
+                CheckStatus::Error,
+                format!("{} doesn't run", ccb.display()),
+                Some("Reinstall ccb from the setup page"),
+            ),
+        },
+        None if find_executable("npx").is_some() => Outcome::new(
+            CheckStatus::Warning,
+            "ccb isn't installed; the bridge will be started through npx",
+            Some("Install ccb from the setup page for faster starts"),
+        ),
+        None => Outcome::new(CheckStatus::Error, "ccb isn't installed", Some("Install Node.js, then ccb from the setup page")),
+    }
+}
+
+async fn check_node() -> Outcome {
+    let Some(node) = find_executable("node") else {
+        return Outcome::new(CheckStatus::Error, "Node.js isn't installed", Some("Install Node.js 20 or newer"));
+    };
+    let Some(version) = probe_output(&node, &["--version"]).await else {
+        return Outcome::new(CheckStatus::Error, format!("{} doesn't run", node.display()), None);
+    };
+    match node_runtime::parse_version(&version).and_then(|v| v.first().copied()) {
+        Some(major) if major < MIN_NODE_MAJOR => Outcome::new(
+            CheckStatus::Error,
+            format!("Node.js {} at {} is too old; the bridge needs {} or newer", version, node.display(), MIN_NODE_MAJOR),
+            Some("Install a newer Node.js, or pick one in the app settings"),
+        ),
+        _ => Outcome::ok(format!("Node.js {} at {}", version, node.display())),
+    }
+}
+
+fn check_config(config: Option<&serde_json::Value>, read_error: Option<String>) -> Outcome {
+    if let Some(e) = read_error {
+        return Outcome::new(CheckStatus::Error, e, None);
+    }
+    let Some(config) = config else {
+        return Outcome::new(CheckStatus::Error, "No config file", Some("Create one from the setup page"));
+    };
+    let errors = config_validation::validate(config);
+    match errors.as_slice() {
+        [] => Outcome::ok("Config is valid"),
+        [first, ..] => Outcome::new(
+            CheckStatus::Error,
+            format!("{} problem(s), e.g. {}: {}", errors.len(), first.path, first.message),
+            Some("Open the config editor to see all of them"),
+        ),
+    }
+}
+
+// Where `channel` is served from, for telling network trouble from bot trouble
+fn platform_url(channel: &str) -> Option<&'static str> {
+    match channel {
+        "telegram" => Some("https://api.telegram.org"),
+        "discord" => Some("https://discord.com/api/v10/gateway"),
+        _ => None,
+    }
+}
+
+// Any HTTP answer counts; only failing to connect is a problem
+async fn platform_reachable(url: &str) -> Result<(), String> {
+    http::client()
+        .get(url)
+        .timeout(NETWORK_TIMEOUT)
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+async fn check_channel(channel: &str, status: Option<&BridgeStatus>) -> Outcome {
+    let reported = status.and_then(|s| s.channels.iter().find(|c| c.name == channel));
+    match reported {
+        Some(c) if c.connected => return Outcome::ok(format!("Connected with {} bot(s)", c.bot_count)),
+        Some(_) => {}
+        None if status.is_some() => {
+            return Outcome::new(CheckStatus::Warning, "Enabled in the config, but the bridge doesn't report it", Some("Restart the bridge"))
+        }
+        None => {}
+    }
+
+    let network = match platform_url(channel) {
+        Some(url) => platform_reachable(url).await.map_err(|e| format!("{} isn't reachable: {}", url, e)),
+        None => Ok(()),
+    };
+    match (reported, network) {
+        (_, Err(e)) => Outcome::new(CheckStatus::Error, e, Some("Check the network and the proxy settings")),
+        (Some(_), Ok(())) => Outcome::new(
+            CheckStatus::Error,
+            "The bridge isn't connected, though the platform is reachable",
+            Some("Check the bot token and the bridge log"),
+        ),
+        (None, Ok(())) => Outcome::new(CheckStatus::Skipped, "The bridge isn't running", None),
+    }
+}
+
+async fn check_claude() -> Outcome {
+    let Some(claude) = find_executable("claude") else {
+        return Outcome::new(
+            CheckStatus::Error,
+            "The Claude Code CLI isn't installed",
+            Some("Install it with npm install -g @anthropic-ai/claude-code"),
+        );
+    };
+    let version = probe_output(&claude, &["--version"]).await.unwrap_or_else(|| "unknown version".to_string());
+    if claude_logged_in() {
+        Outcome::ok(format!("{} at {}, signed in", version, claude.display()))
+    } else {
+        Outcome::new(
+            CheckStatus::Error,
+            format!("{} at {} isn't signed in", version, claude.display()),
+            Some("Run claude in a terminal and log in"),
+        )
+    }
+}
+
+// Bytes available to us on the filesystem holding `path`
+#[cfg(unix)]
+fn free_space(path: &Path) -> Result<u64, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    // The fields are narrower than u64 on some platforms
+    #[allow(clippy::useless_conversion)]
+    let available = u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize));
+    Ok(available)
+}
+
+#[cfg(windows)]
+fn free_space(path: &Path) -> Result<u64, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) } == 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(available)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let gb = bytes as f64 / (1024.0 * 1024.0 * 1024.0);
+    if gb >= 1.0 {
+        format!("{:.1} GB", gb)
+    } else {
+        format!("{} MB", bytes / (1024 * 1024))
+    }
+}
+
+// Agent workspaces, or the home directory when there are none
+fn workspaces(config: Option<&serde_json::Value>) -> BTreeSet<PathBuf> {
+    let mut dirs: BTreeSet<PathBuf> = config
+        .and_then(|c| c.pointer("/agents/list"))
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|agent| agent.get("workspace").and_then(|w| w.as_str()))
+        .map(expand_home)
+        .collect();
+    if dirs.is_empty() {
+        dirs.extend(dirs::home_dir());
+    }
+    dirs
+}
+
+fn check_disk(path: &Path) -> Outcome {
+    // A workspace that doesn't exist yet lands on its parent's filesystem
+    let Some(existing) = path.ancestors().find(|p| p.exists()) else {
+        return Outcome::new(CheckStatus::Error, format!("{} doesn't exist", path.display()), None);
+    };
+    match free_space(existing) {
+        Ok(bytes) if bytes < DISK_ERROR_BYTES => Outcome::new(
+            CheckStatus::Error,
+            format!("Only {} free", format_bytes(bytes)),
+            Some("Free up disk space; agents and the bridge's logs need room to write"),
+        ),
+        Ok(bytes) if bytes < DISK_WARNING_BYTES => {
+            Outcome::new(CheckStatus::Warning, format!("{} free", format_bytes(bytes)), Some("Disk space is running low"))
+        }
+        Ok(bytes) => Outcome::ok(format!("{} free", format_bytes(bytes))),
+        Err(e) => Outcome::new(CheckStatus::Error, format!("Failed to read free space: {}", e), None),
+    }
+}
+
+fn enabled_channels(config: Option<&serde_json::Value>) -> Vec<String> {
+    let Some(channels) = config.and_then(|c| c.get("channels")).and_then(|c| c.as_object()) else {
+        return vec![];
+    };
+    channels
+        .iter()
+        .filter(|(_, section)| section.get("enabled").and_then(|e| e.as_bool()).unwrap_or(false))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+async fn run(store: &ConfigStore, api: &BridgeApiClient) -> DiagnosticsReport {
+    let mut checks = Checks(Vec::new());
+
+    let started = Instant::now();
+    checks.add("ccb", "ccb binary", started, check_ccb().await);
+
+    let started = Instant::now();
+    checks.add("node", "Node.js", started, check_node().await);
+
+    let started = Instant::now();
+    let (config, read_error) = match store.read() {
+        Ok(config) => (config, None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    checks.add("config", "Config", started, check_config(config.as_ref(), read_error));
+
+    let started = Instant::now();
+    let status = api.status().await;
+    let outcome = match status {
+        Ok(ref status) => Outcome::ok(format!("Answering, up for {}s", status.uptime / 1000)),
+        Err(ref e) => Outcome::new(CheckStatus::Error, e.to_string(), Some("Start the bridge")),
+    };
+    checks.add("controlApi", "Control API", started, outcome);
+
+    let status = status.ok();
+    for channel in enabled_channels(config.as_ref()) {
+        let started = Instant::now();
+        let outcome = check_channel(&channel, status.as_ref()).await;
+        checks.add(format!("channel:{}", channel), format!("Channel {}", channel), started, outcome);
+    }
+
+    let started = Instant::now();
+    checks.add("claude", "Claude Code CLI", started, check_claude().await);
+
+    for workspace in workspaces(config.as_ref()) {
+        let started = Instant::now();
+        checks.add("disk", format!("Disk space for {}", workspace.display()), started, check_disk(&workspace));
+    }
+
+    DiagnosticsReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        healthy: checks.0.iter().all(|c| c.status != CheckStatus::Error),
+        checks: checks.0,
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn run_diagnostics(
+    store: State<'_, ConfigStore>,
+    api: State<'_, BridgeApiClient>,
+) -> CommandResult<DiagnosticsReport> {
+    Ok(run(&store, &api).await)
+}
+
+// Runs the checks and writes the report to `path` as JSON; returns the path
+#[tauri::command]
+pub(crate) async fn export_diagnostics(
+    store: State<'_, ConfigStore>,
+    api: State<'_, BridgeApiClient>,
+    path: String,
+) -> CommandResult<String> {
+    let report = run(&store, &api).await;
+    let mut json = serde_json::to_string_pretty(&report).map_err(|e| Error::Other(e.to_string()))?;
+    // Paths name the user; tokens can turn up in error messages
+    if let Some(home) = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).filter(|h| h.len() > 1) {
+        let escaped = serde_json::to_string(&home).unwrap_or_default();
+        json = json.replace(escaped.trim_matches('"'), "~");
+    }
+    let json = secrets::redact_text(&json);
+
+    let path = expand_home(&path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create report dir: {}", e))?;
+    }
+    fs::write(&path, json).map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
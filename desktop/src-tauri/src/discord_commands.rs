@@ -0,0 +1,223 @@
+// Discord slash command registration.
+//
+// Registers the bridge's chat commands (/agent, /new, /status, ...) as the
+// bot's slash commands through Discord's REST API, using the bot's own token,
+// and compares what Discord has on record against what the bridge offers.
+// Commands can be registered globally or for a single guild, where changes
+// show up immediately instead of after Discord's propagation delay. The
+// bridge's Discord adapter answers the resulting interactions like the same
+// command typed as a message.
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::discord_scope::{bot_token, discord_get, DISCORD_API, REQUEST_TIMEOUT};
+use crate::http;
+
+// Option type for a free-form string argument
+const STRING_OPTION: u8 = 3;
+
+// (name, description, optional argument name and description)
+pub(crate) type CommandSpec = (&'static str, &'static str, Option<(&'static str, &'static str)>);
+
+// Mirrors the bridge's command handlers
+pub(crate) const BRIDGE_COMMANDS: &[CommandSpec] = &[
+    ("help", "Show available commands", None),
+    ("status", "Show session status", None),
+    ("whoami", "Show your user info", None),
+    ("ping", "Check if the bot is responsive", None),
+    ("new", "Start a fresh session", None),
+    ("sessions", "List your active sessions", None),
+    ("session", "Switch to or create a named session", Some(("name", "Session name, or 'new <name>'"))),
+    ("delete", "Delete a session", Some(("name", "Session name"))),
+    ("agent", "Show or switch agent", Some(("agent-id", "Agent to switch to"))),
+    ("agents", "List all available agents", None),
+    ("model", "Show or change the model (per-session)", Some(("model-name", "Model to use"))),
+    ("workspace", "Show current workspace", None),
+    ("compact", "Summarize context to save tokens (not yet implemented)", None),
+    ("stop", "Stop current response (not yet implemented)", None),
+    ("abort", "Abort and reset current session", None),
+    ("clear", "Clear all sessions and start fresh", None),
+    ("skills", "List available Claude Code skills", None),
+    ("plugins", "List installed Claude Code plugins", None),
+    ("mcp", "Show MCP (Model Context Protocol) server status", None),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CommandOption {
+    #[serde(rename = "type")]
+    kind: u8,
+    name: String,
+    description: String,
+    #[serde(default)]
+    required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ApplicationCommand {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    name: String,
+    description: String,
+    #[serde(default)]
+    options: Vec<CommandOption>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Application {
+    id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommand {
+    id: String,
+    name: String,
+    description: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandState {
+    application_id: String,
+    guild_id: Option<String>,
+    registered: Vec<SlashCommand>,
+    // Bridge commands Discord doesn't know about
+    missing: Vec<String>,
+    // Registered with a different description or arguments
+    outdated: Vec<String>,
+    // Registered but not offered by the bridge
+    extra: Vec<String>,
+}
+
+fn bridge_commands() -> Vec<ApplicationCommand> {
+    BRIDGE_COMMANDS
+        .iter()
+        .map(|(name, description, option)| ApplicationCommand {
+            id: None,
+            name: name.to_string(),
+            description: description.to_string(),
+            options: option
+                .iter()
+                .map(|(name, description)| CommandOption {
+                    kind: STRING_OPTION,
+                    name: name.to_string(),
+                    description: description.to_string(),
+                    required: false,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn commands_path(application_id: &str, guild_id: Option<&str>) -> String {
+    match guild_id {
+        Some(guild) => format!("/applications/{}/guilds/{}/commands", application_id, guild),
+        None => format!("/applications/{}/commands", application_id),
+    }
+}
+
+fn compare(application_id: String, guild_id: Option<String>, registered: Vec<ApplicationCommand>) -> SlashCommandState {
+    let wanted = bridge_commands();
+    let mut missing = Vec::new();
+    let mut outdated = Vec::new();
+    for command in &wanted {
+        match registered.iter().find(|r| r.name == command.name) {
+            None => missing.push(command.name.clone()),
+            Some(r) if r.description != command.description || r.options != command.options => {
+                outdated.push(command.name.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    let extra = registered
+        .iter()
+        .filter(|r| !wanted.iter().any(|w| w.name == r.name))
+        .map(|r| r.name.clone())
+        .collect();
+
+    SlashCommandState {
+        application_id,
+        guild_id,
+        registered: registered
+            .into_iter()
+            .map(|r| SlashCommand {
+                id: r.id.unwrap_or_default(),
+                name: r.name,
+                description: r.description,
+            })
+            .collect(),
+        missing,
+        outdated,
+        extra,
+    }
+}
+
+async fn application_id(client: &reqwest::Client, token: &str) -> Result<String, String> {
+    let application: Application = discord_get(client, token, "/applications/@me").await?;
+    Ok(application.id)
+}
+
+#[tauri::command]
+pub(crate) async fn get_discord_commands(
+    store: State<'_, ConfigStore>,
+    bot_id: Option<String>,
+    guild_id: Option<String>,
+) -> Result<SlashCommandState, String> {
+    let config = store.read()?.ok_or("Config file not found")?;
+    let token = bot_token(&config, bot_id.as_deref())?;
+    let client = http::client();
+
+    let application_id = application_id(&client, &token).await?;
+    let registered: Vec<ApplicationCommand> =
+        discord_get(&client, &token, &commands_path(&application_id, guild_id.as_deref())).await?;
+    Ok(compare(application_id, guild_id, registered))
+}
+
+// Creates or updates the bridge's commands that are missing or outdated, one
+// at a time; commands registered some other way are left alone
+#[tauri::command]
+pub(crate) async fn register_discord_commands(
+    store: State<'_, ConfigStore>,
+    bot_id: Option<String>,
+    guild_id: Option<String>,
+) -> Result<SlashCommandState, String> {
+    let config = store.read()?.ok_or("Config file not found")?;
+    let token = bot_token(&config, bot_id.as_deref())?;
+    let client = http::client();
+
+    let application_id = application_id(&client, &token).await?;
+    let path = commands_path(&application_id, guild_id.as_deref());
+    let registered: Vec<ApplicationCommand> = discord_get(&client, &token, &path).await?;
+    let state = compare(application_id.clone(), guild_id.clone(), registered);
+
+    // Posting a command with an existing name updates it in place
+    for command in bridge_commands() {
+        if !state.missing.contains(&command.name) && !state.outdated.contains(&command.name) {
+            continue;
+        }
+        let response = client
+            .post(format!("{}{}", DISCORD_API, path))
+            .header("Authorization", format!("Bot {}", token))
+            .json(&command)
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Discord: {}", e))?;
+        match response.status() {
+            status if status.is_success() => {}
+            reqwest::StatusCode::UNAUTHORIZED => return Err("Discord rejected the bot token".to_string()),
+            reqwest::StatusCode::FORBIDDEN => {
+                return Err("The bot isn't allowed to create commands in this server (applications.commands scope)".to_string())
+            }
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("Discord returned {} for /{}: {}", status, command.name, body));
+            }
+        }
+    }
+
+    let registered: Vec<ApplicationCommand> = discord_get(&client, &token, &path).await?;
+    Ok(compare(application_id, guild_id, registered))
+}
@@ -0,0 +1,177 @@
+// Discord server and channel scoping.
+//
+// Lists the guilds and text channels a bot can see, using its own token against
+// Discord's REST API, and stores which of them the bot should respond in as a
+// `scope` object on the bot's config entry. An empty scope means everywhere.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::groups::{bot_section, bot_section_mut};
+use crate::{http, keychain};
+
+pub(crate) const DISCORD_API: &str = "https://discord.com/api/v10";
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Channel types worth scoping to: text, announcement and forum channels
+const TEXT_CHANNEL_TYPES: [u8; 3] = [0, 5, 15];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscordScope {
+    #[serde(default)]
+    guilds: Vec<String>,
+    // Channel IDs within the scoped guilds; empty allows every channel
+    #[serde(default)]
+    channels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ApiGuild {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ApiChannel {
+    id: String,
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: u8,
+    parent_id: Option<String>,
+    position: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscordChannel {
+    id: String,
+    name: String,
+    category_id: Option<String>,
+    in_scope: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscordGuild {
+    id: String,
+    name: String,
+    in_scope: bool,
+    channels: Vec<DiscordChannel>,
+    // Set when the channel list couldn't be fetched, e.g. missing permissions
+    error: Option<String>,
+}
+
+pub(crate) fn bot_token(config: &serde_json::Value, bot_id: Option<&str>) -> Result<String, String> {
+    let section = bot_section(config, "discord", bot_id).ok_or(match bot_id {
+        Some(id) => format!("Discord bot '{}' not found", id),
+        None => "Discord is not configured".to_string(),
+    })?;
+    section
+        .get("token")
+        .and_then(|t| t.as_str())
+        .map(keychain::resolve)
+        .filter(|t| !t.is_empty())
+        .ok_or("Discord bot has no token".to_string())
+}
+
+pub(crate) async fn discord_get<T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    token: &str,
+    path: &str,
+) -> Result<T, String> {
+    let response = client
+        .get(format!("{}{}", DISCORD_API, path))
+        .header("Authorization", format!("Bot {}", token))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Discord: {}", e))?;
+
+    match response.status() {
+        status if status.is_success() => response.json().await.map_err(|e| e.to_string()),
+        reqwest::StatusCode::UNAUTHORIZED => Err("Discord rejected the bot token".to_string()),
+        reqwest::StatusCode::FORBIDDEN => Err("The bot lacks permission to view this".to_string()),
+        status => Err(format!("Discord returned {}", status)),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_discord_guilds(
+    store: State<'_, ConfigStore>,
+    bot_id: Option<String>,
+) -> Result<Vec<DiscordGuild>, String> {
+    let config = store.read()?.ok_or("Config file not found")?;
+    let token = bot_token(&config, bot_id.as_deref())?;
+    let scope: DiscordScope = bot_section(&config, "discord", bot_id.as_deref())
+        .and_then(|s| s.get("scope"))
+        .and_then(|s| serde_json::from_value(s.clone()).ok())
+        .unwrap_or_default();
+
+    let client = http::client();
+    let guilds: Vec<ApiGuild> = discord_get(&client, &token, "/users/@me/guilds").await?;
+
+    let mut result = Vec::new();
+    for guild in guilds {
+        let in_scope = scope.guilds.is_empty() || scope.guilds.contains(&guild.id);
+        let (channels, error) =
+            match discord_get::<Vec<ApiChannel>>(&client, &token, &format!("/guilds/{}/channels", guild.id)).await {
+                Ok(mut channels) => {
+                    channels.retain(|c| TEXT_CHANNEL_TYPES.contains(&c.kind));
+                    channels.sort_by_key(|c| c.position.unwrap_or(0));
+                    let channels = channels
+                        .into_iter()
+                        .map(|c| DiscordChannel {
+                            in_scope: in_scope && (scope.channels.is_empty() || scope.channels.contains(&c.id)),
+                            name: c.name.unwrap_or_else(|| c.id.clone()),
+                            id: c.id,
+                            category_id: c.parent_id,
+                        })
+                        .collect();
+                    (channels, None)
+                }
+                Err(e) => (vec![], Some(e)),
+            };
+
+        result.push(DiscordGuild {
+            id: guild.id,
+            name: guild.name,
+            in_scope,
+            channels,
+            error,
+        });
+    }
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub(crate) fn set_discord_scope(
+    store: State<'_, ConfigStore>,
+    bot_id: Option<String>,
+    scope: DiscordScope,
+) -> Result<bool, String> {
+    let is_snowflake = |id: &String| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+    if let Some(bad) = scope.guilds.iter().chain(&scope.channels).find(|id| !is_snowflake(id)) {
+        return Err(format!("Invalid Discord ID '{}'", bad));
+    }
+
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let section = bot_section_mut(&mut config, "discord", bot_id.as_deref())
+        .and_then(|s| s.as_object_mut())
+        .ok_or(match bot_id {
+            Some(ref id) => format!("Discord bot '{}' not found", id),
+            None => "Discord is not configured".to_string(),
+        })?;
+
+    if scope.guilds.is_empty() && scope.channels.is_empty() {
+        section.remove("scope");
+    } else {
+        section.insert("scope".to_string(), serde_json::to_value(&scope).map_err(|e| e.to_string())?);
+    }
+
+    store.write(&config)?;
+    Ok(true)
+}
@@ -0,0 +1,58 @@
+// Error type shared by the command modules.
+//
+// The frontend has always received a plain message when a command fails, so
+// an `Error` serializes to its message. The variants let Rust callers tell a
+// broken config from an unreachable bridge without matching on text. Modules
+// still returning `Result<_, String>` can `?` an `Error`, and vice versa.
+
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug)]
+pub(crate) enum Error {
+    // config.json is missing, unreadable or malformed
+    Config(String),
+    // The Control API couldn't be reached or refused the request
+    Bridge(String),
+    // Bad input from the frontend
+    Invalid(String),
+    Other(String),
+}
+
+pub(crate) type CommandResult<T> = Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(message) | Error::Bridge(message) | Error::Invalid(message) | Error::Other(message) => {
+                f.write_str(message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Other(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Self {
+        Error::Other(message.to_string())
+    }
+}
+
+impl From<Error> for String {
+    fn from(error: Error) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,130 @@
+// Bridge events pushed to the UI.
+//
+// Bridges that serve `/events` as Server-Sent Events are subscribed to, and
+// their events are re-emitted as Tauri events the moment they arrive: a status
+// change wakes the poller, which emits `bridge://status-changed` with the
+// fresh status, while `bridge://pairing-requested`, `bridge://session-started`,
+// session plan and WhatsApp link changes are forwarded as they are. Older
+// bridges without the endpoint get the same events from polling: the poller
+// notices new pairings and this task watches /sessions for new sessions.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::api_client::{ApiError, BridgeApiClient, SessionSummary};
+use crate::poller::StatusCache;
+use crate::{session_plans, whatsapp};
+
+pub(crate) const PAIRING_REQUESTED_EVENT: &str = "bridge://pairing-requested";
+pub(crate) const SESSION_STARTED_EVENT: &str = "bridge://session-started";
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const SESSION_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// How long to poll before checking whether the bridge gained `/events`, e.g.
+// after an upgrade
+const STREAM_RETRY_AFTER: Duration = Duration::from_secs(5 * 60);
+
+static STREAMING: AtomicBool = AtomicBool::new(false);
+
+// Whether events currently arrive from the bridge itself; the poller only
+// synthesizes them when they don't
+pub(crate) fn is_streaming() -> bool {
+    STREAMING.load(Ordering::Relaxed)
+}
+
+fn dispatch(app: &AppHandle, event: &str, data: &str) {
+    let payload: serde_json::Value = serde_json::from_str(data).unwrap_or(serde_json::Value::Null);
+    // Unnamed events carry their name in the payload
+    let name = match event {
+        "" | "message" => payload.get("type").and_then(|t| t.as_str()).unwrap_or_default(),
+        name => name,
+    };
+    match name {
+        "status" | "status.changed" => app.state::<StatusCache>().request_refresh(),
+        "pairing.requested" => {
+            let _ = app.emit(PAIRING_REQUESTED_EVENT, payload);
+            app.state::<StatusCache>().request_refresh();
+        }
+        "session.started" => {
+            let _ = app.emit(SESSION_STARTED_EVENT, payload);
+        }
+        "session.plan" => session_plans::emit_plan(app, payload),
+        "whatsapp.state" => whatsapp::emit_link_state(app, payload),
+        _ => {}
+    }
+}
+
+// Read the event stream until it ends
+async fn stream(app: &AppHandle, api: &BridgeApiClient) -> Result<(), ApiError> {
+    let response = api
+        .http()
+        .get(BridgeApiClient::url("/events"))
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .send()
+        .await?;
+    let mut response = BridgeApiClient::check(response).await?;
+    STREAMING.store(true, Ordering::Relaxed);
+    // Anything missed while disconnected
+    app.state::<StatusCache>().request_refresh();
+
+    // Bytes, so a character split across chunks is decoded once it's whole
+    let mut buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        buf.extend(chunk.iter().filter(|b| **b != b'\r'));
+        // Events end with a blank line
+        while let Some(end) = buf.windows(2).position(|w| w == b"\n\n") {
+            let bytes: Vec<u8> = buf.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&bytes);
+            let mut event = "";
+            let mut data = Vec::new();
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    event = value.trim();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data.push(value.trim_start());
+                }
+            }
+            if !data.is_empty() {
+                dispatch(app, event, &data.join("\n"));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Emit session-started for sessions that weren't there last time
+async fn poll_sessions(app: &AppHandle, api: &BridgeApiClient, known: &mut Option<HashSet<String>>) {
+    let Ok(body) = api.get::<serde_json::Value>("/sessions").await else {
+        return;
+    };
+    let sessions: Vec<SessionSummary> = serde_json::from_value(body["sessions"].clone()).unwrap_or_default();
+    if let Some(known) = known.as_ref() {
+        for session in sessions.iter().filter(|s| !known.contains(&s.id)) {
+            let _ = app.emit(SESSION_STARTED_EVENT, session);
+        }
+    }
+    *known = Some(sessions.into_iter().map(|s| s.id).collect());
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    let api = app.state::<BridgeApiClient>().inner().clone();
+    let mut known = None;
+    loop {
+        let result = stream(&app, &api).await;
+        STREAMING.store(false, Ordering::Relaxed);
+
+        match result {
+            Err(ApiError::Unsupported(_)) => {
+                let until = Instant::now() + STREAM_RETRY_AFTER;
+                while Instant::now() < until {
+                    poll_sessions(&app, &api, &mut known).await;
+                    tokio::time::sleep(SESSION_POLL_INTERVAL).await;
+                }
+            }
+            // Bridge stopped or the connection dropped
+            _ => tokio::time::sleep(RECONNECT_DELAY).await,
+        }
+    }
+}
@@ -0,0 +1,368 @@
+// Importing from other ccb configs.
+//
+// The bridge reads ~/.ccb/config.json, but people who used the CLI before the
+// app often have configs elsewhere: a project-local `.ccb/config.json`, or a
+// file they pointed the CLI at with CCB_CONFIG or CCB_HOME. Those are found
+// here, with each of their agents and bots compared against the main config,
+// and can be merged into it. An id the main config already uses is skipped,
+// overwritten or imported under a new id, as the user chooses.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::config::{expand_home, get_config_path, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::{config_validation, keychain};
+
+const CHANNELS: [(&str, &str); 2] = [("telegram", "botToken"), ("discord", "token")];
+// Bot id of a channel's legacy single-token setup
+const LEGACY_BOT_ID: &str = "main";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Conflict {
+    // Not in the main config
+    None,
+    // Already there, identical
+    Same,
+    // The id is taken by something else
+    Different,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    Skip,
+    Overwrite,
+    // Import under the id with a numbered suffix
+    Rename,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundItem {
+    id: String,
+    // Agent name, or the channel for bots
+    label: String,
+    conflict: Conflict,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundSetup {
+    path: String,
+    // "env", "xdg" or "project"
+    source: &'static str,
+    agents: Vec<FoundItem>,
+    bots: Vec<FoundItem>,
+    // Set when the file couldn't be read or parsed
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    imported_agents: Vec<String>,
+    imported_bots: Vec<String>,
+    skipped: Vec<String>,
+    // (old id, new id) for renamed items
+    renamed: Vec<(String, String)>,
+}
+
+// Config files to look at, with where they were found, main config excluded
+fn candidates(config: Option<&Value>, extra_dirs: &[String]) -> Vec<(PathBuf, &'static str)> {
+    let mut found: Vec<(PathBuf, &'static str)> = Vec::new();
+    if let Some(path) = std::env::var_os("CCB_CONFIG") {
+        found.push((PathBuf::from(path), "env"));
+    }
+    if let Some(home) = std::env::var_os("CCB_HOME") {
+        found.push((PathBuf::from(home).join("config.json"), "env"));
+    }
+    if let Some(config_dir) = dirs::config_dir() {
+        found.push((config_dir.join("ccb").join("config.json"), "xdg"));
+    }
+
+    // Projects: agent workspaces, folders Claude Code has been used in, and
+    // any the user added
+    let mut projects: Vec<PathBuf> = extra_dirs.iter().map(|d| expand_home(d)).collect();
+    let workspaces = config
+        .and_then(|c| c.get("agents"))
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.get("workspace").and_then(|w| w.as_str()));
+    projects.extend(workspaces.map(expand_home));
+    if let Some(home) = dirs::home_dir() {
+        let claude_projects = fs::read_to_string(home.join(".claude.json"))
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+            .and_then(|v| v.get("projects").and_then(|p| p.as_object()).map(|p| p.keys().cloned().collect::<Vec<_>>()))
+            .unwrap_or_default();
+        projects.extend(claude_projects.into_iter().map(PathBuf::from));
+    }
+    found.extend(projects.into_iter().map(|p| (p.join(".ccb").join("config.json"), "project")));
+
+    let main = fs::canonicalize(get_config_path()).unwrap_or_else(|_| get_config_path());
+    let mut seen = Vec::new();
+    found.retain(|(path, _)| {
+        let Ok(resolved) = fs::canonicalize(path) else {
+            return false;
+        };
+        if resolved == main || seen.contains(&resolved) {
+            return false;
+        }
+        seen.push(resolved);
+        true
+    });
+    found
+}
+
+fn agents(config: &Value) -> Vec<Value> {
+    config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+// (channel, bot) pairs; a legacy single token becomes a bot with id "main"
+fn bots(config: &Value) -> Vec<(&'static str, Value)> {
+    let mut found = Vec::new();
+    for (channel, token_key) in CHANNELS {
+        let Some(section) = config.get("channels").and_then(|c| c.get(channel)) else {
+            continue;
+        };
+        if let Some(list) = section.get("bots").and_then(|b| b.as_array()) {
+            found.extend(list.iter().map(|bot| (channel, bot.clone())));
+        } else if let Some(token) = section.get(token_key).and_then(|t| t.as_str()).filter(|t| !t.is_empty()) {
+            found.push((channel, serde_json::json!({ "id": LEGACY_BOT_ID, token_key: token })));
+        }
+    }
+    found
+}
+
+fn id_of(value: &Value) -> &str {
+    value.get("id").and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+fn conflict(existing: Option<&Value>, incoming: &Value) -> Conflict {
+    match existing {
+        None => Conflict::None,
+        Some(existing) if existing == incoming => Conflict::Same,
+        Some(_) => Conflict::Different,
+    }
+}
+
+fn read_setup(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read config: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))
+}
+
+fn describe(path: PathBuf, source: &'static str, main: &Value) -> FoundSetup {
+    let mut setup = FoundSetup {
+        path: path.to_string_lossy().to_string(),
+        source,
+        agents: vec![],
+        bots: vec![],
+        error: None,
+    };
+    let config = match read_setup(&path) {
+        Ok(config) => config,
+        Err(e) => {
+            setup.error = Some(e);
+            return setup;
+        }
+    };
+
+    let main_agents = agents(main);
+    setup.agents = agents(&config)
+        .iter()
+        .map(|agent| FoundItem {
+            id: id_of(agent).to_string(),
+            label: agent.get("name").and_then(|n| n.as_str()).unwrap_or_default().to_string(),
+            conflict: conflict(main_agents.iter().find(|a| id_of(a) == id_of(agent)), agent),
+        })
+        .collect();
+    let main_bots = bots(main);
+    setup.bots = bots(&config)
+        .iter()
+        .map(|(channel, bot)| FoundItem {
+            id: id_of(bot).to_string(),
+            label: channel.to_string(),
+            conflict: conflict(
+                main_bots.iter().find(|(c, b)| c == channel && id_of(b) == id_of(bot)).map(|(_, b)| b),
+                bot,
+            ),
+        })
+        .collect();
+    setup
+}
+
+// `id` with the lowest numbered suffix not in `taken`
+fn free_id(id: &str, taken: &[String]) -> String {
+    (2..)
+        .map(|n| format!("{}-{}", id, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_default()
+}
+
+// Where an incoming item goes: None to skip it, else its id and whether it
+// replaces an existing entry
+fn place(
+    id: &str,
+    existing: Option<&Value>,
+    incoming: &Value,
+    taken: &[String],
+    on_conflict: ConflictResolution,
+    summary: &mut ImportSummary,
+) -> Option<(String, bool)> {
+    match (conflict(existing, incoming), on_conflict) {
+        (Conflict::None, _) => Some((id.to_string(), false)),
+        (Conflict::Same, _) | (Conflict::Different, ConflictResolution::Skip) => {
+            summary.skipped.push(id.to_string());
+            None
+        }
+        (Conflict::Different, ConflictResolution::Overwrite) => Some((id.to_string(), true)),
+        (Conflict::Different, ConflictResolution::Rename) => {
+            let new_id = free_id(id, taken);
+            summary.renamed.push((id.to_string(), new_id.clone()));
+            Some((new_id, false))
+        }
+    }
+}
+
+fn merge_agents(
+    main: &mut Value,
+    incoming: Vec<Value>,
+    on_conflict: ConflictResolution,
+    summary: &mut ImportSummary,
+) -> CommandResult<()> {
+    let list = main
+        .pointer_mut("/agents/list")
+        .and_then(|l| l.as_array_mut())
+        .ok_or("Invalid config structure")?;
+    for mut agent in incoming {
+        let id = id_of(&agent).to_string();
+        let taken: Vec<String> = list.iter().map(|a| id_of(a).to_string()).collect();
+        let existing = list.iter().position(|a| id_of(a) == id);
+        let Some((new_id, replace)) = place(&id, existing.map(|i| &list[i]), &agent, &taken, on_conflict, summary)
+        else {
+            continue;
+        };
+        agent["id"] = Value::String(new_id.clone());
+        match existing {
+            Some(i) if replace => list[i] = agent,
+            _ => list.push(agent),
+        }
+        summary.imported_agents.push(new_id);
+    }
+    Ok(())
+}
+
+fn merge_bots(
+    main: &mut Value,
+    incoming: Vec<(&'static str, Value)>,
+    on_conflict: ConflictResolution,
+    summary: &mut ImportSummary,
+) -> CommandResult<()> {
+    let renamed_agents = summary.renamed.clone();
+    for (channel, mut bot) in incoming {
+        // Keep bots pointing at their agent when it was imported under a new id
+        if let Some(agent_id) = bot.get("agentId").and_then(|a| a.as_str()) {
+            if let Some((_, new_id)) = renamed_agents.iter().find(|(old, _)| old == agent_id) {
+                bot["agentId"] = Value::String(new_id.clone());
+            }
+        }
+
+        // Existing bots, with a legacy single token turned into a bots entry
+        let existing_bots = bots(main).into_iter().filter(|(c, _)| *c == channel).map(|(_, b)| b).collect::<Vec<_>>();
+        let token_key = CHANNELS.iter().find(|(c, _)| *c == channel).map(|(_, k)| *k).unwrap_or("token");
+        let channels = main
+            .as_object_mut()
+            .ok_or("Invalid config structure")?
+            .entry("channels")
+            .or_insert_with(|| serde_json::json!({}));
+        let section = channels
+            .as_object_mut()
+            .ok_or("Invalid config structure")?
+            .entry(channel)
+            .or_insert_with(|| serde_json::json!({ "enabled": true }));
+        let section = section.as_object_mut().ok_or("Invalid config structure")?;
+        section.remove(token_key);
+        section.insert("bots".to_string(), Value::Array(existing_bots));
+        let list = section.get_mut("bots").and_then(|b| b.as_array_mut()).ok_or("Invalid config structure")?;
+
+        let id = id_of(&bot).to_string();
+        let taken: Vec<String> = list.iter().map(|b| id_of(b).to_string()).collect();
+        let existing = list.iter().position(|b| id_of(b) == id);
+        let Some((new_id, replace)) = place(&id, existing.map(|i| &list[i]), &bot, &taken, on_conflict, summary)
+        else {
+            continue;
+        };
+        bot["id"] = Value::String(new_id.clone());
+        match existing {
+            Some(i) if replace => list[i] = bot,
+            _ => list.push(bot),
+        }
+        summary.imported_bots.push(format!("{}:{}", channel, new_id));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn scan_existing_setup(
+    store: State<'_, ConfigStore>,
+    extra_dirs: Option<Vec<String>>,
+) -> CommandResult<Vec<FoundSetup>> {
+    let main = store.read()?.unwrap_or(Value::Null);
+    Ok(candidates(Some(&main), &extra_dirs.unwrap_or_default())
+        .into_iter()
+        .map(|(path, source)| describe(path, source, &main))
+        .collect())
+}
+
+// Merge agents and bots from `path` into the main config; all of them unless
+// `agent_ids` / `bot_ids` ("channel:id") pick some
+#[tauri::command]
+pub(crate) fn import_existing_setup(
+    store: State<'_, ConfigStore>,
+    path: String,
+    agent_ids: Option<Vec<String>>,
+    bot_ids: Option<Vec<String>>,
+    on_conflict: ConflictResolution,
+) -> CommandResult<ImportSummary> {
+    let source = read_setup(&expand_home(&path)).map_err(Error::Invalid)?;
+    let mut main = store.read()?.unwrap_or_else(|| serde_json::json!({ "agents": { "list": [] }, "channels": {} }));
+
+    let agents: Vec<Value> = agents(&source)
+        .into_iter()
+        .filter(|a| agent_ids.as_ref().is_none_or(|ids| ids.iter().any(|id| id == id_of(a))))
+        .collect();
+    let bots: Vec<(&'static str, Value)> = bots(&source)
+        .into_iter()
+        .filter(|(channel, b)| {
+            bot_ids.as_ref().is_none_or(|ids| ids.contains(&format!("{}:{}", channel, id_of(b))))
+        })
+        .collect();
+
+    let mut summary = ImportSummary::default();
+    merge_agents(&mut main, agents, on_conflict, &mut summary)?;
+    merge_bots(&mut main, bots, on_conflict, &mut summary)?;
+
+    if summary.imported_agents.is_empty() && summary.imported_bots.is_empty() {
+        return Ok(summary);
+    }
+    // Checked first, so a rejected import leaves no tokens behind in the keychain
+    config_validation::ensure_valid(&main)?;
+    keychain::protect_tokens(&mut main)?;
+    if let Some(dir) = get_config_path().parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    store.write(&main)?;
+    Ok(summary)
+}
@@ -0,0 +1,129 @@
+// Paged, tail-first reading of large transcript and log files.
+//
+// Claude Code transcripts under ~/.claude/projects and bridge logs under
+// ~/.ccb/logs can grow to hundreds of megabytes. Rather than loading them whole,
+// the viewer asks for the last page first and walks backwards on demand, so
+// memory use stays proportional to the page size.
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_PAGE_KB: u64 = 64;
+const MAX_PAGE_KB: u64 = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePage {
+    lines: Vec<String>,
+    // Byte offset of the first returned line; pass back as `before` for the previous page
+    offset: u64,
+    file_size: u64,
+    has_more: bool,
+}
+
+// Only transcript and log directories may be read through this command
+fn allowed_roots() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return vec![];
+    };
+    vec![home.join(".claude").join("projects"), home.join(".ccb").join("logs")]
+}
+
+fn check_path(path: &Path) -> Result<PathBuf, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let allowed = allowed_roots()
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| path.starts_with(root));
+    if !allowed {
+        return Err(format!("{} is outside the transcript and log directories", path.display()));
+    }
+    Ok(path)
+}
+
+// Read the page of complete lines ending at byte `end`, using at least `page_bytes`
+pub(crate) fn read_page(path: &Path, end: Option<u64>, page_bytes: u64) -> Result<FilePage, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let file_size = file.metadata().map_err(|e| e.to_string())?.len();
+    let end = end.unwrap_or(file_size).min(file_size);
+
+    // Widen the window until it contains a line boundary, so a single huge line
+    // is still returned whole instead of as an empty page
+    let mut window = page_bytes.max(1);
+    let (start, buf) = loop {
+        let start = end.saturating_sub(window);
+        let mut buf = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start)).map_err(|e| e.to_string())?;
+        file.read_exact(&mut buf).map_err(|e| e.to_string())?;
+
+        if start == 0 {
+            break (start, buf);
+        }
+        // The first line is likely cut off; drop it and let the next page include it.
+        // A newline in the last byte only ends the page's own last line, and
+        // cutting there would return an empty page at the same offset
+        if let Some(newline) = buf[..buf.len() - 1].iter().position(|b| *b == b'\n') {
+            break (start + newline as u64 + 1, buf[newline + 1..].to_vec());
+        }
+        window *= 2;
+    };
+
+    let lines = String::from_utf8_lossy(&buf)
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+
+    Ok(FilePage {
+        lines,
+        offset: start,
+        file_size,
+        has_more: start > 0,
+    })
+}
+
+#[tauri::command]
+pub(crate) fn read_file_page(path: String, before: Option<u64>, page_kb: Option<u64>) -> Result<FilePage, String> {
+    let path = check_path(Path::new(&path))?;
+    let page_kb = page_kb.unwrap_or(DEFAULT_PAGE_KB).clamp(1, MAX_PAGE_KB);
+    read_page(&path, before, page_kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn page_through(name: &str, content: &str, page_bytes: u64) -> Vec<Vec<String>> {
+        let path = std::env::temp_dir().join(format!("ccb-file-tail-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        let mut pages = vec![];
+        let mut before = None;
+        loop {
+            let page = read_page(&path, before, page_bytes).unwrap();
+            assert!(before.is_none_or(|b| page.offset < b), "offset did not move back");
+            before = Some(page.offset);
+            pages.push(page.lines);
+            if !page.has_more {
+                break;
+            }
+        }
+        fs::remove_file(&path).unwrap();
+        pages
+    }
+
+    #[test]
+    fn window_starting_on_newline_moves_back() {
+        let pages = page_through("newline", "aaaa\nbbbb\n", 1);
+        assert_eq!(pages, vec![vec!["bbbb"], vec!["aaaa"]]);
+    }
+
+    #[test]
+    fn pages_keep_whole_lines() {
+        let pages = page_through("lines", "one\ntwo\nthree\n", 6);
+        assert_eq!(pages, vec![vec!["two", "three"], vec!["one"]]);
+    }
+}
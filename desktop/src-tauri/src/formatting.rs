@@ -0,0 +1,290 @@
+// How agent replies are rendered per channel.
+//
+// Claude writes Markdown. Discord renders it as is; Telegram's legacy Markdown
+// mode chokes on much of it, so replies can be converted to Telegram HTML
+// instead. Long replies are split into messages below the platform limit,
+// optionally closing and reopening code fences at each cut so every message
+// renders on its own, or sent as a single file once they pass a threshold.
+// Settings live in `channels.<channel>.formatting` in config.json.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::i18n;
+
+const TELEGRAM_MAX_LENGTH: usize = 4096;
+const DISCORD_MAX_LENGTH: usize = 2000;
+const MIN_SPLIT_LENGTH: usize = 500;
+// Room left in each message for the fences added when balancing code blocks
+const FENCE_RESERVE: usize = 32;
+const FILE_NAME: &str = "response.md";
+
+static FENCED_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)```([\w+#-]*)\n?(.*?)```").unwrap());
+static INLINE_CODE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"`([^`\n]+)`").unwrap());
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^#{1,6}[ \t]+(.+)$").unwrap());
+static BOLD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*\*([^*\n]+)\*\*|__([^_\n]+)__").unwrap());
+static ITALIC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\*([^*\n]+)\*|(^|[^\w])_([^_\n]+)_([^\w]|$)").unwrap());
+static STRIKE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"~~([^~\n]+)~~").unwrap());
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]\n]+)\]\(([^)\s]+)\)").unwrap());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattingSettings {
+    // Telegram only: send replies as HTML converted from Markdown
+    #[serde(default)]
+    markdown_to_html: bool,
+    // Longest message sent; longer replies are split
+    split_length: usize,
+    // Close a code block at a split and reopen it in the next message
+    #[serde(default = "default_true")]
+    split_code_blocks: bool,
+    #[serde(default)]
+    long_output_as_file: bool,
+    // Replies longer than this go out as a file when the above is on
+    file_threshold: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormattingPreview {
+    // Telegram parse mode the messages are sent with, if any
+    parse_mode: Option<&'static str>,
+    messages: Vec<String>,
+    // Set when the reply would be sent as a file instead of messages
+    file_name: Option<&'static str>,
+}
+
+fn max_length(channel: &str) -> CommandResult<usize> {
+    match channel {
+        "telegram" => Ok(TELEGRAM_MAX_LENGTH),
+        "discord" => Ok(DISCORD_MAX_LENGTH),
+        _ => Err(Error::Invalid(format!("Unknown channel '{}'", channel))),
+    }
+}
+
+// What the bridge does today: the platform limit, no conversion, no files
+fn defaults(channel: &str) -> CommandResult<FormattingSettings> {
+    let max = max_length(channel)?;
+    Ok(FormattingSettings {
+        markdown_to_html: false,
+        split_length: max,
+        split_code_blocks: true,
+        long_output_as_file: false,
+        file_threshold: max * 4,
+    })
+}
+
+fn validate(channel: &str, settings: &FormattingSettings) -> CommandResult<()> {
+    let max = max_length(channel)?;
+    if !(MIN_SPLIT_LENGTH..=max).contains(&settings.split_length) {
+        return Err(Error::Invalid(format!(
+            "Split length for {} must be between {} and {}",
+            channel, MIN_SPLIT_LENGTH, max
+        )));
+    }
+    if settings.file_threshold < settings.split_length {
+        return Err(Error::Invalid("The file threshold must not be below the split length".to_string()));
+    }
+    if settings.markdown_to_html && channel != "telegram" {
+        return Err(Error::Invalid("HTML conversion is only available for Telegram".to_string()));
+    }
+    Ok(())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// Text outside code: escaped, then Markdown markers turned into tags
+fn inline_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for code in INLINE_CODE.captures_iter(text) {
+        let whole = code.get(0).unwrap();
+        html.push_str(&markup_to_html(&text[last..whole.start()]));
+        html.push_str(&format!("<code>{}</code>", escape_html(&code[1])));
+        last = whole.end();
+    }
+    html.push_str(&markup_to_html(&text[last..]));
+    html
+}
+
+fn markup_to_html(text: &str) -> String {
+    let html = escape_html(text);
+    let html = HEADING.replace_all(&html, "<b>$1</b>");
+    let html = BOLD.replace_all(&html, "<b>$1$2</b>");
+    let html = ITALIC.replace_all(&html, "$2<i>$1$3</i>$4");
+    let html = STRIKE.replace_all(&html, "<s>$1</s>");
+    LINK.replace_all(&html, "<a href=\"$2\">$1</a>").into_owned()
+}
+
+// Markdown to the subset of HTML Telegram accepts
+fn markdown_to_telegram_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut last = 0;
+    for block in FENCED_CODE.captures_iter(markdown) {
+        let whole = block.get(0).unwrap();
+        html.push_str(&inline_to_html(&markdown[last..whole.start()]));
+        let code = escape_html(block[2].trim_end_matches('\n'));
+        match &block[1] {
+            "" => html.push_str(&format!("<pre>{}</pre>", code)),
+            lang => html.push_str(&format!("<pre><code class=\"language-{}\">{}</code></pre>", lang, code)),
+        }
+        last = whole.end();
+    }
+    html.push_str(&inline_to_html(&markdown[last..]));
+    html
+}
+
+// Cut at the last newline, else the last space, in the second half of each
+// chunk, like the bridge's own splitter. Lengths are in characters.
+fn split(text: &str, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+    while remaining.chars().count() > max {
+        let limit = remaining.char_indices().nth(max).map(|(i, _)| i).unwrap_or(remaining.len());
+        let head = &remaining[..limit];
+        let half = head.len() / 2;
+        let cut = head
+            .rfind('\n')
+            .filter(|&i| i > half)
+            .or_else(|| head.rfind(' ').filter(|&i| i > half))
+            .map(|i| i + 1)
+            .unwrap_or(limit);
+        chunks.push(remaining[..cut].to_string());
+        remaining = &remaining[cut..];
+    }
+    if !remaining.is_empty() {
+        chunks.push(remaining.to_string());
+    }
+    chunks
+}
+
+// Close a code fence left open at the end of a chunk and reopen it, with the
+// same language, at the start of the next
+fn balance_fences(chunks: Vec<String>) -> Vec<String> {
+    let mut carried: Option<String> = None;
+    let mut balanced = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let mut text = match carried.take() {
+            Some(lang) => format!("```{}\n{}", lang, chunk),
+            None => chunk,
+        };
+        let mut open: Option<String> = None;
+        for line in text.lines() {
+            if let Some(info) = line.trim_start().strip_prefix("```") {
+                open = match open {
+                    Some(_) => None,
+                    None => Some(info.trim().to_string()),
+                };
+            }
+        }
+        if open.is_some() {
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str("```");
+        }
+        carried = open;
+        balanced.push(text);
+    }
+    balanced
+}
+
+fn render(text: &str, channel: &str, settings: &FormattingSettings) -> FormattingPreview {
+    if settings.long_output_as_file && text.chars().count() > settings.file_threshold {
+        return FormattingPreview {
+            parse_mode: None,
+            messages: vec![],
+            file_name: Some(FILE_NAME),
+        };
+    }
+
+    // Telegram's limit counts the text after entity parsing, so measuring the
+    // Markdown source stays within it once converted
+    let chunks = if settings.split_code_blocks {
+        balance_fences(split(text, settings.split_length.saturating_sub(FENCE_RESERVE)))
+    } else {
+        split(text, settings.split_length)
+    };
+    let html = settings.markdown_to_html && channel == "telegram";
+    FormattingPreview {
+        parse_mode: match channel {
+            "telegram" if html => Some("HTML"),
+            "telegram" => Some("Markdown"),
+            _ => None,
+        },
+        messages: if html {
+            chunks.iter().map(|c| markdown_to_telegram_html(c)).collect()
+        } else {
+            chunks
+        },
+        file_name: None,
+    }
+}
+
+fn read_settings(config: &serde_json::Value, channel: &str) -> CommandResult<FormattingSettings> {
+    match config.get("channels").and_then(|c| c.get(channel)).and_then(|s| s.get("formatting")) {
+        Some(formatting) => serde_json::from_value(formatting.clone())
+            .map_err(|e| Error::Config(format!("Invalid formatting settings for {}: {}", channel, e))),
+        None => defaults(channel),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_formatting_settings(store: State<'_, ConfigStore>, channel: String) -> CommandResult<FormattingSettings> {
+    max_length(&channel)?;
+    match store.read()? {
+        Some(config) => read_settings(&config, &channel),
+        None => defaults(&channel),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn set_formatting_settings(
+    store: State<'_, ConfigStore>,
+    channel: String,
+    settings: FormattingSettings,
+) -> CommandResult<bool> {
+    validate(&channel, &settings)?;
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    let section = config
+        .get_mut("channels")
+        .and_then(|c| c.get_mut(&channel))
+        .and_then(|s| s.as_object_mut())
+        .ok_or_else(|| Error::Invalid(format!("Channel '{}' is not configured", channel)))?;
+    section.insert("formatting".to_string(), serde_json::to_value(&settings).map_err(|e| e.to_string())?);
+    store.write(&config)?;
+    Ok(true)
+}
+
+// How `sample_text` would be sent with the saved settings, or with `settings`
+// to try changes before saving
+#[tauri::command]
+pub(crate) fn preview_formatting(
+    store: State<'_, ConfigStore>,
+    sample_text: String,
+    channel: String,
+    settings: Option<FormattingSettings>,
+) -> CommandResult<FormattingPreview> {
+    let settings = match settings {
+        Some(settings) => {
+            validate(&channel, &settings)?;
+            settings
+        }
+        None => get_formatting_settings(store, channel.clone())?,
+    };
+    Ok(render(&sample_text, &channel, &settings))
+}
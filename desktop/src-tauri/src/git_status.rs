@@ -0,0 +1,149 @@
+// Git overview of agent workspaces.
+//
+// Agents edit files in their workspaces and rarely commit, so the dashboard
+// shows branch, upstream divergence, uncommitted changes and the last commit
+// for every workspace. Each workspace is queried concurrently with two cheap
+// git calls.
+
+use serde::Serialize;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::State;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::service::get_extended_path;
+use crate::transcripts::agent_workspaces;
+
+const GIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCommit {
+    hash: String,
+    subject: String,
+    date: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceGitStatus {
+    agent_id: String,
+    workspace: String,
+    is_repo: bool,
+    branch: Option<String>,
+    upstream: Option<String>,
+    ahead: u32,
+    behind: u32,
+    dirty_files: u32,
+    last_commit: Option<LastCommit>,
+    error: Option<String>,
+}
+
+pub(crate) async fn run_git(workspace: &Path, args: &[&str]) -> Result<String, String> {
+    let output = tokio::time::timeout(
+        GIT_TIMEOUT,
+        Command::new("git")
+            .args(args)
+            .current_dir(workspace)
+            .env("PATH", get_extended_path())
+            // Never block on credential or editor prompts
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await
+    .map_err(|_| "git timed out".to_string())?
+    .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// Parse `git status --porcelain=v2 --branch` output
+fn apply_porcelain(status: &mut WorkspaceGitStatus, output: &str) {
+    for line in output.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.branch = (head != "(detached)").then(|| head.to_string());
+        } else if let Some(upstream) = line.strip_prefix("# branch.upstream ") {
+            status.upstream = Some(upstream.to_string());
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for part in ab.split_whitespace() {
+                if let Some(n) = part.strip_prefix('+') {
+                    status.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = part.strip_prefix('-') {
+                    status.behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') && !line.is_empty() {
+            status.dirty_files += 1;
+        }
+    }
+}
+
+async fn workspace_status(agent_id: String, workspace: String) -> WorkspaceGitStatus {
+    let mut status = WorkspaceGitStatus {
+        agent_id,
+        workspace: workspace.clone(),
+        ..Default::default()
+    };
+
+    let path = expand_home(&workspace);
+    if !path.is_dir() {
+        status.error = Some("Workspace directory does not exist".to_string());
+        return status;
+    }
+
+    match run_git(&path, &["status", "--porcelain=v2", "--branch"]).await {
+        Ok(output) => {
+            status.is_repo = true;
+            apply_porcelain(&mut status, &output);
+        }
+        Err(e) if e.contains("not a git repository") => return status,
+        Err(e) => {
+            status.error = Some(e);
+            return status;
+        }
+    }
+
+    // Fails on a repo without commits, which just means there's no last commit
+    if let Ok(output) = run_git(&path, &["log", "-1", "--format=%h%x1f%s%x1f%cI"]).await {
+        let mut fields = output.trim_end().splitn(3, '\u{1f}');
+        if let (Some(hash), Some(subject), Some(date)) = (fields.next(), fields.next(), fields.next()) {
+            status.last_commit = Some(LastCommit {
+                hash: hash.to_string(),
+                subject: subject.to_string(),
+                date: date.to_string(),
+            });
+        }
+    }
+
+    status
+}
+
+#[tauri::command]
+pub(crate) async fn get_workspaces_git_status(
+    store: State<'_, ConfigStore>,
+) -> Result<Vec<WorkspaceGitStatus>, String> {
+    let workspaces = store.read()?.map(|c| agent_workspaces(&c)).unwrap_or_default();
+
+    let mut tasks = JoinSet::new();
+    for (index, (agent_id, workspace)) in workspaces.into_iter().enumerate() {
+        tasks.spawn(async move { (index, workspace_status(agent_id, workspace).await) });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+
+    // Keep the agents' config order
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, status)| status).collect())
+}
@@ -0,0 +1,225 @@
+// Group chat access control.
+//
+// Bots can be invited into arbitrary groups, so each bot entry (or the channel
+// section itself for legacy single-bot setups) carries a `groups` object: a
+// default policy plus explicit allow and deny lists of group IDs. The groups a
+// bot is actually in come from the Control API.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::config::ConfigStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupPolicy {
+    // Respond in any group not explicitly denied, as bots without a `groups`
+    // object always have
+    #[default]
+    Open,
+    // Respond only in explicitly allowed groups
+    Allowlist,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupAccessConfig {
+    #[serde(default)]
+    policy: GroupPolicy,
+    #[serde(default)]
+    allow: Vec<String>,
+    #[serde(default)]
+    deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupAccess {
+    Allow,
+    Deny,
+    // Drop any explicit entry and fall back to the bot's policy
+    Default,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiGroup {
+    chat_key: String,
+    title: Option<String>,
+    member_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GroupsResponse {
+    groups: Vec<ApiGroup>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupChat {
+    chat_key: String,
+    channel: String,
+    bot_id: Option<String>,
+    group_id: String,
+    title: Option<String>,
+    member_count: Option<u32>,
+    allowed: bool,
+    // Whether `allowed` comes from an explicit entry rather than the policy
+    explicit: bool,
+}
+
+// Split "telegram:group:<id>", "telegram:<botId>:group:<id>",
+// "discord:channel:<id>" or "discord:<botId>:channel:<id>"
+fn parse_group_key(chat_key: &str) -> Option<(String, Option<String>, String)> {
+    let parts: Vec<&str> = chat_key.splitn(4, ':').collect();
+    let is_marker = |s: &str| s == "group" || s == "channel";
+    match parts.as_slice() {
+        [channel, marker, id] if is_marker(marker) => Some((channel.to_string(), None, id.to_string())),
+        [channel, bot, marker, id] if is_marker(marker) => {
+            Some((channel.to_string(), Some(bot.to_string()), id.to_string()))
+        }
+        _ => None,
+    }
+}
+
+// The config object holding a bot's settings: its `bots[]` entry, or the
+// channel section itself when the bot is the legacy single bot
+pub(crate) fn bot_section<'a>(config: &'a Value, channel: &str, bot_id: Option<&str>) -> Option<&'a Value> {
+    let section = config.get("channels")?.get(channel)?;
+    match bot_id {
+        Some(id) => section
+            .get("bots")?
+            .as_array()?
+            .iter()
+            .find(|b| b.get("id").and_then(|v| v.as_str()) == Some(id)),
+        None => Some(section),
+    }
+}
+
+pub(crate) fn bot_section_mut<'a>(config: &'a mut Value, channel: &str, bot_id: Option<&str>) -> Option<&'a mut Value> {
+    let section = config.get_mut("channels")?.get_mut(channel)?;
+    match bot_id {
+        Some(id) => section
+            .get_mut("bots")?
+            .as_array_mut()?
+            .iter_mut()
+            .find(|b| b.get("id").and_then(|v| v.as_str()) == Some(id)),
+        None => Some(section),
+    }
+}
+
+fn read_access(section: Option<&Value>) -> GroupAccessConfig {
+    section
+        .and_then(|s| s.get("groups"))
+        .and_then(|g| serde_json::from_value(g.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn update_access(
+    store: &ConfigStore,
+    channel: &str,
+    bot_id: Option<&str>,
+    update: impl FnOnce(&mut GroupAccessConfig),
+) -> Result<(), String> {
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let section = bot_section_mut(&mut config, channel, bot_id)
+        .and_then(|s| s.as_object_mut())
+        .ok_or(match bot_id {
+            Some(id) => format!("Bot '{}' not found in {} config", id, channel),
+            None => format!("Channel '{}' is not configured", channel),
+        })?;
+
+    let mut access: GroupAccessConfig = section
+        .get("groups")
+        .and_then(|g| serde_json::from_value(g.clone()).ok())
+        .unwrap_or_default();
+    update(&mut access);
+    // Over the existing object, so keys this screen doesn't know survive
+    let groups = section.entry("groups").or_insert_with(|| serde_json::json!({}));
+    if !groups.is_object() {
+        *groups = serde_json::json!({});
+    }
+    if let (Some(groups), Value::Object(access)) =
+        (groups.as_object_mut(), serde_json::to_value(&access).map_err(|e| e.to_string())?)
+    {
+        groups.extend(access);
+    }
+
+    Ok(store.write(&config)?)
+}
+
+async fn fetch_groups(api: &BridgeApiClient) -> Result<Vec<ApiGroup>, String> {
+    match api.get::<GroupsResponse>("/groups").await {
+        Ok(data) => Ok(data.groups),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't list group chats".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn list_group_chats(
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+) -> Result<Vec<GroupChat>, String> {
+    let groups = fetch_groups(&api).await?;
+    let config = store.read()?.unwrap_or(Value::Null);
+
+    Ok(groups
+        .into_iter()
+        .filter_map(|group| {
+            let (channel, bot_id, group_id) = parse_group_key(&group.chat_key)?;
+            let access = read_access(bot_section(&config, &channel, bot_id.as_deref()));
+            let (allowed, explicit) = if access.deny.contains(&group_id) {
+                (false, true)
+            } else if access.allow.contains(&group_id) {
+                (true, true)
+            } else {
+                (access.policy == GroupPolicy::Open, false)
+            };
+            Some(GroupChat {
+                chat_key: group.chat_key,
+                channel,
+                bot_id,
+                group_id,
+                title: group.title,
+                member_count: group.member_count,
+                allowed,
+                explicit,
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) fn set_group_access(
+    store: State<'_, ConfigStore>,
+    chat_key: String,
+    access: GroupAccess,
+) -> Result<bool, String> {
+    let (channel, bot_id, group_id) =
+        parse_group_key(&chat_key).ok_or(format!("'{}' is not a group chat", chat_key))?;
+
+    update_access(&store, &channel, bot_id.as_deref(), |config| {
+        config.allow.retain(|g| g != &group_id);
+        config.deny.retain(|g| g != &group_id);
+        match access {
+            GroupAccess::Allow => config.allow.push(group_id),
+            GroupAccess::Deny => config.deny.push(group_id),
+            GroupAccess::Default => {}
+        }
+    })?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn set_group_policy(
+    store: State<'_, ConfigStore>,
+    channel: String,
+    bot_id: Option<String>,
+    policy: GroupPolicy,
+) -> Result<bool, String> {
+    update_access(&store, &channel, bot_id.as_deref(), |config| config.policy = policy)?;
+    Ok(true)
+}
@@ -0,0 +1,156 @@
+// External heartbeat pings.
+//
+// While the bridge is healthy the status poller pings a user-configured push
+// URL (healthchecks.io, Uptime Kuma and the like) every `intervalSecs`. When the
+// bridge is down, or the machine is asleep, the pings stop and the external
+// monitor raises the alarm on its own.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::ConfigStore;
+use crate::http;
+
+const MIN_INTERVAL_SECS: u64 = 10;
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatSettings {
+    enabled: bool,
+    #[serde(default)]
+    url: String,
+    interval_secs: u64,
+}
+
+impl Default for HeartbeatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            interval_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatStatus {
+    // False while the bridge is unhealthy and pings are held back
+    active: bool,
+    last_ping_at: Option<String>,
+    last_http_status: Option<u16>,
+    last_error: Option<String>,
+}
+
+// Managed state updated from the poll loop
+#[derive(Default)]
+pub(crate) struct HeartbeatState {
+    status: Mutex<HeartbeatStatus>,
+    last_attempt: Mutex<Option<Instant>>,
+}
+
+fn read_settings(config: &serde_json::Value) -> HeartbeatSettings {
+    config
+        .get("heartbeat")
+        .and_then(|h| serde_json::from_value(h.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn validate(settings: &HeartbeatSettings) -> Result<(), String> {
+    if settings.interval_secs < MIN_INTERVAL_SECS {
+        return Err(format!("Heartbeat interval must be at least {} seconds", MIN_INTERVAL_SECS));
+    }
+    if settings.enabled {
+        let url = reqwest::Url::parse(&settings.url).map_err(|e| format!("Invalid heartbeat URL: {}", e))?;
+        if !["http", "https"].contains(&url.scheme()) {
+            return Err("Heartbeat URL must start with http:// or https://".to_string());
+        }
+    }
+    Ok(())
+}
+
+async fn ping(url: &str) -> HeartbeatStatus {
+    let mut status = HeartbeatStatus {
+        active: true,
+        last_ping_at: Some(chrono::Local::now().to_rfc3339()),
+        ..Default::default()
+    };
+    match http::client().get(url).timeout(PING_TIMEOUT).send().await {
+        Ok(response) => {
+            status.last_http_status = Some(response.status().as_u16());
+            if !response.status().is_success() {
+                status.last_error = Some(format!("Monitor returned {}", response.status()));
+            }
+        }
+        Err(e) => status.last_error = Some(format!("Failed to ping monitor: {}", e)),
+    }
+    status
+}
+
+// Called by the poller after every poll with the bridge's health
+pub(crate) fn tick(app: &AppHandle, healthy: bool) {
+    let heartbeat = app.state::<HeartbeatState>();
+    let settings = app
+        .state::<ConfigStore>()
+        .read()
+        .ok()
+        .flatten()
+        .map(|c| read_settings(&c))
+        .unwrap_or_default();
+
+    if !settings.enabled || !healthy {
+        if let Ok(mut status) = heartbeat.status.lock() {
+            status.active = false;
+        }
+        return;
+    }
+
+    {
+        let Ok(mut last_attempt) = heartbeat.last_attempt.lock() else {
+            return;
+        };
+        let interval = Duration::from_secs(settings.interval_secs.max(MIN_INTERVAL_SECS));
+        if last_attempt.is_some_and(|t| t.elapsed() < interval) {
+            return;
+        }
+        *last_attempt = Some(Instant::now());
+    }
+
+    // Don't hold up the poll loop on a slow monitor
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = ping(&settings.url).await;
+        if let Ok(mut status) = app.state::<HeartbeatState>().status.lock() {
+            *status = result;
+        }
+    });
+}
+
+#[tauri::command]
+pub(crate) fn get_heartbeat_settings(store: State<'_, ConfigStore>) -> Result<HeartbeatSettings, String> {
+    Ok(store.read()?.map(|c| read_settings(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn set_heartbeat_settings(
+    store: State<'_, ConfigStore>,
+    heartbeat: State<'_, HeartbeatState>,
+    settings: HeartbeatSettings,
+) -> Result<bool, String> {
+    validate(&settings)?;
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    config["heartbeat"] = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+
+    // Ping on the next poll with the new settings
+    *heartbeat.last_attempt.lock().map_err(|e| e.to_string())? = None;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn get_heartbeat_status(heartbeat: State<'_, HeartbeatState>) -> Result<HeartbeatStatus, String> {
+    Ok(heartbeat.status.lock().map_err(|e| e.to_string())?.clone())
+}
@@ -0,0 +1,211 @@
+// Searchable archive of Claude Code conversation history.
+//
+// A background task keeps an in-memory inverted index over the transcripts in
+// ~/.claude/projects, reading only what was appended since the last pass. Each
+// user/assistant turn is attributed to the agent whose workspace matches the
+// session's cwd, so searches can be narrowed to a single agent.
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::config::ConfigStore;
+use crate::transcripts::{agent_for_cwd, agent_workspaces, message_text, read_appended_lines, transcript_files};
+
+const INDEX_INTERVAL: Duration = Duration::from_secs(60);
+// Text kept per turn for snippets; the full text is still indexed
+const MAX_STORED_CHARS: usize = 1000;
+const SNIPPET_RADIUS: usize = 80;
+const DEFAULT_LIMIT: usize = 50;
+
+struct IndexedTurn {
+    session_id: String,
+    agent_id: Option<String>,
+    role: String,
+    timestamp: String,
+    text: String,
+}
+
+#[derive(Default)]
+struct IndexData {
+    turns: Vec<IndexedTurn>,
+    postings: HashMap<String, Vec<usize>>,
+    offsets: HashMap<String, u64>,
+}
+
+#[derive(Default)]
+pub(crate) struct HistoryIndex(Mutex<IndexData>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryMatch {
+    session_id: String,
+    agent_id: Option<String>,
+    role: String,
+    timestamp: String,
+    snippet: String,
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2)
+        .map(|word| word.to_lowercase())
+}
+
+impl IndexData {
+    fn add_turn(&mut self, turn: IndexedTurn, full_text: &str) {
+        let id = self.turns.len();
+        let words: HashSet<String> = tokenize(full_text).collect();
+        for word in words {
+            self.postings.entry(word).or_default().push(id);
+        }
+        self.turns.push(turn);
+    }
+
+    fn search(&self, query: &str, agent_id: Option<&str>, limit: usize) -> Vec<HistoryMatch> {
+        let terms: Vec<String> = tokenize(query).collect();
+        let Some(first) = terms.first() else {
+            return vec![];
+        };
+
+        // Intersect postings, starting from the rarest term
+        let mut lists: Vec<&Vec<usize>> = Vec::new();
+        for term in &terms {
+            match self.postings.get(term) {
+                Some(list) => lists.push(list),
+                None => return vec![],
+            }
+        }
+        lists.sort_by_key(|list| list.len());
+        let mut hits: Vec<usize> = lists[0].clone();
+        for list in &lists[1..] {
+            let set: HashSet<&usize> = list.iter().collect();
+            hits.retain(|id| set.contains(id));
+        }
+
+        // Most recent first
+        hits.sort_by(|a, b| self.turns[*b].timestamp.cmp(&self.turns[*a].timestamp));
+
+        hits.into_iter()
+            .map(|id| &self.turns[id])
+            .filter(|turn| agent_id.is_none() || turn.agent_id.as_deref() == agent_id)
+            .take(limit)
+            .map(|turn| HistoryMatch {
+                session_id: turn.session_id.clone(),
+                agent_id: turn.agent_id.clone(),
+                role: turn.role.clone(),
+                timestamp: turn.timestamp.clone(),
+                snippet: snippet(&turn.text, first),
+            })
+            .collect()
+    }
+}
+
+// Turns appended to the transcripts since `offsets`, paired with their full text.
+// Runs without holding the index lock so searches aren't blocked by a long scan.
+fn collect_new_turns(offsets: &mut HashMap<String, u64>, workspaces: &[(String, String)]) -> Vec<(IndexedTurn, String)> {
+    let mut turns = Vec::new();
+
+    for path in transcript_files() {
+        let key = path.to_string_lossy().to_string();
+        let mut offset = offsets.get(&key).copied().unwrap_or(0);
+
+        for line in read_appended_lines(&path, &mut offset) {
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            let role = match entry.get("type").and_then(|t| t.as_str()) {
+                Some(role @ ("user" | "assistant")) => role.to_string(),
+                _ => continue,
+            };
+            let Some(text) = entry.get("message").and_then(message_text) else {
+                continue;
+            };
+
+            let turn = IndexedTurn {
+                session_id: entry.get("sessionId").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                agent_id: entry
+                    .get("cwd")
+                    .and_then(|v| v.as_str())
+                    .and_then(|cwd| agent_for_cwd(workspaces, cwd)),
+                role,
+                timestamp: entry.get("timestamp").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                text: text.chars().take(MAX_STORED_CHARS).collect(),
+            };
+            turns.push((turn, text));
+        }
+
+        offsets.insert(key, offset);
+    }
+
+    turns
+}
+
+// Window of text around the first occurrence of `term`
+fn snippet(text: &str, term: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text.to_lowercase().chars().collect();
+    let term: Vec<char> = term.chars().collect();
+
+    // Lowercasing can change char counts for some scripts; fall back to the start
+    let at = if lower.len() == chars.len() {
+        lower.windows(term.len()).position(|w| w == term.as_slice()).unwrap_or(0)
+    } else {
+        0
+    };
+    let start = at.saturating_sub(SNIPPET_RADIUS);
+    let end = (at + term.len() + SNIPPET_RADIUS).min(chars.len());
+
+    let mut snippet: String = chars[start..end].iter().collect();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+fn refresh(app: &AppHandle) {
+    let workspaces = app
+        .state::<ConfigStore>()
+        .read()
+        .ok()
+        .flatten()
+        .map(|c| agent_workspaces(&c))
+        .unwrap_or_default();
+
+    let index = app.state::<HistoryIndex>();
+    let Some(mut offsets) = index.0.lock().ok().map(|data| data.offsets.clone()) else {
+        return;
+    };
+    let turns = collect_new_turns(&mut offsets, &workspaces);
+
+    if let Ok(mut data) = index.0.lock() {
+        for (turn, text) in turns {
+            data.add_turn(turn, &text);
+        }
+        data.offsets = offsets;
+    };
+}
+
+pub(crate) async fn run_indexer(app: AppHandle) {
+    loop {
+        let handle = app.clone();
+        let _ = tauri::async_runtime::spawn_blocking(move || refresh(&handle)).await;
+        tokio::time::sleep(INDEX_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn search_history(
+    index: State<'_, HistoryIndex>,
+    query: String,
+    agent_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryMatch>, String> {
+    let index = index.0.lock().map_err(|e| e.to_string())?;
+    Ok(index.search(&query, agent_id.as_deref(), limit.unwrap_or(DEFAULT_LIMIT)))
+}
@@ -0,0 +1,188 @@
+// Shared HTTP client and proxy settings.
+//
+// Requests to Telegram, Discord and other outside services go through one
+// pooled client that honours the `proxy` section of config.json, for users
+// behind corporate proxies or in regions where those services are blocked. The
+// same settings are handed to the spawned bridge as the usual proxy env vars.
+// Loopback traffic (the Control API) never goes through the proxy.
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::log_forwarding;
+
+const LOOPBACK_HOSTS: &str = "localhost,127.0.0.1,::1";
+const PROXY_TEST_URL: &str = "https://api.telegram.org";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    enabled: bool,
+    // http://, https://, socks5:// or socks5h://, optionally with user:pass@
+    #[serde(default)]
+    url: String,
+    // Extra hosts or domains that bypass the proxy
+    #[serde(default)]
+    no_proxy: Vec<String>,
+}
+
+struct SharedClient {
+    settings: ProxySettings,
+    client: reqwest::Client,
+}
+
+static CLIENT: RwLock<Option<SharedClient>> = RwLock::new(None);
+
+impl ProxySettings {
+    fn no_proxy_list(&self) -> String {
+        std::iter::once(LOOPBACK_HOSTS.to_string())
+            .chain(self.no_proxy.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+fn read_settings(config: &serde_json::Value) -> ProxySettings {
+    config
+        .get("proxy")
+        .and_then(|p| serde_json::from_value(p.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn validate(settings: &ProxySettings) -> Result<(), String> {
+    if !settings.enabled {
+        return Ok(());
+    }
+    let url = reqwest::Url::parse(&settings.url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if !["http", "https", "socks5", "socks5h"].contains(&url.scheme()) {
+        return Err(format!("Unsupported proxy scheme '{}'", url.scheme()));
+    }
+    if url.host_str().is_none() {
+        return Err("Proxy URL needs a host".to_string());
+    }
+    Ok(())
+}
+
+fn build_client(settings: &ProxySettings) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if settings.enabled {
+        let proxy = reqwest::Proxy::all(&settings.url)
+            .map_err(|e| format!("Invalid proxy URL: {}", e))?
+            .no_proxy(reqwest::NoProxy::from_string(&settings.no_proxy_list()));
+        builder = builder.proxy(proxy);
+    }
+    builder.build().map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Rebuild the shared client when the proxy settings change
+pub(crate) fn configure(config: &serde_json::Value) {
+    let settings = read_settings(config);
+    if CLIENT.read().ok().and_then(|c| c.as_ref().map(|c| c.settings == settings)) == Some(true) {
+        return;
+    }
+    match build_client(&settings) {
+        Ok(client) => {
+            if let Ok(mut shared) = CLIENT.write() {
+                *shared = Some(SharedClient { settings, client });
+            }
+        }
+        Err(e) => log_forwarding::desktop_error(e),
+    }
+}
+
+// The shared client; cheap to call, clones share one connection pool
+pub(crate) fn client() -> reqwest::Client {
+    if let Some(client) = CLIENT.read().ok().and_then(|c| c.as_ref().map(|c| c.client.clone())) {
+        return client;
+    }
+    configure(&serde_json::Value::Null);
+    CLIENT
+        .read()
+        .ok()
+        .and_then(|c| c.as_ref().map(|c| c.client.clone()))
+        .unwrap_or_default()
+}
+
+// The configured proxy, for clients that can't share the pooled one
+pub(crate) fn proxy_url() -> Option<reqwest::Url> {
+    let settings = CLIENT.read().ok().and_then(|c| c.as_ref().map(|c| c.settings.clone()))?;
+    if !settings.enabled {
+        return None;
+    }
+    reqwest::Url::parse(&settings.url).ok()
+}
+
+// Proxy env vars for child processes such as the bridge
+pub(crate) fn proxy_env() -> Vec<(&'static str, String)> {
+    let Some(settings) = CLIENT.read().ok().and_then(|c| c.as_ref().map(|c| c.settings.clone())) else {
+        return vec![];
+    };
+    if !settings.enabled {
+        return vec![];
+    }
+    let no_proxy = settings.no_proxy_list();
+    vec![
+        ("HTTP_PROXY", settings.url.clone()),
+        ("HTTPS_PROXY", settings.url.clone()),
+        ("ALL_PROXY", settings.url.clone()),
+        ("NO_PROXY", no_proxy.clone()),
+        ("http_proxy", settings.url.clone()),
+        ("https_proxy", settings.url.clone()),
+        ("all_proxy", settings.url),
+        ("no_proxy", no_proxy),
+    ]
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    ok: bool,
+    http_status: Option<u16>,
+    latency_ms: u64,
+    error: Option<String>,
+}
+
+#[tauri::command]
+pub(crate) fn get_proxy_settings(store: State<'_, ConfigStore>) -> Result<ProxySettings, String> {
+    Ok(store.read()?.map(|c| read_settings(&c)).unwrap_or_default())
+}
+
+// Takes effect for the desktop immediately and for the bridge on its next start
+#[tauri::command]
+pub(crate) fn set_proxy_settings(store: State<'_, ConfigStore>, settings: ProxySettings) -> Result<bool, String> {
+    validate(&settings)?;
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    config["proxy"] = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+    Ok(true)
+}
+
+// Check proposed settings before saving them
+#[tauri::command]
+pub(crate) async fn test_proxy(settings: ProxySettings) -> Result<ProxyTestResult, String> {
+    validate(&settings)?;
+    let client = build_client(&settings)?;
+
+    let started = Instant::now();
+    let result = client.get(PROXY_TEST_URL).timeout(Duration::from_secs(15)).send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        // Any HTTP answer means we got through; Telegram's root just redirects
+        Ok(response) => ProxyTestResult {
+            ok: true,
+            http_status: Some(response.status().as_u16()),
+            latency_ms,
+            error: None,
+        },
+        Err(e) => ProxyTestResult {
+            ok: false,
+            http_status: None,
+            latency_ms,
+            error: Some(e.to_string()),
+        },
+    })
+}
@@ -0,0 +1,346 @@
+// Backend message catalogs.
+//
+// User-facing text produced in Rust (tray labels, service log annotations,
+// notifications, command errors) is looked up by key in an embedded catalog
+// for the current locale, falling back to English. Placeholders are written
+// `{name}`. The locale follows the system language until the user picks one,
+// which is then remembered in the desktop's data dir.
+
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::config::get_desktop_data_dir;
+
+const DEFAULT_LOCALE: &str = "en";
+
+static LOCALE: RwLock<String> = RwLock::new(String::new());
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+const EN: Catalog = &[
+    ("tray.show", "Show Window"),
+    ("tray.quit", "Quit CCB"),
+    ("tray.pairings", "Pending pairings ({count})"),
+    ("tray.pairing", "{user} ({channel})"),
+    ("tray.approve", "Approve"),
+    ("tray.deny", "Deny"),
+    ("tray.more_pairings", "{count} more in the window…"),
+    ("tray.pairing_title", "Pairing request"),
+    ("tray.pairing_gone", "The request from {user} expired or was already handled"),
+    ("tray.approve_failed", "Failed to approve {user}: {error}"),
+    ("tray.deny_failed", "Failed to deny {user}: {error}"),
+    ("tray.status_stopped", "CCB: bridge stopped"),
+    ("tray.status_starting", "CCB: bridge starting…"),
+    ("tray.status_running", "CCB: bridge running, {sessions} active sessions"),
+    ("tray.status_error", "CCB: bridge needs attention"),
+    ("tray.ccb_update", "A bridge update is available: ccb {version}"),
+    ("service.starting", "Starting CCB bridge..."),
+    ("service.previous_stopped", "Previous process had stopped, starting fresh..."),
+    ("service.stale_state", "Resetting stale state..."),
+    ("service.not_found", "Failed to start: ccb command not found. Install it from the app, or globally with: npm install -g claude-code-bridge"),
+    ("service.stopped", "Bridge stopped."),
+    ("schedule.starting", "Starting the bridge as scheduled"),
+    ("schedule.stopping", "Stopping the bridge as scheduled"),
+    ("service.may_be_running", "Warning: Bridge may still be running"),
+    ("service.remote_active", "The app is managing the remote bridge {name}. Switch to the local bridge to start or stop it here."),
+    ("service.exited_during_sleep", "Bridge exited while the system was asleep."),
+    ("service.exited", "Bridge exited ({status})."),
+    ("service.crashed", "Bridge crashed ({status})."),
+    ("service.restarting", "Restarting in {seconds}s (attempt {attempt} of {max})..."),
+    ("service.restart_limit", "Bridge crashed {max} times in a row; not restarting it again."),
+    ("startup.exited", "Bridge exited during startup ({status})"),
+    ("startup.timeout", "Bridge did not respond within {seconds} seconds"),
+    ("startup.unmet_dependencies", "Agents are missing local services: {list}"),
+    ("config.not_found", "Config file not found"),
+    ("config.invalid", "Invalid config structure"),
+    ("agent.exists", "Agent '{id}' already exists"),
+    ("agent.not_found", "Agent '{id}' not found"),
+    ("agent.last", "Cannot remove the last agent"),
+    ("agent.copy_name", "{name} (copy)"),
+    ("template.code_reviewer.name", "Code reviewer"),
+    ("template.code_reviewer.description", "Reviews diffs for bugs, security issues and missing tests without editing files"),
+    ("template.docs_writer.name", "Docs writer"),
+    ("template.docs_writer.description", "Keeps the README, guides and comments up to date; can edit files but not run commands"),
+    ("template.devops.name", "DevOps agent"),
+    ("template.devops.description", "Investigates deployments, containers and servers with read-only commands first"),
+    ("takeover.title", "Takeover active"),
+    ("takeover.body", "The agent is paused in {chat}. Replies now come from you."),
+    ("pairing.confirm_title", "Approve access to a sensitive agent?"),
+    ("pairing.confirm_body", "{user} is asking to pair with '{agent}', which is marked as high sensitivity. Only approve people you know."),
+    ("pairing.confirm_ok", "Approve"),
+    ("pairing.confirm_cancel", "Cancel"),
+    ("pairing.not_confirmed", "Approval was not confirmed"),
+    ("pairing.not_found", "This pairing request has expired or was already handled"),
+    ("pairing.invite_sensitive", "Invites can't be used for '{agent}', which is marked as high sensitivity. Have each user message the bot and approve them here."),
+    ("pairing.invites_unsupported", "The bridge can't create pairing invites yet. Have users message the bot and approve their pairing requests here."),
+    ("updates.not_configured", "App updates aren't set up for this build yet. Download new versions from the releases page."),
+    ("app_lock.required", "Enter the app lock PIN to approve access to a sensitive agent"),
+    ("app_lock.wrong_pin", "Wrong PIN"),
+    ("pairing.notify_title", "New pairing request"),
+    ("pairing.notify_body", "New pairing request from {user} on {channel} (code {code})"),
+    ("budget.title", "Budget exceeded"),
+    ("budget.session_body", "A session of {agent} has cost {cost}, over its {limit} limit."),
+    ("budget.day_body", "Today's usage has reached {cost}, over the {limit} daily limit."),
+    ("budget.paused", "Paused {count} session(s)."),
+    ("notify.bridge_crashed", "Bridge crashed"),
+    ("notify.config_drift_title", "Config changed"),
+    ("notify.config_drift_body", "config.json has changed since the bridge started. Restart the bridge to apply the changes."),
+    ("permission.title", "Allow this action?"),
+    ("permission.body", "Agent '{agent}' wants to use {tool}:\n\n{detail}"),
+    ("permission.allow", "Allow"),
+    ("permission.deny", "Deny"),
+    ("permission.allowed", "Allowed {tool} from the permission prompt"),
+    ("permission.denied", "Denied {tool} from the permission prompt"),
+    ("permission.timed_out", "Denied {tool}: the permission prompt was not answered in time"),
+    ("permission.denied_message", "The user denied this action"),
+    ("permission.timed_out_message", "The user did not answer in time, so this action was denied"),
+];
+
+const ES: Catalog = &[
+    ("tray.show", "Mostrar ventana"),
+    ("tray.quit", "Salir de CCB"),
+    ("tray.pairings", "Vinculaciones pendientes ({count})"),
+    ("tray.pairing", "{user} ({channel})"),
+    ("tray.approve", "Aprobar"),
+    ("tray.deny", "Rechazar"),
+    ("tray.more_pairings", "{count} más en la ventana…"),
+    ("tray.pairing_title", "Solicitud de vinculación"),
+    ("tray.pairing_gone", "La solicitud de {user} caducó o ya se gestionó"),
+    ("tray.approve_failed", "No se pudo aprobar a {user}: {error}"),
+    ("tray.deny_failed", "No se pudo rechazar a {user}: {error}"),
+    ("tray.status_stopped", "CCB: puente detenido"),
+    ("tray.status_starting", "CCB: iniciando el puente…"),
+    ("tray.status_running", "CCB: puente en marcha, {sessions} sesiones activas"),
+    ("tray.status_error", "CCB: el puente requiere atención"),
+    ("tray.ccb_update", "Hay una actualización del puente: ccb {version}"),
+    ("service.starting", "Iniciando el puente CCB..."),
+    ("service.previous_stopped", "El proceso anterior se había detenido, iniciando de nuevo..."),
+    ("service.stale_state", "Restableciendo estado obsoleto..."),
+    ("service.not_found", "No se pudo iniciar: no se encontró el comando ccb. Instálalo desde la app o globalmente con: npm install -g claude-code-bridge"),
+    ("service.stopped", "Puente detenido."),
+    ("schedule.starting", "Iniciando el puente según lo programado"),
+    ("schedule.stopping", "Deteniendo el puente según lo programado"),
+    ("service.may_be_running", "Advertencia: es posible que el puente siga en ejecución"),
+    ("service.remote_active", "La app está gestionando el puente remoto {name}. Cambia al puente local para iniciarlo o detenerlo aquí."),
+    ("service.exited_during_sleep", "El puente se detuvo mientras el sistema estaba en reposo."),
+    ("service.exited", "El puente se detuvo ({status})."),
+    ("service.crashed", "El puente falló ({status})."),
+    ("service.restarting", "Reiniciando en {seconds}s (intento {attempt} de {max})..."),
+    ("service.restart_limit", "El puente falló {max} veces seguidas; no se volverá a reiniciar."),
+    ("startup.exited", "El puente terminó durante el arranque ({status})"),
+    ("startup.timeout", "El puente no respondió en {seconds} segundos"),
+    ("startup.unmet_dependencies", "Faltan servicios locales para los agentes: {list}"),
+    ("config.not_found", "No se encontró el archivo de configuración"),
+    ("config.invalid", "Estructura de configuración no válida"),
+    ("agent.exists", "El agente '{id}' ya existe"),
+    ("agent.not_found", "No se encontró el agente '{id}'"),
+    ("agent.last", "No se puede eliminar el último agente"),
+    ("agent.copy_name", "{name} (copia)"),
+    ("template.code_reviewer.name", "Revisor de código"),
+    ("template.code_reviewer.description", "Revisa cambios en busca de errores, problemas de seguridad y pruebas que faltan, sin editar archivos"),
+    ("template.docs_writer.name", "Redactor de documentación"),
+    ("template.docs_writer.description", "Mantiene al día el README, las guías y los comentarios; puede editar archivos pero no ejecutar comandos"),
+    ("template.devops.name", "Agente DevOps"),
+    ("template.devops.description", "Investiga despliegues, contenedores y servidores empezando por comandos de solo lectura"),
+    ("takeover.title", "Control manual activo"),
+    ("takeover.body", "El agente está en pausa en {chat}. Ahora respondes tú."),
+    ("pairing.confirm_title", "¿Aprobar el acceso a un agente sensible?"),
+    ("pairing.confirm_body", "{user} solicita vincularse con '{agent}', marcado como de alta sensibilidad. Aprueba solo a personas que conozcas."),
+    ("pairing.confirm_ok", "Aprobar"),
+    ("pairing.confirm_cancel", "Cancelar"),
+    ("pairing.not_confirmed", "La aprobación no se confirmó"),
+    ("pairing.not_found", "Esta solicitud de vinculación caducó o ya se gestionó"),
+    ("pairing.invite_sensitive", "No se pueden usar invitaciones para '{agent}', marcado como de alta sensibilidad. Pide a cada usuario que escriba al bot y apruébalo aquí."),
+    ("pairing.invites_unsupported", "El bridge todavía no puede crear invitaciones de vinculación. Pide a los usuarios que escriban al bot y aprueba sus solicitudes aquí."),
+    ("updates.not_configured", "Las actualizaciones de la app aún no están configuradas en esta versión. Descarga las nuevas versiones desde la página de releases."),
+    ("app_lock.required", "Introduce el PIN de bloqueo de la app para aprobar el acceso a un agente sensible"),
+    ("app_lock.wrong_pin", "PIN incorrecto"),
+    ("pairing.notify_title", "Nueva solicitud de vinculación"),
+    ("pairing.notify_body", "Nueva solicitud de vinculación de {user} en {channel} (código {code})"),
+    ("budget.title", "Presupuesto superado"),
+    ("budget.session_body", "Una sesión de {agent} ha costado {cost}, por encima de su límite de {limit}."),
+    ("budget.day_body", "El uso de hoy ha llegado a {cost}, por encima del límite diario de {limit}."),
+    ("budget.paused", "Se pausaron {count} sesión(es)."),
+    ("notify.bridge_crashed", "El puente falló"),
+    ("notify.config_drift_title", "Configuración modificada"),
+    ("notify.config_drift_body", "config.json cambió desde que se inició el puente. Reinicia el puente para aplicar los cambios."),
+    ("permission.title", "¿Permitir esta acción?"),
+    ("permission.body", "El agente '{agent}' quiere usar {tool}:\n\n{detail}"),
+    ("permission.allow", "Permitir"),
+    ("permission.deny", "Denegar"),
+    ("permission.allowed", "{tool} permitido desde la solicitud de permiso"),
+    ("permission.denied", "{tool} denegado desde la solicitud de permiso"),
+    ("permission.timed_out", "{tool} denegado: la solicitud de permiso no se respondió a tiempo"),
+    ("permission.denied_message", "El usuario denegó esta acción"),
+    ("permission.timed_out_message", "El usuario no respondió a tiempo, así que esta acción se denegó"),
+];
+
+const DE: Catalog = &[
+    ("tray.show", "Fenster anzeigen"),
+    ("tray.quit", "CCB beenden"),
+    ("tray.pairings", "Offene Kopplungen ({count})"),
+    ("tray.pairing", "{user} ({channel})"),
+    ("tray.approve", "Erlauben"),
+    ("tray.deny", "Ablehnen"),
+    ("tray.more_pairings", "{count} weitere im Fenster…"),
+    ("tray.pairing_title", "Kopplungsanfrage"),
+    ("tray.pairing_gone", "Die Anfrage von {user} ist abgelaufen oder wurde schon bearbeitet"),
+    ("tray.approve_failed", "{user} konnte nicht freigegeben werden: {error}"),
+    ("tray.deny_failed", "{user} konnte nicht abgelehnt werden: {error}"),
+    ("tray.status_stopped", "CCB: Bridge gestoppt"),
+    ("tray.status_starting", "CCB: Bridge wird gestartet…"),
+    ("tray.status_running", "CCB: Bridge läuft, {sessions} aktive Sitzungen"),
+    ("tray.status_error", "CCB: Bridge benötigt Aufmerksamkeit"),
+    ("tray.ccb_update", "Ein Bridge-Update ist verfügbar: ccb {version}"),
+    ("service.starting", "CCB-Bridge wird gestartet..."),
+    ("service.previous_stopped", "Vorheriger Prozess war beendet, starte neu..."),
+    ("service.stale_state", "Veralteter Zustand wird zurückgesetzt..."),
+    ("service.not_found", "Start fehlgeschlagen: ccb-Befehl nicht gefunden. Bitte in der App oder global installieren mit: npm install -g claude-code-bridge"),
+    ("service.stopped", "Bridge gestoppt."),
+    ("schedule.starting", "Bridge wird planmäßig gestartet"),
+    ("schedule.stopping", "Bridge wird planmäßig gestoppt"),
+    ("service.may_be_running", "Warnung: Die Bridge läuft möglicherweise noch"),
+    ("service.remote_active", "Die App verwaltet die entfernte Bridge {name}. Wechsle zur lokalen Bridge, um sie hier zu starten oder zu stoppen."),
+    ("service.exited_during_sleep", "Die Bridge wurde beendet, während das System im Ruhezustand war."),
+    ("service.exited", "Bridge wurde beendet ({status})."),
+    ("service.crashed", "Bridge ist abgestürzt ({status})."),
+    ("service.restarting", "Neustart in {seconds}s (Versuch {attempt} von {max})..."),
+    ("service.restart_limit", "Bridge ist {max}-mal in Folge abgestürzt; kein weiterer Neustart."),
+    ("startup.exited", "Die Bridge wurde beim Start beendet ({status})"),
+    ("startup.timeout", "Die Bridge hat nicht innerhalb von {seconds} Sekunden geantwortet"),
+    ("startup.unmet_dependencies", "Den Agenten fehlen lokale Dienste: {list}"),
+    ("config.not_found", "Konfigurationsdatei nicht gefunden"),
+    ("config.invalid", "Ungültige Konfigurationsstruktur"),
+    ("agent.exists", "Agent '{id}' existiert bereits"),
+    ("agent.not_found", "Agent '{id}' nicht gefunden"),
+    ("agent.last", "Der letzte Agent kann nicht entfernt werden"),
+    ("agent.copy_name", "{name} (Kopie)"),
+    ("template.code_reviewer.name", "Code-Reviewer"),
+    ("template.code_reviewer.description", "Prüft Änderungen auf Fehler, Sicherheitsprobleme und fehlende Tests, ohne Dateien zu bearbeiten"),
+    ("template.docs_writer.name", "Dokumentation"),
+    ("template.docs_writer.description", "Hält README, Anleitungen und Kommentare aktuell; darf Dateien bearbeiten, aber keine Befehle ausführen"),
+    ("template.devops.name", "DevOps-Agent"),
+    ("template.devops.description", "Untersucht Deployments, Container und Server, zuerst mit rein lesenden Befehlen"),
+    ("takeover.title", "Manuelle Übernahme aktiv"),
+    ("takeover.body", "Der Agent ist in {chat} pausiert. Antworten kommen jetzt von dir."),
+    ("pairing.confirm_title", "Zugriff auf einen sensiblen Agenten erlauben?"),
+    ("pairing.confirm_body", "{user} möchte sich mit '{agent}' verbinden, der als hochsensibel markiert ist. Erlaube nur Personen, die du kennst."),
+    ("pairing.confirm_ok", "Erlauben"),
+    ("pairing.confirm_cancel", "Abbrechen"),
+    ("pairing.not_confirmed", "Die Freigabe wurde nicht bestätigt"),
+    ("pairing.not_found", "Diese Kopplungsanfrage ist abgelaufen oder wurde bereits bearbeitet"),
+    ("pairing.invite_sensitive", "Für '{agent}', der als hochsensibel markiert ist, sind keine Einladungen möglich. Lass jeden Nutzer dem Bot schreiben und erlaube ihn hier."),
+    ("pairing.invites_unsupported", "Die Bridge kann noch keine Kopplungseinladungen erstellen. Lass Nutzer dem Bot schreiben und erlaube ihre Kopplungsanfragen hier."),
+    ("updates.not_configured", "App-Updates sind für diesen Build noch nicht eingerichtet. Lade neue Versionen von der Releases-Seite herunter."),
+    ("app_lock.required", "Gib die PIN der App-Sperre ein, um den Zugriff auf einen sensiblen Agenten zu erlauben"),
+    ("app_lock.wrong_pin", "Falsche PIN"),
+    ("pairing.notify_title", "Neue Kopplungsanfrage"),
+    ("pairing.notify_body", "Neue Kopplungsanfrage von {user} auf {channel} (Code {code})"),
+    ("budget.title", "Budget überschritten"),
+    ("budget.session_body", "Eine Sitzung von {agent} hat {cost} gekostet und damit das Limit von {limit} überschritten."),
+    ("budget.day_body", "Die heutige Nutzung liegt bei {cost} und damit über dem Tageslimit von {limit}."),
+    ("budget.paused", "{count} Sitzung(en) pausiert."),
+    ("notify.bridge_crashed", "Bridge ist abgestürzt"),
+    ("notify.config_drift_title", "Konfiguration geändert"),
+    ("notify.config_drift_body", "config.json wurde seit dem Start der Bridge geändert. Starte die Bridge neu, um die Änderungen zu übernehmen."),
+    ("permission.title", "Diese Aktion erlauben?"),
+    ("permission.body", "Agent '{agent}' möchte {tool} verwenden:\n\n{detail}"),
+    ("permission.allow", "Erlauben"),
+    ("permission.deny", "Ablehnen"),
+    ("permission.allowed", "{tool} über die Berechtigungsabfrage erlaubt"),
+    ("permission.denied", "{tool} über die Berechtigungsabfrage abgelehnt"),
+    ("permission.timed_out", "{tool} abgelehnt: Die Berechtigungsabfrage wurde nicht rechtzeitig beantwortet"),
+    ("permission.denied_message", "Der Benutzer hat diese Aktion abgelehnt"),
+    ("permission.timed_out_message", "Der Benutzer hat nicht rechtzeitig geantwortet, daher wurde diese Aktion abgelehnt"),
+];
+
+const CATALOGS: &[(&str, Catalog)] = &[("en", EN), ("es", ES), ("de", DE)];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    locale: String,
+    available: Vec<String>,
+}
+
+fn get_locale_path() -> PathBuf {
+    get_desktop_data_dir().join("locale")
+}
+
+fn catalog(locale: &str) -> Option<Catalog> {
+    CATALOGS.iter().find(|(l, _)| *l == locale).map(|(_, c)| *c)
+}
+
+// "de_DE.UTF-8" or "pt-BR" -> a supported language code
+fn normalize(locale: &str) -> Option<String> {
+    let language = locale.split(['_', '-', '.']).next()?.to_lowercase();
+    catalog(&language).map(|_| language)
+}
+
+fn system_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find_map(|value| normalize(&value))
+}
+
+pub(crate) fn current_locale() -> String {
+    LOCALE
+        .read()
+        .ok()
+        .map(|l| l.clone())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+// Pick the saved locale, else the system's; called once at startup
+pub(crate) fn init() {
+    let saved = fs::read_to_string(get_locale_path()).ok().and_then(|l| normalize(l.trim()));
+    let locale = saved.or_else(system_locale).unwrap_or_else(|| DEFAULT_LOCALE.to_string());
+    if let Ok(mut current) = LOCALE.write() {
+        *current = locale;
+    }
+}
+
+// Look up `key` in the current locale and fill in `{name}` placeholders
+pub(crate) fn tr(key: &str, args: &[(&str, &str)]) -> String {
+    let lookup = |c: Catalog| c.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    let template = catalog(&current_locale())
+        .and_then(lookup)
+        .or_else(|| lookup(EN))
+        .unwrap_or(key);
+
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+pub(crate) fn t(key: &str) -> String {
+    tr(key, &[])
+}
+
+#[tauri::command]
+pub(crate) fn get_locale() -> LocaleInfo {
+    LocaleInfo {
+        locale: current_locale(),
+        available: CATALOGS.iter().map(|(l, _)| l.to_string()).collect(),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn set_locale(app: tauri::AppHandle, locale: String) -> Result<LocaleInfo, String> {
+    let locale = normalize(&locale).ok_or(format!("Unsupported locale '{}'", locale))?;
+
+    let path = get_locale_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    fs::write(&path, &locale).map_err(|e| format!("Failed to save locale: {}", e))?;
+    *LOCALE.write().map_err(|e| e.to_string())? = locale;
+
+    // Relabel the tray menu in the new language
+    crate::tray::refresh_tray_menu(&app).map_err(|e| e.to_string())?;
+
+    Ok(get_locale())
+}
@@ -0,0 +1,277 @@
+// Operator identities spanning several chat accounts.
+//
+// An identity links chat users across channels (say a Telegram user and a
+// Discord user) as one person, stored under `identities` in config.json.
+// Being paired on one account pairs the linked accounts too, rate limit
+// overrides can target `identity:<id>`, and sessions are labelled with the
+// identity's name instead of raw numeric IDs. Pairing a linked account with a
+// high-sensitivity agent takes the same confirmation as approving it by hand.
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+use crate::api_client::{ApiError, BridgeApiClient, PairingRequest, UserInfo};
+use crate::broadcast::fetch_paired_chats;
+use crate::config::ConfigStore;
+use crate::{app_lock, log_forwarding, pairing_policy};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityMember {
+    channel: String,
+    user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Identity {
+    id: String,
+    name: String,
+    #[serde(default)]
+    members: Vec<IdentityMember>,
+}
+
+fn read_identities(config: &serde_json::Value) -> Vec<Identity> {
+    config
+        .get("identities")
+        .and_then(|i| serde_json::from_value(i.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn update_identities<T>(
+    store: &ConfigStore,
+    update: impl FnOnce(&mut Vec<Identity>) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let mut identities = read_identities(&config);
+    let result = update(&mut identities)?;
+    config["identities"] = serde_json::to_value(&identities).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+    Ok(result)
+}
+
+// Direct-message chat keys end in the user's ID: telegram:<botId>:<userId> or
+// telegram:<userId> for single-bot setups; group keys don't identify a user
+fn dm_user(chat_key: &str) -> Option<(&str, &str)> {
+    let parts: Vec<&str> = chat_key.split(':').collect();
+    if parts.len() < 2 || parts.contains(&"group") || parts.contains(&"channel") {
+        return None;
+    }
+    Some((parts[0], parts[parts.len() - 1]))
+}
+
+fn find_identity<'a>(identities: &'a [Identity], channel: &str, user_id: &str) -> Option<&'a Identity> {
+    identities
+        .iter()
+        .find(|i| i.members.iter().any(|m| m.channel == channel && m.user_id == user_id))
+}
+
+// Name of the identity behind a chat, for labelling sessions
+pub(crate) fn label_for_chat(config: &serde_json::Value, chat_key: &str) -> Option<String> {
+    let (channel, user_id) = dm_user(chat_key)?;
+    find_identity(&read_identities(config), channel, user_id).map(|i| i.name.clone())
+}
+
+// The DM chat keys the bridge uses for a member, one per configured bot
+fn member_chat_keys(config: &serde_json::Value, member: &IdentityMember) -> Vec<String> {
+    let bot_ids: Vec<&str> = config
+        .get("channels")
+        .and_then(|c| c.get(&member.channel))
+        .and_then(|c| c.get("bots"))
+        .and_then(|b| b.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|b| b.get("id").and_then(|v| v.as_str()))
+        .collect();
+    if bot_ids.is_empty() {
+        vec![format!("{}:{}", member.channel, member.user_id)]
+    } else {
+        bot_ids
+            .into_iter()
+            .map(|bot| format!("{}:{}:{}", member.channel, bot, member.user_id))
+            .collect()
+    }
+}
+
+// Every DM chat key of an identity's accounts, for `identity:<id>` overrides
+pub(crate) fn identity_chat_keys(config: &serde_json::Value, identity_id: &str) -> Option<Vec<String>> {
+    let identities = read_identities(config);
+    let identity = identities.iter().find(|i| i.id == identity_id)?;
+    Some(identity.members.iter().flat_map(|m| member_chat_keys(config, m)).collect())
+}
+
+// The pairing the bridge would have shown for this account, for the policy checks
+fn as_pairing(identity: &Identity, member: &IdentityMember, chat_key: &str) -> PairingRequest {
+    PairingRequest {
+        code: String::new(),
+        chat_key: chat_key.to_string(),
+        user_info: UserInfo {
+            id: member.user_id.clone(),
+            username: member.username.clone(),
+            display_name: Some(identity.name.clone()),
+            channel: member.channel.clone(),
+        },
+        created_at: String::new(),
+        expires_at: String::new(),
+    }
+}
+
+// If any linked account is paired, pair the rest; returns the chat keys added.
+// Accounts routed to a high-sensitivity agent need the app lock PIN and a
+// native confirmation, and are skipped when the operator declines
+async fn sync_allowlist(
+    app: &AppHandle,
+    config: &serde_json::Value,
+    identity: &Identity,
+    pin: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let api = app.state::<BridgeApiClient>();
+    let paired = fetch_paired_chats(&api).await?;
+    let any_paired = identity
+        .members
+        .iter()
+        .flat_map(|m| member_chat_keys(config, m))
+        .any(|key| paired.contains(&key));
+    if !any_paired {
+        return Ok(vec![]);
+    }
+
+    let mut added = Vec::new();
+    for member in &identity.members {
+        for chat_key in member_chat_keys(config, member) {
+            if paired.contains(&chat_key) {
+                continue;
+            }
+            let pairing = as_pairing(identity, member, &chat_key);
+            if let Some(agent_id) = pairing_policy::sensitive_target(config, &pairing) {
+                app_lock::verify(pin).await.map_err(|e| e.to_string())?;
+                if !pairing_policy::confirm_approval(app, &pairing, &agent_id).await {
+                    continue;
+                }
+            }
+            let body = serde_json::json!({
+                "chatKey": chat_key,
+                "userInfo": {
+                    "id": member.user_id,
+                    "username": member.username,
+                    "displayName": identity.name,
+                    "channel": member.channel,
+                }
+            });
+            match api.post("/allowlist", Some(&body)).await {
+                Ok(_) => added.push(chat_key),
+                Err(ApiError::Unsupported(_)) => return Err("This bridge version can't pair chats".to_string()),
+                // An account the bridge refuses doesn't hold up the others
+                Err(ApiError::Status(..)) | Err(ApiError::NotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    Ok(added)
+}
+
+// After a pairing is approved, extend it to the user's linked accounts
+pub(crate) async fn sync_after_pairing(app: &AppHandle, config: &serde_json::Value, chat_key: &str, pin: Option<&str>) {
+    let Some((channel, user_id)) = dm_user(chat_key) else {
+        return;
+    };
+    if let Some(identity) = find_identity(&read_identities(config), channel, user_id) {
+        if let Err(e) = sync_allowlist(app, config, identity, pin).await {
+            log_forwarding::desktop_error(format!("Failed to sync linked accounts: {}", e));
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn list_identities(store: State<'_, ConfigStore>) -> Result<Vec<Identity>, String> {
+    Ok(store.read()?.map(|c| read_identities(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn create_identity(store: State<'_, ConfigStore>, name: String) -> Result<Identity, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Identity name cannot be empty".to_string());
+    }
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let identity = Identity {
+        id: format!("identity-{}", millis),
+        name,
+        members: vec![],
+    };
+    update_identities(&store, |identities| {
+        identities.push(identity.clone());
+        Ok(identity)
+    })
+}
+
+#[tauri::command]
+pub(crate) fn remove_identity(store: State<'_, ConfigStore>, id: String) -> Result<bool, String> {
+    update_identities(&store, |identities| {
+        let before = identities.len();
+        identities.retain(|i| i.id != id);
+        Ok(identities.len() != before)
+    })
+}
+
+// Link a chat account; returns chat keys that were paired to match the identity
+#[tauri::command]
+pub(crate) async fn link_identity(
+    app: AppHandle,
+    store: State<'_, ConfigStore>,
+    identity_id: String,
+    member: IdentityMember,
+    // The app lock PIN, needed to pair accounts with sensitive agents while a lock is set
+    pin: Option<String>,
+) -> Result<Vec<String>, String> {
+    if member.channel.is_empty() || member.user_id.is_empty() {
+        return Err("Channel and user ID are required".to_string());
+    }
+    let identity = update_identities(&store, |identities| {
+        if let Some(other) = find_identity(identities, &member.channel, &member.user_id) {
+            if other.id != identity_id {
+                return Err(format!("This account is already linked to '{}'", other.name));
+            }
+        }
+        let identity = identities
+            .iter_mut()
+            .find(|i| i.id == identity_id)
+            .ok_or(format!("Identity '{}' not found", identity_id))?;
+        if !identity.members.contains(&member) {
+            identity.members.retain(|m| !(m.channel == member.channel && m.user_id == member.user_id));
+            identity.members.push(member);
+        }
+        Ok(identity.clone())
+    })?;
+
+    let config = store.read()?.unwrap_or_default();
+    // The link is saved even when the bridge isn't running to sync pairings
+    match sync_allowlist(&app, &config, &identity, pin.as_deref()).await {
+        Ok(added) => Ok(added),
+        Err(e) => {
+            log_forwarding::desktop_error(format!("Failed to sync linked accounts: {}", e));
+            Ok(vec![])
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn unlink_identity(
+    store: State<'_, ConfigStore>,
+    identity_id: String,
+    channel: String,
+    user_id: String,
+) -> Result<bool, String> {
+    update_identities(&store, |identities| {
+        let identity = identities
+            .iter_mut()
+            .find(|i| i.id == identity_id)
+            .ok_or(format!("Identity '{}' not found", identity_id))?;
+        let before = identity.members.len();
+        identity.members.retain(|m| !(m.channel == channel && m.user_id == user_id));
+        Ok(identity.members.len() != before)
+    })
+}
@@ -0,0 +1,101 @@
+// Status across several bridges.
+//
+// Users with more than one bridge (a laptop plus a home server, say) list the
+// others in the app settings. Their statuses are fetched concurrently, each
+// with its own deadline, so an unreachable remote shows up as an error on its
+// own row instead of holding up the rest, and the results are rolled up into
+// totals for the dashboard.
+
+use futures::future::join_all;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::api_client::BridgeStatus;
+use crate::app_settings::{self, DEFAULT_INSTANCE};
+use crate::bridge_endpoints;
+
+// A remote slower than this counts as unreachable for this refresh
+const INSTANCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStatus {
+    id: String,
+    name: String,
+    url: String,
+    status: Option<BridgeStatus>,
+    error: Option<String>,
+    latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceTotals {
+    reachable: u32,
+    unreachable: u32,
+    channels_connected: u32,
+    channels_total: u32,
+    active_sessions: u32,
+    pending_pairings: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiBridgeStatus {
+    instances: Vec<InstanceStatus>,
+    totals: InstanceTotals,
+}
+
+async fn fetch(id: String, name: String, url: String) -> InstanceStatus {
+    let started = Instant::now();
+    let (status, error) = match bridge_endpoints::api_for(&id) {
+        Ok(api) => match tokio::time::timeout(INSTANCE_TIMEOUT, api.status()).await {
+            Ok(Ok(status)) => (Some(status), None),
+            Ok(Err(e)) => (None, Some(e.to_string())),
+            Err(_) => (None, Some("Bridge did not answer in time".to_string())),
+        },
+        Err(e) => (None, Some(e.to_string())),
+    };
+    InstanceStatus {
+        id,
+        name,
+        url,
+        latency_ms: status.is_some().then(|| started.elapsed().as_millis() as u64),
+        status,
+        error,
+    }
+}
+
+fn totals(instances: &[InstanceStatus]) -> InstanceTotals {
+    let mut totals = InstanceTotals::default();
+    for instance in instances {
+        let Some(ref status) = instance.status else {
+            totals.unreachable += 1;
+            continue;
+        };
+        totals.reachable += 1;
+        totals.channels_total += status.channels.iter().filter(|c| c.enabled).count() as u32;
+        totals.channels_connected += status.channels.iter().filter(|c| c.enabled && c.connected).count() as u32;
+        totals.active_sessions += status.sessions.active;
+        totals.pending_pairings += status.pairings.pending;
+    }
+    totals
+}
+
+#[tauri::command]
+pub(crate) async fn get_bridges_status() -> Result<MultiBridgeStatus, String> {
+    let main_url = app_settings::local_api_url();
+    let mut targets = vec![(DEFAULT_INSTANCE.to_string(), main_url.trim_start_matches("http://").to_string(), main_url)];
+    targets.extend(
+        app_settings::current()
+            .instances
+            .into_iter()
+            .map(|instance| (instance.id, instance.name, instance.url)),
+    );
+
+    let instances = join_all(targets.into_iter().map(|(id, name, url)| fetch(id, name, url))).await;
+    Ok(MultiBridgeStatus {
+        totals: totals(&instances),
+        instances,
+    })
+}
@@ -0,0 +1,185 @@
+// Incremental parsing of large Control API responses.
+//
+// Endpoints like /sessions and session transcripts answer with a single object
+// wrapping one big array (`{"sessions": [...]}`). Instead of buffering the whole
+// body and deserializing it at once, the response is read chunk by chunk and
+// every array element is deserialized as soon as its closing byte arrives.
+
+use serde::de::DeserializeOwned;
+
+// Elements handed to the caller per batch
+const BATCH_SIZE: usize = 50;
+
+enum Phase {
+    // Looking for `"<key>"` followed by `:` and `[`
+    SeekingArray,
+    InArray,
+    Done,
+}
+
+pub(crate) struct JsonArrayStream {
+    key: String,
+    buf: Vec<u8>,
+    phase: Phase,
+    // Start of the element currently being scanned, if any
+    start: Option<usize>,
+    // Scan position and lexer state, kept across chunks so no byte is rescanned
+    pos: usize,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+impl JsonArrayStream {
+    pub(crate) fn new(key: &str) -> Self {
+        Self {
+            key: format!("\"{}\"", key),
+            buf: Vec::new(),
+            phase: Phase::SeekingArray,
+            start: None,
+            pos: 0,
+            depth: 0,
+            in_string: false,
+            escaped: false,
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        matches!(self.phase, Phase::Done)
+    }
+
+    // Feed the next chunk of the body, returning every element it completed
+    pub(crate) fn feed<T: DeserializeOwned>(&mut self, chunk: &[u8]) -> Result<Vec<T>, String> {
+        self.buf.extend_from_slice(chunk);
+        let mut items = Vec::new();
+
+        if let Phase::SeekingArray = self.phase {
+            if !self.seek_array() {
+                return Ok(items);
+            }
+        }
+
+        while let Phase::InArray = self.phase {
+            if self.pos >= self.buf.len() {
+                break;
+            }
+            let byte = self.buf[self.pos];
+
+            let Some(start) = self.start else {
+                // Between elements
+                match byte {
+                    b']' => self.phase = Phase::Done,
+                    b',' | b' ' | b'\n' | b'\r' | b'\t' => {}
+                    _ => {
+                        self.start = Some(self.pos);
+                        continue;
+                    }
+                }
+                self.pos += 1;
+                continue;
+            };
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+            } else {
+                match byte {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' if self.depth > 0 => self.depth -= 1,
+                    b',' | b']' if self.depth == 0 => {
+                        let item = serde_json::from_slice(&self.buf[start..self.pos])
+                            .map_err(|e| format!("Failed to parse response item: {}", e))?;
+                        items.push(item);
+                        self.start = None;
+                        // Leave the delimiter for the between-elements branch
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            self.pos += 1;
+        }
+
+        self.compact();
+        Ok(items)
+    }
+
+    fn seek_array(&mut self) -> bool {
+        let Some(key_at) = find(&self.buf, self.key.as_bytes()) else {
+            return false;
+        };
+        let after_key = key_at + self.key.len();
+        let Some(offset) = self.buf[after_key..].iter().position(|b| *b == b'[') else {
+            return false;
+        };
+        self.pos = after_key + offset + 1;
+        self.phase = Phase::InArray;
+        true
+    }
+
+    // Drop bytes that belong to already-emitted elements
+    fn compact(&mut self) {
+        let keep_from = match self.phase {
+            Phase::SeekingArray => return,
+            Phase::InArray => self.start.unwrap_or(self.pos),
+            Phase::Done => self.pos,
+        };
+        self.buf.drain(..keep_from);
+        self.pos -= keep_from;
+        if let Some(ref mut start) = self.start {
+            *start -= keep_from;
+        }
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// GET `url` and stream the array under `key` to `on_batch`, returning the item count
+pub(crate) async fn fetch_array<T, F>(
+    client: &reqwest::Client,
+    url: &str,
+    key: &str,
+    mut on_batch: F,
+) -> Result<usize, String>
+where
+    T: DeserializeOwned,
+    F: FnMut(Vec<T>),
+{
+    let mut response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("Control API returned {}", response.status()));
+    }
+
+    let mut parser = JsonArrayStream::new(key);
+    let mut batch = Vec::new();
+    let mut count = 0;
+
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        for item in parser.feed(&chunk)? {
+            batch.push(item);
+            count += 1;
+            if batch.len() >= BATCH_SIZE {
+                on_batch(std::mem::take(&mut batch));
+            }
+        }
+        if parser.is_done() {
+            break;
+        }
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+    if !parser.is_done() {
+        return Err(format!("Response ended before the '{}' list was complete", key));
+    }
+    Ok(count)
+}
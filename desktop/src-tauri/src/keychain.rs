@@ -0,0 +1,198 @@
+// Bot tokens in the OS keychain.
+//
+// With `useKeychain` on in the app settings, `save_config` stores bot tokens
+// in the macOS Keychain, Windows Credential Manager or the Secret Service
+// (libsecret) and writes a `${CCB_SECRET_<NAME>}` reference into config.json
+// instead. The bridge already expands `${VAR}` in its config, so the desktop
+// resolves the references when starting it and passes the tokens as env vars.
+
+use crate::{app_settings, log_forwarding};
+
+const SERVICE: &str = "ccb-desktop";
+const ENV_PREFIX: &str = "CCB_SECRET_";
+
+fn entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, name).map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid_chars = name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    if name.is_empty() || !valid_chars {
+        return Err("Secret names may only use A-Z, 0-9 and _".to_string());
+    }
+    Ok(())
+}
+
+// Keychain names allow A-Z, 0-9 and _ only. Lowercase letters are upper-cased
+// and anything else becomes _XX per UTF-8 byte, so "Main", "main" and "ma-in"
+// each get a name of their own and `_` is never followed by another `_`
+fn encode_name(part: &str) -> String {
+    let mut name = String::new();
+    for c in part.chars() {
+        if c.is_ascii_lowercase() || c.is_ascii_digit() {
+            name.push(c.to_ascii_uppercase());
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                name.push_str(&format!("_{:02X}", byte));
+            }
+        }
+    }
+    name
+}
+
+// TELEGRAM__MAIN for the telegram bot "main"
+fn secret_name(channel: &str, bot_id: &str) -> String {
+    format!("{}__{}", encode_name(channel), encode_name(bot_id))
+}
+
+fn reference(name: &str) -> String {
+    format!("${{{}{}}}", ENV_PREFIX, name)
+}
+
+// The secret name in a `${CCB_SECRET_<NAME>}` reference
+fn referenced_name(value: &str) -> Option<&str> {
+    value.strip_prefix("${")?.strip_suffix('}')?.strip_prefix(ENV_PREFIX)
+}
+
+pub(crate) fn is_reference(value: &str) -> bool {
+    referenced_name(value).is_some()
+}
+
+pub(crate) fn store(name: &str, value: &str) -> Result<String, String> {
+    validate_name(name)?;
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store secret in keychain: {}", e))?;
+    Ok(reference(name))
+}
+
+pub(crate) fn get(name: &str) -> Result<Option<String>, String> {
+    validate_name(name)?;
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret from keychain: {}", e)),
+    }
+}
+
+// A token as written in config.json, with a keychain reference looked up;
+// anything else comes back unchanged
+pub(crate) fn resolve(value: &str) -> String {
+    match referenced_name(value) {
+        Some(name) => get(name).ok().flatten().unwrap_or_default(),
+        None => value.to_string(),
+    }
+}
+
+fn bot_token_fields(config: &mut serde_json::Value) -> Vec<(String, &mut serde_json::Value)> {
+    let mut fields = Vec::new();
+    let Some(channels) = config.get_mut("channels").and_then(|c| c.as_object_mut()) else {
+        return fields;
+    };
+    for (channel, section) in channels.iter_mut() {
+        let token_key = if channel == "discord" { "token" } else { "botToken" };
+        let Some(section) = section.as_object_mut() else {
+            continue;
+        };
+        for (key, value) in section.iter_mut() {
+            if key == token_key {
+                fields.push((secret_name(channel, "main"), value));
+            } else if key == "bots" {
+                for bot in value.as_array_mut().into_iter().flatten() {
+                    let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main").to_string();
+                    if let Some(token) = bot.get_mut(token_key) {
+                        fields.push((secret_name(channel, &id), token));
+                    }
+                }
+            }
+        }
+    }
+    fields
+}
+
+// Move plaintext bot tokens into the keychain when the user opted in
+pub(crate) fn protect_tokens(config: &mut serde_json::Value) -> Result<(), String> {
+    if !app_settings::current().use_keychain {
+        return Ok(());
+    }
+    for (name, field) in bot_token_fields(config) {
+        let Some(token) = field.as_str().filter(|t| !t.is_empty() && referenced_name(t).is_none()) else {
+            continue;
+        };
+        *field = serde_json::Value::String(store(&name, token)?);
+    }
+    Ok(())
+}
+
+// Point references at other keychain entries, copying each secret across;
+// `rename` gives the new name, or None to leave a reference alone
+pub(crate) fn rename_references(
+    value: &mut serde_json::Value,
+    rename: &impl Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    match value {
+        serde_json::Value::String(text) => {
+            let Some(name) = referenced_name(text).and_then(rename) else {
+                return Ok(());
+            };
+            let secret = referenced_name(text).map(get).transpose()?.flatten();
+            let Some(secret) = secret else {
+                return Err(format!("Keychain has no secret for {}", text));
+            };
+            *text = store(&name, &secret)?;
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                rename_references(item, rename)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                rename_references(item, rename)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Env vars the bridge needs to expand the references anywhere in `config`
+pub(crate) fn secret_env(config: &serde_json::Value) -> Vec<(String, String)> {
+    fn collect<'a>(value: &'a serde_json::Value, names: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::String(text) => names.extend(referenced_name(text)),
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect(v, names)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, names)),
+            _ => {}
+        }
+    }
+    let mut names = Vec::new();
+    collect(config, &mut names);
+    names.sort_unstable();
+    names.dedup();
+
+    let mut env = Vec::new();
+    for name in names {
+        match get(name) {
+            Ok(Some(value)) => env.push((format!("{}{}", ENV_PREFIX, name), value)),
+            Ok(None) => log_forwarding::desktop_error(format!("Keychain has no secret {}", name)),
+            Err(e) => log_forwarding::desktop_error(e),
+        }
+    }
+    env
+}
+
+// Returns the reference to put in config.json
+#[tauri::command]
+pub(crate) fn store_secret(name: String, value: String) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("Secret is empty".to_string());
+    }
+    store(&name, &value)
+}
+
+#[tauri::command]
+pub(crate) fn get_secret(name: String) -> Result<Option<String>, String> {
+    get(&name)
+}
@@ -1,968 +1,186 @@
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
 use std::sync::{Arc, Mutex};
-use std::thread;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, State,
-};
-
-// Bridge status from the Control API
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BridgeStatus {
-    running: bool,
-    uptime: u64,
-    channels: Vec<ChannelStatus>,
-    sessions: SessionStats,
-    pairings: PairingStats,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChannelStatus {
-    name: String,
-    enabled: bool,
-    connected: bool,
-    #[serde(rename = "botCount")]
-    bot_count: u32,
-    bots: Vec<BotInfo>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BotInfo {
-    id: String,
-    username: Option<String>,
-    #[serde(rename = "agentId")]
-    agent_id: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SessionStats {
-    active: u32,
-    total: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PairingStats {
-    pending: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PairingRequest {
-    code: String,
-    #[serde(rename = "chatKey")]
-    chat_key: String,
-    #[serde(rename = "userInfo")]
-    user_info: UserInfo,
-    #[serde(rename = "createdAt")]
-    created_at: String,
-    #[serde(rename = "expiresAt")]
-    expires_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserInfo {
-    id: String,
-    username: Option<String>,
-    #[serde(rename = "displayName")]
-    display_name: Option<String>,
-    channel: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PairingsResponse {
-    pairings: Vec<PairingRequest>,
-}
-
-// Service state
-struct ServiceState {
-    process: Option<Child>,
-    is_running: bool,
-    logs: Vec<String>,
-}
-
-impl Default for ServiceState {
-    fn default() -> Self {
-        Self {
-            process: None,
-            is_running: false,
-            logs: Vec::new(),
-        }
-    }
-}
-
-type AppState = Arc<Mutex<ServiceState>>;
-
-const API_URL: &str = "http://127.0.0.1:38792";
-
-// Commands
-
-fn get_extended_path() -> String {
-    // macOS GUI apps don't inherit shell PATH, so we need to build it ourselves
-    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
-    let current_path = std::env::var("PATH").unwrap_or_default();
-
-    let mut extra_paths = vec![
-        format!("{home}/.volta/bin"),
-        format!("{home}/.npm/bin"),
-        format!("{home}/.local/bin"),
-        "/opt/homebrew/bin".to_string(),
-        "/usr/local/bin".to_string(),
-        "/usr/bin".to_string(),
-    ];
-
-    // Dynamically find all nvm node versions (sorted by version desc so newest is first)
-    let nvm_dir = format!("{home}/.nvm/versions/node");
-    if let Ok(entries) = std::fs::read_dir(&nvm_dir) {
-        let mut versions: Vec<_> = entries
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().is_dir())
-            .map(|e| format!("{}/bin", e.path().display()))
-            .collect();
-        versions.sort_by(|a, b| b.cmp(a)); // Newest versions first
-        extra_paths.splice(0..0, versions);
-    }
-
-    format!("{}:{}", extra_paths.join(":"), current_path)
-}
-
-fn try_start_ccb() -> Option<Child> {
-    let extended_path = get_extended_path();
-
-    // Try 1: ccb command with extended PATH
-    if let Ok(child) = Command::new("ccb")
-        .arg("start")
-        .env("PATH", &extended_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        return Some(child);
-    }
-
-    // Try 2: npx ccb with extended PATH
-    if let Ok(child) = Command::new("npx")
-        .args(["cc-bridge", "start"])
-        .env("PATH", &extended_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        return Some(child);
-    }
-
-    // Try 3: Check common global npm paths directly
-    let home = dirs::home_dir()?;
-    let npm_paths = [
-        home.join(".nvm/versions/node").join("*").join("bin/ccb"),
-        home.join(".volta/bin/ccb"),
-        home.join(".npm/bin/ccb"),
-        PathBuf::from("/usr/local/bin/ccb"),
-        PathBuf::from("/opt/homebrew/bin/ccb"),
-    ];
-
-    for pattern in &npm_paths {
-        if let Ok(entries) = glob::glob(pattern.to_string_lossy().as_ref()) {
-            for entry in entries.filter_map(Result::ok) {
-                if let Ok(child) = Command::new(&entry)
-                    .arg("start")
-                    .env("PATH", &extended_path)
-                    .stdout(Stdio::piped())
-                    .stderr(Stdio::piped())
-                    .spawn()
-                {
-                    return Some(child);
-                }
-            }
-        }
-    }
-
-    None
-}
-
-#[tauri::command]
-async fn start_service(state: State<'_, AppState>) -> Result<bool, String> {
-    // Clear old logs
-    {
-        let mut service = state.lock().map_err(|e| e.to_string())?;
-        service.logs.clear();
-        service.logs.push("Starting CCB bridge...".to_string());
-
-        // Check if process is actually running (not just the flag)
-        if service.is_running {
-            if let Some(ref mut child) = service.process {
-                // Check if process is still alive
-                match child.try_wait() {
-                    Ok(Some(_)) => {
-                        // Process has exited, reset state
-                        service.is_running = false;
-                        service.process = None;
-                        service.logs.push("Previous process had stopped, starting fresh...".to_string());
-                    }
-                    Ok(None) => {
-                        // Process is still running
-                        service.logs.push("Bridge is already running".to_string());
-                        return Ok(true);
-                    }
-                    Err(_) => {
-                        // Error checking, reset state
-                        service.is_running = false;
-                        service.process = None;
-                    }
-                }
-            } else {
-                // Flag is set but no process handle, reset state
-                service.is_running = false;
-                service.logs.push("Resetting stale state...".to_string());
-            }
-        }
-    }
-
-    // Try multiple ways to start the bridge
-    let child = try_start_ccb();
-
-    match child {
-        Some(mut child) => {
-            // Capture stderr for logs
-            if let Some(stderr) = child.stderr.take() {
-                let state_clone = Arc::clone(state.inner());
-                thread::spawn(move || {
-                    let reader = BufReader::new(stderr);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if let Ok(mut service) = state_clone.lock() {
-                                service.logs.push(line);
-                                // Keep last 50 lines
-                                if service.logs.len() > 50 {
-                                    service.logs.remove(0);
-                                }
-                            }
-                        }
-                    }
-                });
-            }
-
-            // Capture stdout too
-            if let Some(stdout) = child.stdout.take() {
-                let state_clone = Arc::clone(state.inner());
-                thread::spawn(move || {
-                    let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            if let Ok(mut service) = state_clone.lock() {
-                                service.logs.push(line);
-                                if service.logs.len() > 50 {
-                                    service.logs.remove(0);
-                                }
-                            }
-                        }
-                    }
-                });
-            }
-
-            {
-                let mut service = state.lock().map_err(|e| e.to_string())?;
-                service.process = Some(child);
-                service.is_running = true;
-            }
-
-            // Wait a bit for the service to start
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-
-            Ok(true)
-        }
-        None => {
-            let mut service = state.lock().map_err(|e| e.to_string())?;
-            let error_msg = "Failed to start: ccb command not found. Please install ccb globally with: npm install -g claude-code-bridge".to_string();
-            service.logs.push(error_msg.clone());
-            Err(error_msg)
-        }
-    }
-}
-
-#[tauri::command]
-async fn stop_service(state: State<'_, AppState>) -> Result<bool, String> {
-    // Try to stop gracefully via API first (works even if started outside this app)
-    let client = reqwest::Client::new();
-    let _api_result = client
-        .post(format!("{}/stop", API_URL))
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await;
-
-    // Kill our tracked process if we have one
-    {
-        let mut service = state.lock().map_err(|e| e.to_string())?;
-        if let Some(ref mut child) = service.process {
-            let _ = child.kill();
-            let _ = child.wait(); // Wait for process to actually exit
-        }
-        service.process = None;
-        service.is_running = false;
-        service.logs.push("Bridge stopped.".to_string());
-    }
-
-    // Also try to kill any ccb process by name (fallback for processes started outside this app)
-    #[cfg(unix)]
-    {
-        let _ = std::process::Command::new("pkill")
-            .args(["-f", "ccb start"])
-            .output();
-    }
-
-    // Wait a moment then verify it's stopped
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-    // Check if API is still responding
-    let still_running = client
-        .get(format!("{}/status", API_URL))
-        .timeout(std::time::Duration::from_secs(2))
-        .send()
-        .await
-        .is_ok();
-
-    if still_running {
-        let mut service = state.lock().map_err(|e| e.to_string())?;
-        service.logs.push("Warning: Bridge may still be running".to_string());
-    }
-
-    Ok(!still_running)
-}
-
-#[tauri::command]
-async fn get_status() -> Result<Option<BridgeStatus>, String> {
-    let client = reqwest::Client::new();
-
-    match client.get(format!("{}/status", API_URL)).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                let status: BridgeStatus = response.json().await.map_err(|e| e.to_string())?;
-                Ok(Some(status))
-            } else {
-                Ok(None)
-            }
-        }
-        Err(_) => Ok(None),
-    }
-}
-
-#[tauri::command]
-async fn get_pairings() -> Result<Vec<PairingRequest>, String> {
-    let client = reqwest::Client::new();
-
-    match client.get(format!("{}/pairings", API_URL)).send().await {
-        Ok(response) => {
-            if response.status().is_success() {
-                let data: PairingsResponse = response.json().await.map_err(|e| e.to_string())?;
-                Ok(data.pairings)
-            } else {
-                Ok(vec![])
-            }
-        }
-        Err(_) => Ok(vec![]),
-    }
-}
-
-#[tauri::command]
-async fn approve_pairing(code: String) -> Result<bool, String> {
-    let client = reqwest::Client::new();
-
-    match client
-        .post(format!("{}/pairings/{}/approve", API_URL, code))
-        .send()
-        .await
-    {
-        Ok(response) => Ok(response.status().is_success()),
-        Err(e) => Err(e.to_string()),
-    }
-}
-
-#[tauri::command]
-async fn deny_pairing(code: String) -> Result<bool, String> {
-    let client = reqwest::Client::new();
-
-    match client
-        .post(format!("{}/pairings/{}/deny", API_URL, code))
-        .send()
-        .await
-    {
-        Ok(response) => Ok(response.status().is_success()),
-        Err(e) => Err(e.to_string()),
-    }
-}
-
-#[tauri::command]
-fn is_service_running(state: State<'_, AppState>) -> bool {
-    state.lock().map(|s| s.is_running).unwrap_or(false)
-}
-
-#[tauri::command]
-fn get_logs(state: State<'_, AppState>) -> Vec<String> {
-    state.lock().map(|s| s.logs.clone()).unwrap_or_default()
-}
-
-#[tauri::command]
-fn clear_logs(state: State<'_, AppState>) {
-    if let Ok(mut s) = state.lock() {
-        s.logs.clear();
-    }
-}
-
-fn get_config_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".ccb").join("config.json")
-}
-
-fn get_plugins_path() -> PathBuf {
-    let home = dirs::home_dir().expect("Could not find home directory");
-    home.join(".claude").join("plugins").join("installed_plugins.json")
-}
-
-#[tauri::command]
-fn get_installed_plugins() -> Result<Vec<InstalledPlugin>, String> {
-    let plugins_path = get_plugins_path();
-
-    if !plugins_path.exists() {
-        return Ok(vec![]);
-    }
-
-    let content = fs::read_to_string(&plugins_path)
-        .map_err(|e| format!("Failed to read plugins file: {}", e))?;
-
-    let plugins_file: InstalledPluginsFile = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse plugins file: {}", e))?;
-
-    let mut installed_plugins: Vec<InstalledPlugin> = Vec::new();
-
-    for (name, entries) in plugins_file.plugins {
-        // Take the first entry (most recent) for each plugin
-        if let Some(entry) = entries.first() {
-            installed_plugins.push(InstalledPlugin {
-                name: name.clone(),
-                path: entry.install_path.clone(),
-                version: entry.version.clone(),
-            });
-        }
-    }
-
-    // Sort by name for consistent ordering
-    installed_plugins.sort_by(|a, b| a.name.cmp(&b.name));
-
-    Ok(installed_plugins)
-}
-
-#[tauri::command]
-fn check_config() -> bool {
-    get_config_path().exists()
-}
-
-// Response structure for reading config
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct ConfigResponse {
-    telegram_bots: Vec<BotConfig>,
-    discord_bots: Vec<BotConfig>,
-}
-
-#[tauri::command]
-fn read_config() -> Result<ConfigResponse, String> {
-    let config_path = get_config_path();
-
-    if !config_path.exists() {
-        return Ok(ConfigResponse::default());
-    }
-
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
-
-    let mut response = ConfigResponse::default();
-
-    // Extract Telegram bots
-    if let Some(telegram) = config.get("channels").and_then(|c| c.get("telegram")) {
-        if let Some(bots) = telegram.get("bots").and_then(|b| b.as_array()) {
-            for bot in bots {
-                let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main").to_string();
-                let token = bot.get("botToken").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                let agent_id = bot.get("agentId").and_then(|v| v.as_str()).map(|s| s.to_string());
-                response.telegram_bots.push(BotConfig { id, token, agent_id });
-            }
-        }
-    }
-
-    // Extract Discord bots (Discord uses "token" not "botToken")
-    if let Some(discord) = config.get("channels").and_then(|c| c.get("discord")) {
-        if let Some(bots) = discord.get("bots").and_then(|b| b.as_array()) {
-            for bot in bots {
-                let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main").to_string();
-                let token = bot.get("token").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                let agent_id = bot.get("agentId").and_then(|v| v.as_str()).map(|s| s.to_string());
-                response.discord_bots.push(BotConfig { id, token, agent_id });
-            }
-        }
-    }
-
-    Ok(response)
-}
-
-// Bot configuration for multi-bot support
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BotConfig {
-    id: String,
-    token: String,
-    #[serde(rename = "agentId")]
-    agent_id: Option<String>,
-}
-
-// Agent configuration
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-#[serde(rename_all = "camelCase")]
-pub struct AgentConfig {
-    id: String,
-    name: String,
-    workspace: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    model: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system_prompt: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    max_turns: Option<u32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    permission_mode: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tools: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    disallowed_tools: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    allowed_tools: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    skills: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    plugins: Option<Vec<PluginConfig>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    mcp_servers: Option<Vec<McpServerConfig>>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PluginConfig {
-    #[serde(rename = "type")]
-    plugin_type: String,
-    path: String,
-}
-
-// Installed plugin from ~/.claude/plugins/installed_plugins.json
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InstalledPlugin {
-    name: String,
-    path: String,
-    version: String,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct InstalledPluginEntry {
-    #[serde(rename = "installPath")]
-    install_path: String,
-    version: String,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-struct InstalledPluginsFile {
-    plugins: std::collections::HashMap<String, Vec<InstalledPluginEntry>>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct McpServerConfig {
-    name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    command: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    args: Option<Vec<String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    env: Option<std::collections::HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "type")]
-    server_type: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    url: Option<String>,
-}
-
-// Agent management commands
-#[tauri::command]
-fn get_agents() -> Result<Vec<AgentConfig>, String> {
-    let config_path = get_config_path();
-    if !config_path.exists() {
-        return Ok(vec![]);
-    }
-
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-
-    let config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
-
-    let agents = config
-        .get("agents")
-        .and_then(|a| a.get("list"))
-        .and_then(|l| l.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| serde_json::from_value(v.clone()).ok())
-                .collect()
-        })
-        .unwrap_or_default();
-
-    Ok(agents)
-}
-
-#[tauri::command]
-fn add_agent(agent: AgentConfig) -> Result<bool, String> {
-    let config_path = get_config_path();
-
-    let mut config: serde_json::Value = if config_path.exists() {
-        let content = fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config: {}", e))?;
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse config: {}", e))?
-    } else {
-        serde_json::json!({
-            "agents": { "list": [] },
-            "channels": {}
-        })
-    };
-
-    // Get or create agents list
-    let agents_list = config
-        .get_mut("agents")
-        .and_then(|a| a.get_mut("list"))
-        .and_then(|l| l.as_array_mut())
-        .ok_or("Invalid config structure")?;
-
-    // Check if agent already exists
-    if agents_list.iter().any(|a| a.get("id").and_then(|v| v.as_str()) == Some(&agent.id)) {
-        return Err(format!("Agent '{}' already exists", agent.id));
-    }
-
-    // Add new agent
-    let agent_value = serde_json::to_value(&agent).map_err(|e| e.to_string())?;
-    agents_list.push(agent_value);
-
-    // Write config
-    let config_str = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))?;
-
-    Ok(true)
-}
-
-#[tauri::command]
-fn update_agent(agent: AgentConfig) -> Result<bool, String> {
-    let config_path = get_config_path();
-
-    if !config_path.exists() {
-        return Err("Config file not found".to_string());
-    }
-
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-    let mut config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
-
-    let agents_list = config
-        .get_mut("agents")
-        .and_then(|a| a.get_mut("list"))
-        .and_then(|l| l.as_array_mut())
-        .ok_or("Invalid config structure")?;
-
-    // Find and update agent
-    let mut found = false;
-    for a in agents_list.iter_mut() {
-        if a.get("id").and_then(|v| v.as_str()) == Some(&agent.id) {
-            *a = serde_json::to_value(&agent).map_err(|e| e.to_string())?;
-            found = true;
-            break;
-        }
-    }
-
-    if !found {
-        return Err(format!("Agent '{}' not found", agent.id));
-    }
-
-    // Write config
-    let config_str = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))?;
-
-    Ok(true)
-}
-
-#[tauri::command]
-fn remove_agent(id: String) -> Result<bool, String> {
-    let config_path = get_config_path();
-
-    if !config_path.exists() {
-        return Err("Config file not found".to_string());
-    }
-
-    let content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config: {}", e))?;
-    let mut config: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse config: {}", e))?;
-
-    let agents_list = config
-        .get_mut("agents")
-        .and_then(|a| a.get_mut("list"))
-        .and_then(|l| l.as_array_mut())
-        .ok_or("Invalid config structure")?;
-
-    // Don't allow removing last agent
-    if agents_list.len() <= 1 {
-        return Err("Cannot remove the last agent".to_string());
-    }
-
-    // Remove agent
-    let original_len = agents_list.len();
-    agents_list.retain(|a| a.get("id").and_then(|v| v.as_str()) != Some(&id));
-
-    if agents_list.len() == original_len {
-        return Err(format!("Agent '{}' not found", id));
-    }
-
-    // Write config
-    let config_str = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))?;
-
-    Ok(true)
-}
-
-#[tauri::command]
-fn save_config(
-    telegram_bots: Option<Vec<BotConfig>>,
-    discord_bots: Option<Vec<BotConfig>>,
-    // Legacy single-token support for backward compatibility
-    telegram_token: Option<String>,
-    discord_token: Option<String>,
-) -> Result<bool, String> {
-    let config_path = get_config_path();
-    let config_dir = config_path.parent().unwrap();
-
-    // Create directory if it doesn't exist
-    fs::create_dir_all(config_dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
-
-    // Load existing config or create default
-    let mut config: serde_json::Value = if config_path.exists() {
-        let content = fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config: {}", e))?;
-        serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse config: {}", e))?
-    } else {
-        let home_dir = dirs::home_dir().unwrap().to_string_lossy().to_string();
-        serde_json::json!({
-            "agents": {
-                "default": "claude",
-                "list": [{
-                    "id": "claude",
-                    "name": "Claude",
-                    "workspace": home_dir
-                }]
-            },
-            "channels": {}
-        })
-    };
-
-    // Ensure channels object exists
-    if config.get("channels").is_none() {
-        config["channels"] = serde_json::json!({});
-    }
-
-    // Handle Telegram bots - only update if provided
-    if telegram_bots.is_some() || telegram_token.is_some() {
-        let tg_bots: Vec<serde_json::Value> = if let Some(bots) = telegram_bots {
-            bots.iter()
-                .filter(|b| !b.token.is_empty())
-                .map(|b| {
-                    let mut bot = serde_json::json!({
-                        "id": b.id,
-                        "botToken": b.token,
-                        "dmPolicy": "pairing"
-                    });
-                    if let Some(ref agent_id) = b.agent_id {
-                        if !agent_id.is_empty() {
-                            bot["agentId"] = serde_json::json!(agent_id);
-                        }
-                    }
-                    bot
-                })
-                .collect()
-        } else if let Some(ref token) = telegram_token {
-            if !token.is_empty() {
-                vec![serde_json::json!({
-                    "id": "main",
-                    "botToken": token,
-                    "dmPolicy": "pairing"
-                })]
-            } else {
-                vec![]
-            }
-        } else {
-            vec![]
-        };
-
-        if !tg_bots.is_empty() {
-            config["channels"]["telegram"] = serde_json::json!({
-                "enabled": true,
-                "bots": tg_bots
-            });
-        } else {
-            // Remove telegram if no bots
-            if let Some(channels) = config["channels"].as_object_mut() {
-                channels.remove("telegram");
-            }
-        }
-    }
-
-    // Handle Discord bots - only update if provided
-    if discord_bots.is_some() || discord_token.is_some() {
-        let dc_bots: Vec<serde_json::Value> = if let Some(bots) = discord_bots {
-            bots.iter()
-                .filter(|b| !b.token.is_empty())
-                .map(|b| {
-                    let mut bot = serde_json::json!({
-                        "id": b.id,
-                        "token": b.token,
-                        "dmPolicy": "pairing"
-                    });
-                    if let Some(ref agent_id) = b.agent_id {
-                        if !agent_id.is_empty() {
-                            bot["agentId"] = serde_json::json!(agent_id);
-                        }
-                    }
-                    bot
-                })
-                .collect()
-        } else if let Some(ref token) = discord_token {
-            if !token.is_empty() {
-                vec![serde_json::json!({
-                    "id": "main",
-                    "token": token,
-                    "dmPolicy": "pairing"
-                })]
-            } else {
-                vec![]
-            }
-        } else {
-            vec![]
-        };
-
-        if !dc_bots.is_empty() {
-            config["channels"]["discord"] = serde_json::json!({
-                "enabled": true,
-                "bots": dc_bots
-            });
-        } else {
-            // Remove discord if no bots
-            if let Some(channels) = config["channels"].as_object_mut() {
-                channels.remove("discord");
-            }
-        }
-    }
-
-    // Ensure agents exist with at least a default
-    if config.get("agents").is_none() || config["agents"].get("list").map(|l| l.as_array().map(|a| a.is_empty()).unwrap_or(true)).unwrap_or(true) {
-        let home_dir = dirs::home_dir().unwrap().to_string_lossy().to_string();
-        config["agents"] = serde_json::json!({
-            "default": "claude",
-            "list": [{
-                "id": "claude",
-                "name": "Claude",
-                "workspace": home_dir
-            }]
-        });
-    }
-
-    // Write config file
-    let config_str = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, config_str).map_err(|e| format!("Failed to write config: {}", e))?;
-
-    Ok(true)
-}
+use tauri::Manager;
+
+mod agent_dependencies;
+mod agent_templates;
+mod agents;
+mod analytics;
+mod api_client;
+mod app_lock;
+mod app_settings;
+mod app_updates;
+mod attachments;
+mod autostart;
+mod backups;
+mod bot_tokens;
+mod bridge_endpoints;
+mod bridge_schedule;
+mod broadcast;
+mod budgets;
+mod ccb_install;
+mod ccb_updates;
+mod config;
+mod config_drift;
+mod config_reload;
+mod config_transfer;
+mod config_validation;
+mod console;
+mod crash_reports;
+mod demo;
+mod diagnostics;
+mod discord_commands;
+mod discord_scope;
+mod error;
+mod events;
+mod existing_setups;
+mod file_tail;
+mod formatting;
+mod git_status;
+mod groups;
+mod heartbeat;
+mod history;
+mod http;
+mod i18n;
+mod identities;
+mod instances;
+mod json_stream;
+mod keychain;
+mod local_db;
+mod log_forwarding;
+mod logs;
+mod mcp_test;
+mod media;
+mod metrics;
+mod node_runtime;
+mod notifications;
+mod observer;
+mod outbox;
+mod pairing;
+mod pairing_policy;
+mod permission_prompts;
+mod poller;
+mod power;
+mod process_limits;
+mod profiles;
+mod prompt_presets;
+mod rate_limits;
+mod redaction;
+mod runtime_info;
+mod scheduled_messages;
+mod scheduler;
+mod secrets;
+mod self_test;
+mod service;
+mod session_plans;
+mod sessions;
+mod setup;
+mod shutdown;
+mod status_report;
+mod supervisor;
+mod takeover;
+mod tasks;
+mod telegram_profile;
+mod telegram_webhook;
+mod templates;
+mod tool_sync;
+mod transcripts;
+mod tray;
+mod usage;
+mod voice;
+mod whatsapp;
+mod workspace_disk;
+mod workspace_templates;
+mod workspace_validation;
+
+use api_client::BridgeApiClient;
+use broadcast::PendingBroadcasts;
+use config::ConfigStore;
+use console::Console;
+use heartbeat::HeartbeatState;
+use history::HistoryIndex;
+use poller::StatusCache;
+use service::{ServiceState, StartLock};
+use takeover::Takeovers;
+use tasks::TaskRegistry;
+use telegram_webhook::TunnelState;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    crash_reports::install_panic_hook();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![autostart::LAUNCH_ARG]),
+        ))
         .manage(Arc::new(Mutex::new(ServiceState::default())))
+        .manage(StartLock::default())
+        .manage(BridgeApiClient::default())
+        .manage(ConfigStore::default())
+        .manage(StatusCache::default())
+        .manage(HistoryIndex::default())
+        .manage(PendingBroadcasts::default())
+        .manage(TunnelState::default())
+        .manage(Takeovers::default())
+        .manage(HeartbeatState::default())
+        .manage(TaskRegistry::default())
+        .manage(Console::default())
         .setup(|app| {
-            // Create tray menu
-            let quit = MenuItem::with_id(app, "quit", "Quit CCB", true, None::<&str>)?;
-            let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show, &quit])?;
+            i18n::init();
 
-            // Create tray icon using the default window icon
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .icon_as_template(true)
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        rect,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            // Toggle window visibility
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                            } else {
-                                // Position window below tray icon
-                                let window_size = window.outer_size().unwrap_or(tauri::PhysicalSize { width: 320, height: 480 });
+            tray::create(app)?;
 
-                                // Extract tray rect position and size
-                                let (tray_x, tray_y) = match rect.position {
-                                    tauri::Position::Physical(pos) => (pos.x as i32, pos.y as i32),
-                                    tauri::Position::Logical(pos) => (pos.x as i32, pos.y as i32),
-                                };
-                                let (tray_w, tray_h) = match rect.size {
-                                    tauri::Size::Physical(size) => (size.width as i32, size.height as i32),
-                                    tauri::Size::Logical(size) => (size.width as i32, size.height as i32),
-                                };
+            // `--demo`: a fake bridge answers instead of ccb
+            if demo::is_enabled() {
+                demo::init(app.handle())?;
+            }
 
-                                // Calculate position: center horizontally under tray, with small gap below
-                                let x = tray_x + (tray_w / 2) - (window_size.width as i32 / 2);
-                                let y = tray_y + tray_h + 4; // 4px gap below tray
+            // Single backend loop for status and pairing polling
+            tauri::async_runtime::spawn(poller::run(app.handle().clone()));
+            tauri::async_runtime::spawn(events::run(app.handle().clone()));
+            tauri::async_runtime::spawn(log_forwarding::run());
+            tauri::async_runtime::spawn(logs::run_streaming(app.handle().clone()));
+            tauri::async_runtime::spawn(power::run(app.handle().clone()));
+            tauri::async_runtime::spawn(supervisor::run(app.handle().clone()));
+            tauri::async_runtime::spawn(backups::run());
+            tauri::async_runtime::spawn(budgets::run(app.handle().clone()));
+            tauri::async_runtime::spawn(bridge_schedule::run(app.handle().clone()));
+            tauri::async_runtime::spawn(metrics::run(app.handle().clone()));
+            tauri::async_runtime::spawn(permission_prompts::run(app.handle().clone()));
+            tauri::async_runtime::spawn(autostart::start_bridge_on_launch(app.handle().clone()));
+
+            // Keep the conversation history search index up to date
+            tauri::async_runtime::spawn(history::run_indexer(app.handle().clone()));
+
+            // Apply the attachments retention policy
+            tauri::async_runtime::spawn(attachments::run_cleanup(app.handle().clone()));
+
+            // Deliver scheduled and delayed messages
+            let api = app.state::<BridgeApiClient>().inner().clone();
+            tauri::async_runtime::spawn(scheduled_messages::run_dispatcher(api));
+
+            // Config writes ask the running bridge to reload
+            app.state::<ConfigStore>().attach(app.handle());
+
+            // Route outside requests (and the bridge) through the configured proxy
+            if let Ok(Some(config)) = app.state::<ConfigStore>().read() {
+                http::configure(&config);
+            }
 
-                                let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                    }
-                })
-                .build(app)?;
+            // Releases aren't signed yet; the updater only runs once a key is configured
+            if app_updates::is_configured(app.handle()) {
+                app.handle().plugin(tauri_plugin_updater::Builder::new().build())?;
+            }
+
+            // Remember the ccb version for crash reports
+            tauri::async_runtime::spawn(crash_reports::detect_ccb_version());
+            tauri::async_runtime::spawn(ccb_updates::run(app.handle().clone()));
 
             // Hide window when it loses focus (menu bar app behavior)
-            if let Some(window) = app.get_webview_window("main") {
+            if let Some(window) = app.get_webview_window("main").filter(|_| tray::hides_on_blur()) {
                 let window_clone = window.clone();
                 window.on_window_event(move |event| {
                     if let tauri::WindowEvent::Focused(false) = event {
@@ -973,25 +191,213 @@ pub fn run() {
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            start_service,
-            stop_service,
-            get_status,
-            get_pairings,
-            approve_pairing,
-            deny_pairing,
-            is_service_running,
-            check_config,
-            read_config,
-            save_config,
-            get_logs,
-            clear_logs,
-            get_agents,
-            add_agent,
-            update_agent,
-            remove_agent,
-            get_installed_plugins,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .invoke_handler(crash_reports::remember_commands(analytics::track_commands(observer::guard_commands(tauri::generate_handler![
+            service::start_service,
+            service::stop_service,
+            service::get_status,
+            pairing::get_pairings,
+            pairing::approve_pairing,
+            pairing::deny_pairing,
+            service::is_service_running,
+            config::check_config,
+            config::read_config,
+            config::save_config,
+            config::set_bot_enabled,
+            service::get_logs,
+            service::clear_logs,
+            agents::get_agents,
+            agents::add_agent,
+            agents::update_agent,
+            agents::remove_agent,
+            agents::duplicate_agent,
+            agent_templates::get_agent_templates,
+            agents::get_installed_plugins,
+            api_client::stream_sessions,
+            api_client::stream_transcript,
+            file_tail::read_file_page,
+            usage::get_usage_dashboard,
+            history::search_history,
+            attachments::get_attachment_settings,
+            attachments::set_attachment_settings,
+            attachments::list_received_files,
+            attachments::save_file_to,
+            attachments::cleanup_received_files,
+            voice::get_voice_settings,
+            voice::set_voice_settings,
+            voice::test_transcription,
+            media::get_media_policies,
+            media::set_media_policy,
+            templates::list_templates,
+            templates::add_template,
+            templates::remove_template,
+            templates::send_template,
+            scheduled_messages::schedule_message,
+            scheduled_messages::list_scheduled_messages,
+            scheduled_messages::cancel_scheduled_message,
+            broadcast::broadcast_message,
+            groups::list_group_chats,
+            groups::set_group_access,
+            groups::set_group_policy,
+            rate_limits::get_rate_limits,
+            rate_limits::set_default_rate_limit,
+            rate_limits::set_rate_limit_override,
+            analytics::set_analytics_enabled,
+            analytics::get_local_analytics,
+            analytics::purge_analytics,
+            metrics::get_metrics,
+            git_status::get_workspaces_git_status,
+            workspace_disk::get_workspace_disk_usage,
+            workspace_disk::clean_workspace_artifacts,
+            workspace_validation::validate_workspace,
+            tool_sync::sync_agent_tools,
+            crash_reports::list_crash_reports,
+            crash_reports::delete_crash_report,
+            crash_reports::export_crash_report,
+            crash_reports::submit_crash_report,
+            prompt_presets::list_prompt_presets,
+            prompt_presets::add_prompt_preset,
+            prompt_presets::update_prompt_preset,
+            prompt_presets::remove_prompt_preset,
+            prompt_presets::render_prompt,
+            runtime_info::get_runtime_info,
+            discord_scope::list_discord_guilds,
+            discord_scope::set_discord_scope,
+            telegram_webhook::get_webhook_settings,
+            telegram_webhook::set_webhook_settings,
+            telegram_webhook::start_tunnel,
+            telegram_webhook::stop_tunnel,
+            telegram_webhook::get_tunnel_status,
+            telegram_webhook::test_webhook,
+            takeover::request_takeover,
+            takeover::release_takeover,
+            takeover::list_takeovers,
+            takeover::send_takeover_reply,
+            self_test::run_self_test,
+            http::get_proxy_settings,
+            http::set_proxy_settings,
+            http::test_proxy,
+            i18n::get_locale,
+            i18n::set_locale,
+            observer::get_observer_mode,
+            observer::set_observer_mode,
+            heartbeat::get_heartbeat_settings,
+            heartbeat::set_heartbeat_settings,
+            heartbeat::get_heartbeat_status,
+            status_report::export_status_report,
+            outbox::get_pending_messages,
+            outbox::retry_pending_messages,
+            outbox::drop_pending_message,
+            config_drift::get_config_drift,
+            config_reload::reload_bridge_config,
+            tasks::list_tasks,
+            tasks::cancel_task,
+            self_test::start_self_test,
+            workspace_templates::create_workspace_from_template,
+            console::open_console_session,
+            console::get_console_session,
+            console::console_send,
+            console::close_console_session,
+            identities::list_identities,
+            identities::create_identity,
+            identities::remove_identity,
+            identities::link_identity,
+            identities::unlink_identity,
+            backups::get_backup_settings,
+            backups::set_backup_settings,
+            backups::get_backup_status,
+            backups::backup_config_now,
+            backups::list_config_backups,
+            backups::restore_config_backup,
+            supervisor::get_supervisor_settings,
+            supervisor::set_supervisor_settings,
+            supervisor::get_supervisor_status,
+            budgets::get_budget_settings,
+            budgets::set_budget_settings,
+            discord_commands::get_discord_commands,
+            discord_commands::register_discord_commands,
+            app_lock::get_app_lock_status,
+            app_lock::set_app_lock,
+            app_settings::get_app_settings,
+            app_settings::set_app_settings,
+            app_updates::check_for_app_update,
+            app_updates::install_app_update,
+            telegram_profile::configure_telegram_bot_profile,
+            secrets::scan_config_secrets,
+            keychain::store_secret,
+            keychain::get_secret,
+            instances::get_bridges_status,
+            log_forwarding::get_log_forwarding_settings,
+            log_forwarding::set_log_forwarding_settings,
+            log_forwarding::get_log_forwarding_status,
+            log_forwarding::test_log_forwarding,
+            sessions::get_sessions,
+            sessions::get_session_detail,
+            sessions::end_session,
+            redaction::get_redaction_settings,
+            redaction::set_redaction_settings,
+            redaction::preview_transcript_redaction,
+            redaction::export_transcript,
+            agent_dependencies::test_agent,
+            mcp_test::test_mcp_server,
+            autostart::get_autostart,
+            autostart::set_autostart,
+            logs::get_logs_filtered,
+            logs::subscribe_logs,
+            logs::unsubscribe_logs,
+            logs::search_logs,
+            notifications::get_notifications,
+            notifications::mark_notifications_read,
+            notifications::clear_notifications,
+            config_validation::validate_config,
+            permission_prompts::get_permission_prompt_settings,
+            permission_prompts::set_permission_prompt_settings,
+            pairing::create_pairing_invite,
+            pairing::get_paired_users,
+            pairing::revoke_pairing,
+            formatting::get_formatting_settings,
+            formatting::set_formatting_settings,
+            formatting::preview_formatting,
+            node_runtime::refresh_runtime_cache,
+            whatsapp::get_whatsapp_config,
+            whatsapp::save_whatsapp_config,
+            whatsapp::get_whatsapp_qr,
+            whatsapp::get_whatsapp_status,
+            process_limits::get_process_limits,
+            process_limits::set_process_limits,
+            profiles::list_profiles,
+            profiles::clone_profile,
+            profiles::switch_profile,
+            setup::get_setup_state,
+            setup::run_setup_step,
+            existing_setups::scan_existing_setup,
+            existing_setups::import_existing_setup,
+            config_transfer::export_config,
+            config_transfer::import_config,
+            ccb_install::get_ccb_version,
+            ccb_install::install_ccb,
+            ccb_install::update_ccb,
+            ccb_updates::get_bridge_version,
+            bot_tokens::validate_bot_token,
+            session_plans::get_session_plan,
+            bridge_endpoints::get_bridge_endpoints,
+            bridge_endpoints::save_bridge_endpoint,
+            bridge_endpoints::remove_bridge_endpoint,
+            bridge_endpoints::set_active_endpoint,
+            bridge_endpoints::get_endpoint_status,
+            bridge_endpoints::get_endpoint_pairings,
+            bridge_endpoints::get_endpoint_sessions,
+            scheduler::preview_schedule,
+            bridge_schedule::set_schedule,
+            bridge_schedule::get_schedule_status,
+            diagnostics::run_diagnostics,
+            diagnostics::export_diagnostics,
+        ]))))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Stop a bridge the app started before quitting
+            tauri::RunEvent::ExitRequested { api, code, .. } => shutdown::on_exit_requested(app, &api, code),
+            tauri::RunEvent::Exit => shutdown::on_exit(app),
+            _ => {}
+        });
 }
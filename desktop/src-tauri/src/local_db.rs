@@ -0,0 +1,77 @@
+// SQLite files the desktop keeps for itself.
+//
+// Metrics and local analytics each store their rows in a database of their
+// own in the desktop's data dir. A `LocalDb` holds the one connection to such
+// a file: it's opened on first use, with the tables created if they're
+// missing, and can be closed or deleted again when the data is turned off or
+// purged.
+
+use rusqlite::Connection;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::get_desktop_data_dir;
+
+pub(crate) struct LocalDb {
+    // File name in the data dir
+    file: &'static str,
+    // What it holds, for error messages
+    name: &'static str,
+    // Run on every open, so only `CREATE ... IF NOT EXISTS`
+    schema: &'static str,
+    conn: Mutex<Option<Connection>>,
+}
+
+impl LocalDb {
+    pub(crate) const fn new(file: &'static str, name: &'static str, schema: &'static str) -> Self {
+        Self {
+            file,
+            name,
+            schema,
+            conn: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        get_desktop_data_dir().join(self.file)
+    }
+
+    fn open(&self) -> Result<Connection, String> {
+        let path = self.path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+        }
+        let conn = Connection::open(&path).map_err(|e| format!("Failed to open {} database: {}", self.name, e))?;
+        conn.execute_batch(self.schema)
+            .map_err(|e| format!("Failed to initialize {} database: {}", self.name, e))?;
+        Ok(conn)
+    }
+
+    // Run `f` on the connection, opening it first if needed
+    pub(crate) fn with<T>(&self, f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+        let mut db = self.conn.lock().map_err(|e| e.to_string())?;
+        if db.is_none() {
+            *db = Some(self.open()?);
+        }
+        let conn = db.as_mut().ok_or_else(|| format!("The {} database is not open", self.name))?;
+        f(conn).map_err(|e| format!("Error in the {} database: {}", self.name, e))
+    }
+
+    // Until the next `with`
+    pub(crate) fn close(&self) -> Result<(), String> {
+        *self.conn.lock().map_err(|e| e.to_string())? = None;
+        Ok(())
+    }
+
+    // Close the connection and remove the file
+    pub(crate) fn delete(&self) -> Result<(), String> {
+        let mut db = self.conn.lock().map_err(|e| e.to_string())?;
+        *db = None;
+        let path = self.path();
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to delete {} database: {}", self.name, e))?;
+        }
+        Ok(())
+    }
+}
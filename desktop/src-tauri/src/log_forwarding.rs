@@ -0,0 +1,315 @@
+// Forwarding logs to the system log or a remote collector.
+//
+// Bridge output and the desktop's own errors can be shipped to syslog (macOS
+// and Linux), the Windows Event Log, or a Grafana Loki push endpoint, for
+// headless machines whose logs belong in existing infrastructure. Entries are
+// queued in memory and sent in batches by a background task; if the sink is
+// down the oldest entries are dropped rather than piling up.
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+
+use crate::config::get_desktop_data_dir;
+use crate::http;
+use crate::logs::{detect_level, LogEntry, LogLevel, LogSource};
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_QUEUED: usize = 5000;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const LOKI_PUSH_PATH: &str = "/loki/api/v1/push";
+const SYSLOG_TAG: &str = "ccb";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogSink {
+    #[default]
+    Off,
+    // syslog on macOS and Linux, the Event Log on Windows
+    System,
+    Loki,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardingSettings {
+    sink: LogSink,
+    // Loki base URL or full push URL
+    #[serde(default)]
+    loki_url: String,
+    // Extra stream labels, e.g. {"env": "home"}
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardingStatus {
+    sent: u64,
+    dropped: u64,
+    last_error: Option<String>,
+    last_error_at: Option<String>,
+}
+
+static SETTINGS: RwLock<Option<ForwardingSettings>> = RwLock::new(None);
+static QUEUE: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+static STATUS: Mutex<Option<ForwardingStatus>> = Mutex::new(None);
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("log_forwarding.json")
+}
+
+fn current() -> ForwardingSettings {
+    if let Some(settings) = SETTINGS.read().ok().and_then(|s| s.clone()) {
+        return settings;
+    }
+    let settings: ForwardingSettings = fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    if let Ok(mut cached) = SETTINGS.write() {
+        *cached = Some(settings.clone());
+    }
+    settings
+}
+
+fn update_status(update: impl FnOnce(&mut ForwardingStatus)) {
+    if let Ok(mut status) = STATUS.lock() {
+        update(status.get_or_insert_with(ForwardingStatus::default));
+    }
+}
+
+fn enqueue(source: LogSource, level: Option<LogLevel>, lines: &[String]) {
+    if current().sink == LogSink::Off || lines.is_empty() {
+        return;
+    }
+    let Ok(mut queue) = QUEUE.lock() else {
+        return;
+    };
+    for line in lines.iter().filter(|l| !l.trim().is_empty()) {
+        queue.push_back(LogEntry::new(source, level.unwrap_or_else(|| detect_level(line)), line.clone()));
+    }
+    let excess = queue.len().saturating_sub(MAX_QUEUED);
+    if excess > 0 {
+        queue.drain(..excess);
+        update_status(|s| s.dropped += excess as u64);
+    }
+}
+
+// Lines printed by the bridge
+pub(crate) fn forward(source: LogSource, lines: &[String]) {
+    enqueue(source, None, lines);
+}
+
+// An error in one of the desktop's background tasks; also printed to stderr
+pub(crate) fn desktop_error(message: String) {
+    eprintln!("{}", message);
+    enqueue(LogSource::Desktop, Some(LogLevel::Error), &[message]);
+}
+
+#[cfg(unix)]
+fn send_syslog(entries: &[LogEntry]) -> Result<(), String> {
+    use std::os::unix::net::UnixDatagram;
+
+    let path = ["/dev/log", "/var/run/syslog"]
+        .into_iter()
+        .find(|p| std::path::Path::new(p).exists())
+        .ok_or("No syslog socket found")?;
+    let socket = UnixDatagram::unbound().map_err(|e| format!("Failed to open syslog socket: {}", e))?;
+    for entry in entries {
+        // Facility "user" (1) with the entry's severity
+        let severity = match entry.level {
+            LogLevel::Error => 3,
+            LogLevel::Warn => 4,
+            LogLevel::Info => 6,
+            LogLevel::Debug => 7,
+        };
+        let message = format!(
+            "<{}>{} {}[{}]: [{}] {}",
+            8 + severity,
+            entry.timestamp.format("%b %e %H:%M:%S"),
+            SYSLOG_TAG,
+            std::process::id(),
+            entry.source.name(),
+            entry.message
+        );
+        socket
+            .send_to(message.as_bytes(), path)
+            .map_err(|e| format!("Failed to write to syslog: {}", e))?;
+    }
+    Ok(())
+}
+
+// One event per level, so a chatty bridge doesn't spawn a process per line
+#[cfg(windows)]
+fn send_syslog(entries: &[LogEntry]) -> Result<(), String> {
+    for (level, kind) in [
+        (LogLevel::Info, "INFORMATION"),
+        (LogLevel::Warn, "WARNING"),
+        (LogLevel::Error, "ERROR"),
+    ] {
+        let lines: Vec<&str> = entries
+            .iter()
+            .filter(|e| e.level.max(LogLevel::Info) == level)
+            .map(|e| e.message.as_str())
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        let output = std::process::Command::new("eventcreate")
+            .args(["/L", "APPLICATION", "/T", kind, "/SO", "CCB", "/ID", "1", "/D"])
+            .arg(lines.join("\n"))
+            .output()
+            .map_err(|e| format!("Failed to run eventcreate: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "eventcreate failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn loki_push_url(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    if url.ends_with(LOKI_PUSH_PATH) {
+        url.to_string()
+    } else {
+        format!("{}{}", url, LOKI_PUSH_PATH)
+    }
+}
+
+async fn send_loki(settings: &ForwardingSettings, entries: &[LogEntry]) -> Result<(), String> {
+    let host = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    // One stream per source and level
+    let mut streams: BTreeMap<(LogSource, LogLevel), Vec<[String; 2]>> = BTreeMap::new();
+    for entry in entries {
+        let nanos = entry.timestamp.timestamp_nanos_opt().unwrap_or_default();
+        streams
+            .entry((entry.source, entry.level))
+            .or_default()
+            .push([nanos.to_string(), entry.message.clone()]);
+    }
+    let streams: Vec<serde_json::Value> = streams
+        .into_iter()
+        .map(|((source, level), values)| {
+            let mut labels = serde_json::json!({
+                "job": SYSLOG_TAG,
+                "host": host,
+                "source": source,
+                "level": level,
+            });
+            for (name, value) in &settings.labels {
+                labels[name] = serde_json::json!(value);
+            }
+            serde_json::json!({ "stream": labels, "values": values })
+        })
+        .collect();
+
+    let response = http::client()
+        .post(loki_push_url(&settings.loki_url))
+        .json(&serde_json::json!({ "streams": streams }))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Loki: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Loki returned {}: {}", status, body.trim()));
+    }
+    Ok(())
+}
+
+async fn send(settings: &ForwardingSettings, entries: Vec<LogEntry>) -> Result<(), String> {
+    match settings.sink {
+        LogSink::Off => Ok(()),
+        LogSink::System => tauri::async_runtime::spawn_blocking(move || send_syslog(&entries))
+            .await
+            .map_err(|e| e.to_string())?,
+        LogSink::Loki => send_loki(settings, &entries).await,
+    }
+}
+
+pub(crate) async fn run() {
+    loop {
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        let entries: Vec<LogEntry> = match QUEUE.lock() {
+            Ok(mut queue) => queue.drain(..).collect(),
+            Err(_) => continue,
+        };
+        if entries.is_empty() {
+            continue;
+        }
+        let count = entries.len() as u64;
+        match send(&current(), entries).await {
+            Ok(()) => update_status(|s| s.sent += count),
+            Err(e) => update_status(|s| {
+                s.dropped += count;
+                s.last_error = Some(e);
+                s.last_error_at = Some(Local::now().to_rfc3339());
+            }),
+        }
+    }
+}
+
+fn validate(settings: &ForwardingSettings) -> Result<(), String> {
+    if settings.sink == LogSink::Loki {
+        let url = reqwest::Url::parse(&settings.loki_url).map_err(|e| format!("Invalid Loki URL: {}", e))?;
+        if !["http", "https"].contains(&url.scheme()) {
+            return Err("Loki URL must start with http:// or https://".to_string());
+        }
+    }
+    // Loki's label name rules
+    for name in settings.labels.keys() {
+        let valid = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && !name.starts_with(|c: char| c.is_ascii_digit());
+        if name.is_empty() || !valid {
+            return Err(format!("'{}' is not a valid label name", name));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_log_forwarding_settings() -> ForwardingSettings {
+    current()
+}
+
+#[tauri::command]
+pub(crate) fn set_log_forwarding_settings(settings: ForwardingSettings) -> Result<bool, String> {
+    validate(&settings)?;
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save log forwarding settings: {}", e))?;
+    *SETTINGS.write().map_err(|e| e.to_string())? = Some(settings);
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn get_log_forwarding_status() -> ForwardingStatus {
+    STATUS.lock().ok().and_then(|s| s.clone()).unwrap_or_default()
+}
+
+// Send one entry right away with the given settings, before saving them
+#[tauri::command]
+pub(crate) async fn test_log_forwarding(settings: ForwardingSettings) -> Result<bool, String> {
+    validate(&settings)?;
+    if settings.sink == LogSink::Off {
+        return Err("Choose where to forward logs first".to_string());
+    }
+    let entry = LogEntry::new(LogSource::Desktop, LogLevel::Info, "Test entry from CCB Desktop".to_string());
+    send(&settings, vec![entry]).await?;
+    Ok(true)
+}
@@ -0,0 +1,506 @@
+// Structured service logs.
+//
+// The bridge's output and the desktop's own notes about the service
+// (starting, crashed, restarting) are kept as entries with a timestamp, level
+// and source, in a ring buffer capped by lines and optionally bytes in the app
+// settings. The first lines of each run are kept apart from the ring, so a
+// startup error survives a chatty bridge, and a line repeating the one before
+// it only bumps that entry's count. Levels come from the bridge's JSON log
+// lines where it writes them and are guessed from the wording otherwise.
+// Every entry is also handed to a writer thread that appends it to
+// ~/.ccb/logs/desktop.log, rotated once it grows past a few megabytes.
+//
+// The log view doesn't poll: `subscribe_logs` hands it the buffer so far, and
+// from then on new entries matching its filter are queued apart from the
+// service state and pushed as `log://line` events every 100ms. A repeat is
+// sent as the entry it bumped, with its new count, to replace the view's last
+// line. Clearing the buffer sends `log://reset` ahead of any later lines.
+
+use chrono::{DateTime, Local};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::app_settings;
+use crate::service::AppState;
+
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+// desktop.log.1 (newest) to desktop.log.3 (oldest)
+const ROTATED_FILES: u32 = 3;
+
+pub(crate) const LOG_LINE_EVENT: &str = "log://line";
+pub(crate) const LOG_RESET_EVENT: &str = "log://reset";
+const STREAM_INTERVAL: Duration = Duration::from_millis(100);
+// Entries of each run kept however much follows
+const STARTUP_LINES: usize = 100;
+const DEFAULT_SEARCH_LIMIT: usize = 200;
+
+// Entries held for the next event; a stalled frontend loses the oldest
+const MAX_PENDING: usize = 5000;
+
+// The log view's filter, None while nothing is subscribed
+static SUBSCRIPTION: Mutex<Option<LogFilter>> = Mutex::new(None);
+static PENDING: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+// Set with PENDING held when the buffer is cleared
+static RESET: AtomicBool = AtomicBool::new(false);
+// Feeds the thread appending to desktop.log, so no file I/O happens under the state lock
+static WRITER: OnceLock<Sender<Vec<LogEntry>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogSource {
+    Bridge,
+    Desktop,
+}
+
+impl LogSource {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            LogSource::Bridge => "bridge",
+            LogSource::Desktop => "desktop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub(crate) timestamp: DateTime<Local>,
+    pub(crate) level: LogLevel,
+    pub(crate) source: LogSource,
+    pub(crate) message: String,
+    // Times the same line followed straight after
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub(crate) repeats: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+impl LogEntry {
+    pub(crate) fn new(source: LogSource, level: LogLevel, message: String) -> Self {
+        Self {
+            timestamp: Local::now(),
+            level,
+            source,
+            message,
+            repeats: 0,
+        }
+    }
+
+    fn repeats(&self, other: &LogEntry) -> bool {
+        self.source == other.source && self.level == other.level && self.message == other.message
+    }
+
+    // The message, with how often it repeated
+    fn line(&self) -> String {
+        if self.repeats == 0 {
+            self.message.clone()
+        } else {
+            format!("{} (repeated {} times)", self.message, self.repeats)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogFilter {
+    // This level or above
+    level: Option<LogLevel>,
+    source: Option<LogSource>,
+    // Case-insensitive text the message must contain
+    text: Option<String>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        self.level.is_none_or(|level| entry.level >= level)
+            && self.source.is_none_or(|source| entry.source == source)
+            && self
+                .text
+                .as_deref()
+                .is_none_or(|text| entry.message.to_lowercase().contains(&text.to_lowercase()))
+    }
+}
+
+// Queue entries for the next event if the log view wants them
+fn stream(entries: &[LogEntry]) {
+    let Ok(subscription) = SUBSCRIPTION.lock() else {
+        return;
+    };
+    let Some(filter) = subscription.as_ref() else {
+        return;
+    };
+    let Ok(mut pending) = PENDING.lock() else {
+        return;
+    };
+    pending.extend(entries.iter().filter(|e| filter.matches(e)).cloned());
+    let excess = pending.len().saturating_sub(MAX_PENDING);
+    pending.drain(..excess);
+}
+
+// Drop queued entries and have the log view start over
+fn reset_stream() {
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.clear();
+        RESET.store(true, Ordering::SeqCst);
+    }
+}
+
+// Emit queued entries as one `log://line` event per tick, after a pending reset
+pub(crate) async fn run_streaming(app: AppHandle) {
+    let mut interval = tokio::time::interval(STREAM_INTERVAL);
+    loop {
+        interval.tick().await;
+        let (reset, batch): (bool, Vec<LogEntry>) = match PENDING.lock() {
+            Ok(mut pending) => (RESET.swap(false, Ordering::SeqCst), pending.drain(..).collect()),
+            Err(_) => continue,
+        };
+        if reset {
+            let _ = app.emit(LOG_RESET_EVENT, ());
+        }
+        if !batch.is_empty() {
+            let _ = app.emit(LOG_LINE_EVENT, batch);
+        }
+    }
+}
+
+// pino writes JSON lines with a numeric level; anything else is guessed from
+// its wording
+pub(crate) fn detect_level(line: &str) -> LogLevel {
+    if let Some(level) = serde_json::from_str::<serde_json::Value>(line)
+        .ok()
+        .and_then(|v| v.get("level").and_then(|l| l.as_u64()))
+    {
+        return match level {
+            50.. => LogLevel::Error,
+            40..=49 => LogLevel::Warn,
+            30..=39 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        };
+    }
+    let lower = line.to_lowercase();
+    if lower.contains("error") || lower.contains("fatal") {
+        LogLevel::Error
+    } else if lower.contains("warn") {
+        LogLevel::Warn
+    } else if lower.contains("debug") {
+        LogLevel::Debug
+    } else {
+        LogLevel::Info
+    }
+}
+
+fn get_log_dir() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not find home directory");
+    home.join(".ccb").join("logs")
+}
+
+fn rotate(path: &Path) {
+    for n in (1..ROTATED_FILES).rev() {
+        let _ = fs::rename(path.with_extension(format!("log.{}", n)), path.with_extension(format!("log.{}", n + 1)));
+    }
+    let _ = fs::rename(path, path.with_extension("log.1"));
+}
+
+// Best effort: a full disk must not stop the bridge's output from being shown
+fn persist(entries: &[LogEntry]) {
+    let dir = get_log_dir();
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+    let path = dir.join("desktop.log");
+    if fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_FILE_BYTES) {
+        rotate(&path);
+    }
+    let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) else {
+        return;
+    };
+    let mut text = String::new();
+    for entry in entries {
+        text.push_str(&format!(
+            "{} {:<5} {:<7} {}\n",
+            entry.timestamp.format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            entry.level.label(),
+            entry.source.name(),
+            entry.message
+        ));
+    }
+    let _ = file.write_all(text.as_bytes());
+}
+
+// Hand entries to the writer thread, started on first use
+fn queue_persist(entries: &[LogEntry]) {
+    let writer = WRITER.get_or_init(|| {
+        let (tx, rx) = mpsc::channel::<Vec<LogEntry>>();
+        thread::spawn(move || {
+            for entries in rx {
+                persist(&entries);
+            }
+        });
+        tx
+    });
+    let _ = writer.send(entries.to_vec());
+}
+
+// Held in `ServiceState`
+#[derive(Default)]
+pub(crate) struct LogBuffer {
+    // The run's first entries, never evicted
+    startup: Vec<LogEntry>,
+    entries: VecDeque<LogEntry>,
+    // Message bytes held in both
+    bytes: usize,
+}
+
+impl LogBuffer {
+    fn add(&mut self, entries: Vec<LogEntry>) {
+        queue_persist(&entries);
+        let settings = app_settings::current();
+        let max_bytes = settings.log_buffer_bytes.unwrap_or(usize::MAX);
+        let changed = self.insert(entries, settings.log_buffer_lines, max_bytes);
+        stream(&changed);
+    }
+
+    // Buffer `entries` and evict the oldest past the caps; returns the new
+    // entries and the bumped repeats, in order, for the log view
+    fn insert(&mut self, entries: Vec<LogEntry>, max_lines: usize, max_bytes: usize) -> Vec<LogEntry> {
+        // Half the buffer at most, so small buffers still have room for the tail
+        let startup_lines = STARTUP_LINES.min(max_lines / 2);
+        let mut changed: Vec<LogEntry> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let last = self.entries.back_mut().or(self.startup.last_mut());
+            if let Some(last) = last.filter(|last| last.repeats(&entry)) {
+                last.repeats += 1;
+                // One update per batch is enough
+                match changed.last_mut() {
+                    Some(prev) if prev.repeats > 0 && prev.repeats(last) => *prev = last.clone(),
+                    _ => changed.push(last.clone()),
+                }
+                continue;
+            }
+            self.bytes += entry.message.len();
+            changed.push(entry.clone());
+            if self.startup.len() < startup_lines {
+                self.startup.push(entry);
+            } else {
+                self.entries.push_back(entry);
+            }
+        }
+
+        while self.startup.len() + self.entries.len() > max_lines || self.bytes > max_bytes {
+            let Some(evicted) = self.entries.pop_front() else {
+                break;
+            };
+            self.bytes -= evicted.message.len();
+        }
+        changed
+    }
+
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &LogEntry> {
+        self.startup.iter().chain(self.entries.iter())
+    }
+
+    fn len(&self) -> usize {
+        self.startup.len() + self.entries.len()
+    }
+
+    pub(crate) fn info(&mut self, message: String) {
+        self.add(vec![LogEntry::new(LogSource::Desktop, LogLevel::Info, message)]);
+    }
+
+    pub(crate) fn warn(&mut self, message: String) {
+        self.add(vec![LogEntry::new(LogSource::Desktop, LogLevel::Warn, message)]);
+    }
+
+    pub(crate) fn error(&mut self, message: String) {
+        self.add(vec![LogEntry::new(LogSource::Desktop, LogLevel::Error, message)]);
+    }
+
+    // Lines printed by the bridge
+    pub(crate) fn extend_bridge(&mut self, lines: &[String]) {
+        let entries = lines
+            .iter()
+            .map(|line| LogEntry::new(LogSource::Bridge, detect_level(line), line.clone()))
+            .collect();
+        self.add(entries);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.startup.clear();
+        self.entries.clear();
+        self.bytes = 0;
+        reset_stream();
+    }
+
+    // The last `count` messages as plain lines, for excerpts and reports
+    pub(crate) fn tail(&self, count: usize) -> Vec<String> {
+        let skip = self.len().saturating_sub(count);
+        self.iter().skip(skip).map(LogEntry::line).collect()
+    }
+
+    pub(crate) fn lines(&self) -> Vec<String> {
+        self.tail(self.len())
+    }
+}
+
+// Entries at `level` or above, from `source`, newer than `since` (RFC 3339)
+#[tauri::command]
+pub(crate) fn get_logs_filtered(
+    state: State<'_, AppState>,
+    level: Option<LogLevel>,
+    source: Option<LogSource>,
+    since: Option<String>,
+) -> Result<Vec<LogEntry>, String> {
+    let since = since
+        .map(|s| DateTime::parse_from_rfc3339(&s).map_err(|e| format!("Invalid time '{}': {}", s, e)))
+        .transpose()?;
+    let filter = LogFilter { level, source, text: None };
+    let service = state.lock().map_err(|e| e.to_string())?;
+    Ok(service
+        .logs
+        .iter()
+        .filter(|e| filter.matches(e))
+        .filter(|e| since.is_none_or(|since| e.timestamp > since))
+        .cloned()
+        .collect())
+}
+
+// Start (or refilter) the `log://line` events; returns the matching entries
+// buffered so far, with nothing lost or repeated between the two
+#[tauri::command]
+pub(crate) fn subscribe_logs(state: State<'_, AppState>, filter: Option<LogFilter>) -> Result<Vec<LogEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    // Under the state lock so no entry lands between the snapshot and the events
+    let service = state.lock().map_err(|e| e.to_string())?;
+    let entries = service.logs.iter().filter(|e| filter.matches(e)).cloned().collect();
+    *SUBSCRIPTION.lock().map_err(|e| e.to_string())? = Some(filter);
+    PENDING.lock().map_err(|e| e.to_string())?.clear();
+    // The snapshot already reflects any clear before it
+    RESET.store(false, Ordering::SeqCst);
+    Ok(entries)
+}
+
+#[tauri::command]
+pub(crate) fn unsubscribe_logs() -> Result<bool, String> {
+    *SUBSCRIPTION.lock().map_err(|e| e.to_string())? = None;
+    PENDING.lock().map_err(|e| e.to_string())?.clear();
+    Ok(true)
+}
+
+// The newest `limit` entries whose message contains `query`, case-insensitively,
+// or matches it as a regular expression; oldest first
+#[tauri::command]
+pub(crate) fn search_logs(
+    state: State<'_, AppState>,
+    query: String,
+    regex: bool,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, String> {
+    let pattern = if regex { query } else { regex::escape(&query) };
+    let pattern = RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))?;
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let service = state.lock().map_err(|e| e.to_string())?;
+    let mut matches: Vec<LogEntry> = service
+        .logs
+        .iter()
+        .rev()
+        .filter(|e| pattern.is_match(&e.message))
+        .take(limit)
+        .cloned()
+        .collect();
+    matches.reverse();
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge(message: &str) -> LogEntry {
+        LogEntry::new(LogSource::Bridge, LogLevel::Info, message.to_string())
+    }
+
+    fn messages(buffer: &LogBuffer) -> Vec<String> {
+        buffer.iter().map(|e| e.message.clone()).collect()
+    }
+
+    #[test]
+    fn ring_keeps_startup_and_newest() {
+        let mut buffer = LogBuffer::default();
+        let entries = (0..10).map(|n| bridge(&n.to_string())).collect();
+        buffer.insert(entries, 6, usize::MAX);
+        assert_eq!(messages(&buffer), ["0", "1", "2", "7", "8", "9"]);
+        assert_eq!(buffer.bytes, 6);
+    }
+
+    #[test]
+    fn byte_cap_evicts_oldest_after_startup() {
+        let mut buffer = LogBuffer::default();
+        buffer.insert(vec![bridge("aa"), bridge("bb")], 4, usize::MAX);
+        buffer.insert(vec![bridge("cc"), bridge("dd")], 4, 6);
+        assert_eq!(messages(&buffer), ["aa", "bb", "dd"]);
+        assert_eq!(buffer.bytes, 6);
+    }
+
+    #[test]
+    fn repeats_collapse_into_one_entry() {
+        let mut buffer = LogBuffer::default();
+        let changed = buffer.insert(vec![bridge("x"), bridge("x"), bridge("x"), bridge("y")], 10, usize::MAX);
+        assert_eq!(buffer.lines(), ["x (repeated 2 times)", "y"]);
+        let repeats: Vec<u32> = changed.iter().map(|e| e.repeats).collect();
+        assert_eq!(repeats, [0, 2, 0]);
+    }
+
+    #[test]
+    fn repeat_of_earlier_batch_is_sent_as_update() {
+        let mut buffer = LogBuffer::default();
+        buffer.insert(vec![bridge("x")], 10, usize::MAX);
+        let changed = buffer.insert(vec![bridge("x")], 10, usize::MAX);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].repeats, 1);
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn different_level_is_not_a_repeat() {
+        let mut buffer = LogBuffer::default();
+        let warn = LogEntry::new(LogSource::Bridge, LogLevel::Warn, "x".to_string());
+        buffer.insert(vec![bridge("x"), warn], 10, usize::MAX);
+        assert_eq!(buffer.len(), 2);
+    }
+}
@@ -0,0 +1,465 @@
+// Trying out an MCP server entry.
+//
+// An agent's `mcpServers` entry is only exercised once Claude Code starts it
+// inside a session, where a typo in the command or a missing env var shows up
+// as tools that silently aren't there. `test_mcp_server` starts the server the
+// way the bridge would, in the agent's workspace when it's given one (or
+// connects to its URL), performs the MCP handshake, lists the tools it
+// advertises and times how long it took to answer. Stdio servers explain their
+// failures on stderr, so its last lines come along.
+//
+// URL servers are tried with streamable HTTP first and fall back to the older
+// SSE transport when the URL doesn't accept a POST.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tauri::State;
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use crate::agents::McpServerConfig;
+use crate::config::{expand_home, ConfigStore};
+use crate::runtime_info::find_executable;
+use crate::service::{self, get_extended_path};
+use crate::transcripts::agent_workspaces;
+use crate::{http, i18n, keychain};
+
+// Package runners such as npx may download the server first
+const TEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Time a stdio server gets to exit on its own once its stdin is closed
+const EXIT_GRACE: Duration = Duration::from_secs(2);
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const STDERR_LINES: usize = 20;
+const MAX_TOOL_PAGES: usize = 10;
+
+type StderrTail = Arc<Mutex<VecDeque<String>>>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTool {
+    name: String,
+    description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpTestReport {
+    name: String,
+    // "stdio", "http" (streamable HTTP) or "sse"
+    transport: String,
+    // Answered `initialize` with a JSON-RPC result
+    speaks_mcp: bool,
+    server_name: Option<String>,
+    server_version: Option<String>,
+    protocol_version: Option<String>,
+    // From starting the process, or sending the first request, to the answer
+    // to `initialize`
+    startup_ms: u64,
+    tools: Vec<McpTool>,
+    error: Option<String>,
+    stderr: Vec<String>,
+}
+
+// Server-sent events from a response body
+struct SseReader {
+    response: reqwest::Response,
+    // Bytes, so a character split across chunks is decoded once it's whole
+    buf: Vec<u8>,
+}
+
+impl SseReader {
+    // The next (event, data); None when the stream ends
+    async fn next(&mut self) -> Result<Option<(String, String)>, String> {
+        loop {
+            // Events end with a blank line
+            if let Some(end) = self.buf.windows(2).position(|w| w == b"\n\n") {
+                let bytes: Vec<u8> = self.buf.drain(..end + 2).collect();
+                let block = String::from_utf8_lossy(&bytes);
+                let mut event = "message".to_string();
+                let mut data = Vec::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        event = value.trim().to_string();
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push(value.trim_start());
+                    }
+                }
+                if !data.is_empty() {
+                    return Ok(Some((event, data.join("\n"))));
+                }
+                continue;
+            }
+            match self.response.chunk().await.map_err(|e| e.to_string())? {
+                Some(chunk) => self.buf.extend(chunk.iter().filter(|b| **b != b'\r')),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    // The JSON-RPC response to `id`, skipping notifications and the like
+    async fn response(&mut self, id: u64) -> Result<Value, String> {
+        while let Some((_, data)) = self.next().await? {
+            if let Some(message) = serde_json::from_str::<Value>(&data).ok().filter(|m| m["id"] == id) {
+                return Ok(message);
+            }
+        }
+        Err("The server closed the stream without answering".to_string())
+    }
+}
+
+// A started stdio server. Dropping it, e.g. when the test times out, kills
+// the server along with anything it started
+struct ServerProcess(Child);
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        service::kill_tree(&mut self.0);
+    }
+}
+
+enum Connection {
+    Stdio {
+        child: ServerProcess,
+        stdin: Option<ChildStdin>,
+        stdout: Lines<BufReader<ChildStdout>>,
+    },
+    Http {
+        url: String,
+        session: Option<String>,
+    },
+    Sse {
+        endpoint: reqwest::Url,
+        events: SseReader,
+    },
+}
+
+fn message(id: Option<u64>, method: &str, params: Value) -> Value {
+    match id {
+        Some(id) => json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+        None => json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+    }
+}
+
+// The result of a JSON-RPC response, or its error as text
+fn result(response: Value) -> Result<Value, String> {
+    if let Some(error) = response.get("error") {
+        let text = error["message"].as_str().unwrap_or("unknown error");
+        return Err(format!("The server answered with an error: {} (code {})", text, error["code"]));
+    }
+    Ok(response["result"].clone())
+}
+
+impl Connection {
+    // Send `message`; for requests over HTTP, the response when the POST
+    // carries it
+    async fn send(&mut self, message: &Value) -> Result<Option<Value>, String> {
+        match self {
+            Connection::Stdio { stdin, .. } => {
+                let stdin = stdin.as_mut().ok_or("The server's stdin is closed")?;
+                let line = format!("{}\n", message);
+                stdin
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|e| format!("Failed to write to the server: {}", e))?;
+                stdin.flush().await.map_err(|e| format!("Failed to write to the server: {}", e))?;
+                Ok(None)
+            }
+            Connection::Http { url, session } => {
+                let mut request = http::client()
+                    .post(url.as_str())
+                    .header(reqwest::header::ACCEPT, "application/json, text/event-stream")
+                    .json(message);
+                if let Some(ref id) = session {
+                    request = request.header("Mcp-Session-Id", id);
+                }
+                let response = request.send().await.map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("HTTP {}", response.status()));
+                }
+                if let Some(id) = response.headers().get("mcp-session-id").and_then(|v| v.to_str().ok()) {
+                    *session = Some(id.to_string());
+                }
+                let Some(id) = message["id"].as_u64() else {
+                    return Ok(None);
+                };
+                let is_stream = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .is_some_and(|t| t.contains("text/event-stream"));
+                if is_stream {
+                    let mut events = SseReader { response, buf: Vec::new() };
+                    return events.response(id).await.map(Some);
+                }
+                let body = response.json().await.map_err(|e| format!("The server's answer isn't JSON-RPC: {}", e))?;
+                Ok(Some(body))
+            }
+            Connection::Sse { endpoint, .. } => {
+                let response = http::client()
+                    .post(endpoint.clone())
+                    .json(message)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to reach {}: {}", endpoint, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("HTTP {}", response.status()));
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    async fn request(&mut self, id: u64, method: &str, params: Value) -> Result<Value, String> {
+        if let Some(response) = self.send(&message(Some(id), method, params)).await? {
+            return result(response);
+        }
+        match self {
+            Connection::Stdio { stdout, .. } => loop {
+                let line = stdout
+                    .next_line()
+                    .await
+                    .map_err(|e| format!("Failed to read from the server: {}", e))?
+                    .ok_or("The server exited without answering")?;
+                // Anything that isn't our response, such as log lines a server
+                // shouldn't be printing to stdout, is skipped
+                if let Some(response) = serde_json::from_str::<Value>(&line).ok().filter(|m| m["id"] == id) {
+                    return result(response);
+                }
+            },
+            Connection::Sse { events, .. } => result(events.response(id).await?),
+            Connection::Http { .. } => Err("The server sent no answer".to_string()),
+        }
+    }
+
+    async fn notify(&mut self, method: &str) -> Result<(), String> {
+        self.send(&message(None, method, json!({}))).await.map(|_| ())
+    }
+
+    // Stdio servers exit once their stdin closes; the kill is for those that don't
+    async fn close(self) {
+        if let Connection::Stdio { mut child, stdin, .. } = self {
+            drop(stdin);
+            let _ = tokio::time::timeout(EXIT_GRACE, child.0.wait()).await;
+        }
+    }
+}
+
+// Env values as the bridge would see them, with `${VAR}` and keychain
+// references filled in
+fn resolve_env(value: &str) -> String {
+    if keychain::is_reference(value) {
+        return keychain::resolve(value);
+    }
+    match value.strip_prefix("${").and_then(|v| v.strip_suffix('}')) {
+        Some(name) => std::env::var(name).unwrap_or_default(),
+        None => value.to_string(),
+    }
+}
+
+fn spawn_stdio(
+    command: &str,
+    config: &McpServerConfig,
+    workspace: Option<&Path>,
+    stderr_tail: &StderrTail,
+) -> Result<Connection, String> {
+    let program = if command.contains('/') || command.contains('\\') || command.starts_with('~') {
+        expand_home(command)
+    } else {
+        find_executable(command).unwrap_or_else(|| PathBuf::from(command))
+    };
+    let mut cmd = Command::new(&program);
+    cmd.args(config.args.iter().flatten())
+        .env("PATH", get_extended_path())
+        .envs(http::proxy_env())
+        .envs(config.env.iter().flatten().map(|(k, v)| (k, resolve_env(v))))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Relative paths in the entry resolve against the workspace, as in a session
+    if let Some(workspace) = workspace {
+        cmd.current_dir(workspace);
+    }
+    // A group of its own, so kill_tree reaches servers started through a wrapper
+    #[cfg(unix)]
+    cmd.process_group(0);
+    #[cfg(windows)]
+    cmd.creation_flags(crate::process_limits::CREATE_NO_WINDOW);
+
+    let mut child = cmd.spawn().map_err(|e| format!("Failed to start {}: {}", command, e))?;
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take().ok_or("Failed to read the server's output")?;
+    if let Some(stderr) = child.stderr.take() {
+        let tail = stderr_tail.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Ok(mut tail) = tail.lock() {
+                    if tail.len() == STDERR_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            }
+        });
+    }
+    Ok(Connection::Stdio {
+        child: ServerProcess(child),
+        stdin,
+        stdout: BufReader::new(stdout).lines(),
+    })
+}
+
+// The older transport: a GET stream whose first event names the URL to POST to
+async fn connect_sse(url: &str) -> Result<Connection, String> {
+    let response = http::client()
+        .get(url)
+        .header(reqwest::header::ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {} from {}", response.status(), url));
+    }
+    let mut events = SseReader { response, buf: Vec::new() };
+    loop {
+        match events.next().await? {
+            Some((event, data)) if event == "endpoint" => {
+                let base = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+                let endpoint = base.join(data.trim()).map_err(|e| format!("Invalid endpoint '{}': {}", data, e))?;
+                return Ok(Connection::Sse { endpoint, events });
+            }
+            Some(_) => continue,
+            None => return Err(format!("{} doesn't look like an MCP SSE endpoint", url)),
+        }
+    }
+}
+
+fn initialize_params() -> Value {
+    json!({
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": {},
+        "clientInfo": { "name": "ccb-desktop", "version": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+async fn run_test(
+    config: &McpServerConfig,
+    workspace: Option<&Path>,
+    report: &mut McpTestReport,
+    stderr_tail: &StderrTail,
+) -> Result<(), String> {
+    let started = Instant::now();
+    let url = config.url.as_deref().filter(|u| !u.trim().is_empty());
+    let mut connection = match (config.command.as_deref(), url) {
+        (Some(command), _) if !command.trim().is_empty() && config.server_type.as_deref() != Some("sse") => {
+            report.transport = "stdio".to_string();
+            spawn_stdio(command.trim(), config, workspace, stderr_tail)?
+        }
+        (_, Some(url)) if config.server_type.as_deref() == Some("sse") => {
+            report.transport = "sse".to_string();
+            connect_sse(url).await?
+        }
+        (_, Some(url)) => {
+            report.transport = "http".to_string();
+            Connection::Http { url: url.to_string(), session: None }
+        }
+        _ => return Err(format!("MCP server '{}' needs a command or a URL", config.name)),
+    };
+
+    let initialized = match connection.request(1, "initialize", initialize_params()).await {
+        // Servers from before streamable HTTP only take POSTs on the endpoint
+        // their SSE stream names
+        Err(e) if report.transport == "http" && (e.starts_with("HTTP 404") || e.starts_with("HTTP 405")) => {
+            connection = connect_sse(url.unwrap_or_default()).await?;
+            report.transport = "sse".to_string();
+            connection.request(1, "initialize", initialize_params()).await
+        }
+        other => other,
+    };
+    let initialized = match initialized {
+        Ok(initialized) => initialized,
+        Err(e) => {
+            connection.close().await;
+            return Err(e);
+        }
+    };
+    report.startup_ms = started.elapsed().as_millis() as u64;
+    report.speaks_mcp = true;
+    report.server_name = initialized["serverInfo"]["name"].as_str().map(str::to_string);
+    report.server_version = initialized["serverInfo"]["version"].as_str().map(str::to_string);
+    report.protocol_version = initialized["protocolVersion"].as_str().map(str::to_string);
+
+    let listed = list_tools(&mut connection, &initialized, &mut report.tools).await;
+    connection.close().await;
+    listed
+}
+
+async fn list_tools(connection: &mut Connection, initialized: &Value, tools: &mut Vec<McpTool>) -> Result<(), String> {
+    connection.notify("notifications/initialized").await?;
+    if initialized["capabilities"].get("tools").is_none() {
+        return Ok(());
+    }
+    let mut cursor: Option<String> = None;
+    for page in 0..MAX_TOOL_PAGES {
+        let params = match cursor {
+            Some(ref cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let listed = connection.request(2 + page as u64, "tools/list", params).await?;
+        tools.extend(listed["tools"].as_array().into_iter().flatten().filter_map(|tool| {
+            Some(McpTool {
+                name: tool["name"].as_str()?.to_string(),
+                description: tool["description"].as_str().map(str::to_string),
+            })
+        }));
+        cursor = listed["nextCursor"].as_str().map(str::to_string);
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// The workspace of agent `agent_id`, where the bridge starts its servers
+fn agent_workspace(store: &ConfigStore, agent_id: &str) -> Result<PathBuf, String> {
+    let config = store.read()?.unwrap_or_default();
+    let (_, workspace) = agent_workspaces(&config)
+        .into_iter()
+        .find(|(id, _)| id == agent_id)
+        .ok_or_else(|| i18n::tr("agent.not_found", &[("id", agent_id)]))?;
+    let workspace = expand_home(&workspace);
+    if !workspace.is_dir() {
+        return Err(format!("Workspace {} doesn't exist", workspace.display()));
+    }
+    Ok(workspace)
+}
+
+// `agent_id` names the agent the entry belongs to, if it's saved under one
+#[tauri::command]
+pub(crate) async fn test_mcp_server(
+    store: State<'_, ConfigStore>,
+    config: McpServerConfig,
+    agent_id: Option<String>,
+) -> Result<McpTestReport, String> {
+    let workspace = agent_id.map(|id| agent_workspace(&store, &id)).transpose()?;
+    let mut report = McpTestReport {
+        name: config.name.clone(),
+        ..Default::default()
+    };
+    let stderr_tail: StderrTail = Arc::default();
+    let test = run_test(&config, workspace.as_deref(), &mut report, &stderr_tail);
+    let outcome = tokio::time::timeout(TEST_TIMEOUT, test).await;
+    report.error = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e),
+        Err(_) => Some(format!("The server didn't answer within {} seconds", TEST_TIMEOUT.as_secs())),
+    };
+    report.stderr = stderr_tail.lock().map(|tail| tail.iter().cloned().collect()).unwrap_or_default();
+    Ok(report)
+}
@@ -0,0 +1,106 @@
+// Per-channel media handling policies.
+//
+// Each channel section in config.json may carry a `media` object telling the
+// bridge how big an attachment may be, which MIME types are accepted, and
+// whether images are passed to Claude as vision input or only saved to disk.
+// Group chats can otherwise flood an agent with huge uploads.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::config::ConfigStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageHandling {
+    // Attach images to the prompt as vision input
+    Vision,
+    // Store images with the other attachments but don't show them to Claude
+    Save,
+    Reject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaPolicy {
+    max_size_mb: u32,
+    // MIME patterns such as "image/*" or "application/pdf"
+    allowed_types: Vec<String>,
+    images: ImageHandling,
+}
+
+impl Default for MediaPolicy {
+    fn default() -> Self {
+        Self {
+            max_size_mb: 10,
+            allowed_types: vec!["image/*".to_string(), "text/*".to_string(), "application/pdf".to_string()],
+            images: ImageHandling::Vision,
+        }
+    }
+}
+
+// Largest download each platform allows a bot to make
+fn platform_limit_mb(channel: &str) -> Option<u32> {
+    match channel {
+        "telegram" => Some(20),
+        "discord" => Some(500),
+        _ => None,
+    }
+}
+
+fn is_valid_mime_pattern(pattern: &str) -> bool {
+    let Some((kind, subtype)) = pattern.split_once('/') else {
+        return false;
+    };
+    let token = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "-+.".contains(c));
+    (kind == "*" && subtype == "*") || (token(kind) && (subtype == "*" || token(subtype)))
+}
+
+fn validate(channel: &str, policy: &MediaPolicy) -> Result<(), String> {
+    let limit = platform_limit_mb(channel).ok_or(format!("Unknown channel '{}'", channel))?;
+    if policy.max_size_mb == 0 || policy.max_size_mb > limit {
+        return Err(format!("Max size for {} must be between 1 and {} MB", channel, limit));
+    }
+    if let Some(bad) = policy.allowed_types.iter().find(|t| !is_valid_mime_pattern(t)) {
+        return Err(format!("Invalid MIME type pattern '{}'", bad));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_media_policies(store: State<'_, ConfigStore>) -> Result<HashMap<String, MediaPolicy>, String> {
+    let Some(config) = store.read()? else {
+        return Ok(HashMap::new());
+    };
+    let Some(channels) = config.get("channels").and_then(|c| c.as_object()) else {
+        return Ok(HashMap::new());
+    };
+
+    Ok(channels
+        .iter()
+        .map(|(name, channel)| {
+            let policy = channel
+                .get("media")
+                .and_then(|m| serde_json::from_value(m.clone()).ok())
+                .unwrap_or_default();
+            (name.clone(), policy)
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub(crate) fn set_media_policy(store: State<'_, ConfigStore>, channel: String, policy: MediaPolicy) -> Result<bool, String> {
+    validate(&channel, &policy)?;
+
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let section = config
+        .get_mut("channels")
+        .and_then(|c| c.get_mut(&channel))
+        .and_then(|c| c.as_object_mut())
+        .ok_or(format!("Channel '{}' is not configured", channel))?;
+    section.insert("media".to_string(), serde_json::to_value(&policy).map_err(|e| e.to_string())?);
+
+    store.write(&config)?;
+    Ok(true)
+}
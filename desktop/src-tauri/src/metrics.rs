@@ -0,0 +1,386 @@
+// Usage metrics for the dashboard charts.
+//
+// While the bridge is up, a collector samples the Control API once a minute
+// and adds what happened since the last sample to hourly rows in a sqlite
+// database in the desktop's data dir: messages processed, invocations per
+// agent, token usage, and which sessions were active on which channel.
+// Bridges that serve `/metrics` report the counters themselves; for older
+// ones they're worked out from `/sessions`, counting a session's messages
+// (or, without a message count, each time it was active) and leaving tokens
+// out. `get_metrics` rolls the hourly rows up into hour, day or week buckets.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveDateTime, TimeZone, Timelike};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::api_client::{ApiError, BridgeApiClient, SessionSummary};
+use crate::local_db::LocalDb;
+use crate::log_forwarding;
+use crate::poller::StatusCache;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+// Rows older than this are dropped
+const RETENTION_DAYS: i64 = 366;
+
+const HOUR_FORMAT: &str = "%Y-%m-%dT%H";
+
+const METRIC_MESSAGES: &str = "messages";
+const METRIC_INVOCATIONS: &str = "invocations";
+const METRIC_INPUT_TOKENS: &str = "input_tokens";
+const METRIC_OUTPUT_TOKENS: &str = "output_tokens";
+
+// Opened on the first sample
+static DATABASE: LocalDb = LocalDb::new(
+    "metrics.db",
+    "metrics",
+    "CREATE TABLE IF NOT EXISTS counters (
+        hour TEXT NOT NULL,
+        metric TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (hour, metric, key)
+    );
+    CREATE TABLE IF NOT EXISTS sessions (
+        hour TEXT NOT NULL,
+        channel TEXT NOT NULL,
+        session_id TEXT NOT NULL,
+        PRIMARY KEY (hour, channel, session_id)
+    )",
+);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsRange {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Granularity {
+    Hour,
+    Day,
+    Week,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsBucket {
+    // Local time the bucket starts at, e.g. 2024-05-06T00:00:00+02:00
+    start: String,
+    messages: u64,
+    input_tokens: u64,
+    output_tokens: u64,
+    invocations_by_agent: BTreeMap<String, u64>,
+    // Distinct sessions active during the bucket
+    sessions_by_channel: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsReport {
+    range: MetricsRange,
+    granularity: Granularity,
+    // Oldest first, one per bucket in the range, empty ones included
+    buckets: Vec<MetricsBucket>,
+    // The whole range; sessions counted once however many buckets they span
+    totals: MetricsBucket,
+    // Whether any sample in the range came with token counts
+    tokens_reported: bool,
+}
+
+// Counters a bridge with `/metrics` keeps since it started
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeMetrics {
+    #[serde(default)]
+    messages_processed: u64,
+    #[serde(default)]
+    agent_invocations: HashMap<String, u64>,
+    #[serde(default)]
+    tokens: Option<TokenCounts>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenCounts {
+    #[serde(default)]
+    input: u64,
+    #[serde(default)]
+    output: u64,
+}
+
+// Where the last sample left off, so the next one records only the difference
+#[derive(Default)]
+struct Collector {
+    counters: Option<BridgeMetrics>,
+    // Session id -> (last active, message count)
+    sessions: HashMap<String, (String, Option<u32>)>,
+    // False until the first sample after launch, which only sets the baseline
+    primed: bool,
+}
+
+// (metric, key, amount) added this sample, e.g. ("invocations", "main", 3)
+type Count = (&'static str, String, u64);
+// (channel, session id) of a session active this sample
+type Activity = (String, String);
+
+// Growth of a counter that starts over when the bridge restarts
+fn delta(current: u64, previous: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else {
+        current
+    }
+}
+
+fn channel_of(session: &SessionSummary) -> String {
+    session.chat_key.split(':').next().unwrap_or_default().to_string()
+}
+
+impl Collector {
+    // What changed since the last sample
+    fn sample(&mut self, metrics: Option<BridgeMetrics>, sessions: &[SessionSummary]) -> (Vec<Count>, Vec<Activity>) {
+        let mut counts = Vec::new();
+        let mut active = Vec::new();
+        let mut invocations: HashMap<String, u64> = HashMap::new();
+        let mut messages = 0;
+
+        for session in sessions {
+            let previous = self.sessions.get(&session.id);
+            let changed = previous.is_none_or(|(last_active, _)| *last_active != session.last_active);
+            if !changed {
+                continue;
+            }
+            active.push((channel_of(session), session.id.clone()));
+            let new_messages = match (session.message_count, previous.and_then(|(_, count)| *count)) {
+                (Some(count), Some(before)) => delta(count as u64, before as u64),
+                (Some(count), None) => count as u64,
+                (None, _) => 1,
+            };
+            messages += new_messages;
+            let agent = session.agent_id.clone().unwrap_or_else(|| "main".to_string());
+            *invocations.entry(agent).or_default() += new_messages.max(1);
+        }
+
+        match (&metrics, &self.counters) {
+            (Some(current), Some(previous)) => {
+                counts.push((METRIC_MESSAGES, String::new(), delta(current.messages_processed, previous.messages_processed)));
+                for (agent, count) in &current.agent_invocations {
+                    let before = previous.agent_invocations.get(agent).copied().unwrap_or(0);
+                    counts.push((METRIC_INVOCATIONS, agent.clone(), delta(*count, before)));
+                }
+                if let Some(tokens) = current.tokens {
+                    let before = previous.tokens.unwrap_or_default();
+                    counts.push((METRIC_INPUT_TOKENS, String::new(), delta(tokens.input, before.input)));
+                    counts.push((METRIC_OUTPUT_TOKENS, String::new(), delta(tokens.output, before.output)));
+                }
+            }
+            // No baseline yet for the bridge's counters
+            (Some(_), None) => {}
+            (None, _) => {
+                counts.push((METRIC_MESSAGES, String::new(), messages));
+                counts.extend(invocations.into_iter().map(|(agent, count)| (METRIC_INVOCATIONS, agent, count)));
+            }
+        }
+
+        self.counters = metrics;
+        self.sessions = sessions
+            .iter()
+            .map(|s| (s.id.clone(), (s.last_active.clone(), s.message_count)))
+            .collect();
+        if !self.primed {
+            self.primed = true;
+            return (vec![], vec![]);
+        }
+        counts.retain(|(_, _, amount)| *amount > 0);
+        (counts, active)
+    }
+}
+
+fn store(counts: &[Count], active: &[Activity]) -> Result<(), String> {
+    let hour = Local::now().format(HOUR_FORMAT).to_string();
+    let cutoff = (Local::now() - ChronoDuration::days(RETENTION_DAYS)).format(HOUR_FORMAT).to_string();
+    DATABASE.with(|conn| {
+        let tx = conn.transaction()?;
+        for (metric, key, amount) in counts {
+            tx.execute(
+                "INSERT INTO counters (hour, metric, key, value) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (hour, metric, key) DO UPDATE SET value = value + ?4",
+                params![hour, metric, key, *amount as i64],
+            )?;
+        }
+        for (channel, session_id) in active {
+            tx.execute(
+                "INSERT OR IGNORE INTO sessions (hour, channel, session_id) VALUES (?1, ?2, ?3)",
+                params![hour, channel, session_id],
+            )?;
+        }
+        tx.execute("DELETE FROM counters WHERE hour < ?1", params![cutoff])?;
+        tx.execute("DELETE FROM sessions WHERE hour < ?1", params![cutoff])?;
+        tx.commit()
+    })
+}
+
+async fn fetch(api: &BridgeApiClient) -> Result<(Option<BridgeMetrics>, Vec<SessionSummary>), ApiError> {
+    let metrics = match api.get::<BridgeMetrics>("/metrics").await {
+        Ok(metrics) => Some(metrics),
+        Err(ApiError::Unsupported(_)) => None,
+        Err(e) => return Err(e),
+    };
+    let sessions = crate::sessions::list(api).await?;
+    Ok((metrics, sessions))
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    let mut collector = Collector::default();
+    loop {
+        tokio::time::sleep(SAMPLE_INTERVAL).await;
+        // Nothing to sample while the bridge is down; start over once it's back
+        if app.state::<StatusCache>().status().is_none() {
+            collector = Collector::default();
+            continue;
+        }
+        let api = app.state::<BridgeApiClient>().inner().clone();
+        let (metrics, sessions) = match fetch(&api).await {
+            Ok(sample) => sample,
+            Err(_) => continue,
+        };
+        let (counts, active) = collector.sample(metrics, &sessions);
+        if counts.is_empty() && active.is_empty() {
+            continue;
+        }
+        if let Err(e) = store(&counts, &active) {
+            log_forwarding::desktop_error(e);
+        }
+    }
+}
+
+fn range_start(range: MetricsRange, granularity: Granularity) -> DateTime<Local> {
+    let now = Local::now();
+    let start = match range {
+        MetricsRange::Day => now - ChronoDuration::hours(23),
+        MetricsRange::Week => now - ChronoDuration::days(6),
+        MetricsRange::Month => now - ChronoDuration::days(29),
+        MetricsRange::Year => now - ChronoDuration::days(364),
+    };
+    bucket_start(start, granularity)
+}
+
+// The start of the bucket `time` falls in
+fn bucket_start(time: DateTime<Local>, granularity: Granularity) -> DateTime<Local> {
+    let hour = time.date_naive().and_hms_opt(time.hour(), 0, 0).unwrap_or_default();
+    let start = match granularity {
+        Granularity::Hour => hour,
+        Granularity::Day => hour.date().and_hms_opt(0, 0, 0).unwrap_or_default(),
+        Granularity::Week => {
+            let monday = hour.date() - ChronoDuration::days(time.weekday().num_days_from_monday() as i64);
+            monday.and_hms_opt(0, 0, 0).unwrap_or_default()
+        }
+    };
+    // A DST gap has no such local time; fall back to the hour itself
+    Local.from_local_datetime(&start).earliest().unwrap_or(time)
+}
+
+fn next_bucket(start: DateTime<Local>, granularity: Granularity) -> DateTime<Local> {
+    match granularity {
+        Granularity::Hour => start + ChronoDuration::hours(1),
+        // The extra hour keeps a 25-hour day from landing back in the same bucket
+        Granularity::Day => bucket_start(start + ChronoDuration::hours(25), granularity),
+        Granularity::Week => bucket_start(start + ChronoDuration::hours(7 * 24 + 1), granularity),
+    }
+}
+
+fn parse_hour(hour: &str) -> Option<DateTime<Local>> {
+    let naive = NaiveDateTime::parse_from_str(&format!("{}:00", hour), "%Y-%m-%dT%H:%M").ok()?;
+    Local.from_local_datetime(&naive).earliest()
+}
+
+fn add_count(bucket: &mut MetricsBucket, metric: &str, key: String, value: u64) {
+    match metric {
+        METRIC_MESSAGES => bucket.messages += value,
+        METRIC_INPUT_TOKENS => bucket.input_tokens += value,
+        METRIC_OUTPUT_TOKENS => bucket.output_tokens += value,
+        METRIC_INVOCATIONS => *bucket.invocations_by_agent.entry(key).or_default() += value,
+        _ => {}
+    }
+}
+
+type Rows = (Vec<(String, String, String, u64)>, Vec<(String, String, String)>);
+
+fn load_rows(since: &str) -> Result<Rows, String> {
+    DATABASE.with(|conn| {
+        let counters = conn
+            .prepare("SELECT hour, metric, key, value FROM counters WHERE hour >= ?1")?
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, i64>(3)? as u64)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let sessions = conn
+            .prepare("SELECT hour, channel, session_id FROM sessions WHERE hour >= ?1")?
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok((counters, sessions))
+    })
+}
+
+#[tauri::command]
+pub(crate) fn get_metrics(range: MetricsRange, granularity: Option<Granularity>) -> Result<MetricsReport, String> {
+    let granularity = granularity.unwrap_or(match range {
+        MetricsRange::Day => Granularity::Hour,
+        MetricsRange::Week | MetricsRange::Month => Granularity::Day,
+        MetricsRange::Year => Granularity::Week,
+    });
+    let start = range_start(range, granularity);
+
+    let mut buckets: BTreeMap<DateTime<Local>, MetricsBucket> = BTreeMap::new();
+    let mut at = start;
+    while at <= Local::now() {
+        buckets.insert(at, MetricsBucket { start: at.to_rfc3339(), ..Default::default() });
+        at = next_bucket(at, granularity);
+    }
+
+    let (counters, sessions) = if DATABASE.path().exists() {
+        load_rows(&start.format(HOUR_FORMAT).to_string())?
+    } else {
+        (vec![], vec![])
+    };
+
+    let mut totals = MetricsBucket { start: start.to_rfc3339(), ..Default::default() };
+    let mut tokens_reported = false;
+    for (hour, metric, key, value) in counters {
+        let Some(bucket) = parse_hour(&hour).and_then(|t| buckets.get_mut(&bucket_start(t, granularity))) else {
+            continue;
+        };
+        tokens_reported |= metric == METRIC_INPUT_TOKENS || metric == METRIC_OUTPUT_TOKENS;
+        add_count(bucket, &metric, key.clone(), value);
+        add_count(&mut totals, &metric, key, value);
+    }
+
+    let mut per_bucket: BTreeMap<(DateTime<Local>, String), BTreeSet<String>> = BTreeMap::new();
+    let mut per_range: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (hour, channel, session_id) in sessions {
+        let Some(at) = parse_hour(&hour).map(|t| bucket_start(t, granularity)) else {
+            continue;
+        };
+        per_bucket.entry((at, channel.clone())).or_default().insert(session_id.clone());
+        per_range.entry(channel).or_default().insert(session_id);
+    }
+    for ((at, channel), ids) in per_bucket {
+        if let Some(bucket) = buckets.get_mut(&at) {
+            bucket.sessions_by_channel.insert(channel, ids.len() as u64);
+        }
+    }
+    totals.sessions_by_channel = per_range.into_iter().map(|(channel, ids)| (channel, ids.len() as u64)).collect();
+
+    Ok(MetricsReport {
+        range,
+        granularity,
+        buckets: buckets.into_values().collect(),
+        totals,
+        tokens_reported,
+    })
+}
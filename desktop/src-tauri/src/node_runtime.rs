@@ -0,0 +1,228 @@
+// Where Node and the bridge's binaries live.
+//
+// GUI apps don't inherit the shell's PATH, so the directories version and
+// package managers install into are added to it for every spawned command.
+// Scanning the version managers' install dirs on every spawn is wasted work,
+// so the result is cached until one of those dirs changes (a version was
+// installed or removed) or the preferred Node version in the app settings
+// does. Versions are ordered by their numbers, so v10 sorts above v9.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use crate::app_settings;
+
+struct Cache {
+    paths: Vec<String>,
+    preferred: Option<String>,
+    // Modification times of `version_roots()` when scanned
+    roots: Vec<Option<SystemTime>>,
+}
+
+static CACHE: Mutex<Option<Cache>> = Mutex::new(None);
+
+// "v20.11.1", "20.11" or "20" as numbers; None for anything else, e.g. nightlies
+pub(crate) fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let parts = version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|p| p.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    (1..=3).contains(&parts.len()).then_some(parts)
+}
+
+// Newest first, with installs matching the preferred version ahead of the rest
+fn sort_versions(dirs: &mut [PathBuf], preferred: Option<&str>) {
+    let preferred = preferred.and_then(parse_version);
+    dirs.sort_by_cached_key(|dir| {
+        let version = dir.file_name().and_then(|n| parse_version(&n.to_string_lossy()));
+        let is_preferred = matches!((&preferred, &version), (Some(p), Some(v)) if v.starts_with(p));
+        (Reverse(is_preferred), Reverse(version))
+    });
+}
+
+fn version_dirs(root: &Path) -> Vec<PathBuf> {
+    fs::read_dir(root)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(not(windows))]
+fn nvm_root() -> PathBuf {
+    dirs::home_dir().unwrap_or_default().join(".nvm").join("versions").join("node")
+}
+
+// ~/.local/share/fnm on Linux, ~/Library/Application Support/fnm on macOS
+#[cfg(not(windows))]
+fn fnm_versions() -> PathBuf {
+    std::env::var_os("FNM_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dirs::data_dir().unwrap_or_default().join("fnm"))
+        .join("node-versions")
+}
+
+// Dirs whose contents change when a Node version is installed or removed
+#[cfg(not(windows))]
+fn version_roots() -> Vec<PathBuf> {
+    vec![nvm_root(), fnm_versions()]
+}
+
+// Directories where Node version managers and package managers put binaries
+#[cfg(not(windows))]
+fn scan(preferred: Option<&str>) -> Vec<String> {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut extra_paths = vec![
+        format!("{home}/.volta/bin"),
+        format!("{home}/.npm/bin"),
+        // The user-level npm prefix npm's docs suggest instead of sudo
+        format!("{home}/.npm-global/bin"),
+        format!("{home}/.local/bin"),
+        format!("{home}/.asdf/shims"),
+        "/opt/homebrew/bin".to_string(),
+        "/usr/local/bin".to_string(),
+        "/usr/bin".to_string(),
+    ];
+    #[cfg(target_os = "linux")]
+    extra_paths.extend(linux_paths(&home));
+
+    // fnm installs after the fixed dirs, nvm ones first; the preferred or
+    // newest version ahead within each
+    let mut versions = version_dirs(&fnm_versions());
+    sort_versions(&mut versions, preferred);
+    let fnm = versions.iter().map(|v| v.join("installation").join("bin")).filter(|p| p.is_dir());
+    extra_paths.extend(fnm.map(|p| p.to_string_lossy().to_string()));
+
+    let mut versions = version_dirs(&nvm_root());
+    sort_versions(&mut versions, preferred);
+    extra_paths.splice(0..0, versions.iter().map(|v| v.join("bin").to_string_lossy().to_string()));
+
+    extra_paths
+}
+
+// Node from snaps, and when the app itself runs as a Flatpak, the Node SDK
+// extension inside the sandbox along with apps exported from outside it
+#[cfg(target_os = "linux")]
+fn linux_paths(home: &str) -> Vec<String> {
+    let mut paths = vec!["/snap/bin".to_string()];
+    let mut sdks: Vec<PathBuf> = glob::glob("/usr/lib/sdk/node*/bin")
+        .map(|entries| entries.filter_map(Result::ok).collect())
+        .unwrap_or_default();
+    // node22 ahead of node20
+    sdks.sort_by_cached_key(|dir| {
+        Reverse(
+            dir.parent()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_string_lossy().trim_start_matches("node").parse::<u32>().ok()),
+        )
+    });
+    paths.extend(sdks.into_iter().map(|p| p.to_string_lossy().to_string()));
+    paths.push(format!("{home}/.local/share/flatpak/exports/bin"));
+    paths.push("/var/lib/flatpak/exports/bin".to_string());
+    paths
+}
+
+#[cfg(windows)]
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from)
+}
+
+#[cfg(windows)]
+fn app_data() -> PathBuf {
+    env_dir("APPDATA").unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("AppData").join("Roaming"))
+}
+
+#[cfg(windows)]
+fn nvm_home() -> PathBuf {
+    env_dir("NVM_HOME").unwrap_or_else(|| app_data().join("nvm"))
+}
+
+#[cfg(windows)]
+fn fnm_versions() -> PathBuf {
+    env_dir("FNM_DIR").unwrap_or_else(|| app_data().join("fnm")).join("node-versions")
+}
+
+#[cfg(windows)]
+fn version_roots() -> Vec<PathBuf> {
+    vec![nvm_home(), fnm_versions()]
+}
+
+#[cfg(windows)]
+fn scan(preferred: Option<&str>) -> Vec<String> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let app_data = app_data();
+    let local_app_data = env_dir("LOCALAPPDATA").unwrap_or_else(|| home.join("AppData").join("Local"));
+    let program_files = env_dir("ProgramFiles").unwrap_or_else(|| PathBuf::from(r"C:\Program Files"));
+
+    let mut extra_paths = vec![
+        // npm's global prefix, where `npm install -g` puts ccb.cmd
+        app_data.join("npm"),
+        local_app_data.join("Volta").join("bin"),
+        program_files.join("Volta"),
+        env_dir("SCOOP").unwrap_or_else(|| home.join("scoop")).join("shims"),
+        program_files.join("nodejs"),
+    ];
+
+    // nvm-windows symlinks the active version; fall back to the installs
+    if let Some(symlink) = env_dir("NVM_SYMLINK") {
+        extra_paths.push(symlink);
+    }
+    let mut versions: Vec<_> = version_dirs(&nvm_home())
+        .into_iter()
+        .filter(|p| p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('v')))
+        .collect();
+    sort_versions(&mut versions, preferred);
+    extra_paths.extend(versions);
+
+    // fnm exports its per-shell dir when run from a configured shell; otherwise
+    // use the installed versions directly
+    if let Some(multishell) = env_dir("FNM_MULTISHELL_PATH") {
+        extra_paths.push(multishell);
+    }
+    let mut versions = version_dirs(&fnm_versions());
+    sort_versions(&mut versions, preferred);
+    extra_paths.extend(versions.into_iter().map(|v| v.join("installation")).filter(|p| p.is_dir()));
+
+    extra_paths.into_iter().map(|p| p.to_string_lossy().to_string()).collect()
+}
+
+fn root_times() -> Vec<Option<SystemTime>> {
+    version_roots()
+        .iter()
+        .map(|root| fs::metadata(root).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+// The runtime directories, from the cache while it's current
+pub(crate) fn runtime_paths() -> Vec<String> {
+    let preferred = app_settings::current().preferred_node_version;
+    let roots = root_times();
+    let Ok(mut cache) = CACHE.lock() else {
+        return scan(preferred.as_deref());
+    };
+    if let Some(ref cached) = *cache {
+        if cached.preferred == preferred && cached.roots == roots {
+            return cached.paths.clone();
+        }
+    }
+    let paths = scan(preferred.as_deref());
+    *cache = Some(Cache {
+        paths: paths.clone(),
+        preferred,
+        roots,
+    });
+    paths
+}
+
+// Rescan now, e.g. after installing Node somewhere the cache doesn't watch
+#[tauri::command]
+pub(crate) fn refresh_runtime_cache() -> Vec<String> {
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = None;
+    }
+    runtime_paths()
+}
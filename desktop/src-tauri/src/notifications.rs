@@ -0,0 +1,200 @@
+// Notification center.
+//
+// Every alert the backend raises is kept here, so one that fires while nobody
+// is looking is still there after its native toast is gone. Repeats of the same
+// alert within a few minutes are folded into one entry with a count, and toasts
+// are capped per minute so a flapping bridge can't flood the desktop; alerts
+// over the cap are still stored. History is kept in the desktop's data dir.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::get_desktop_data_dir;
+use crate::log_forwarding;
+
+pub(crate) const NOTIFICATION_EVENT: &str = "notifications://new";
+
+const MAX_STORED: usize = 500;
+const DEDUPE_WINDOW: chrono::TimeDelta = chrono::TimeDelta::minutes(10);
+const TOAST_WINDOW: Duration = Duration::from_secs(60);
+const MAX_TOASTS_PER_WINDOW: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationCategory {
+    Pairing,
+    Budget,
+    Takeover,
+    Bridge,
+    Config,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    id: String,
+    category: NotificationCategory,
+    title: String,
+    body: String,
+    created_at: String,
+    // Last repeat folded into this entry
+    last_at: String,
+    count: u32,
+    read: bool,
+}
+
+// Newest last; None until loaded from disk
+static HISTORY: Mutex<Option<Vec<Notification>>> = Mutex::new(None);
+static TOASTS: Mutex<VecDeque<Instant>> = Mutex::new(VecDeque::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn get_history_path() -> PathBuf {
+    get_desktop_data_dir().join("notifications.json")
+}
+
+fn load_history() -> Vec<Notification> {
+    fs::read_to_string(get_history_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(history: &[Notification]) -> Result<(), String> {
+    let path = get_history_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(history).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save notifications: {}", e))
+}
+
+fn snapshot() -> Vec<Notification> {
+    let Ok(mut history) = HISTORY.lock() else {
+        return vec![];
+    };
+    history.get_or_insert_with(load_history).clone()
+}
+
+// Changes are saved right away
+fn with_history<T>(update: impl FnOnce(&mut Vec<Notification>) -> T) -> Result<T, String> {
+    let mut history = HISTORY.lock().map_err(|e| e.to_string())?;
+    let history = history.get_or_insert_with(load_history);
+    let result = update(history);
+    save_history(history)?;
+    Ok(result)
+}
+
+// Whether a toast fits under the cap; counts it if so
+fn take_toast_slot() -> bool {
+    let Ok(mut toasts) = TOASTS.lock() else {
+        return false;
+    };
+    while toasts.front().is_some_and(|t| t.elapsed() >= TOAST_WINDOW) {
+        toasts.pop_front();
+    }
+    if toasts.len() >= MAX_TOASTS_PER_WINDOW {
+        return false;
+    }
+    toasts.push_back(Instant::now());
+    true
+}
+
+// Store the alert; returns it unless it repeated one already in the window
+fn store(category: NotificationCategory, title: &str, body: &str) -> Result<Option<Notification>, String> {
+    let now = chrono::Local::now();
+    with_history(|history| {
+        let repeat = history.iter_mut().rev().find(|n| {
+            n.category == category
+                && n.title == title
+                && n.body == body
+                && chrono::DateTime::parse_from_rfc3339(&n.last_at).is_ok_and(|t| now.signed_duration_since(t) < DEDUPE_WINDOW)
+        });
+        if let Some(repeat) = repeat {
+            repeat.count += 1;
+            repeat.last_at = now.to_rfc3339();
+            repeat.read = false;
+            return None;
+        }
+
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        let notification = Notification {
+            id: format!("notif-{}-{}", millis, NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+            category,
+            title: title.to_string(),
+            body: body.to_string(),
+            created_at: now.to_rfc3339(),
+            last_at: now.to_rfc3339(),
+            count: 1,
+            read: false,
+        };
+        history.push(notification.clone());
+        let excess = history.len().saturating_sub(MAX_STORED);
+        history.drain(..excess);
+        Some(notification)
+    })
+}
+
+// Keep an alert in the center without a toast, for ones the UI shows anyway
+pub(crate) fn record(app: &AppHandle, category: NotificationCategory, title: &str, body: &str) -> bool {
+    match store(category, title, body) {
+        Ok(Some(notification)) => {
+            let _ = app.emit(NOTIFICATION_EVENT, notification);
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            log_forwarding::desktop_error(e);
+            false
+        }
+    }
+}
+
+// Keep an alert and show it as a native toast, unless it's a repeat or
+// toasts are over the cap
+pub(crate) fn notify(app: &AppHandle, category: NotificationCategory, title: &str, body: &str) {
+    if record(app, category, title, body) && take_toast_slot() {
+        let _ = app.notification().builder().title(title).body(body).show();
+    }
+}
+
+// Newest first
+#[tauri::command]
+pub(crate) fn get_notifications(
+    unread_only: Option<bool>,
+    category: Option<NotificationCategory>,
+) -> Result<Vec<Notification>, String> {
+    Ok(snapshot()
+        .into_iter()
+        .rev()
+        .filter(|n| unread_only != Some(true) || !n.read)
+        .filter(|n| category.is_none_or(|c| n.category == c))
+        .collect())
+}
+
+// All of them when `ids` is None; returns how many changed
+#[tauri::command]
+pub(crate) fn mark_notifications_read(ids: Option<Vec<String>>) -> Result<usize, String> {
+    with_history(|history| {
+        let mut changed = 0;
+        for notification in history.iter_mut() {
+            if !notification.read && ids.as_ref().is_none_or(|ids| ids.contains(&notification.id)) {
+                notification.read = true;
+                changed += 1;
+            }
+        }
+        changed
+    })
+}
+
+#[tauri::command]
+pub(crate) fn clear_notifications() -> Result<bool, String> {
+    with_history(|history| history.clear())?;
+    Ok(true)
+}
@@ -0,0 +1,208 @@
+// Read-only observer mode.
+//
+// For a dashboard left running on a shared machine: only monitoring commands
+// are served, everything else is rejected before it reaches its handler. The
+// mode is switched on by the `--observer` launch flag or a saved setting. It
+// can't be switched off from the dashboard itself; relaunch without the flag
+// and delete the setting file instead.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+use crate::config::get_desktop_data_dir;
+
+const CLI_FLAG: &str = "--observer";
+
+// Commands that only read state. Anything not listed is blocked, so new
+// commands are safe by default.
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "get_status",
+    "get_pairings",
+    "get_paired_users",
+    "is_service_running",
+    "check_config",
+    "read_config",
+    "get_logs",
+    "get_agents",
+    "get_installed_plugins",
+    "get_agent_templates",
+    "stream_sessions",
+    "stream_transcript",
+    "read_file_page",
+    "get_usage_dashboard",
+    "search_history",
+    "get_attachment_settings",
+    "list_received_files",
+    "get_voice_settings",
+    "get_media_policies",
+    "list_templates",
+    "list_scheduled_messages",
+    "list_group_chats",
+    "get_rate_limits",
+    "get_local_analytics",
+    "get_metrics",
+    "get_workspaces_git_status",
+    "get_workspace_disk_usage",
+    "validate_workspace",
+    "list_crash_reports",
+    "list_prompt_presets",
+    "render_prompt",
+    "get_runtime_info",
+    "list_discord_guilds",
+    "get_webhook_settings",
+    "get_tunnel_status",
+    "list_takeovers",
+    "get_proxy_settings",
+    "get_locale",
+    // Display language is a viewer preference
+    "set_locale",
+    "get_observer_mode",
+    "get_heartbeat_settings",
+    "get_heartbeat_status",
+    "get_pending_messages",
+    "get_config_drift",
+    "list_tasks",
+    "get_console_session",
+    "list_identities",
+    "get_backup_settings",
+    "get_backup_status",
+    "list_config_backups",
+    "get_supervisor_settings",
+    "get_supervisor_status",
+    "get_budget_settings",
+    "get_discord_commands",
+    "get_app_settings",
+    "scan_config_secrets",
+    "get_bridges_status",
+    "get_log_forwarding_settings",
+    "get_log_forwarding_status",
+    "get_sessions",
+    "get_session_detail",
+    "get_redaction_settings",
+    "preview_transcript_redaction",
+    "test_agent",
+    "get_autostart",
+    "get_logs_filtered",
+    "subscribe_logs",
+    "unsubscribe_logs",
+    "search_logs",
+    "get_notifications",
+    "validate_config",
+    "get_permission_prompt_settings",
+    "get_formatting_settings",
+    "preview_formatting",
+    "get_whatsapp_config",
+    "get_whatsapp_status",
+    "get_process_limits",
+    "list_profiles",
+    "get_schedule_status",
+    "get_setup_state",
+    "scan_existing_setup",
+    "get_ccb_version",
+    "get_bridge_version",
+    "get_app_lock_status",
+    "check_for_app_update",
+    "validate_bot_token",
+    "get_session_plan",
+    "get_bridge_endpoints",
+    "get_endpoint_status",
+    "get_endpoint_pairings",
+    "get_endpoint_sessions",
+    "preview_schedule",
+    "run_diagnostics",
+];
+
+static CLI_ENABLED: OnceLock<bool> = OnceLock::new();
+// The saved setting, read once; turning it off takes a relaunch anyway
+static SETTING: RwLock<Option<bool>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ObserverSettings {
+    enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObserverMode {
+    enabled: bool,
+    // "flag" or "setting" when enabled
+    source: Option<String>,
+    settings_path: String,
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("observer.json")
+}
+
+fn cli_enabled() -> bool {
+    *CLI_ENABLED.get_or_init(|| std::env::args().any(|a| a == CLI_FLAG))
+}
+
+fn setting_enabled() -> bool {
+    if let Some(enabled) = SETTING.read().ok().and_then(|s| *s) {
+        return enabled;
+    }
+    let enabled = fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|s| serde_json::from_str::<ObserverSettings>(&s).ok())
+        .is_some_and(|s| s.enabled);
+    if let Ok(mut cached) = SETTING.write() {
+        *cached = Some(enabled);
+    }
+    enabled
+}
+
+pub(crate) fn is_enabled() -> bool {
+    cli_enabled() || setting_enabled()
+}
+
+// Reject mutating commands while observer mode is on
+pub(crate) fn guard_commands<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command();
+        if !READ_ONLY_COMMANDS.contains(&command) && is_enabled() {
+            let message = format!("'{}' is disabled in read-only observer mode", command);
+            invoke.resolver.reject(message);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_observer_mode() -> ObserverMode {
+    let source = if cli_enabled() {
+        Some("flag".to_string())
+    } else if setting_enabled() {
+        Some("setting".to_string())
+    } else {
+        None
+    };
+    ObserverMode {
+        enabled: source.is_some(),
+        source,
+        settings_path: get_settings_path().to_string_lossy().to_string(),
+    }
+}
+
+// Only reachable while observer mode is off, so in practice this turns it on
+#[tauri::command]
+pub(crate) fn set_observer_mode(enabled: bool) -> Result<ObserverMode, String> {
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&ObserverSettings { enabled }).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save observer setting: {}", e))?;
+    if let Ok(mut cached) = SETTING.write() {
+        *cached = Some(enabled);
+    }
+    Ok(get_observer_mode())
+}
@@ -0,0 +1,89 @@
+// Outbound message queue.
+//
+// While a channel is disconnected the bridge holds replies in an outbox and
+// delivers them once it reconnects. After a long outage some of them may no
+// longer be worth sending, so the operator can inspect the queue, flush it
+// now, or drop individual messages.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::State;
+
+use crate::api_client::{ApiError, BridgeApiClient};
+
+// Retrying waits on the platforms, so give it longer
+const RETRY_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingMessage {
+    id: String,
+    chat_key: String,
+    text: String,
+    queued_at: String,
+    #[serde(default)]
+    attempts: u32,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OutboxResponse {
+    messages: Vec<PendingMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryResult {
+    delivered: u32,
+    failed: u32,
+    // Still queued, e.g. because their channel is still down
+    remaining: u32,
+}
+
+#[tauri::command]
+pub(crate) async fn get_pending_messages(
+    api: State<'_, BridgeApiClient>,
+    channel: Option<String>,
+) -> Result<Vec<PendingMessage>, String> {
+    let mut messages = match api.get::<OutboxResponse>("/outbox").await {
+        Ok(response) => response.messages,
+        Err(ApiError::Unsupported(_)) => return Err("This bridge version can't list queued messages".to_string()),
+        Err(e) => return Err(e.into()),
+    };
+    if let Some(channel) = channel {
+        messages.retain(|m| m.chat_key.split(':').next() == Some(channel.as_str()));
+    }
+    Ok(messages)
+}
+
+// Retry everything queued, or only the given messages
+#[tauri::command]
+pub(crate) async fn retry_pending_messages(
+    api: State<'_, BridgeApiClient>,
+    ids: Option<Vec<String>>,
+) -> Result<RetryResult, String> {
+    let response = api
+        .http()
+        .post(BridgeApiClient::url("/outbox/retry"))
+        .json(&serde_json::json!({ "ids": ids }))
+        .timeout(RETRY_TIMEOUT)
+        .send()
+        .await
+        .map_err(ApiError::from)?;
+    match BridgeApiClient::check(response).await {
+        Ok(response) => Ok(response.json().await.map_err(ApiError::from)?),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't retry queued messages".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn drop_pending_message(api: State<'_, BridgeApiClient>, id: String) -> Result<bool, String> {
+    // Not found can also mean the message was delivered meanwhile
+    match api.delete(&format!("/outbox/{}", id)).await {
+        Ok(_) => Ok(true),
+        Err(ApiError::NotFound(_)) => Ok(false),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't drop queued messages".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,249 @@
+// Approving and denying pairing requests.
+//
+// Unknown users asking to talk to a bot get a pairing code; the bridge holds
+// the request until someone approves or denies it here or from the tray menu.
+// New requests raise a native notification so nobody has to keep the window
+// open to notice them. Invites work the other way round: the operator creates
+// a code to send to someone, who is paired as soon as they redeem it. Chats
+// already paired are listed from the bridge's allowlist, and revoking one
+// removes it there, so the user has to pair again to get back in.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::api_client::{ApiError, BridgeApiClient, PairingInvite, PairingRequest, UserInfo};
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::groups::bot_section;
+use crate::notifications::{self, NotificationCategory};
+use crate::poller::StatusCache;
+use crate::{app_lock, app_settings, i18n, identities, pairing_policy};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AllowlistEntry {
+    chat_key: String,
+    #[serde(default)]
+    added_at: Option<String>,
+    #[serde(default)]
+    added_by: Option<String>,
+    // Only some bridges keep who the chat belongs to
+    #[serde(default)]
+    user_info: Option<UserInfo>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AllowlistResponse {
+    allowlist: Vec<AllowlistEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedUser {
+    chat_key: String,
+    channel: String,
+    added_at: Option<String>,
+    // "pairing", "invite" or whoever added the chat by hand
+    added_by: Option<String>,
+    username: Option<String>,
+    display_name: Option<String>,
+    // Name of the linked identity, if any
+    identity: Option<String>,
+}
+
+// Percent-encode a chat key ("telegram:bot1:12345") for use in a path
+fn path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// A pairing that expired or was already handled (404 or 409) is reported as
+// not done; any other failure is an error
+fn decided(result: Result<(), ApiError>) -> CommandResult<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(ApiError::NotFound(_)) | Err(ApiError::Status(409, _)) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// "telegram" -> "Telegram"
+pub(crate) fn channel_label(channel: &str) -> String {
+    let mut label = channel.to_string();
+    if let Some(first) = label.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    label
+}
+
+// Desktop notifications can't carry buttons, so the request is approved or
+// denied from the window or the tray menu. Muted requests still reach the
+// notification center.
+pub(crate) fn notify_new(app: &AppHandle, pairings: &[PairingRequest]) {
+    let muted = app_settings::current().mute_pairing_notifications;
+    for pairing in pairings {
+        let channel = channel_label(&pairing.user_info.channel);
+        let title = i18n::t("pairing.notify_title");
+        let body = i18n::tr(
+            "pairing.notify_body",
+            &[
+                ("user", &pairing_policy::user_label(pairing)),
+                ("channel", &channel),
+                ("code", &pairing.code),
+            ],
+        );
+        if muted {
+            notifications::record(app, NotificationCategory::Pairing, &title, &body);
+        } else {
+            notifications::notify(app, NotificationCategory::Pairing, &title, &body);
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_pairings(cache: State<'_, StatusCache>) -> CommandResult<Vec<PairingRequest>> {
+    Ok(cache.pairings())
+}
+
+#[tauri::command]
+pub(crate) async fn approve_pairing(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+    cache: State<'_, StatusCache>,
+    code: String,
+    // The app lock PIN, needed for sensitive agents while a lock is set
+    pin: Option<String>,
+) -> CommandResult<bool> {
+    // Pairings with high-sensitivity agents need a second, native confirmation;
+    // without the pairing or the config there's no telling, so don't approve
+    let pairing = match cache.pairings().into_iter().find(|p| p.code == code) {
+        Some(pairing) => pairing,
+        None => api
+            .pairings()
+            .await?
+            .into_iter()
+            .find(|p| p.code == code)
+            .ok_or_else(|| Error::Invalid(i18n::t("pairing.not_found")))?,
+    };
+    let config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    if let Some(agent_id) = pairing_policy::sensitive_target(&config, &pairing) {
+        app_lock::verify(pin.as_deref()).await?;
+        if !pairing_policy::confirm_approval(&app, &pairing, &agent_id).await {
+            return Err(Error::Invalid(i18n::t("pairing.not_confirmed")));
+        }
+    }
+
+    let approved = decided(api.approve(&code).await)?;
+    if approved {
+        cache.remove_pairing(&code);
+        // Accounts linked to the same person get paired along with this one
+        identities::sync_after_pairing(&app, &config, &pairing.chat_key, pin.as_deref()).await;
+    }
+    cache.request_refresh();
+    Ok(approved)
+}
+
+#[tauri::command]
+pub(crate) async fn deny_pairing(
+    api: State<'_, BridgeApiClient>,
+    cache: State<'_, StatusCache>,
+    code: String,
+) -> CommandResult<bool> {
+    let denied = decided(api.deny(&code).await)?;
+    if denied {
+        cache.remove_pairing(&code);
+    }
+    cache.request_refresh();
+    Ok(denied)
+}
+
+const MIN_INVITE_SECS: u64 = 60;
+const MAX_INVITE_SECS: u64 = 7 * 24 * 60 * 60;
+
+// The channel a bot id belongs to; "main" is the single-token bot
+fn bot_channel(config: &serde_json::Value, bot_id: &str) -> Option<&'static str> {
+    ["telegram", "discord"].into_iter().find(|channel| {
+        bot_section(config, channel, Some(bot_id)).is_some()
+            || (bot_id == "main" && bot_section(config, channel, None).is_some_and(|s| s.get("bots").is_none()))
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn create_pairing_invite(
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+    bot_id: String,
+    agent_id: Option<String>,
+    expires_in: u64,
+) -> CommandResult<PairingInvite> {
+    if !(MIN_INVITE_SECS..=MAX_INVITE_SECS).contains(&expires_in) {
+        return Err(Error::Invalid("Invites must expire between one minute and seven days from now".to_string()));
+    }
+    let config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    let channel = bot_channel(&config, &bot_id).ok_or_else(|| Error::Invalid(format!("Bot '{}' not found", bot_id)))?;
+    if let Some(ref agent_id) = agent_id {
+        let agents = config.get("agents").and_then(|a| a.get("list")).and_then(|l| l.as_array());
+        if !agents.into_iter().flatten().any(|a| a.get("id").and_then(|v| v.as_str()) == Some(agent_id)) {
+            return Err(Error::Invalid(i18n::tr("agent.not_found", &[("id", agent_id)])));
+        }
+    }
+    // Invites skip approval, which sensitive agents never do
+    if let Some(agent) = pairing_policy::sensitive_invite_target(&config, channel, &bot_id, agent_id.as_deref()) {
+        return Err(Error::Invalid(i18n::tr("pairing.invite_sensitive", &[("agent", &agent)])));
+    }
+
+    let body = serde_json::json!({
+        "botId": bot_id,
+        "channel": channel,
+        "agentId": agent_id,
+        // The bridge's pairing expiries are in milliseconds
+        "expiresInMs": expires_in * 1000,
+    });
+    match api.create_invite(&body).await {
+        Ok(invite) => Ok(invite),
+        Err(ApiError::Unsupported(_)) => Err(Error::Bridge(i18n::t("pairing.invites_unsupported"))),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Newest first, as the bridge lists them
+#[tauri::command]
+pub(crate) async fn get_paired_users(
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+) -> CommandResult<Vec<PairedUser>> {
+    let config = store.read()?.unwrap_or_default();
+    let allowlist = api.get::<AllowlistResponse>("/allowlist").await?.allowlist;
+    Ok(allowlist
+        .into_iter()
+        .map(|entry| PairedUser {
+            channel: entry.chat_key.split(':').next().unwrap_or_default().to_string(),
+            identity: identities::label_for_chat(&config, &entry.chat_key),
+            username: entry.user_info.as_ref().and_then(|u| u.username.clone()),
+            display_name: entry.user_info.and_then(|u| u.display_name),
+            chat_key: entry.chat_key,
+            added_at: entry.added_at,
+            added_by: entry.added_by,
+        })
+        .collect())
+}
+
+// Returns false if the chat wasn't paired
+#[tauri::command]
+pub(crate) async fn revoke_pairing(api: State<'_, BridgeApiClient>, chat_key: String) -> CommandResult<bool> {
+    let paired = api.get::<AllowlistResponse>("/allowlist").await?.allowlist;
+    if !paired.iter().any(|entry| entry.chat_key == chat_key) {
+        return Ok(false);
+    }
+    match api.delete(&format!("/allowlist/{}", path_segment(&chat_key))).await {
+        Ok(_) => Ok(true),
+        Err(ApiError::Unsupported(_)) => Err(Error::Bridge("This bridge version can't revoke pairings".to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
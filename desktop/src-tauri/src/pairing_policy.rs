@@ -0,0 +1,192 @@
+// Pairing policy for sensitive agents.
+//
+// Agents marked `sensitivity: "high"` get two extra guards. Approving a pairing
+// that routes to one needs a second, native confirmation that the webview
+// can't answer on its own, plus the app lock PIN when one is set. And bots
+// routed to one never let users in through allowlist rules: a config write
+// that gives such a bot a `dmPolicy` other than "pairing" or an `allowFrom`
+// list is refused, as are pairing invites for one, so each user goes through
+// approval.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::api_client::PairingRequest;
+use crate::i18n;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sensitivity {
+    #[default]
+    Normal,
+    High,
+}
+
+fn agents(config: &Value) -> impl Iterator<Item = &Value> {
+    config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+}
+
+fn is_high(config: &Value, agent_id: &str) -> bool {
+    agents(config)
+        .find(|a| a.get("id").and_then(|v| v.as_str()) == Some(agent_id))
+        .and_then(|a| a.get("sensitivity"))
+        .and_then(|s| serde_json::from_value::<Sensitivity>(s.clone()).ok())
+        == Some(Sensitivity::High)
+}
+
+// The bridge's fallback: a binding without match criteria, else the first agent
+fn default_agent(config: &Value) -> Option<&str> {
+    let bindings = config.get("bindings").and_then(|b| b.as_array());
+    bindings
+        .into_iter()
+        .flatten()
+        .find(|b| b.get("match").is_none_or(|m| m.is_null()))
+        .and_then(|b| b.get("agentId"))
+        .or_else(|| agents(config).next().and_then(|a| a.get("id")))
+        .and_then(|id| id.as_str())
+}
+
+fn bot_agent<'a>(config: &'a Value, channel: &str, bot_id: &str) -> Option<&'a str> {
+    config
+        .get("channels")
+        .and_then(|c| c.get(channel))
+        .and_then(|c| c.get("bots"))
+        .and_then(|b| b.as_array())
+        .into_iter()
+        .flatten()
+        .find(|b| b.get("id").and_then(|v| v.as_str()) == Some(bot_id))
+        .and_then(|b| b.get("agentId"))
+        .and_then(|a| a.as_str())
+}
+
+// Which agent a pairing's chat will talk to, following the bridge's routing:
+// the bot's own agent, then the first matching binding, then the default
+fn target_agent<'a>(config: &'a Value, pairing: &PairingRequest) -> Option<&'a str> {
+    let parts: Vec<&str> = pairing.chat_key.split(':').collect();
+    let channel = *parts.first()?;
+    // telegram:<botId>:<chatId> for multi-bot setups, telegram:<chatId> otherwise
+    let bot_id = (parts.len() >= 3 && !matches!(parts[1], "group" | "channel")).then(|| parts[1]);
+
+    if let Some(agent) = bot_id.and_then(|id| bot_agent(config, channel, id)) {
+        return Some(agent);
+    }
+
+    let bindings = config.get("bindings").and_then(|b| b.as_array());
+    let matching = bindings.into_iter().flatten().find(|b| {
+        let Some(m) = b.get("match").filter(|m| !m.is_null()) else {
+            return false;
+        };
+        let field = |name: &str| m.get(name).and_then(|v| v.as_str());
+        field("channel").is_none_or(|c| c == channel)
+            && field("peer").is_none_or(|p| p == pairing.user_info.id)
+            && field("group").is_none()
+    });
+    matching
+        .and_then(|b| b.get("agentId"))
+        .and_then(|a| a.as_str())
+        .or_else(|| default_agent(config))
+}
+
+// The high-sensitivity agent an invite for a bot would pair with, if any:
+// the invite's own agent, else the bot's, else the default
+pub(crate) fn sensitive_invite_target(
+    config: &Value,
+    channel: &str,
+    bot_id: &str,
+    agent_id: Option<&str>,
+) -> Option<String> {
+    agent_id
+        .or_else(|| bot_agent(config, channel, bot_id))
+        .or_else(|| default_agent(config))
+        .filter(|agent| is_high(config, agent))
+        .map(String::from)
+}
+
+// The high-sensitivity agent a pairing targets, if any
+pub(crate) fn sensitive_target(config: &Value, pairing: &PairingRequest) -> Option<String> {
+    target_agent(config, pairing)
+        .filter(|agent| is_high(config, agent))
+        .map(String::from)
+}
+
+// @username, or the platform user id for users without one
+pub(crate) fn user_label(pairing: &PairingRequest) -> String {
+    pairing
+        .user_info
+        .username
+        .as_ref()
+        .map(|u| format!("@{}", u))
+        .unwrap_or_else(|| pairing.user_info.id.clone())
+}
+
+// Ask the user directly through a native dialog; resolves to false on cancel
+pub(crate) async fn confirm_approval(app: &AppHandle, pairing: &PairingRequest, agent_id: &str) -> bool {
+    let user = user_label(pairing);
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(i18n::tr("pairing.confirm_body", &[("user", &user), ("agent", agent_id)]))
+        .title(i18n::t("pairing.confirm_title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t("pairing.confirm_ok"),
+            i18n::t("pairing.confirm_cancel"),
+        ))
+        .show(move |approved| {
+            let _ = tx.send(approved);
+        });
+    rx.await.unwrap_or(false)
+}
+
+// Where a bot or channel routed to a high-sensitivity agent would let users
+// in without approval, if anywhere
+fn open_section(section: &serde_json::Map<String, Value>) -> bool {
+    let policy = section.get("dmPolicy").and_then(|p| p.as_str());
+    let allow_from = section.get("allowFrom").and_then(|a| a.as_array()).is_some_and(|a| !a.is_empty());
+    policy.is_some_and(|p| p != "pairing") || allow_from
+}
+
+// Refuse configs that open bots routed to high-sensitivity agents to allowlist rules
+pub(crate) fn check(config: &Value) -> Result<(), String> {
+    let default_agent = default_agent(config).filter(|a| is_high(config, a));
+    let Some(channels) = config.get("channels").and_then(|c| c.as_object()) else {
+        return Ok(());
+    };
+    let refuse = |place: String, agent: &str| {
+        Err(format!(
+            "{} routes to '{}', which is marked as high sensitivity, so it must use dmPolicy \"pairing\" without allowFrom",
+            place, agent
+        ))
+    };
+
+    for (name, channel) in channels {
+        let Some(channel) = channel.as_object() else {
+            continue;
+        };
+        for bot in channel.get("bots").and_then(|b| b.as_array()).into_iter().flatten() {
+            let Some(bot) = bot.as_object() else {
+                continue;
+            };
+            let agent = match bot.get("agentId").and_then(|a| a.as_str()) {
+                Some(agent) => is_high(config, agent).then_some(agent),
+                None => default_agent,
+            };
+            if let Some(agent) = agent.filter(|_| open_section(bot)) {
+                let id = bot.get("id").and_then(|v| v.as_str()).unwrap_or("main");
+                return refuse(format!("Bot '{}' on {}", id, name), agent);
+            }
+        }
+        // Channel-level settings are inherited by bots without their own agent
+        if let Some(agent) = default_agent.filter(|_| open_section(channel)) {
+            return refuse(format!("channels.{}", name), agent);
+        }
+    }
+    Ok(())
+}
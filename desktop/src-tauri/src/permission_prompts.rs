@@ -0,0 +1,338 @@
+// Permission prompts from the bridge.
+//
+// When an agent wants to do something its permission mode doesn't allow on its
+// own (a write outside the workspace, a risky bash command), the Agent SDK asks
+// a `canUseTool` callback. The bridge answers it by posting the request here:
+//
+//   POST http://127.0.0.1:<port>/permission
+//   Authorization: Bearer <token>
+//   {"agentId": "...", "toolName": "Bash", "input": {...}, "reason": "..."}
+//
+// The desktop shows a blocking native dialog and replies with the SDK's own
+// result shape, `{"behavior": "allow", "updatedInput": {...}}` or
+// `{"behavior": "deny", "message": "..."}`. Nobody answering within the
+// configured timeout is a deny. The URL and token reach the bridge as
+// CCB_PERMISSION_WEBHOOK_URL and CCB_PERMISSION_WEBHOOK_TOKEN, which its
+// session manager turns into the SDK's `canUseTool` callback.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+
+use crate::config::get_desktop_data_dir;
+use crate::service::AppState;
+use crate::{i18n, log_forwarding};
+
+const PATH: &str = "/permission";
+const MAX_BODY_BYTES: usize = 256 * 1024;
+const MAX_HEADER_LINES: usize = 100;
+// A client that connects and goes quiet is dropped after this
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+// Longest excerpt of the tool input shown in the dialog
+const MAX_DETAIL_CHARS: usize = 600;
+const MIN_TIMEOUT_SECS: u64 = 5;
+const MAX_TIMEOUT_SECS: u64 = 600;
+
+// Wakes the server to pick up changed settings
+static RELOAD: Notify = Notify::const_new();
+// One dialog at a time; later requests wait, on their own timeout
+static DIALOG: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static TOKEN: OnceLock<String> = OnceLock::new();
+// Port the server is listening on, 0 when it isn't
+static LISTENING: AtomicU16 = AtomicU16::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionPromptSettings {
+    enabled: bool,
+    port: u16,
+    // Requests nobody answers in time are denied
+    timeout_secs: u64,
+}
+
+impl Default for PermissionPromptSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 38794,
+            timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PermissionRequest {
+    #[serde(default)]
+    agent_id: Option<String>,
+    tool_name: String,
+    #[serde(default)]
+    input: serde_json::Value,
+    // Why the SDK is asking, when it says
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "behavior", rename_all = "lowercase")]
+enum PermissionResult {
+    #[serde(rename_all = "camelCase")]
+    Allow { updated_input: serde_json::Value },
+    Deny { message: String },
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("permission_prompts.json")
+}
+
+// A hand-edited timeout is held to the range the settings screen allows
+fn load_settings() -> PermissionPromptSettings {
+    let mut settings: PermissionPromptSettings = fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    settings.timeout_secs = settings.timeout_secs.clamp(MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS);
+    settings
+}
+
+// New every launch, from the OS's random source
+fn token() -> &'static str {
+    TOKEN.get_or_init(|| {
+        let mut bytes = [0u8; 32];
+        if let Err(e) = getrandom::fill(&mut bytes) {
+            // Requests are refused without a token
+            log_forwarding::desktop_error(format!("Failed to generate a permission webhook token: {}", e));
+            return String::new();
+        }
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    })
+}
+
+// Env vars that point a starting bridge at the endpoint; empty when disabled
+// or when the port couldn't be bound, so the bridge doesn't post to nothing
+pub(crate) fn bridge_env() -> Vec<(String, String)> {
+    let port = LISTENING.load(Ordering::SeqCst);
+    if !load_settings().enabled || port == 0 {
+        return vec![];
+    }
+    vec![
+        (
+            "CCB_PERMISSION_WEBHOOK_URL".to_string(),
+            format!("http://127.0.0.1:{}{}", port, PATH),
+        ),
+        ("CCB_PERMISSION_WEBHOOK_TOKEN".to_string(), token().to_string()),
+    ]
+}
+
+fn log(app: &AppHandle, message: String) {
+    if let Ok(mut service) = app.state::<AppState>().lock() {
+        service.logs.info(message);
+    }
+}
+
+// What the agent is about to do, in the words that matter for the tool
+fn describe(request: &PermissionRequest) -> String {
+    let input = &request.input;
+    let detail = ["command", "file_path", "path", "url"]
+        .iter()
+        .find_map(|key| input.get(key).and_then(|v| v.as_str()).map(String::from))
+        .unwrap_or_else(|| input.to_string());
+    let mut detail: String = detail.chars().take(MAX_DETAIL_CHARS).collect();
+    if let Some(ref reason) = request.reason {
+        detail = format!("{}\n\n{}", detail, reason);
+    }
+    detail
+}
+
+async fn ask(app: &AppHandle, request: &PermissionRequest) -> bool {
+    let _turn = DIALOG.lock().await;
+    let agent = request.agent_id.as_deref().unwrap_or("?");
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(i18n::tr(
+            "permission.body",
+            &[("agent", agent), ("tool", &request.tool_name), ("detail", &describe(request))],
+        ))
+        .title(i18n::t("permission.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            i18n::t("permission.allow"),
+            i18n::t("permission.deny"),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+async fn decide(app: &AppHandle, request: PermissionRequest, timeout: Duration) -> PermissionResult {
+    let tool = request.tool_name.clone();
+    // The dialog can't be closed from here, so an answer after the timeout
+    // is simply ignored
+    let (result, outcome) = match tokio::time::timeout(timeout, ask(app, &request)).await {
+        Ok(true) => (PermissionResult::Allow { updated_input: request.input }, "permission.allowed"),
+        Ok(false) => (
+            PermissionResult::Deny { message: i18n::t("permission.denied_message") },
+            "permission.denied",
+        ),
+        Err(_) => (
+            PermissionResult::Deny { message: i18n::t("permission.timed_out_message") },
+            "permission.timed_out",
+        ),
+    };
+    log(app, i18n::tr(outcome, &[("tool", &tool)]));
+    result
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &serde_json::Value) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+// Request line, headers and body of a single HTTP/1.1 request
+async fn read_request(stream: &mut TcpStream) -> Result<(String, Vec<(String, String)>, Vec<u8>), String> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await.map_err(|e| e.to_string())?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.map_err(|e| e.to_string())?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() >= MAX_HEADER_LINES {
+            return Err("Too many headers".to_string());
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY_BYTES {
+        return Err("Request body too large".to_string());
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.map_err(|e| e.to_string())?;
+    Ok((request_line, headers, body))
+}
+
+async fn handle(app: AppHandle, mut stream: TcpStream, timeout: Duration) {
+    let (request_line, headers, body) = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => request,
+        Ok(Err(e)) => return respond(&mut stream, "400 Bad Request", &error_body(&e)).await,
+        Err(_) => return respond(&mut stream, "408 Request Timeout", &error_body("Request timed out")).await,
+    };
+
+    let mut parts = request_line.split_whitespace();
+    if parts.next() != Some("POST") || parts.next() != Some(PATH) {
+        return respond(&mut stream, "404 Not Found", &error_body("Not found")).await;
+    }
+    let expected = format!("Bearer {}", token());
+    if token().is_empty() || !headers.iter().any(|(name, value)| name == "authorization" && *value == expected) {
+        return respond(&mut stream, "401 Unauthorized", &error_body("Invalid token")).await;
+    }
+    let request: PermissionRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            let message = format!("Invalid permission request: {}", e);
+            return respond(&mut stream, "400 Bad Request", &error_body(&message)).await;
+        }
+    };
+
+    let result = decide(&app, request, timeout).await;
+    let body = serde_json::to_value(&result).unwrap_or_default();
+    respond(&mut stream, "200 OK", &body).await;
+}
+
+async fn serve(app: &AppHandle, listener: TcpListener, timeout: Duration) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tauri::async_runtime::spawn(handle(app.clone(), stream, timeout));
+            }
+            Err(e) => {
+                log_forwarding::desktop_error(format!("Failed to accept a permission prompt: {}", e));
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+// Listens while enabled; restarted whenever the settings change
+pub(crate) async fn run(app: AppHandle) {
+    loop {
+        let settings = load_settings();
+        if settings.enabled {
+            match TcpListener::bind(("127.0.0.1", settings.port)).await {
+                Ok(listener) => {
+                    LISTENING.store(settings.port, Ordering::SeqCst);
+                    let timeout = Duration::from_secs(settings.timeout_secs);
+                    tokio::select! {
+                        _ = serve(&app, listener, timeout) => {}
+                        _ = RELOAD.notified() => {}
+                    }
+                    LISTENING.store(0, Ordering::SeqCst);
+                    continue;
+                }
+                Err(e) => log_forwarding::desktop_error(format!(
+                    "Failed to listen for permission prompts on port {}: {}",
+                    settings.port, e
+                )),
+            }
+        }
+        RELOAD.notified().await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_permission_prompt_settings() -> PermissionPromptSettings {
+    load_settings()
+}
+
+// The bridge picks up a changed port on its next start
+#[tauri::command]
+pub(crate) fn set_permission_prompt_settings(settings: PermissionPromptSettings) -> Result<bool, String> {
+    if settings.port == 0 {
+        return Err("Port must not be 0".to_string());
+    }
+    if !(MIN_TIMEOUT_SECS..=MAX_TIMEOUT_SECS).contains(&settings.timeout_secs) {
+        return Err(format!(
+            "Timeout must be between {} and {} seconds",
+            MIN_TIMEOUT_SECS, MAX_TIMEOUT_SECS
+        ));
+    }
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save permission prompt settings: {}", e))?;
+    RELOAD.notify_one();
+    Ok(true)
+}
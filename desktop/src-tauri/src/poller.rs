@@ -0,0 +1,169 @@
+// Background polling of the Control API.
+//
+// A single loop fetches /status and /pairings together on the shared client and
+// caches the results, so the window and tray read from memory instead of each
+// firing their own HTTP requests. Changes are pushed to the frontend as events,
+// including `bridge://pairing-requested` for bridges that don't push their own.
+// Readers get the status with the time of the poll it came from, and a stale
+// flag once polls stop landing (a hung request, the machine waking from
+// sleep), so the UI can tell an old answer from a current one.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+use tokio::time::MissedTickBehavior;
+
+use crate::api_client::{BridgeApiClient, BridgeStatus, PairingRequest};
+use crate::events::{self, PAIRING_REQUESTED_EVENT};
+use crate::{config_drift, heartbeat, pairing, tray};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+// Three missed polls
+const STALE_AFTER: Duration = Duration::from_secs(6);
+
+pub(crate) const STATUS_CHANGED_EVENT: &str = "bridge://status-changed";
+pub(crate) const PAIRINGS_CHANGED_EVENT: &str = "bridge://pairings-changed";
+
+#[derive(Default)]
+struct Snapshot {
+    status: Option<BridgeStatus>,
+    pairings: Vec<PairingRequest>,
+    fetched_at: Option<DateTime<Local>>,
+    // Until when the status counts as current; None once marked stale
+    fresh_until: Option<Instant>,
+}
+
+impl Snapshot {
+    fn cached(&self) -> CachedStatus {
+        CachedStatus {
+            status: self.status.clone(),
+            fetched_at: self.fetched_at.map(|t| t.to_rfc3339()),
+            is_stale: self.fresh_until.is_none_or(|t| Instant::now() > t),
+        }
+    }
+}
+
+// What `get_status` and the status-changed event hand the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedStatus {
+    // None while the bridge isn't answering
+    status: Option<BridgeStatus>,
+    // When the poll this came from finished; None before the first one
+    fetched_at: Option<String>,
+    is_stale: bool,
+}
+
+// Latest poll results, shared with the commands as managed state
+#[derive(Default)]
+pub(crate) struct StatusCache {
+    snapshot: Mutex<Snapshot>,
+    refresh: Notify,
+}
+
+impl StatusCache {
+    pub(crate) fn status(&self) -> Option<BridgeStatus> {
+        self.snapshot.lock().ok().and_then(|s| s.status.clone())
+    }
+
+    pub(crate) fn cached(&self) -> CachedStatus {
+        self.snapshot.lock().map(|s| s.cached()).unwrap_or(CachedStatus {
+            status: None,
+            fetched_at: None,
+            is_stale: true,
+        })
+    }
+
+    pub(crate) fn pairings(&self) -> Vec<PairingRequest> {
+        self.snapshot.lock().map(|s| s.pairings.clone()).unwrap_or_default()
+    }
+
+    // Drop a pairing we just acted on so readers don't see it until the next poll
+    pub(crate) fn remove_pairing(&self, code: &str) {
+        if let Ok(mut snapshot) = self.snapshot.lock() {
+            snapshot.pairings.retain(|p| p.code != code);
+        }
+    }
+
+    // Wake the poll loop early, e.g. after start/stop or a pairing decision
+    pub(crate) fn request_refresh(&self) {
+        self.refresh.notify_one();
+    }
+
+    // Forget channel connections we can no longer vouch for, e.g. after the
+    // machine slept; the next poll fills in the real state
+    pub(crate) fn mark_stale(&self) -> Option<CachedStatus> {
+        let mut snapshot = self.snapshot.lock().ok()?;
+        snapshot.fresh_until = None;
+        let status = snapshot.status.as_mut()?;
+        for channel in status.channels.iter_mut() {
+            channel.connected = false;
+        }
+        Some(snapshot.cached())
+    }
+
+    // Store a fresh poll result, returning which parts changed
+    fn update(&self, status: Option<BridgeStatus>, pairings: Vec<PairingRequest>) -> (bool, bool) {
+        let Ok(mut snapshot) = self.snapshot.lock() else {
+            return (false, false);
+        };
+        let status_changed = snapshot.status != status;
+        let pairings_changed = snapshot.pairings != pairings;
+        snapshot.status = status;
+        snapshot.pairings = pairings;
+        snapshot.fetched_at = Some(Local::now());
+        snapshot.fresh_until = Some(Instant::now() + STALE_AFTER);
+        (status_changed, pairings_changed)
+    }
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    let api = app.state::<BridgeApiClient>().inner().clone();
+
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // Pairings already pending at launch aren't news
+    let mut first_poll = true;
+
+    loop {
+        let cache = app.state::<StatusCache>();
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = cache.refresh.notified() => interval.reset(),
+        }
+
+        let (status, pairings) = tokio::join!(api.status(), api.pairings());
+        let (status, pairings) = (status.ok(), pairings.unwrap_or_default());
+        if !first_poll {
+            let known = cache.pairings();
+            let new: Vec<PairingRequest> = pairings
+                .iter()
+                .filter(|p| !known.iter().any(|k| k.code == p.code))
+                .cloned()
+                .collect();
+            if !events::is_streaming() {
+                for pairing in &new {
+                    let _ = app.emit(PAIRING_REQUESTED_EVENT, pairing);
+                }
+            }
+            pairing::notify_new(&app, &new);
+        }
+        first_poll = false;
+        let (status_changed, pairings_changed) = cache.update(status.clone(), pairings.clone());
+        heartbeat::tick(&app, status.as_ref().is_some_and(|s| s.running));
+        config_drift::check(&app, status.as_ref());
+
+        if status_changed {
+            let _ = app.emit(STATUS_CHANGED_EVENT, cache.cached());
+        }
+        if pairings_changed {
+            let _ = app.emit(PAIRINGS_CHANGED_EVENT, pairings);
+            let _ = tray::refresh_tray_menu(&app);
+        }
+        tray::update_tray_status(&app);
+    }
+}
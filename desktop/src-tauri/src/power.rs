@@ -0,0 +1,100 @@
+// Sleep/wake and network change recovery.
+//
+// On macOS and Linux the monotonic clock stops while the machine sleeps but
+// the wall clock doesn't, so a gap between the two across one tick means we
+// just woke up. Network changes show up as a different local address for outbound traffic. Either
+// way the cached status is stale: the child process is re-checked, channel
+// connections are marked unknown, the poller refreshes at once, and the
+// frontend is told to reconnect its streams.
+
+use serde::Serialize;
+use std::net::{IpAddr, UdpSocket};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::poller::{StatusCache, STATUS_CHANGED_EVENT};
+use crate::service::AppState;
+use crate::{i18n, supervisor};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+// Wall clock running this far ahead of the monotonic clock means we slept
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(10);
+
+pub(crate) const RESUMED_EVENT: &str = "system://resumed";
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ResumeReason {
+    Wake,
+    NetworkChange,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResumedPayload {
+    reason: ResumeReason,
+    // Roughly how long the machine was asleep
+    slept_secs: u64,
+}
+
+// Address the OS would use to reach the internet; connecting a UDP socket
+// only picks a route, nothing is sent
+fn local_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("1.1.1.1:80").ok()?;
+    socket.local_addr().ok().map(|a| a.ip())
+}
+
+fn recover(app: &AppHandle, payload: ResumedPayload) {
+    // The bridge may have died while we were away
+    if supervisor::check_exit(app) {
+        if let Ok(mut service) = app.state::<AppState>().lock() {
+            service.logs.warn(i18n::t("service.exited_during_sleep"));
+        }
+    }
+
+    let cache = app.state::<StatusCache>();
+    if let Some(status) = cache.mark_stale() {
+        let _ = app.emit(STATUS_CHANGED_EVENT, status);
+    }
+    cache.request_refresh();
+
+    let _ = app.emit(RESUMED_EVENT, payload);
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    let mut last_instant = Instant::now();
+    let mut last_wall = SystemTime::now();
+    let mut last_address = local_address();
+
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let monotonic = last_instant.elapsed();
+        let wall = last_wall.elapsed().unwrap_or_default();
+        last_instant = Instant::now();
+        last_wall = SystemTime::now();
+
+        let address = tokio::task::spawn_blocking(local_address).await.ok().flatten();
+        let network_changed = address != last_address;
+        last_address = address;
+
+        if wall > monotonic + SLEEP_THRESHOLD {
+            recover(
+                &app,
+                ResumedPayload {
+                    reason: ResumeReason::Wake,
+                    slept_secs: (wall - monotonic).as_secs(),
+                },
+            );
+        } else if network_changed {
+            recover(
+                &app,
+                ResumedPayload {
+                    reason: ResumeReason::NetworkChange,
+                    slept_secs: 0,
+                },
+            );
+        }
+    }
+}
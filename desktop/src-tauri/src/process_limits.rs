@@ -0,0 +1,197 @@
+// Priority and memory limits for the bridge process.
+//
+// Agent sessions run as children of the bridge, and so do the commands they
+// run, so a busy or runaway session can eat the machine. The bridge can be
+// started at a lower priority (a higher nice level, plus SCHED_IDLE on Linux
+// for "background"; a lower priority class on Windows), which every process
+// it spawns inherits. A memory limit caps each of those processes: through
+// RLIMIT_DATA on Unix (the memory a process has written to, enforced by Linux
+// but not macOS) and a job object on Windows. Not RLIMIT_AS: node and the
+// claude CLI reserve gigabytes of address space up front and fail to start
+// under it. Node is also told to keep its heap below the limit so it collects
+// garbage instead of crashing into it. Changes apply at the next start.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tokio::process::{Child, Command};
+
+use crate::config::get_desktop_data_dir;
+
+// The claude CLI doesn't start with less
+const MIN_MEMORY_LIMIT_MB: u64 = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    Low,
+    // Only runs when nothing else wants the CPU
+    Background,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessLimits {
+    #[serde(default)]
+    priority: ProcessPriority,
+    // Per process, in megabytes; none when unset
+    #[serde(default)]
+    memory_limit_mb: Option<u64>,
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("process_limits.json")
+}
+
+fn load_settings() -> ProcessLimits {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// NODE_OPTIONS with the heap capped at three quarters of the memory limit,
+// keeping whatever the user already set
+fn node_options(limit_mb: u64) -> String {
+    let heap = format!("--max-old-space-size={}", limit_mb * 3 / 4);
+    match std::env::var("NODE_OPTIONS") {
+        Ok(existing) if !existing.trim().is_empty() => format!("{} {}", existing, heap),
+        _ => heap,
+    }
+}
+
+#[cfg(unix)]
+fn apply_os(command: &mut Command, limits: &ProcessLimits) {
+    let nice = match limits.priority {
+        ProcessPriority::Normal => 0,
+        ProcessPriority::Low => 10,
+        ProcessPriority::Background => 19,
+    };
+    let background = limits.priority == ProcessPriority::Background;
+    let memory_bytes = limits.memory_limit_mb.map(|mb| mb * 1024 * 1024);
+    if nice == 0 && memory_bytes.is_none() {
+        return;
+    }
+
+    // Runs in the child between fork and exec, so only plain syscalls here
+    unsafe {
+        command.pre_exec(move || {
+            if nice != 0 {
+                libc::setpriority(libc::PRIO_PROCESS, 0, nice);
+            }
+            #[cfg(target_os = "linux")]
+            if background {
+                let param = libc::sched_param { sched_priority: 0 };
+                libc::sched_setscheduler(0, libc::SCHED_IDLE, &param);
+            }
+            #[cfg(not(target_os = "linux"))]
+            let _ = background;
+            if let Some(bytes) = memory_bytes {
+                let limit = libc::rlimit {
+                    rlim_cur: bytes as libc::rlim_t,
+                    rlim_max: bytes as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+// Keeps console programs from opening a window, the bridge (and the cmd.exe
+// running ccb.cmd) included
+#[cfg(windows)]
+pub(crate) const CREATE_NO_WINDOW: u32 = windows_sys::Win32::System::Threading::CREATE_NO_WINDOW;
+
+#[cfg(windows)]
+fn apply_os(command: &mut Command, limits: &ProcessLimits) {
+    use windows_sys::Win32::System::Threading::{BELOW_NORMAL_PRIORITY_CLASS, IDLE_PRIORITY_CLASS};
+
+    let priority = match limits.priority {
+        ProcessPriority::Normal => 0,
+        ProcessPriority::Low => BELOW_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::Background => IDLE_PRIORITY_CLASS,
+    };
+    command.creation_flags(CREATE_NO_WINDOW | priority);
+}
+
+// Set up a bridge command with the saved limits
+pub(crate) fn apply(command: &mut Command) -> &mut Command {
+    let mut limits = load_settings();
+    // Saved before the minimum went up
+    limits.memory_limit_mb = limits.memory_limit_mb.map(|mb| mb.max(MIN_MEMORY_LIMIT_MB));
+    if let Some(mb) = limits.memory_limit_mb {
+        command.env("NODE_OPTIONS", node_options(mb));
+    }
+    apply_os(command, &limits);
+    command
+}
+
+// Windows applies memory limits to a running process through a job object,
+// which the processes it starts from then on belong to as well
+#[cfg(windows)]
+pub(crate) fn contain(child: &Child) -> Result<(), String> {
+    use std::ffi::c_void;
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+    };
+
+    let Some(mb) = load_settings().memory_limit_mb else {
+        return Ok(());
+    };
+    let Some(process) = child.raw_handle() else {
+        return Ok(());
+    };
+    // The job handle is left open for the app's lifetime; closing it would
+    // drop the limit
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            return Err(format!("Failed to create job object: {}", std::io::Error::last_os_error()));
+        }
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+        info.ProcessMemoryLimit = (mb * 1024 * 1024) as usize;
+        let set = SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const JOBOBJECT_EXTENDED_LIMIT_INFORMATION as *const c_void,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+        if set == 0 || AssignProcessToJobObject(job, process as HANDLE) == 0 {
+            return Err(format!("Failed to limit bridge memory: {}", std::io::Error::last_os_error()));
+        }
+    }
+    Ok(())
+}
+
+// RLIMIT_DATA was set before exec
+#[cfg(unix)]
+pub(crate) fn contain(_child: &Child) -> Result<(), String> {
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn get_process_limits() -> ProcessLimits {
+    load_settings()
+}
+
+#[tauri::command]
+pub(crate) fn set_process_limits(limits: ProcessLimits) -> Result<bool, String> {
+    if limits.memory_limit_mb.is_some_and(|mb| mb < MIN_MEMORY_LIMIT_MB) {
+        return Err(format!("Memory limit must be at least {} MB", MIN_MEMORY_LIMIT_MB));
+    }
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&limits).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save process limits: {}", e))?;
+    Ok(true)
+}
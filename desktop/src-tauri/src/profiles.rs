@@ -0,0 +1,259 @@
+// Named config profiles.
+//
+// Each profile (e.g. work, personal, demo) is a config file under
+// ~/.ccb/profiles/. The bridge only reads ~/.ccb/config.json, so that file
+// holds the active profile's config: switching saves it back to its profile,
+// copies the chosen one in, and restarts the bridge if the app was running
+// it. Keychain references are scoped to the profile in its file, so two
+// profiles with a bot of the same id don't overwrite each other's tokens.
+// Before any profile is saved, config.json itself is the "default" profile.
+
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+
+use crate::app_settings::{self, DEFAULT_INSTANCE};
+use crate::config::{get_ccb_dir, get_config_path, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::poller::StatusCache;
+use crate::service::{self, AppState, StartLock};
+use crate::{bridge_endpoints, keychain};
+
+pub(crate) const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    name: String,
+    active: bool,
+    agents: usize,
+    bots: usize,
+    // Last saved, RFC 3339; None for the active profile before its first save
+    modified: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSwitch {
+    profile: String,
+    // Whether the bridge was restarted with the new config
+    restarted: bool,
+    // Why the bridge didn't come back up, if it didn't
+    start_error: Option<String>,
+}
+
+fn get_profiles_dir() -> PathBuf {
+    get_ccb_dir().join("profiles")
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    get_profiles_dir().join(format!("{}.json", name))
+}
+
+pub(crate) fn active_profile() -> String {
+    app_settings::current().active_profile.unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+fn validate_name(name: &str) -> CommandResult<()> {
+    let valid_chars = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if name.is_empty() || name.len() > 40 || !valid_chars {
+        return Err(Error::Invalid(format!(
+            "'{}' is not a valid profile name; use up to 40 letters, digits, - and _",
+            name
+        )));
+    }
+    Ok(())
+}
+
+// PROFILE_WORK_ for "work"; keychain names allow A-Z, 0-9 and _ only
+fn secret_prefix(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("PROFILE_{}_", name)
+}
+
+fn read_json(path: &PathBuf) -> CommandResult<serde_json::Value> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| Error::Config(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+fn write_profile(name: &str, config: &serde_json::Value) -> CommandResult<()> {
+    let dir = get_profiles_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+    fs::write(profile_path(name), content).map_err(|e| format!("Failed to save profile '{}': {}", name, e).into())
+}
+
+// Keychain references moved from one profile's names to another's; "" for
+// the plain names config.json uses
+fn rescope(config: &mut serde_json::Value, from: &str, to: &str) -> CommandResult<()> {
+    let from_profile = !from.is_empty();
+    keychain::rename_references(config, &|name: &str| {
+        let rest = if from_profile {
+            name.strip_prefix(from)?
+        } else if name.starts_with("PROFILE_") {
+            return None;
+        } else {
+            name
+        };
+        Some(format!("{}{}", to, rest))
+    })
+    .map_err(Error::Config)
+}
+
+// The active profile's config as it would be saved to its file
+fn snapshot_active(name: &str) -> CommandResult<Option<serde_json::Value>> {
+    let path = get_config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut config = read_json(&path)?;
+    rescope(&mut config, "", &secret_prefix(name))?;
+    Ok(Some(config))
+}
+
+fn count(config: &serde_json::Value) -> (usize, usize) {
+    let agents = config["agents"]["list"].as_array().map_or(0, |list| list.len());
+    let bots = ["telegram", "discord"]
+        .iter()
+        .map(|channel| {
+            let section = &config["channels"][channel];
+            match section["bots"].as_array() {
+                Some(bots) => bots.len(),
+                None if section.get("botToken").or(section.get("token")).is_some() => 1,
+                None => 0,
+            }
+        })
+        .sum();
+    (agents, bots)
+}
+
+fn modified(path: &PathBuf) -> Option<String> {
+    let time = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(chrono::DateTime::<chrono::Local>::from(time).to_rfc3339())
+}
+
+fn info(name: &str, active: &str) -> ProfileInfo {
+    // The active profile's live config is config.json
+    let path = if name == active { get_config_path() } else { profile_path(name) };
+    let (agents, bots) = read_json(&path).map(|c| count(&c)).unwrap_or((0, 0));
+    ProfileInfo {
+        name: name.to_string(),
+        active: name == active,
+        agents,
+        bots,
+        modified: modified(&path),
+    }
+}
+
+// Sorted by name
+#[tauri::command]
+pub(crate) fn list_profiles() -> CommandResult<Vec<ProfileInfo>> {
+    let active = active_profile();
+    let mut names: Vec<String> = fs::read_dir(get_profiles_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(".json").map(str::to_string))
+        .filter(|name| validate_name(name).is_ok())
+        .collect();
+    if !names.contains(&active) {
+        names.push(active.clone());
+    }
+    names.sort();
+    Ok(names.iter().map(|name| info(name, &active)).collect())
+}
+
+// A copy of `source` named `name`; the active profile is copied as it is now
+#[tauri::command]
+pub(crate) fn clone_profile(source: String, name: String) -> CommandResult<ProfileInfo> {
+    validate_name(&name)?;
+    let active = active_profile();
+    if name == active || profile_path(&name).exists() {
+        return Err(Error::Invalid(format!("A profile named '{}' already exists", name)));
+    }
+    let mut config = if source == active {
+        snapshot_active(&source)?.ok_or_else(|| Error::Config(crate::i18n::t("config.not_found")))?
+    } else {
+        validate_name(&source)?;
+        let path = profile_path(&source);
+        if !path.exists() {
+            return Err(Error::Invalid(format!("No profile named '{}'", source)));
+        }
+        read_json(&path)?
+    };
+    rescope(&mut config, &secret_prefix(&source), &secret_prefix(&name))?;
+    write_profile(&name, &config)?;
+    Ok(info(&name, &active))
+}
+
+// Save the active profile, then put `name`'s config in config.json. The order
+// matters: moving `name`'s secrets to the plain keychain names overwrites the
+// ones the active profile's references point at.
+fn swap_in(store: &ConfigStore, active: &str, name: &str, config: &mut serde_json::Value) -> CommandResult<()> {
+    if let Some(current) = snapshot_active(active)? {
+        write_profile(active, &current)?;
+    }
+    rescope(config, &secret_prefix(name), "")?;
+    if let Some(dir) = get_config_path().parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    store.write(config)?;
+    let mut settings = app_settings::current();
+    settings.active_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+    app_settings::save(settings).map_err(Error::Invalid)
+}
+
+// Make `name` the config the bridge runs with, restarting it if the app started it
+#[tauri::command]
+pub(crate) async fn switch_profile(
+    app: AppHandle,
+    store: State<'_, ConfigStore>,
+    state: State<'_, AppState>,
+    cache: State<'_, StatusCache>,
+    start_lock: State<'_, StartLock>,
+    name: String,
+) -> CommandResult<ProfileSwitch> {
+    validate_name(&name)?;
+    let active = active_profile();
+    let mut switch = ProfileSwitch {
+        profile: name.clone(),
+        restarted: false,
+        start_error: None,
+    };
+    if name == active {
+        return Ok(switch);
+    }
+    let path = profile_path(&name);
+    if !path.exists() {
+        return Err(Error::Invalid(format!("No profile named '{}'", name)));
+    }
+    let mut config = read_json(&path)?;
+
+    // The keychain is left alone until the bridge is down
+    let was_running = state.lock().map(|s| s.process.is_some()).unwrap_or(false);
+    if was_running {
+        let api = bridge_endpoints::api_for(DEFAULT_INSTANCE)?;
+        service::stop(&api, &state, &cache).await?;
+    }
+
+    if let Err(e) = swap_in(&store, &active, &name, &mut config) {
+        // Bring the bridge back on the config it had
+        if was_running {
+            let _ = service::launch(&app, &state, &cache, &start_lock).await;
+        }
+        return Err(e);
+    }
+
+    if was_running {
+        match service::launch(&app, &state, &cache, &start_lock).await {
+            Ok(_) => switch.restarted = true,
+            Err(failure) => switch.start_error = Some(failure.message),
+        }
+    }
+    cache.request_refresh();
+    Ok(switch)
+}
@@ -0,0 +1,121 @@
+// Reusable system prompt presets.
+//
+// Presets are system prompt snippets with `{{variable}}` placeholders
+// ("You are working on {{project_name}}. Keep a {{tone}} tone.") kept in the
+// desktop's data dir. The agent editor renders one with concrete values and
+// uses the result as the agent's `systemPrompt`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::get_desktop_data_dir;
+use crate::templates::{render_template, template_variables};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptPreset {
+    id: String,
+    name: String,
+    text: String,
+    // Placeholder names found in `text`, for the editor to ask values for
+    #[serde(default)]
+    variables: Vec<String>,
+}
+
+fn get_presets_path() -> PathBuf {
+    get_desktop_data_dir().join("prompt-presets.json")
+}
+
+fn load_presets() -> Result<Vec<PromptPreset>, String> {
+    let path = get_presets_path();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read prompt presets: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse prompt presets: {}", e))
+}
+
+fn save_presets(presets: &[PromptPreset]) -> Result<(), String> {
+    let path = get_presets_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(presets).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write prompt presets: {}", e))
+}
+
+fn validate(name: &str, text: &str) -> Result<(), String> {
+    if name.trim().is_empty() || text.trim().is_empty() {
+        return Err("Preset name and text are required".to_string());
+    }
+    if text.matches("{{").count() != text.matches("}}").count() {
+        return Err("Preset has an unclosed '{{' placeholder".to_string());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) fn list_prompt_presets() -> Result<Vec<PromptPreset>, String> {
+    load_presets()
+}
+
+#[tauri::command]
+pub(crate) fn add_prompt_preset(name: String, text: String) -> Result<PromptPreset, String> {
+    validate(&name, &text)?;
+
+    let mut presets = load_presets()?;
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let preset = PromptPreset {
+        id: format!("preset-{}", millis),
+        name,
+        variables: template_variables(&text),
+        text,
+    };
+    presets.push(preset.clone());
+    save_presets(&presets)?;
+
+    Ok(preset)
+}
+
+#[tauri::command]
+pub(crate) fn update_prompt_preset(id: String, name: String, text: String) -> Result<PromptPreset, String> {
+    validate(&name, &text)?;
+
+    let mut presets = load_presets()?;
+    let preset = presets
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or(format!("Prompt preset '{}' not found", id))?;
+    preset.name = name;
+    preset.variables = template_variables(&text);
+    preset.text = text;
+    let updated = preset.clone();
+    save_presets(&presets)?;
+
+    Ok(updated)
+}
+
+#[tauri::command]
+pub(crate) fn remove_prompt_preset(id: String) -> Result<bool, String> {
+    let mut presets = load_presets()?;
+    let original_len = presets.len();
+    presets.retain(|p| p.id != id);
+    if presets.len() == original_len {
+        return Err(format!("Prompt preset '{}' not found", id));
+    }
+    save_presets(&presets)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn render_prompt(preset_id: String, vars: Option<HashMap<String, String>>) -> Result<String, String> {
+    let preset = load_presets()?
+        .into_iter()
+        .find(|p| p.id == preset_id)
+        .ok_or(format!("Prompt preset '{}' not found", preset_id))?;
+
+    render_template(&preset.text, &vars.unwrap_or_default())
+}
@@ -0,0 +1,133 @@
+// Per-user and per-chat rate limits.
+//
+// Limits live in the `rateLimits` section of config.json: a default applied to
+// every chat plus overrides keyed by chat key ("telegram:12345") or allowlist
+// key ("discord:group:987"). An `identity:<id>` override is stored as one
+// override per chat key of the identity's linked accounts, since the bridge
+// only knows chats. The bridge reports each session's live throttle state
+// alongside the session list.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::identities;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    messages_per_minute: u32,
+    // Prompts from the same chat allowed to run at once
+    max_concurrent: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            messages_per_minute: 10,
+            max_concurrent: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    default: RateLimit,
+    #[serde(default)]
+    overrides: HashMap<String, RateLimit>,
+}
+
+// As reported by the bridge for each session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThrottleState {
+    messages_last_minute: u32,
+    in_flight: u32,
+    throttled: bool,
+    throttled_until: Option<String>,
+}
+
+fn read_limits(config: &serde_json::Value) -> RateLimitConfig {
+    config
+        .get("rateLimits")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn validate(limit: &RateLimit) -> Result<(), String> {
+    if limit.messages_per_minute == 0 || limit.messages_per_minute > 600 {
+        return Err("Messages per minute must be between 1 and 600".to_string());
+    }
+    if limit.max_concurrent == 0 || limit.max_concurrent > 16 {
+        return Err("Concurrent requests must be between 1 and 16".to_string());
+    }
+    Ok(())
+}
+
+fn update_limits(
+    store: &ConfigStore,
+    update: impl FnOnce(&serde_json::Value, &mut RateLimitConfig) -> Result<(), String>,
+) -> Result<RateLimitConfig, String> {
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let mut limits = read_limits(&config);
+    update(&config, &mut limits)?;
+    config["rateLimits"] = serde_json::to_value(&limits).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+    Ok(limits)
+}
+
+#[tauri::command]
+pub(crate) fn get_rate_limits(store: State<'_, ConfigStore>) -> Result<RateLimitConfig, String> {
+    Ok(store.read()?.map(|c| read_limits(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn set_default_rate_limit(
+    store: State<'_, ConfigStore>,
+    enabled: bool,
+    limit: RateLimit,
+) -> Result<RateLimitConfig, String> {
+    validate(&limit)?;
+    update_limits(&store, |_, limits| {
+        limits.enabled = enabled;
+        limits.default = limit;
+        Ok(())
+    })
+}
+
+// Pass no limit to remove the override and fall back to the default
+#[tauri::command]
+pub(crate) fn set_rate_limit_override(
+    store: State<'_, ConfigStore>,
+    key: String,
+    limit: Option<RateLimit>,
+) -> Result<RateLimitConfig, String> {
+    // A chat key, or identity:<id> for every account linked to a person
+    if !key.contains(':') {
+        return Err(format!("Invalid chat key '{}', expected e.g. 'telegram:12345'", key));
+    }
+    if let Some(ref limit) = limit {
+        validate(limit)?;
+    }
+    update_limits(&store, |config, limits| {
+        let keys = match key.strip_prefix("identity:") {
+            Some(id) => identities::identity_chat_keys(config, id).ok_or(format!("Identity '{}' not found", id))?,
+            None => vec![key],
+        };
+        if keys.is_empty() {
+            return Err("This identity has no linked accounts yet".to_string());
+        }
+        for key in keys {
+            match limit {
+                Some(limit) => limits.overrides.insert(key, limit),
+                None => limits.overrides.remove(&key),
+            };
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,210 @@
+// Transcript redaction.
+//
+// Transcripts shared for debugging carry whatever users typed into the chat.
+// Before one is exported, email addresses, phone numbers and any patterns the
+// operator adds are masked, as are API keys and bot tokens, which are always
+// masked. The preview lists each match with the message it was found in, so
+// what was caught (and what wasn't) can be checked before the file is shared.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::api_client::BridgeApiClient;
+use crate::config::{expand_home, get_desktop_data_dir};
+use crate::secrets;
+use crate::sessions;
+
+const EMAIL_PATTERN: &str = r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+// International numbers with a leading +, or the (555) 123-4567 shape
+const PHONE_PATTERN: &str = r"\+\d[\d\s().-]{7,}\d|\(?\b\d{3}\)?[\s.-]\d{3}[\s.-]\d{4}\b";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionSettings {
+    emails: bool,
+    phone_numbers: bool,
+    // Regular expressions, e.g. customer ids or internal host names
+    #[serde(default)]
+    custom_patterns: Vec<String>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            emails: true,
+            phone_numbers: true,
+            custom_patterns: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactedMatch {
+    message_index: usize,
+    kind: String,
+    // What was masked, shown only in the local preview
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionPreview {
+    messages: Vec<serde_json::Value>,
+    matches: Vec<RedactedMatch>,
+}
+
+struct Rule {
+    kind: String,
+    placeholder: &'static str,
+    regex: Regex,
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("redaction.json")
+}
+
+fn load_settings() -> RedactionSettings {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn rules(settings: &RedactionSettings) -> Result<Vec<Rule>, String> {
+    let mut rules = Vec::new();
+    if settings.emails {
+        rules.push(Rule {
+            kind: "Email address".to_string(),
+            placeholder: "[EMAIL]",
+            regex: Regex::new(EMAIL_PATTERN).map_err(|e| e.to_string())?,
+        });
+    }
+    if settings.phone_numbers {
+        rules.push(Rule {
+            kind: "Phone number".to_string(),
+            placeholder: "[PHONE]",
+            regex: Regex::new(PHONE_PATTERN).map_err(|e| e.to_string())?,
+        });
+    }
+    for pattern in &settings.custom_patterns {
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        // Would put a placeholder between every character
+        if regex.is_match("") {
+            return Err(format!("Pattern '{}' matches empty text", pattern));
+        }
+        rules.push(Rule {
+            kind: format!("Pattern {}", pattern),
+            placeholder: secrets::REDACTED,
+            regex,
+        });
+    }
+    Ok(rules)
+}
+
+fn redact_string(text: &str, rules: &[Rule], message_index: usize, matches: &mut Vec<RedactedMatch>) -> String {
+    let mut text = text.to_string();
+    let tokens: Vec<(String, &str)> = secrets::find_tokens(&text).into_iter().map(|(t, kind)| (t.to_string(), kind)).collect();
+    for (token, kind) in tokens {
+        text = text.replace(&token, secrets::REDACTED);
+        matches.push(RedactedMatch {
+            message_index,
+            kind: kind.to_string(),
+            text: token,
+        });
+    }
+    for rule in rules {
+        matches.extend(rule.regex.find_iter(&text).map(|m| RedactedMatch {
+            message_index,
+            kind: rule.kind.clone(),
+            text: m.as_str().to_string(),
+        }));
+        text = rule.regex.replace_all(&text, rule.placeholder).into_owned();
+    }
+    text
+}
+
+fn redact_value(value: &mut serde_json::Value, rules: &[Rule], message_index: usize, matches: &mut Vec<RedactedMatch>) {
+    match value {
+        serde_json::Value::String(text) => *text = redact_string(text, rules, message_index, matches),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_value(item, rules, message_index, matches);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for child in map.values_mut() {
+                redact_value(child, rules, message_index, matches);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_messages(messages: &mut [serde_json::Value], settings: &RedactionSettings) -> Result<Vec<RedactedMatch>, String> {
+    let rules = rules(settings)?;
+    let mut matches = Vec::new();
+    for (i, message) in messages.iter_mut().enumerate() {
+        redact_value(message, &rules, i, &mut matches);
+    }
+    Ok(matches)
+}
+
+#[tauri::command]
+pub(crate) fn get_redaction_settings() -> RedactionSettings {
+    load_settings()
+}
+
+#[tauri::command]
+pub(crate) fn set_redaction_settings(settings: RedactionSettings) -> Result<bool, String> {
+    rules(&settings)?;
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save redaction settings: {}", e))?;
+    Ok(true)
+}
+
+// The transcript as it would be exported; `settings` tries out unsaved changes
+#[tauri::command]
+pub(crate) async fn preview_transcript_redaction(
+    api: State<'_, BridgeApiClient>,
+    session_id: String,
+    settings: Option<RedactionSettings>,
+) -> Result<RedactionPreview, String> {
+    let (_, mut messages) = sessions::transcript(&api, &session_id).await?;
+    let matches = redact_messages(&mut messages, &settings.unwrap_or_else(load_settings))?;
+    Ok(RedactionPreview { messages, matches })
+}
+
+// Write the redacted transcript as JSON; returns the path written
+#[tauri::command]
+pub(crate) async fn export_transcript(
+    api: State<'_, BridgeApiClient>,
+    session_id: String,
+    path: String,
+) -> Result<String, String> {
+    let (summary, mut messages) = sessions::transcript(&api, &session_id).await?;
+    let matches = redact_messages(&mut messages, &load_settings())?;
+    let export = serde_json::json!({
+        "sessionId": summary.id,
+        "agentId": summary.agent_id,
+        "channel": summary.chat_key.split(':').next().unwrap_or_default(),
+        "exportedAt": chrono::Local::now().to_rfc3339(),
+        "redactions": matches.len(),
+        "messages": messages,
+    });
+
+    let path = expand_home(&path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create export dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write transcript: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,172 @@
+// Node and CLI environment discovery.
+//
+// Surfaces what start_service would find on its own: every Node install the
+// extended PATH can see, which ccb binary would be launched, npm's global
+// prefix, and whether the Claude Code CLI is installed and signed in.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::node_runtime::runtime_paths;
+use crate::service::get_extended_path;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInstall {
+    version: String,
+    path: String,
+    // "nvm", "volta", "fnm" or "system"
+    source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCliInfo {
+    installed: bool,
+    path: Option<String>,
+    version: Option<String>,
+    logged_in: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeInfo {
+    node_installs: Vec<NodeInstall>,
+    // What start_service would launch: a ccb binary, or npx as the fallback
+    ccb_path: Option<String>,
+    ccb_via_npx: bool,
+    npm_prefix: Option<String>,
+    claude: ClaudeCliInfo,
+    path: String,
+}
+
+fn executable_names(name: &str) -> Vec<String> {
+    if cfg!(windows) {
+        vec![format!("{}.exe", name), format!("{}.cmd", name)]
+    } else {
+        vec![name.to_string()]
+    }
+}
+
+// First match for `name` on the extended PATH, the same lookup spawning does
+pub(crate) fn find_executable(name: &str) -> Option<PathBuf> {
+    let path = get_extended_path();
+    std::env::split_paths(&path)
+        .flat_map(|dir| executable_names(name).into_iter().map(move |n| dir.join(n)))
+        .find(|candidate| candidate.is_file())
+}
+
+pub(crate) async fn probe_output(program: &Path, args: &[&str]) -> Option<String> {
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        Command::new(program)
+            .args(args)
+            .env("PATH", get_extended_path())
+            .stdin(Stdio::null())
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn node_source(dir: &str) -> &'static str {
+    let dir = dir.to_lowercase();
+    if dir.contains("nvm") {
+        "nvm"
+    } else if dir.contains("volta") {
+        "volta"
+    } else if dir.contains("fnm") {
+        "fnm"
+    } else {
+        "system"
+    }
+}
+
+async fn detect_node_installs() -> Vec<NodeInstall> {
+    let system_path = std::env::var("PATH").unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = runtime_paths().into_iter().map(PathBuf::from).collect();
+    dirs.extend(std::env::split_paths(&system_path));
+
+    let mut seen = Vec::new();
+    let mut installs = Vec::new();
+    for dir in dirs {
+        let Some(node) = executable_names("node").into_iter().map(|n| dir.join(n)).find(|p| p.is_file()) else {
+            continue;
+        };
+        // Shims and symlinks often point several PATH entries at one install
+        let resolved = std::fs::canonicalize(&node).unwrap_or_else(|_| node.clone());
+        if seen.contains(&resolved) {
+            continue;
+        }
+        seen.push(resolved);
+
+        if let Some(version) = probe_output(&node, &["--version"]).await {
+            let path = node.to_string_lossy().to_string();
+            installs.push(NodeInstall {
+                version,
+                source: node_source(&path).to_string(),
+                path,
+            });
+        }
+    }
+    installs
+}
+
+// Claude Code records the signed-in account in ~/.claude.json; API key users
+// authenticate through the environment instead
+pub(crate) fn claude_logged_in() -> bool {
+    if std::env::var_os("ANTHROPIC_API_KEY").is_some() {
+        return true;
+    }
+    let Some(home) = dirs::home_dir() else {
+        return false;
+    };
+    let has_account = std::fs::read_to_string(home.join(".claude.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .is_some_and(|v| v.get("oauthAccount").is_some_and(|a| !a.is_null()));
+    has_account || home.join(".claude").join(".credentials.json").is_file()
+}
+
+#[tauri::command]
+pub(crate) async fn get_runtime_info() -> Result<RuntimeInfo, String> {
+    let node_installs = detect_node_installs().await;
+
+    let ccb_path = find_executable("ccb");
+    let ccb_via_npx = ccb_path.is_none() && find_executable("npx").is_some();
+
+    let npm_prefix = match find_executable("npm") {
+        Some(npm) => probe_output(&npm, &["prefix", "-g"]).await,
+        None => None,
+    };
+
+    let claude_path = find_executable("claude");
+    let claude_version = match claude_path {
+        Some(ref claude) => probe_output(claude, &["--version"]).await,
+        None => None,
+    };
+
+    Ok(RuntimeInfo {
+        node_installs,
+        ccb_path: ccb_path.map(|p| p.to_string_lossy().to_string()),
+        ccb_via_npx,
+        npm_prefix,
+        claude: ClaudeCliInfo {
+            installed: claude_path.is_some(),
+            path: claude_path.map(|p| p.to_string_lossy().to_string()),
+            version: claude_version,
+            logged_in: claude_logged_in(),
+        },
+        path: get_extended_path(),
+    })
+}
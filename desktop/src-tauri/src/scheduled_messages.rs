@@ -0,0 +1,216 @@
+// Scheduled and delayed outbound messages.
+//
+// Messages are queued in the desktop's data dir with either a one-off send time
+// or a cron expression (daily standup prompts, reminders to paired users) and
+// dispatched through the Control API by a background task. One-off messages are
+// dropped once delivered; failed sends are retried a few times before giving up.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::config::get_desktop_data_dir;
+use crate::scheduler;
+
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_ATTEMPTS: u32 = 5;
+
+// Guards read-modify-write cycles on the queue file
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MessageSchedule {
+    At { send_at: String },
+    Cron { expression: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledMessage {
+    id: String,
+    chat_key: String,
+    text: String,
+    schedule: MessageSchedule,
+    next_run: Option<String>,
+    #[serde(default)]
+    attempts: u32,
+    last_sent: Option<String>,
+    last_error: Option<String>,
+}
+
+fn get_queue_path() -> PathBuf {
+    get_desktop_data_dir().join("scheduled-messages.json")
+}
+
+fn load_queue() -> Result<Vec<ScheduledMessage>, String> {
+    let path = get_queue_path();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read scheduled messages: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse scheduled messages: {}", e))
+}
+
+fn save_queue(queue: &[ScheduledMessage]) -> Result<(), String> {
+    let path = get_queue_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(queue).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write scheduled messages: {}", e))
+}
+
+fn next_cron_run(expression: &str, after: DateTime<Local>) -> Result<Option<DateTime<Local>>, String> {
+    Ok(scheduler::next_run(&scheduler::parse_cron(expression)?, &Local, after))
+}
+
+// Send a plain text message to a chat via the bridge
+pub(crate) async fn send_chat_message(api: &BridgeApiClient, chat_key: &str, text: &str) -> Result<(), String> {
+    let body = serde_json::json!({ "chatKey": chat_key, "text": text });
+    match api.post("/messages", Some(&body)).await {
+        Ok(_) => Ok(()),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't send outbound messages".to_string()),
+        Err(e) => Err(format!("Failed to send to {}: {}", chat_key, e)),
+    }
+}
+
+async fn dispatch_due(api: &BridgeApiClient) {
+    let now = Local::now();
+    let due: Vec<ScheduledMessage> = {
+        let Ok(_guard) = QUEUE_LOCK.lock() else {
+            return;
+        };
+        load_queue()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| {
+                m.next_run
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .is_some_and(|t| t <= now)
+            })
+            .collect()
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let mut results = Vec::new();
+    for message in due {
+        let result = send_chat_message(api, &message.chat_key, &message.text).await;
+        results.push((message.id, result));
+    }
+
+    let Ok(_guard) = QUEUE_LOCK.lock() else {
+        return;
+    };
+    let Ok(mut queue) = load_queue() else {
+        return;
+    };
+    for (id, result) in results {
+        let Some(message) = queue.iter_mut().find(|m| m.id == id) else {
+            // Cancelled while we were sending
+            continue;
+        };
+        let is_cron = matches!(message.schedule, MessageSchedule::Cron { .. });
+
+        match result {
+            Ok(()) => {
+                message.attempts = 0;
+                message.last_sent = Some(now.to_rfc3339());
+                message.last_error = None;
+                message.next_run = None;
+            }
+            Err(e) => {
+                message.attempts += 1;
+                message.last_error = Some(e);
+                if !is_cron && message.attempts >= MAX_ATTEMPTS {
+                    message.next_run = None;
+                }
+            }
+        }
+
+        if let MessageSchedule::Cron { ref expression } = message.schedule {
+            message.next_run = next_cron_run(expression, now).ok().flatten().map(|t| t.to_rfc3339());
+        }
+    }
+
+    // Delivered one-off messages are done; failed ones stay listed with their error
+    queue.retain(|m| m.next_run.is_some() || m.last_sent.is_none());
+    let _ = save_queue(&queue);
+}
+
+pub(crate) async fn run_dispatcher(api: BridgeApiClient) {
+    loop {
+        dispatch_due(&api).await;
+        tokio::time::sleep(DISPATCH_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) fn schedule_message(
+    chat_key: String,
+    text: String,
+    send_at: Option<String>,
+    cron: Option<String>,
+) -> Result<ScheduledMessage, String> {
+    if chat_key.trim().is_empty() || text.trim().is_empty() {
+        return Err("Chat and message text are required".to_string());
+    }
+
+    let (schedule, next_run) = match (send_at, cron) {
+        (Some(send_at), None) => {
+            let at = DateTime::parse_from_rfc3339(&send_at).map_err(|e| format!("Invalid send time: {}", e))?;
+            (MessageSchedule::At { send_at }, at.with_timezone(&Local))
+        }
+        (None, Some(expression)) => {
+            let next = next_cron_run(&expression, Local::now())?
+                .ok_or(format!("Cron expression '{}' never fires", expression))?;
+            (MessageSchedule::Cron { expression }, next)
+        }
+        _ => return Err("Provide exactly one of a send time or a cron expression".to_string()),
+    };
+
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let message = ScheduledMessage {
+        id: format!("msg-{}", millis),
+        chat_key,
+        text,
+        schedule,
+        next_run: Some(next_run.to_rfc3339()),
+        attempts: 0,
+        last_sent: None,
+        last_error: None,
+    };
+
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut queue = load_queue()?;
+    queue.push(message.clone());
+    save_queue(&queue)?;
+
+    Ok(message)
+}
+
+#[tauri::command]
+pub(crate) fn list_scheduled_messages() -> Result<Vec<ScheduledMessage>, String> {
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    load_queue()
+}
+
+#[tauri::command]
+pub(crate) fn cancel_scheduled_message(id: String) -> Result<bool, String> {
+    let _guard = QUEUE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut queue = load_queue()?;
+    let original_len = queue.len();
+    queue.retain(|m| m.id != id);
+    if queue.len() == original_len {
+        return Err(format!("Scheduled message '{}' not found", id));
+    }
+    save_queue(&queue)?;
+    Ok(true)
+}
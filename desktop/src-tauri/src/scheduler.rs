@@ -0,0 +1,237 @@
+// Cron and time zone handling shared by everything that runs on a schedule.
+//
+// Scheduled messages, agent working hours and previews in the UI all parse
+// the same expressions, so parsing and next-run computation live here.
+// Expressions are matched against wall-clock time in the schedule's time zone
+// rather than against UTC, which keeps "every day at 9:00" at 9:00 across
+// daylight saving changes. Around those changes a run whose time doesn't exist
+// (the hour skipped in spring) happens once the clocks have moved, shifted by
+// the length of the gap, and one whose time exists twice (the hour repeated in
+// autumn) happens only the first time.
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use cron::Schedule;
+use std::str::FromStr;
+
+use crate::error::{CommandResult, Error};
+
+// Upper bound for `preview_schedule`
+const MAX_PREVIEW: usize = 50;
+
+// A standard cron day of week, 0-7 from Sunday (both 0 and 7), as the cron
+// crate's 1-7 from Sunday; names and anything else are left alone
+fn translate_weekday(item: &str) -> String {
+    let (days, step) = match item.split_once('/') {
+        Some((days, step)) => (days, Some(step)),
+        None => (item, None),
+    };
+    let suffix = step.map(|step| format!("/{}", step)).unwrap_or_default();
+    let day = |s: &str| s.parse::<u32>().ok().filter(|day| *day <= 7);
+    if let Some(day) = day(days) {
+        return format!("{}{}", day % 7 + 1, suffix);
+    }
+    match days.split_once('-').map(|(from, to)| (day(from), day(to))) {
+        // A range ending on Sunday as 7 wraps around to the crate's 1
+        Some((Some(from), Some(7))) if from > 0 => {
+            let step = step.and_then(|step| step.parse::<u32>().ok()).unwrap_or(1).max(1);
+            let range = format!("{}-7{}", from + 1, suffix);
+            if (7 - from) % step == 0 {
+                format!("{},1", range)
+            } else {
+                range
+            }
+        }
+        Some((Some(from), Some(to))) => format!("{}-{}{}", from + 1, to.min(6) + 1, suffix),
+        _ => item.to_string(),
+    }
+}
+
+// Standard 5-field cron as well as the cron crate's seconds-first form, with
+// an optional year. Numeric weekdays follow each form's own convention
+pub(crate) fn parse_cron(expression: &str) -> Result<Schedule, String> {
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let normalized = if fields.len() == 5 {
+        let weekdays: Vec<String> = fields[4].split(',').map(translate_weekday).collect();
+        format!("0 {} {}", fields[..4].join(" "), weekdays.join(","))
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&normalized).map_err(|e| format!("Invalid cron expression '{}': {}", expression, e))
+}
+
+// IANA name such as "Europe/Berlin"
+pub(crate) fn parse_timezone(name: &str) -> Result<chrono_tz::Tz, String> {
+    name.parse().map_err(|_| format!("Unknown timezone '{}'", name))
+}
+
+// "HH:MM", as used by working hours
+pub(crate) fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| format!("Invalid time '{}', expected HH:MM", time))
+}
+
+// The instant a wall-clock time in `tz` refers to: the first of two when the
+// clocks go back, and the time shifted past the gap when they go forward
+pub(crate) fn resolve_local<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> Option<DateTime<Z>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) => Some(time),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            // Gaps are at most a few hours, so the offset from before it
+            // applies a day earlier
+            let before = tz.from_local_datetime(&(local - Duration::days(1))).earliest()?;
+            let utc = local - before.offset().fix();
+            Some(tz.from_utc_datetime(&utc))
+        }
+    }
+}
+
+// The next `count` runs strictly after `after`, in `tz`
+pub(crate) fn upcoming<Z: TimeZone>(schedule: &Schedule, tz: &Z, after: DateTime<Z>, count: usize) -> Vec<DateTime<Z>> {
+    // Walk the expression over wall-clock times; Utc stands in for "no zone"
+    let start = Utc.from_utc_datetime(&after.naive_local());
+    let mut runs: Vec<DateTime<Z>> = Vec::with_capacity(count);
+    for local in schedule.after(&start) {
+        if runs.len() == count {
+            break;
+        }
+        let Some(run) = resolve_local(tz, local.naive_utc()) else {
+            continue;
+        };
+        // A shifted run can land on or before one already found
+        if run > after && runs.last().is_none_or(|last| run > *last) {
+            runs.push(run);
+        }
+    }
+    runs
+}
+
+pub(crate) fn next_run<Z: TimeZone>(schedule: &Schedule, tz: &Z, after: DateTime<Z>) -> Option<DateTime<Z>> {
+    upcoming(schedule, tz, after, 1).into_iter().next()
+}
+
+// Upcoming run times (RFC 3339, in the schedule's zone) for checking an
+// expression before saving it; the system time zone when none is given
+#[tauri::command]
+pub(crate) fn preview_schedule(expr: String, n: usize, timezone: Option<String>) -> CommandResult<Vec<String>> {
+    let schedule = parse_cron(expr.trim()).map_err(Error::Invalid)?;
+    let count = n.clamp(1, MAX_PREVIEW);
+    let runs = match timezone.as_deref().map(str::trim).filter(|tz| !tz.is_empty()) {
+        Some(name) => {
+            let tz = parse_timezone(name).map_err(Error::Invalid)?;
+            upcoming(&schedule, &tz, Utc::now().with_timezone(&tz), count)
+                .iter()
+                .map(|t| t.to_rfc3339())
+                .collect()
+        }
+        None => upcoming(&schedule, &chrono::Local, chrono::Local::now(), count)
+            .iter()
+            .map(|t| t.to_rfc3339())
+            .collect(),
+    };
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Europe::Berlin;
+    use std::collections::BTreeSet;
+
+    fn berlin(s: &str) -> DateTime<chrono_tz::Tz> {
+        resolve_local(&Berlin, NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()).unwrap()
+    }
+
+    fn times(runs: &[DateTime<chrono_tz::Tz>]) -> Vec<String> {
+        runs.iter().map(|t| t.to_rfc3339()).collect()
+    }
+
+    #[test]
+    fn parses_five_and_six_field_expressions() {
+        assert!(parse_cron("30 9 * * Mon-Fri").is_ok());
+        assert!(parse_cron("0 30 9 * * Mon-Fri").is_ok());
+        assert!(parse_cron("0 30 9 * * * 2030").is_ok());
+        assert!(parse_cron("61 * * * *").is_err());
+        assert!(parse_cron("every day").is_err());
+    }
+
+    #[test]
+    fn numeric_weekdays_count_from_sunday_as_zero() {
+        let days = |expression: &str| -> Vec<String> {
+            let schedule = parse_cron(expression).unwrap();
+            // 2026-06-01 is a Monday
+            upcoming(&schedule, &Berlin, berlin("2026-05-31 12:00"), 7)
+                .iter()
+                .map(|t| t.format("%a").to_string())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        };
+        assert_eq!(days("0 9 * * 1-5"), ["Fri", "Mon", "Thu", "Tue", "Wed"]);
+        assert_eq!(days("0 9 * * 0"), ["Sun"]);
+        assert_eq!(days("0 9 * * 7"), ["Sun"]);
+        assert_eq!(days("0 9 * * 1,3"), ["Mon", "Wed"]);
+        assert_eq!(days("0 9 * * 5-7"), ["Fri", "Sat", "Sun"]);
+        assert_eq!(days("0 9 * * 0-2"), ["Mon", "Sun", "Tue"]);
+        assert_eq!(days("0 9 * * 1-7/2"), ["Fri", "Mon", "Sun", "Wed"]);
+        assert_eq!(days("0 9 * * */3"), ["Sat", "Sun", "Wed"]);
+        assert_eq!(days("0 9 * * Sat"), ["Sat"]);
+        // The seconds-first form keeps the crate's own numbering
+        assert_eq!(days("0 0 9 * * 1"), ["Sun"]);
+    }
+
+    #[test]
+    fn parses_timezones_and_times() {
+        assert!(parse_timezone("Europe/Berlin").is_ok());
+        assert!(parse_timezone("Mars/Olympus").is_err());
+        assert_eq!(parse_time("09:05"), Ok(NaiveTime::from_hms_opt(9, 5, 0).unwrap()));
+        assert!(parse_time("9am").is_err());
+    }
+
+    #[test]
+    fn next_run_is_strictly_after() {
+        let schedule = parse_cron("0 9 * * *").unwrap();
+        let next = next_run(&schedule, &Berlin, berlin("2026-06-01 09:00")).unwrap();
+        assert_eq!(next.to_rfc3339(), "2026-06-02T09:00:00+02:00");
+    }
+
+    #[test]
+    fn keeps_wall_clock_time_across_dst() {
+        let schedule = parse_cron("0 9 * * *").unwrap();
+        let runs = upcoming(&schedule, &Berlin, berlin("2026-03-28 12:00"), 2);
+        assert_eq!(times(&runs), ["2026-03-29T09:00:00+02:00", "2026-03-30T09:00:00+02:00"]);
+    }
+
+    #[test]
+    fn skipped_hour_runs_after_the_gap() {
+        // Clocks go from 02:00 to 03:00 on 2026-03-29 in Berlin
+        let schedule = parse_cron("30 2 * * *").unwrap();
+        let runs = upcoming(&schedule, &Berlin, berlin("2026-03-28 12:00"), 2);
+        assert_eq!(times(&runs), ["2026-03-29T03:30:00+02:00", "2026-03-30T02:30:00+02:00"]);
+    }
+
+    #[test]
+    fn repeated_hour_runs_once() {
+        // Clocks go from 03:00 back to 02:00 on 2026-10-25 in Berlin
+        let schedule = parse_cron("30 2 * * *").unwrap();
+        let runs = upcoming(&schedule, &Berlin, berlin("2026-10-24 12:00"), 2);
+        assert_eq!(times(&runs), ["2026-10-25T02:30:00+02:00", "2026-10-26T02:30:00+01:00"]);
+    }
+
+    #[test]
+    fn hourly_runs_do_not_repeat_in_the_repeated_hour() {
+        let schedule = parse_cron("0 * * * *").unwrap();
+        let runs = upcoming(&schedule, &Berlin, berlin("2026-10-25 01:30"), 3);
+        assert_eq!(
+            times(&runs),
+            ["2026-10-25T02:00:00+02:00", "2026-10-25T03:00:00+01:00", "2026-10-25T04:00:00+01:00"]
+        );
+    }
+
+    #[test]
+    fn preview_caps_and_rejects_bad_input() {
+        assert_eq!(preview_schedule("0 9 * * *".into(), 500, Some("UTC".into())).unwrap().len(), MAX_PREVIEW);
+        assert_eq!(preview_schedule("0 9 * * *".into(), 0, None).unwrap().len(), 1);
+        assert!(preview_schedule("nope".into(), 3, None).is_err());
+        assert!(preview_schedule("0 9 * * *".into(), 3, Some("Nowhere/Land".into())).is_err());
+    }
+}
@@ -0,0 +1,277 @@
+// Plaintext secret detection.
+//
+// Anything the app writes for sharing (status reports, crash report issues)
+// or copies out of ~/.ccb (backups, config exports) goes through here first.
+// Config values are flagged by their key (botToken, apiKey, MCP server env
+// vars and the like) and free text by the shape of well-known tokens: Telegram
+// and Discord bot tokens, Anthropic/OpenAI, GitHub, Slack and AWS keys.
+// Putting redacted secrets back matches list entries such as bots by id, so a
+// reordered list still gets each entry's own secret.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::config::ConfigStore;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+// Key fragments, lowercased with `_` and `-` removed
+const SECRET_KEY_PARTS: &[&str] = &["token", "secret", "password", "apikey", "privatekey", "credential"];
+
+const TOKEN_PREFIXES: &[(&str, &str)] = &[
+    ("sk-ant-", "Anthropic API key"),
+    ("sk-", "API key"),
+    ("ghp_", "GitHub token"),
+    ("gho_", "GitHub token"),
+    ("ghs_", "GitHub token"),
+    ("github_pat_", "GitHub token"),
+    ("xoxb-", "Slack token"),
+    ("xoxp-", "Slack token"),
+    ("xapp-", "Slack token"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretFinding {
+    // JSON path such as channels.telegram.botToken, or "line 12"
+    pub(crate) location: String,
+    pub(crate) kind: String,
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key: String = key
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+// Telegram: <bot id>:<35 chars>
+pub(crate) fn is_telegram_token(word: &str) -> bool {
+    let Some((id, secret)) = word.split_once(':') else {
+        return false;
+    };
+    (6..=12).contains(&id.len())
+        && id.chars().all(|c| c.is_ascii_digit())
+        && secret.len() >= 30
+        && secret.chars().all(is_token_char)
+}
+
+// Discord: three base64url parts separated by dots
+pub(crate) fn is_discord_token(word: &str) -> bool {
+    let parts: Vec<&str> = word.split('.').collect();
+    parts.len() == 3
+        && parts[0].len() >= 20
+        && parts[1].len() >= 6
+        && parts[2].len() >= 20
+        && parts.iter().all(|p| p.chars().all(is_token_char))
+}
+
+fn is_aws_key(word: &str) -> bool {
+    word.len() == 20
+        && word.starts_with("AKIA")
+        && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+// What kind of token a single word looks like, if any
+fn token_kind(word: &str) -> Option<&'static str> {
+    if is_telegram_token(word) {
+        return Some("Telegram bot token");
+    }
+    if is_discord_token(word) {
+        return Some("Discord bot token");
+    }
+    if is_aws_key(word) {
+        return Some("AWS access key");
+    }
+    TOKEN_PREFIXES
+        .iter()
+        .find(|(prefix, _)| word.starts_with(prefix) && word.len() >= prefix.len() + 16)
+        .map(|(_, kind)| *kind)
+}
+
+// Word boundaries for free text: anything that can't be part of a token,
+// except the separators the Telegram and Discord formats use
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(is_token_char(c) || c == ':' || c == '.'))
+        .map(|w| w.trim_matches(|c| c == ':' || c == '.'))
+        .filter(|w| w.len() >= 20)
+}
+
+pub(crate) fn scan_text(text: &str) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        for word in words(line) {
+            if let Some(kind) = token_kind(word) {
+                findings.push(SecretFinding {
+                    location: format!("line {}", i + 1),
+                    kind: kind.to_string(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+// Each token in `text` with its kind
+pub(crate) fn find_tokens(text: &str) -> Vec<(&str, &'static str)> {
+    words(text).filter_map(|word| Some((word, token_kind(word)?))).collect()
+}
+
+pub(crate) fn redact_text(text: &str) -> String {
+    let mut redacted = text.to_string();
+    for (word, _) in find_tokens(text) {
+        redacted = redacted.replace(word, REDACTED);
+    }
+    redacted
+}
+
+fn walk(value: &mut serde_json::Value, path: &str, redact: bool, findings: &mut Vec<SecretFinding>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                // `${VAR}` references (env vars, the keychain) hold no secret themselves
+                let secret = child
+                    .as_str()
+                    .filter(|s| !s.is_empty() && *s != REDACTED && !(s.starts_with("${") && s.ends_with('}')));
+                if let Some(secret) = secret {
+                    let kind = if is_secret_key(key) { token_kind(secret).or(Some("Secret")) } else { token_kind(secret) };
+                    if let Some(kind) = kind {
+                        findings.push(SecretFinding {
+                            location: child_path,
+                            kind: kind.to_string(),
+                        });
+                        if redact {
+                            *child = serde_json::Value::String(REDACTED.to_string());
+                        }
+                        continue;
+                    }
+                }
+                walk(child, &child_path, redact, findings);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                walk(item, &format!("{}[{}]", path, i), redact, findings);
+            }
+        }
+        serde_json::Value::String(text) => {
+            // Tokens embedded in longer strings, e.g. a URL or a prompt
+            let found = scan_text(text);
+            if !found.is_empty() {
+                findings.extend(found.into_iter().map(|f| SecretFinding {
+                    location: path.to_string(),
+                    kind: f.kind,
+                }));
+                if redact {
+                    *text = redact_text(text);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub(crate) fn scan_config(config: &serde_json::Value) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    walk(&mut config.clone(), "", false, &mut findings);
+    findings
+}
+
+// Replace every secret with a placeholder; returns what was replaced
+pub(crate) fn redact_config(config: &mut serde_json::Value) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    walk(config, "", true, &mut findings);
+    findings
+}
+
+pub(crate) fn id_of(value: &serde_json::Value) -> Option<&str> {
+    value.get("id").and_then(|v| v.as_str())
+}
+
+// Lists of agents, bots and the like, which are matched up by id
+pub(crate) fn keyed(items: &[serde_json::Value]) -> bool {
+    items.iter().all(|item| id_of(item).is_some())
+}
+
+// Put secrets back from `source` where `config` has placeholders; returns the
+// paths that `source` couldn't fill
+pub(crate) fn restore_redacted(config: &mut serde_json::Value, source: &serde_json::Value) -> Vec<String> {
+    fn restore(value: &mut serde_json::Value, source: Option<&serde_json::Value>, path: &str, missing: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map.iter_mut() {
+                    let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    restore(child, source.and_then(|s| s.get(key)), &child_path, missing);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                let by_id = keyed(items);
+                for (i, item) in items.iter_mut().enumerate() {
+                    let counterpart = match source.and_then(|s| s.as_array()) {
+                        Some(source) if by_id => {
+                            let id = id_of(item);
+                            source.iter().find(|s| id_of(s) == id)
+                        }
+                        _ => source.and_then(|s| s.get(i)),
+                    };
+                    restore(item, counterpart, &format!("{}[{}]", path, i), missing);
+                }
+            }
+            serde_json::Value::String(text) if text.contains(REDACTED) => {
+                match source.and_then(|s| s.as_str()).filter(|s| !s.contains(REDACTED)) {
+                    Some(original) => *text = original.to_string(),
+                    None => missing.push(path.to_string()),
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut missing = Vec::new();
+    restore(config, Some(source), "", &mut missing);
+    missing
+}
+
+// Plaintext secrets in the current config, for showing before anything is
+// shared
+#[tauri::command]
+pub(crate) fn scan_config_secrets(store: State<'_, ConfigStore>) -> Result<Vec<SecretFinding>, String> {
+    Ok(store.read()?.map(|c| scan_config(&c)).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reordered_bots_get_their_own_tokens() {
+        let current = json!({ "bots": [
+            { "id": "a", "botToken": "token-a" },
+            { "id": "b", "botToken": "token-b" },
+        ] });
+        let mut backup = json!({ "bots": [
+            { "id": "b", "botToken": REDACTED },
+            { "id": "c", "botToken": REDACTED },
+            { "id": "a", "botToken": REDACTED },
+        ] });
+        let missing = restore_redacted(&mut backup, &current);
+        assert_eq!(backup["bots"][0]["botToken"], "token-b");
+        assert_eq!(backup["bots"][2]["botToken"], "token-a");
+        assert_eq!(missing, ["bots[1].botToken"]);
+    }
+
+    #[test]
+    fn lists_without_ids_match_by_position() {
+        let current = json!({ "args": ["--key", "secret"] });
+        let mut backup = json!({ "args": ["--key", REDACTED] });
+        assert!(restore_redacted(&mut backup, &current).is_empty());
+        assert_eq!(backup["args"][1], "secret");
+    }
+}
@@ -0,0 +1,235 @@
+// End-to-end self test.
+//
+// Walks the whole chain the way a message would: make sure the bridge is up,
+// check the Control API answers, validate every bot token against its
+// platform, then run a trivial prompt through the default agent. Each stage is
+// reported separately so a failure points at the broken link.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::bot_tokens::{discord_identity, telegram_identity};
+use crate::config::ConfigStore;
+use crate::poller::StatusCache;
+use crate::service::{start_service, AppState, StartLock};
+use crate::tasks::{spawn_task, TaskHandle};
+use crate::{http, keychain};
+
+const TEST_PROMPT: &str = "Reply with the single word OK.";
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StageResult {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestStage {
+    name: String,
+    result: StageResult,
+    detail: String,
+    duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    passed: bool,
+    stages: Vec<SelfTestStage>,
+}
+
+struct Stages {
+    done: Vec<SelfTestStage>,
+    // Set when running as a background task
+    task: Option<TaskHandle>,
+}
+
+impl Stages {
+    fn record(&mut self, stage: SelfTestStage) {
+        if let Some(ref task) = self.task {
+            task.progress(None, format!("{}: {}", stage.name, stage.detail));
+        }
+        self.done.push(stage);
+    }
+
+    fn push(&mut self, name: impl Into<String>, started: Instant, outcome: Result<String, String>) -> bool {
+        let (result, detail) = match outcome {
+            Ok(detail) => (StageResult::Pass, detail),
+            Err(detail) => (StageResult::Fail, detail),
+        };
+        self.record(SelfTestStage {
+            name: name.into(),
+            result,
+            detail,
+            duration_ms: started.elapsed().as_millis() as u64,
+        });
+        result == StageResult::Pass
+    }
+
+    fn skip(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.record(SelfTestStage {
+            name: name.into(),
+            result: StageResult::Skip,
+            detail: detail.into(),
+            duration_ms: 0,
+        });
+    }
+}
+
+// Returns the bot's username on success
+pub(crate) async fn check_telegram_token(client: &reqwest::Client, token: &str) -> Result<String, String> {
+    Ok(telegram_identity(client, token).await?.username)
+}
+
+// Returns the bot's username on success
+pub(crate) async fn check_discord_token(client: &reqwest::Client, token: &str) -> Result<String, String> {
+    Ok(discord_identity(client, token).await?.username)
+}
+
+// (stage name, channel, token) for every configured bot
+fn configured_bots(config: &serde_json::Value) -> Vec<(String, &'static str, String)> {
+    let mut bots = Vec::new();
+    for (channel, token_key) in [("telegram", "botToken"), ("discord", "token")] {
+        let Some(section) = config.get("channels").and_then(|c| c.get(channel)) else {
+            continue;
+        };
+        if section.get("enabled").and_then(|e| e.as_bool()) == Some(false) {
+            continue;
+        }
+        if let Some(token) = section
+            .get(token_key)
+            .and_then(|t| t.as_str())
+            .map(keychain::resolve)
+            .filter(|t| !t.is_empty())
+        {
+            bots.push((format!("{} bot", channel), channel, token));
+        }
+        for bot in section.get("bots").and_then(|b| b.as_array()).into_iter().flatten() {
+            if let (Some(id), Some(token)) = (
+                bot.get("id").and_then(|v| v.as_str()),
+                bot.get(token_key).and_then(|v| v.as_str()),
+            ) {
+                bots.push((format!("{} bot '{}'", channel, id), channel, keychain::resolve(token)));
+            }
+        }
+    }
+    bots
+}
+
+async fn run_test_prompt(api: &BridgeApiClient, agent_id: &str) -> Result<String, String> {
+    let response = api
+        .http()
+        .post(BridgeApiClient::url(&format!("/agents/{}/prompt", agent_id)))
+        .json(&serde_json::json!({ "prompt": TEST_PROMPT }))
+        .timeout(PROMPT_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Prompt failed: {}", ApiError::from(e)))?;
+    let response = match BridgeApiClient::check(response).await {
+        Ok(response) => response,
+        Err(ApiError::Unsupported(_)) => return Err("This bridge version can't run test prompts".to_string()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let body: serde_json::Value = response.json().await.map_err(ApiError::from)?;
+    let reply = body["text"].as_str().unwrap_or_default().trim().to_string();
+    if reply.is_empty() {
+        Err("The agent returned an empty reply".to_string())
+    } else {
+        Ok(format!("Agent replied: {}", reply))
+    }
+}
+
+async fn run(app: AppHandle, task: Option<TaskHandle>) -> Result<SelfTestReport, String> {
+    let mut stages = Stages { done: Vec::new(), task };
+    let client = http::client();
+
+    // 1. Bridge process, started if it isn't running yet
+    let started = Instant::now();
+    let bridge = start_service(
+        app.clone(),
+        app.state::<AppState>(),
+        app.state::<StatusCache>(),
+        app.state::<StartLock>(),
+        None,
+    )
+    .await
+    .map(|status| format!("Bridge is up ({}s uptime)", status.uptime))
+    .map_err(|failure| failure.message);
+    let bridge_up = stages.push("Bridge process", started, bridge);
+
+    // 2. Control API
+    let started = Instant::now();
+    let api_ok = if bridge_up {
+        let api = match app.state::<BridgeApiClient>().get::<serde_json::Value>("/health").await {
+            Ok(_) => Ok("Control API is answering".to_string()),
+            Err(ApiError::Status(401, _)) | Err(ApiError::Status(403, _)) => {
+                Err("Control API rejected the desktop's credentials".to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        stages.push("Control API", started, api)
+    } else {
+        stages.skip("Control API", "Bridge is not running");
+        false
+    };
+
+    // 3. Bot tokens
+    let config = app.state::<ConfigStore>().read()?.unwrap_or(serde_json::Value::Null);
+    let bots = configured_bots(&config);
+    if bots.is_empty() {
+        stages.skip("Bot tokens", "No bots are configured");
+    }
+    for (name, channel, token) in bots {
+        let started = Instant::now();
+        let check = match channel {
+            "telegram" => check_telegram_token(&client, &token).await,
+            _ => check_discord_token(&client, &token).await,
+        };
+        stages.push(name, started, check.map(|username| format!("Token is valid for {}", username)));
+    }
+
+    // 4. Trivial prompt through the default agent
+    let default_agent = config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .and_then(|l| l.first())
+        .and_then(|a| a.get("id"))
+        .and_then(|id| id.as_str());
+    match default_agent {
+        Some(agent_id) if api_ok => {
+            let started = Instant::now();
+            let prompt = run_test_prompt(&app.state::<BridgeApiClient>(), agent_id).await;
+            stages.push(format!("Prompt via agent '{}'", agent_id), started, prompt);
+        }
+        Some(_) => stages.skip("Test prompt", "Control API is not available"),
+        None => stages.skip("Test prompt", "No agents are configured"),
+    }
+
+    Ok(SelfTestReport {
+        passed: stages.done.iter().all(|s| s.result != StageResult::Fail),
+        stages: stages.done,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn run_self_test(app: AppHandle) -> Result<SelfTestReport, String> {
+    run(app, None).await
+}
+
+// Same as run_self_test, but as a cancellable task reporting each stage
+#[tauri::command]
+pub(crate) fn start_self_test(app: AppHandle) -> Result<String, String> {
+    let handle = app.clone();
+    spawn_task(&app, "self_test", "Self test", move |task| async move {
+        let report = run(handle, Some(task)).await?;
+        serde_json::to_value(report).map_err(|e| e.to_string())
+    })
+}
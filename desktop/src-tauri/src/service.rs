@@ -0,0 +1,541 @@
+// Running the bridge process.
+//
+// Spawns `ccb start` (falling back to npx and well-known install paths, since
+// GUI apps don't inherit the shell's PATH), captures its output into the
+// service log (see `logs`), and waits for the Control API to come up before
+// reporting the bridge as started.
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+use crate::agent_dependencies::{self, DependencyCheck};
+use crate::api_client::{BridgeApiClient, BridgeStatus};
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::log_forwarding;
+use crate::logs::{LogBuffer, LogSource};
+use crate::poller::{CachedStatus, StatusCache};
+#[cfg(windows)]
+use crate::runtime_info::find_executable;
+use crate::{
+    analytics, app_settings, config_drift, crash_reports, demo, http, i18n, keychain, node_runtime, permission_prompts,
+    process_limits,
+};
+
+// Service state
+#[derive(Default)]
+pub(crate) struct ServiceState {
+    pub(crate) process: Option<Child>,
+    pub(crate) is_running: bool,
+    pub(crate) logs: LogBuffer,
+    // When the current process was spawned
+    pub(crate) started_at: Option<Instant>,
+    // Crash restarts since the bridge was last started by hand
+    pub(crate) restarts: u32,
+    // Set while the supervisor waits to bring a crashed bridge back
+    pub(crate) restart_at: Option<Instant>,
+    // The last run ended in a crash; cleared by the next start
+    pub(crate) crashed: bool,
+}
+
+pub(crate) type AppState = Arc<Mutex<ServiceState>>;
+
+// Held for the whole of start_service so a double-clicked Start or an
+// auto-start racing a manual one waits for the spawn in flight instead of
+// launching a second bridge
+#[derive(Default)]
+pub(crate) struct StartLock(pub(crate) tokio::sync::Mutex<()>);
+
+pub(crate) fn get_extended_path() -> String {
+    // GUI apps don't inherit the shell's PATH, so we need to build it ourselves
+    let current_path = std::env::var("PATH").unwrap_or_default();
+    let separator = if cfg!(windows) { ";" } else { ":" };
+
+    format!("{}{}{}", node_runtime::runtime_paths().join(separator), separator, current_path)
+}
+
+const CCB_START: &[&str] = &["start"];
+const NPX_START: &[&str] = &["cc-bridge", "start"];
+
+// What to try launching, in order: ccb on the PATH, npx, then well-known
+// global install paths
+#[cfg(not(windows))]
+fn launch_candidates() -> Vec<(PathBuf, &'static [&'static str])> {
+    let mut candidates = vec![(PathBuf::from("ccb"), CCB_START), (PathBuf::from("npx"), NPX_START)];
+
+    let Some(home) = dirs::home_dir() else {
+        return candidates;
+    };
+    let npm_paths = [
+        home.join(".nvm/versions/node").join("*").join("bin/ccb"),
+        home.join(".volta/bin/ccb"),
+        home.join(".npm/bin/ccb"),
+        home.join(".npm-global/bin/ccb"),
+        home.join(".local/bin/ccb"),
+        dirs::data_dir().unwrap_or_default().join("fnm/node-versions/*/installation/bin/ccb"),
+        PathBuf::from("/usr/local/bin/ccb"),
+        PathBuf::from("/opt/homebrew/bin/ccb"),
+        PathBuf::from("/usr/bin/ccb"),
+    ];
+    for pattern in &npm_paths {
+        if let Ok(entries) = glob::glob(pattern.to_string_lossy().as_ref()) {
+            candidates.extend(entries.filter_map(Result::ok).map(|entry| (entry, CCB_START)));
+        }
+    }
+    candidates
+}
+
+// npm installs ccb.cmd and npx.cmd, which CreateProcess doesn't find by bare
+// name, so both are resolved against the extended PATH first
+#[cfg(windows)]
+fn launch_candidates() -> Vec<(PathBuf, &'static [&'static str])> {
+    let mut candidates = vec![];
+    if let Some(ccb) = find_executable("ccb") {
+        candidates.push((ccb, CCB_START));
+    }
+    if let Some(npx) = find_executable("npx") {
+        candidates.push((npx, NPX_START));
+    }
+    // npm's default global prefix, for when APPDATA points elsewhere
+    if let Some(home) = dirs::home_dir() {
+        candidates.push((home.join(r"AppData\Roaming\npm\ccb.cmd"), CCB_START));
+    }
+    candidates
+}
+
+fn try_start_ccb(secret_env: &[(String, String)]) -> Option<Child> {
+    let extended_path = get_extended_path();
+
+    launch_candidates().into_iter().find_map(|(program, args)| {
+        process_limits::apply(&mut Command::new(&program))
+            .args(args)
+            .env("PATH", &extended_path)
+            .envs(app_settings::start_env())
+            .envs(http::proxy_env())
+            .envs(secret_env.iter().cloned())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .ok()
+    })
+}
+
+// Kill the bridge along with what it started. On Windows the child is
+// usually cmd.exe running ccb.cmd, and killing only that leaves node running.
+// On Unix a child started as a process group leader takes its group with it
+pub(crate) fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+    }
+    #[cfg(windows)]
+    if let Some(pid) = child.id() {
+        use std::os::windows::process::CommandExt;
+        let _ = std::process::Command::new("taskkill")
+            .args(["/PID", &pid.to_string(), "/T", "/F"])
+            .creation_flags(process_limits::CREATE_NO_WINDOW)
+            .output();
+    }
+    let _ = child.start_kill();
+}
+
+// Bridge output included with startup failures and crash reports
+const LOG_EXCERPT_LINES: usize = 50;
+// Lines are handed to the log buffer in batches so a chatty bridge doesn't
+// re-lock the state for every line it prints
+const LOG_BATCH_LINES: usize = 64;
+const LOG_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+// Batches in flight before the readers stop pulling from the pipes, which in
+// turn makes the child block on write instead of us buffering without bound
+const LOG_CHANNEL_CAPACITY: usize = 16;
+
+fn spawn_log_readers(child: &mut Child, state: AppState) {
+    let (tx, rx) = mpsc::channel(LOG_CHANNEL_CAPACITY);
+
+    if let Some(stdout) = child.stdout.take() {
+        tauri::async_runtime::spawn(read_log_lines(stdout, tx.clone()));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tauri::async_runtime::spawn(read_log_lines(stderr, tx));
+    }
+
+    tauri::async_runtime::spawn(collect_logs(rx, state));
+}
+
+async fn read_log_lines<R: AsyncRead + Unpin>(pipe: R, tx: mpsc::Sender<Vec<String>>) {
+    let mut lines = BufReader::new(pipe).lines();
+    let mut batch = Vec::new();
+    let mut flush = tokio::time::interval(LOG_FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) => {
+                    batch.push(line);
+                    if batch.len() >= LOG_BATCH_LINES && tx.send(std::mem::take(&mut batch)).await.is_err() {
+                        return;
+                    }
+                }
+                // EOF or read error: the child closed its end
+                _ => break,
+            },
+            _ = flush.tick() => {
+                if !batch.is_empty() && tx.send(std::mem::take(&mut batch)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    if !batch.is_empty() {
+        let _ = tx.send(batch).await;
+    }
+}
+
+async fn collect_logs(mut rx: mpsc::Receiver<Vec<String>>, state: AppState) {
+    while let Some(batch) = rx.recv().await {
+        log_forwarding::forward(LogSource::Bridge, &batch);
+        if let Ok(mut service) = state.lock() {
+            service.logs.extend_bridge(&batch);
+        }
+    }
+
+    // Both pipes closed, so the bridge has exited or is about to
+    report_unexpected_exit(&state).await;
+}
+
+async fn report_unexpected_exit(state: &AppState) {
+    for _ in 0..20 {
+        {
+            let Ok(mut service) = state.lock() else {
+                return;
+            };
+            // stop_service takes the child out before killing it, so a
+            // missing handle means the exit was requested
+            let Some(child) = service.process.as_mut() else {
+                return;
+            };
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    if !status.success() {
+                        crash_reports::record_bridge_exit(status.code(), &service.logs.tail(crash_reports::CRASH_LOG_LINES));
+                    }
+                    return;
+                }
+                Ok(None) => {}
+                Err(_) => return,
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const STARTUP_PROGRESS_EVENT: &str = "bridge://startup-progress";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StartupProgress {
+    attempt: u32,
+    elapsed_ms: u64,
+}
+
+// Why start_service gave up, with the bridge's last log lines for context
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StartupFailure {
+    pub(crate) message: String,
+    exit_code: Option<i32>,
+    log_excerpt: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unmet_dependencies: Vec<DependencyCheck>,
+}
+
+impl From<String> for StartupFailure {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            exit_code: None,
+            log_excerpt: vec![],
+            unmet_dependencies: vec![],
+        }
+    }
+}
+
+// Poll /status until the Control API answers, the child exits, or we time out
+async fn wait_until_ready(app: &AppHandle, state: &AppState) -> Result<BridgeStatus, StartupFailure> {
+    let api = app.state::<BridgeApiClient>();
+    let started = std::time::Instant::now();
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+        if let Ok(status) = api.status().await {
+            return Ok(status);
+        }
+
+        let exit = {
+            let mut service = state.lock().map_err(|e| e.to_string())?;
+            service.process.as_mut().and_then(|c| c.try_wait().ok().flatten())
+        };
+        if let Some(exit) = exit {
+            // Let the log readers flush the child's last words
+            tokio::time::sleep(LOG_FLUSH_INTERVAL * 2).await;
+            let log_excerpt = state.lock().map(|s| s.logs.tail(LOG_EXCERPT_LINES)).unwrap_or_default();
+            return Err(StartupFailure {
+                message: i18n::tr("startup.exited", &[("status", &exit.to_string())]),
+                exit_code: exit.code(),
+                log_excerpt,
+                unmet_dependencies: vec![],
+            });
+        }
+
+        if started.elapsed() >= READY_TIMEOUT {
+            let log_excerpt = state.lock().map(|s| s.logs.tail(LOG_EXCERPT_LINES)).unwrap_or_default();
+            return Err(StartupFailure {
+                message: i18n::tr("startup.timeout", &[("seconds", &READY_TIMEOUT.as_secs().to_string())]),
+                exit_code: None,
+                log_excerpt,
+                unmet_dependencies: vec![],
+            });
+        }
+
+        let _ = app.emit(
+            STARTUP_PROGRESS_EVENT,
+            StartupProgress {
+                attempt,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+            },
+        );
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn start_service(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cache: State<'_, StatusCache>,
+    start_lock: State<'_, StartLock>,
+    force: Option<bool>,
+) -> Result<BridgeStatus, StartupFailure> {
+    // Readiness is checked through the Control API, which points elsewhere
+    if let Some(remote) = app_settings::active_instance() {
+        return Err(i18n::tr("service.remote_active", &[("name", &remote.name)]).into());
+    }
+    // Agents' local services; `force` starts the bridge without them
+    if force != Some(true) {
+        let config = app.state::<ConfigStore>().read().ok().flatten().unwrap_or_default();
+        let unmet = agent_dependencies::unmet(&config).await;
+        if !unmet.is_empty() {
+            let list = unmet.iter().map(|c| c.to_string()).collect::<Vec<_>>().join("; ");
+            return Err(StartupFailure {
+                message: i18n::tr("startup.unmet_dependencies", &[("list", &list)]),
+                unmet_dependencies: unmet,
+                ..StartupFailure::from(String::new())
+            });
+        }
+    }
+    // A start by hand gets a fresh restart budget and overrides a pending restart
+    {
+        let mut service = state.lock().map_err(|e| e.to_string())?;
+        service.restarts = 0;
+        service.restart_at = None;
+    }
+    launch(&app, &state, &cache, &start_lock).await
+}
+
+// Start the bridge unless it's already up; shared with the supervisor's restarts
+pub(crate) async fn launch(
+    app: &AppHandle,
+    state: &AppState,
+    cache: &StatusCache,
+    start_lock: &StartLock,
+) -> Result<BridgeStatus, StartupFailure> {
+    let _starting = start_lock.0.lock().await;
+    if demo::is_enabled() {
+        let status = demo::start(state);
+        cache.request_refresh();
+        return Ok(status);
+    }
+
+    let already_running = {
+        let mut service = state.lock().map_err(|e| e.to_string())?;
+
+        // A start that finished while we waited for the lock keeps its logs
+        if service.is_running && service.process.as_mut().is_some_and(|c| matches!(c.try_wait(), Ok(None))) {
+            true
+        } else {
+            service.crashed = false;
+            // Clear old logs
+            service.logs.clear();
+            service.logs.info(i18n::t("service.starting"));
+
+            // Flag is set but the process has exited or its handle is gone
+            if service.is_running {
+                let message = if service.process.is_some() {
+                    i18n::t("service.previous_stopped")
+                } else {
+                    i18n::t("service.stale_state")
+                };
+                service.is_running = false;
+                service.process = None;
+                service.logs.warn(message);
+            }
+            false
+        }
+    };
+    if already_running {
+        return wait_until_ready(app, state).await;
+    }
+
+    // Try multiple ways to start the bridge
+    config_drift::remember_launch_config();
+    // Tokens kept in the keychain reach the bridge as env vars
+    let mut secret_env = app
+        .state::<ConfigStore>()
+        .read()
+        .ok()
+        .flatten()
+        .map(|c| keychain::secret_env(&c))
+        .unwrap_or_default();
+    secret_env.extend(permission_prompts::bridge_env());
+    let child = try_start_ccb(&secret_env);
+
+    match child {
+        Some(mut child) => {
+            if let Err(e) = process_limits::contain(&child) {
+                log_forwarding::desktop_error(e);
+            }
+            // Capture stdout/stderr for logs
+            spawn_log_readers(&mut child, Arc::clone(state));
+
+            {
+                let mut service = state.lock().map_err(|e| e.to_string())?;
+                service.process = Some(child);
+                service.is_running = true;
+                service.started_at = Some(Instant::now());
+            }
+
+            let result = wait_until_ready(app, state).await;
+            if result.is_err() {
+                analytics::record_error("startup_failed");
+            }
+            cache.request_refresh();
+            result
+        }
+        None => {
+            let mut service = state.lock().map_err(|e| e.to_string())?;
+            let error_msg = i18n::t("service.not_found");
+            service.logs.error(error_msg.clone());
+            analytics::record_error("start_failed");
+            Err(error_msg.into())
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn stop_service(
+    api: State<'_, BridgeApiClient>,
+    state: State<'_, AppState>,
+    cache: State<'_, StatusCache>,
+) -> CommandResult<bool> {
+    if let Some(remote) = app_settings::active_instance() {
+        return Err(Error::Invalid(i18n::tr("service.remote_active", &[("name", &remote.name)])));
+    }
+    Ok(stop(&api, &state, &cache).await?)
+}
+
+// Stop through the Control API, then kill the bridge we started and any other
+// `ccb start`; true once the API no longer answers
+pub(crate) async fn stop(api: &BridgeApiClient, state: &AppState, cache: &StatusCache) -> Result<bool, String> {
+    // Stopping on purpose, so the supervisor must not take the exit for a crash
+    {
+        let mut service = state.lock().map_err(|e| e.to_string())?;
+        service.is_running = false;
+        service.restart_at = None;
+        service.crashed = false;
+    }
+    if demo::is_enabled() {
+        demo::stop(state);
+        cache.request_refresh();
+        return Ok(true);
+    }
+
+    // Try to stop gracefully via API first (works even if started outside this app)
+    let _api_result = api.stop().await;
+
+    // Kill our tracked process if we have one
+    let child = state.lock().map_err(|e| e.to_string())?.process.take();
+    if let Some(mut child) = child {
+        kill_tree(&mut child);
+        let _ = child.wait().await;
+    }
+    {
+        let mut service = state.lock().map_err(|e| e.to_string())?;
+        service.logs.info(i18n::t("service.stopped"));
+    }
+
+    // Also try to kill any ccb process by name (fallback for processes started outside this app)
+    #[cfg(unix)]
+    {
+        let _ = std::process::Command::new("pkill")
+            .args(["-f", "ccb start"])
+            .output();
+    }
+    // Windows has no pkill; match command lines the same way, minus our own
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // ccb.cmd runs node on cc-bridge\dist\cli\index.js, which is what has to go
+        let script = r#"Get-CimInstance Win32_Process | Where-Object { $_.ProcessId -ne $PID -and $_.CommandLine -match '(ccb(\.cmd)?|cc-bridge(\\dist\\cli\\index\.js)?)"? start' } | ForEach-Object { taskkill /PID $_.ProcessId /T /F }"#;
+        let _ = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .creation_flags(process_limits::CREATE_NO_WINDOW)
+            .output();
+    }
+
+    // Wait a moment then verify it's stopped
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    // Check if API is still responding
+    let still_running = api.status().await.is_ok();
+
+    if still_running {
+        let mut service = state.lock().map_err(|e| e.to_string())?;
+        service.logs.warn(i18n::t("service.may_be_running"));
+    }
+    cache.request_refresh();
+
+    Ok(!still_running)
+}
+
+#[tauri::command]
+pub(crate) fn get_status(cache: State<'_, StatusCache>) -> CommandResult<CachedStatus> {
+    Ok(cache.cached())
+}
+
+#[tauri::command]
+pub(crate) fn is_service_running(state: State<'_, AppState>) -> bool {
+    state.lock().map(|s| s.is_running).unwrap_or(false)
+}
+
+#[tauri::command]
+pub(crate) fn get_logs(state: State<'_, AppState>) -> Vec<String> {
+    state.lock().map(|s| s.logs.lines()).unwrap_or_default()
+}
+
+#[tauri::command]
+pub(crate) fn clear_logs(state: State<'_, AppState>) {
+    if let Ok(mut s) = state.lock() {
+        s.logs.clear();
+    }
+}
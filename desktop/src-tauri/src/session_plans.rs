@@ -0,0 +1,148 @@
+// Live task lists of running sessions.
+//
+// Claude Code keeps a todo list while it works through a multi-step task,
+// rewriting it with the TodoWrite tool as items start and finish. Showing
+// that list next to a session lets the operator follow progress without
+// scrolling through the chat. Bridges that serve a session's plan are asked
+// directly and push `session.plan` events when it changes; for the rest the
+// latest TodoWrite call is read from the local transcript, which is then
+// watched for newer ones while someone is looking. Either way changes are
+// emitted as `bridge://session-plan`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::error::{CommandResult, Error};
+use crate::sessions;
+use crate::transcripts::{read_appended_lines, transcript_file};
+
+pub(crate) const SESSION_PLAN_EVENT: &str = "bridge://session-plan";
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+// Stop watching a transcript that hasn't changed for this long
+const WATCH_IDLE: Duration = Duration::from_secs(10 * 60);
+
+// Sessions whose transcripts are being watched
+static WATCHING: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanItem {
+    content: String,
+    // "pending", "in_progress" or "completed"
+    status: String,
+    // Present-tense wording shown while the item is in progress
+    #[serde(default)]
+    active_form: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPlan {
+    #[serde(default)]
+    session_id: String,
+    items: Vec<PlanItem>,
+    #[serde(default)]
+    updated_at: Option<String>,
+}
+
+// Forwarded from the bridge's event stream
+pub(crate) fn emit_plan(app: &AppHandle, payload: serde_json::Value) {
+    let _ = app.emit(SESSION_PLAN_EVENT, payload);
+}
+
+// The todo list from a transcript line, if it's a TodoWrite call
+fn plan_from_line(line: &str) -> Option<(Vec<PlanItem>, Option<String>)> {
+    let entry: serde_json::Value = serde_json::from_str(line).ok()?;
+    if entry.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return None;
+    }
+    let call = entry
+        .pointer("/message/content")?
+        .as_array()?
+        .iter()
+        .rev()
+        .find(|block| {
+            block.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                && block.get("name").and_then(|n| n.as_str()) == Some("TodoWrite")
+        })?;
+    let items = serde_json::from_value(call.pointer("/input/todos")?.clone()).ok()?;
+    let timestamp = entry.get("timestamp").and_then(|t| t.as_str()).map(String::from);
+    Some((items, timestamp))
+}
+
+// The latest plan in the lines appended since `offset`
+fn read_plan(path: &Path, offset: &mut u64) -> Option<(Vec<PlanItem>, Option<String>)> {
+    read_appended_lines(path, offset).iter().rev().find_map(|line| plan_from_line(line))
+}
+
+// Emit the plan whenever a newer TodoWrite call lands in the transcript
+async fn watch_transcript(app: AppHandle, session_id: String, path: PathBuf, mut offset: u64) {
+    let mut last_change = Instant::now();
+    while last_change.elapsed() < WATCH_IDLE {
+        tokio::time::sleep(WATCH_INTERVAL).await;
+        if let Some((items, updated_at)) = read_plan(&path, &mut offset) {
+            last_change = Instant::now();
+            let plan = SessionPlan {
+                session_id: session_id.clone(),
+                items,
+                updated_at,
+            };
+            let _ = app.emit(SESSION_PLAN_EVENT, &plan);
+        }
+    }
+    if let Ok(mut watching) = WATCHING.lock() {
+        watching.get_or_insert_with(HashSet::new).remove(&session_id);
+    }
+}
+
+// The plan from Claude Code's transcript of the session, with a watcher
+// started for later changes
+async fn local_plan(app: AppHandle, api: &BridgeApiClient, session_id: &str) -> CommandResult<SessionPlan> {
+    let summary = sessions::list(api)
+        .await?
+        .into_iter()
+        .find(|s| s.id == session_id)
+        .ok_or_else(|| Error::Invalid(format!("Session {} not found", session_id)))?;
+    let path = summary.sdk_session_id.as_deref().and_then(transcript_file).ok_or_else(|| {
+        Error::Bridge("This bridge version doesn't report plans and the session's transcript isn't on this machine".to_string())
+    })?;
+
+    let mut offset = 0;
+    let (items, updated_at) = read_plan(&path, &mut offset).unwrap_or_default();
+    let first_watch = WATCHING
+        .lock()
+        .map(|mut watching| watching.get_or_insert_with(HashSet::new).insert(session_id.to_string()))
+        .unwrap_or(false);
+    if first_watch {
+        tauri::async_runtime::spawn(watch_transcript(app, session_id.to_string(), path, offset));
+    }
+    Ok(SessionPlan {
+        session_id: session_id.to_string(),
+        items,
+        updated_at,
+    })
+}
+
+// Empty when the session hasn't made a plan
+#[tauri::command]
+pub(crate) async fn get_session_plan(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    session_id: String,
+) -> CommandResult<SessionPlan> {
+    match api.get::<SessionPlan>(&format!("/sessions/{}/plan", session_id)).await {
+        Ok(mut plan) => {
+            plan.session_id = session_id;
+            Ok(plan)
+        }
+        Err(ApiError::Unsupported(_)) => local_plan(app, &api, &session_id).await,
+        Err(ApiError::NotFound(_)) => Err(Error::Invalid(format!("Session {} not found", session_id))),
+        Err(e) => Err(e.into()),
+    }
+}
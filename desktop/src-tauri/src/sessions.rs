@@ -0,0 +1,219 @@
+// Session browser.
+//
+// Individual conversations as the Control API lists them, with the channel
+// taken from the chat key and the linked identity filled in. Bridges that
+// don't report a message count or serve transcripts fall back to Claude
+// Code's own transcript of the session, when it's on this machine; those
+// counts are kept per file and only redone when the file changes. Ending a
+// session lets the operator cut off a runaway or unwanted conversation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::State;
+
+use crate::api_client::{ApiError, BridgeApiClient, SessionSummary};
+use crate::config::ConfigStore;
+use crate::identities;
+use crate::poller::StatusCache;
+use crate::transcripts::{message_text, read_appended_lines, transcript_file};
+
+// The detail view shows the tail; `stream_transcript` has the whole thing
+const MAX_DETAIL_MESSAGES: usize = 200;
+
+// Message counts of local transcripts, by file
+static COUNTS: Mutex<Option<HashMap<PathBuf, CachedCount>>> = Mutex::new(None);
+
+struct CachedCount {
+    modified: Option<SystemTime>,
+    len: u64,
+    count: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    id: String,
+    chat_key: String,
+    session_name: Option<String>,
+    agent_id: Option<String>,
+    channel: String,
+    status: String,
+    message_count: Option<u32>,
+    created_at: String,
+    last_activity: String,
+    identity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDetail {
+    #[serde(flatten)]
+    session: SessionInfo,
+    // Oldest first
+    messages: Vec<serde_json::Value>,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SessionsResponse {
+    sessions: Vec<SessionSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MessagesResponse {
+    messages: Vec<serde_json::Value>,
+}
+
+// User and assistant turns from the local transcript of `sdk_session_id`
+fn local_messages(sdk_session_id: &str) -> Option<Vec<serde_json::Value>> {
+    let path = transcript_file(sdk_session_id)?;
+    let messages = read_appended_lines(&path, &mut 0)
+        .iter()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter_map(|entry| {
+            let role = entry.get("type").and_then(|t| t.as_str()).filter(|t| *t == "user" || *t == "assistant")?;
+            let text = entry.get("message").and_then(message_text)?;
+            Some(serde_json::json!({
+                "role": role,
+                "content": text,
+                "timestamp": entry.get("timestamp"),
+            }))
+        })
+        .collect();
+    Some(messages)
+}
+
+// Transcripts only grow, so an unchanged mtime and size means an unchanged count
+fn local_message_count(sdk_session_id: &str) -> Option<u32> {
+    let path = transcript_file(sdk_session_id)?;
+    let metadata = fs::metadata(&path).ok()?;
+    let (modified, len) = (metadata.modified().ok(), metadata.len());
+    if let Ok(mut counts) = COUNTS.lock() {
+        let cached = counts.get_or_insert_with(HashMap::new).get(&path);
+        if let Some(cached) = cached.filter(|c| c.modified == modified && c.len == len) {
+            return Some(cached.count);
+        }
+    }
+
+    let count = local_messages(sdk_session_id)?.len() as u32;
+    if let Ok(mut counts) = COUNTS.lock() {
+        counts
+            .get_or_insert_with(HashMap::new)
+            .insert(path, CachedCount { modified, len, count });
+    }
+    Some(count)
+}
+
+// Forget transcripts of sessions the bridge no longer lists
+fn prune_counts(sessions: &[SessionSummary]) {
+    let listed: HashSet<PathBuf> = sessions
+        .iter()
+        .filter_map(|s| s.sdk_session_id.as_deref().and_then(transcript_file))
+        .collect();
+    if let Ok(mut counts) = COUNTS.lock() {
+        if let Some(counts) = counts.as_mut() {
+            counts.retain(|path, _| listed.contains(path));
+        }
+    }
+}
+
+fn session_info(config: &serde_json::Value, session: SessionSummary) -> SessionInfo {
+    let message_count = session
+        .message_count
+        .or_else(|| session.sdk_session_id.as_deref().and_then(local_message_count));
+    SessionInfo {
+        channel: session.chat_key.split(':').next().unwrap_or_default().to_string(),
+        identity: identities::label_for_chat(config, &session.chat_key),
+        message_count,
+        id: session.id,
+        chat_key: session.chat_key,
+        session_name: session.session_name,
+        agent_id: session.agent_id,
+        status: session.status,
+        created_at: session.created_at,
+        last_activity: session.last_active,
+    }
+}
+
+pub(crate) async fn list(api: &BridgeApiClient) -> Result<Vec<SessionSummary>, ApiError> {
+    Ok(api.get::<SessionsResponse>("/sessions").await?.sessions)
+}
+
+// Most recently active first
+#[tauri::command]
+pub(crate) async fn get_sessions(
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+) -> Result<Vec<SessionInfo>, String> {
+    let config = store.read()?.unwrap_or_default();
+    let summaries = list(&api).await?;
+    prune_counts(&summaries);
+    let mut sessions: Vec<SessionInfo> = summaries
+        .into_iter()
+        .map(|session| session_info(&config, session))
+        .collect();
+    sessions.sort_by(|a, b| b.last_activity.cmp(&a.last_activity));
+    Ok(sessions)
+}
+
+// The session's summary and its messages, oldest first
+pub(crate) async fn transcript(api: &BridgeApiClient, id: &str) -> Result<(SessionSummary, Vec<serde_json::Value>), String> {
+    let summary = list(api)
+        .await?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| format!("Session {} not found", id))?;
+
+    let messages = match api.get::<MessagesResponse>(&format!("/sessions/{}/messages", id)).await {
+        Ok(response) => response.messages,
+        Err(ApiError::Unsupported(_)) => summary
+            .sdk_session_id
+            .as_deref()
+            .and_then(local_messages)
+            .unwrap_or_default(),
+        Err(e) => return Err(e.into()),
+    };
+    Ok((summary, messages))
+}
+
+#[tauri::command]
+pub(crate) async fn get_session_detail(
+    api: State<'_, BridgeApiClient>,
+    store: State<'_, ConfigStore>,
+    id: String,
+) -> Result<SessionDetail, String> {
+    let config = store.read()?.unwrap_or_default();
+    let (summary, mut messages) = transcript(&api, &id).await?;
+    let total = messages.len();
+    messages.drain(..total.saturating_sub(MAX_DETAIL_MESSAGES));
+
+    let mut session = session_info(&config, summary);
+    session.message_count = session.message_count.or(Some(total as u32));
+    Ok(SessionDetail {
+        session,
+        truncated: total > messages.len(),
+        messages,
+    })
+}
+
+// False if the session had already ended
+#[tauri::command]
+pub(crate) async fn end_session(
+    api: State<'_, BridgeApiClient>,
+    cache: State<'_, StatusCache>,
+    id: String,
+) -> Result<bool, String> {
+    match api.delete(&format!("/sessions/{}", id)).await {
+        Ok(_) => {
+            cache.request_refresh();
+            Ok(true)
+        }
+        Err(ApiError::NotFound(_)) => Ok(false),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't end sessions".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
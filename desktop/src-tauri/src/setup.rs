@@ -0,0 +1,145 @@
+// First-run setup.
+//
+// A new user needs Node, the ccb package, a config.json with at least one
+// channel, and a signed-in Claude Code before the bridge does anything useful.
+// `get_setup_state` reports which of those are in place so the UI can show a
+// wizard instead of an empty panel, and `run_setup_step` performs the steps
+// the app can do on its own.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::State;
+
+use crate::ccb_install::{npm_install_global, CCB_PACKAGE};
+use crate::config::{expand_home, get_config_path, ConfigStore};
+use crate::error::{CommandResult, Error};
+use crate::runtime_info::{claude_logged_in, find_executable, probe_output};
+use crate::self_test::{check_discord_token, check_telegram_token};
+use crate::service::AppState;
+use crate::{config_validation, http};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupState {
+    node_installed: bool,
+    node_version: Option<String>,
+    npm_installed: bool,
+    ccb_installed: bool,
+    config_exists: bool,
+    // An enabled channel with at least one bot
+    channel_configured: bool,
+    claude_installed: bool,
+    claude_logged_in: bool,
+    // Everything needed to start the bridge is in place
+    complete: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "step", rename_all = "camelCase")]
+pub enum SetupStep {
+    // `npm install -g claude-code-bridge`
+    InstallCcb,
+    // A config.json with a single agent and no channels yet
+    #[serde(rename_all = "camelCase")]
+    CreateConfig { workspace: Option<String> },
+    // Check a bot token with its platform before it's saved
+    TestToken { channel: String, token: String },
+}
+
+fn channel_configured(config: &serde_json::Value) -> bool {
+    [("telegram", "botToken"), ("discord", "token")].iter().any(|(channel, token_key)| {
+        let Some(section) = config.get("channels").and_then(|c| c.get(channel)) else {
+            return false;
+        };
+        let has_token = section.get(token_key).and_then(|t| t.as_str()).is_some_and(|t| !t.is_empty());
+        let has_bots = section.get("bots").and_then(|b| b.as_array()).is_some_and(|b| !b.is_empty());
+        section.get("enabled").and_then(|e| e.as_bool()) == Some(true) && (has_token || has_bots)
+    })
+}
+
+fn default_config(workspace: &str) -> serde_json::Value {
+    serde_json::json!({
+        "agents": {
+            "default": "claude",
+            "list": [{
+                "id": "claude",
+                "name": "Claude",
+                "workspace": workspace
+            }]
+        },
+        "channels": {}
+    })
+}
+
+fn create_config(store: &ConfigStore, workspace: Option<String>) -> CommandResult<String> {
+    let path = get_config_path();
+    if path.exists() {
+        return Err(Error::Invalid(format!("{} already exists", path.display())));
+    }
+    let workspace = match workspace {
+        Some(workspace) => workspace,
+        None => dirs::home_dir()
+            .ok_or("Could not find home directory")?
+            .to_string_lossy()
+            .to_string(),
+    };
+    if !expand_home(&workspace).is_dir() {
+        return Err(Error::Invalid(format!("{} is not a directory", workspace)));
+    }
+
+    let config = default_config(&workspace);
+    config_validation::ensure_valid(&config)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    store.write(&config)?;
+    Ok(format!("Created {}", path.display()))
+}
+
+#[tauri::command]
+pub(crate) async fn get_setup_state(store: State<'_, ConfigStore>) -> CommandResult<SetupState> {
+    let node = find_executable("node");
+    let node_version = match node {
+        Some(ref node) => probe_output(node, &["--version"]).await,
+        None => None,
+    };
+    let config = store.read()?;
+    let ccb_installed = find_executable("ccb").is_some();
+    let claude_installed = find_executable("claude").is_some();
+    let logged_in = claude_logged_in();
+    let channel_configured = config.as_ref().is_some_and(channel_configured);
+
+    Ok(SetupState {
+        node_installed: node.is_some(),
+        node_version,
+        npm_installed: find_executable("npm").is_some(),
+        ccb_installed,
+        config_exists: config.is_some(),
+        channel_configured,
+        claude_installed,
+        claude_logged_in: logged_in,
+        complete: node.is_some() && ccb_installed && channel_configured && claude_installed && logged_in,
+    })
+}
+
+// Returns what was done, e.g. the bot's name for a token test
+#[tauri::command]
+pub(crate) async fn run_setup_step(
+    store: State<'_, ConfigStore>,
+    state: State<'_, AppState>,
+    step: SetupStep,
+) -> CommandResult<String> {
+    match step {
+        SetupStep::InstallCcb => npm_install_global(&state, CCB_PACKAGE).await,
+        SetupStep::CreateConfig { workspace } => create_config(&store, workspace),
+        SetupStep::TestToken { channel, token } => {
+            let client = http::client();
+            let name = match channel.as_str() {
+                "telegram" => check_telegram_token(&client, token.trim()).await?,
+                "discord" => check_discord_token(&client, token.trim()).await?,
+                _ => return Err(Error::Invalid(format!("Unknown channel '{}'", channel))),
+            };
+            Ok(format!("Token belongs to {}", name))
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Stopping the bridge when the app quits.
+//
+// Quitting from the tray, the app menu or by closing the last window all end
+// in an exit request. If the app started the bridge, the exit is held back
+// while the bridge goes through the same stop as the Stop button (Control
+// API, kill, wait, verify), bounded so a hung bridge can't keep the app from
+// quitting. With "keep bridge running on quit" set, the bridge is left alone.
+// Should the exit happen anyway, the child is killed as a last resort rather
+// than leaked. Relaunching into an app update stops the bridge the same way
+// before the new version is installed.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, ExitRequestApi, Manager};
+
+use crate::app_settings::{self, DEFAULT_INSTANCE};
+use crate::bridge_endpoints;
+use crate::log_forwarding;
+use crate::poller::StatusCache;
+use crate::service::{self, AppState};
+
+// Longest the app waits for the bridge before quitting regardless
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Set once the bridge has been dealt with, so the exit that follows goes through
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+// Whether quitting has to stop a bridge this app started
+fn owns_bridge(app: &AppHandle) -> bool {
+    if app_settings::current().keep_bridge_on_quit {
+        return false;
+    }
+    let state = app.state::<AppState>();
+    let owns = state.lock().map(|s| s.process.is_some()).unwrap_or(false);
+    owns
+}
+
+async fn stop_bridge(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let cache = app.state::<StatusCache>();
+    // The local bridge, even while a remote endpoint is active
+    let api = match bridge_endpoints::api_for(DEFAULT_INSTANCE) {
+        Ok(api) => api,
+        Err(e) => {
+            log_forwarding::desktop_error(format!("Failed to reach the bridge on quit: {}", e));
+            kill_child(&state);
+            return;
+        }
+    };
+    match tokio::time::timeout(SHUTDOWN_TIMEOUT, service::stop(&api, &state, &cache)).await {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => log_forwarding::desktop_error("Bridge still answers after quitting".to_string()),
+        Ok(Err(e)) => log_forwarding::desktop_error(format!("Failed to stop the bridge on quit: {}", e)),
+        Err(_) => {
+            log_forwarding::desktop_error("Bridge didn't stop in time on quit".to_string());
+            kill_child(&state);
+        }
+    }
+}
+
+// Before installing an app update and relaunching; returns whether a bridge
+// was stopped, so it can be started again should the install fail
+pub(crate) async fn before_relaunch(app: &AppHandle) -> bool {
+    if !owns_bridge(app) {
+        return false;
+    }
+    stop_bridge(app).await;
+    true
+}
+
+// Kill without waiting; the OS reaps the process once the app is gone
+fn kill_child(state: &AppState) {
+    let child = state.lock().ok().and_then(|mut s| s.process.take());
+    if let Some(mut child) = child {
+        service::kill_tree(&mut child);
+    }
+}
+
+// RunEvent::ExitRequested
+pub(crate) fn on_exit_requested(app: &AppHandle, api: &ExitRequestApi, code: Option<i32>) {
+    if STOPPED.load(Ordering::SeqCst) || !owns_bridge(app) {
+        return;
+    }
+    api.prevent_exit();
+    // Stopping can take a few seconds; don't leave the window up meanwhile
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        stop_bridge(&app).await;
+        STOPPED.store(true, Ordering::SeqCst);
+        app.exit(code.unwrap_or(0));
+    });
+}
+
+// RunEvent::Exit, the last chance before the process ends
+pub(crate) fn on_exit(app: &AppHandle) {
+    if !app_settings::current().keep_bridge_on_quit {
+        kill_child(&app.state::<AppState>());
+    }
+}
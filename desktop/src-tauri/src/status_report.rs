@@ -0,0 +1,230 @@
+// Shareable status report.
+//
+// Renders a single self-contained HTML file (inline CSS and SVG, no scripts or
+// external assets) with the bridge status, channel health, agents, usage over
+// the last month and recent errors. Tokens, prompts, MCP server env vars and
+// other secrets are left out, and any token that shows up in a log line is
+// redacted, so the file can be attached to an incident.
+
+use std::fmt::Write as _;
+use std::fs;
+use tauri::State;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::poller::StatusCache;
+use crate::service::AppState;
+use crate::{crash_reports, secrets, usage};
+
+const RECENT_ERROR_LINES: usize = 30;
+const RECENT_CRASHES: usize = 5;
+
+const STYLE: &str = "body{font:14px -apple-system,system-ui,sans-serif;max-width:880px;margin:32px auto;padding:0 16px;color:#1f2328}\
+h1{font-size:22px}h2{font-size:16px;margin-top:28px;border-bottom:1px solid #d0d7de;padding-bottom:4px}\
+table{border-collapse:collapse;width:100%}td,th{text-align:left;padding:4px 8px;border-bottom:1px solid #eaeef2}\
+.ok{color:#1a7f37}.bad{color:#cf222e}.muted{color:#656d76}pre{background:#f6f8fa;padding:8px;overflow-x:auto;font-size:12px}";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn badge(ok: bool, yes: &str, no: &str) -> String {
+    if ok {
+        format!("<span class=\"ok\">{}</span>", yes)
+    } else {
+        format!("<span class=\"bad\">{}</span>", no)
+    }
+}
+
+// Simple bar chart; labels are dates, so only the first and last are drawn
+fn bar_chart(title: &str, points: &[(String, f64)], format_value: impl Fn(f64) -> String) -> String {
+    if points.is_empty() {
+        return format!("<p class=\"muted\">No {} recorded.</p>", escape(&title.to_lowercase()));
+    }
+    let (width, height, bottom) = (840.0, 160.0, 20.0);
+    let max = points.iter().map(|(_, v)| *v).fold(0.0, f64::max).max(f64::EPSILON);
+    let slot = width / points.len() as f64;
+
+    let mut svg = format!(
+        "<h3>{}</h3><svg width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"{}\">",
+        escape(title),
+        width,
+        height + bottom,
+        escape(title)
+    );
+    for (i, (label, value)) in points.iter().enumerate() {
+        let bar = value / max * (height - 16.0);
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#0969da\"><title>{}: {}</title></rect>",
+            i as f64 * slot + 2.0,
+            height - bar,
+            (slot - 4.0).max(1.0),
+            bar,
+            escape(label),
+            escape(&format_value(*value))
+        );
+    }
+    let first = &points[0].0;
+    let last = &points[points.len() - 1].0;
+    let _ = write!(
+        svg,
+        "<text x=\"0\" y=\"{}\" font-size=\"11\" fill=\"#656d76\">{}</text>\
+         <text x=\"{}\" y=\"{}\" font-size=\"11\" fill=\"#656d76\" text-anchor=\"end\">{}</text>\
+         <text x=\"0\" y=\"11\" font-size=\"11\" fill=\"#656d76\">max {}</text></svg>",
+        height + 14.0,
+        escape(first),
+        width,
+        height + 14.0,
+        escape(last),
+        escape(&format_value(max))
+    );
+    svg
+}
+
+fn render(config: Option<&serde_json::Value>, cache: &StatusCache, logs: &[String]) -> String {
+    let mut html = String::new();
+    let generated = chrono::Local::now().format("%Y-%m-%d %H:%M %Z").to_string();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>CCB status report</title><style>{}</style></head><body>\
+         <h1>CCB status report</h1><p class=\"muted\">Generated {} by CCB Desktop {}</p>",
+        STYLE,
+        escape(&generated),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // Bridge and channels
+    html.push_str("<h2>Bridge</h2>");
+    match cache.status() {
+        Some(status) => {
+            let _ = write!(
+                html,
+                "<p>{} &middot; up {}h {}m &middot; {} active of {} sessions &middot; {} pending pairings</p>",
+                badge(status.running, "Running", "Not running"),
+                status.uptime / 3600,
+                status.uptime % 3600 / 60,
+                status.sessions.active,
+                status.sessions.total,
+                status.pairings.pending
+            );
+            html.push_str("<table><tr><th>Channel</th><th>Enabled</th><th>Connection</th><th>Bots</th></tr>");
+            for channel in &status.channels {
+                let bots = channel
+                    .bots
+                    .iter()
+                    .map(|b| {
+                        let name = b.username.as_deref().map(|u| format!("@{}", u)).unwrap_or_else(|| b.id.clone());
+                        match b.agent_id {
+                            Some(ref agent) => format!("{} &rarr; {}", escape(&name), escape(agent)),
+                            None => escape(&name),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = write!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&channel.name),
+                    if channel.enabled { "yes" } else { "no" },
+                    badge(channel.connected, "Connected", "Disconnected"),
+                    bots
+                );
+            }
+            html.push_str("</table>");
+        }
+        None => html.push_str("<p class=\"bad\">The Control API is not responding.</p>"),
+    }
+
+    // Agents, without prompts or MCP credentials
+    html.push_str("<h2>Agents</h2><table><tr><th>ID</th><th>Name</th><th>Workspace</th><th>Model</th><th>Permissions</th><th>MCP servers</th></tr>");
+    let agents = config
+        .and_then(|c| c.get("agents"))
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array());
+    for agent in agents.into_iter().flatten() {
+        let field = |name: &str| escape(agent.get(name).and_then(|v| v.as_str()).unwrap_or("-"));
+        let mcp = agent
+            .get("mcpServers")
+            .and_then(|m| m.as_array())
+            .map(|servers| {
+                servers
+                    .iter()
+                    .filter_map(|s| s.get("name").and_then(|n| n.as_str()))
+                    .map(escape)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            field("id"),
+            field("name"),
+            field("workspace"),
+            field("model"),
+            field("permissionMode"),
+            mcp
+        );
+    }
+    html.push_str("</table>");
+
+    // Usage over the last month
+    html.push_str("<h2>Usage (last 30 days)</h2>");
+    let daily = usage::recent_daily_usage();
+    let messages: Vec<(String, f64)> = daily.iter().map(|(d, m, _)| (d.clone(), *m as f64)).collect();
+    let cost: Vec<(String, f64)> = daily.iter().map(|(d, _, c)| (d.clone(), *c)).collect();
+    html.push_str(&bar_chart("Messages per day", &messages, |v| format!("{:.0}", v)));
+    html.push_str(&bar_chart("Estimated cost per day", &cost, |v| format!("${:.2}", v)));
+
+    // Recent errors
+    html.push_str("<h2>Recent errors</h2>");
+    let crashes = crash_reports::list_crash_reports().unwrap_or_default();
+    for crash in crashes.iter().take(RECENT_CRASHES) {
+        let crash = serde_json::to_value(crash).unwrap_or_default();
+        let _ = write!(
+            html,
+            "<p><strong>{}</strong> <span class=\"muted\">{}</span></p>",
+            escape(crash["message"].as_str().unwrap_or_default()),
+            escape(crash["timestamp"].as_str().unwrap_or_default())
+        );
+    }
+    let error_lines: Vec<&String> = logs
+        .iter()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("error") || lower.contains("warn") || lower.contains("fail")
+        })
+        .collect();
+    let error_lines = &error_lines[error_lines.len().saturating_sub(RECENT_ERROR_LINES)..];
+    if error_lines.is_empty() && crashes.is_empty() {
+        html.push_str("<p class=\"muted\">No recent errors.</p>");
+    } else if !error_lines.is_empty() {
+        let lines = error_lines.iter().map(|l| escape(l)).collect::<Vec<_>>().join("\n");
+        let _ = write!(html, "<pre>{}</pre>", lines);
+    }
+
+    html.push_str("</body></html>");
+    html
+}
+
+#[tauri::command]
+pub(crate) fn export_status_report(
+    store: State<'_, ConfigStore>,
+    cache: State<'_, StatusCache>,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<String, String> {
+    let config = store.read()?;
+    let logs = state.lock().map(|s| s.logs.lines()).unwrap_or_default();
+    let html = secrets::redact_text(&render(config.as_ref(), &cache, &logs));
+
+    let path = expand_home(&path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create report dir: {}", e))?;
+    }
+    fs::write(&path, html).map_err(|e| format!("Failed to write status report: {}", e))?;
+    Ok(path.to_string_lossy().to_string())
+}
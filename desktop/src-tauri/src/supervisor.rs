@@ -0,0 +1,210 @@
+// Watching the bridge process.
+//
+// Checks the child on an interval so a bridge that dies on its own is shown
+// as stopped right away instead of "running" until the next start. A crash
+// emits `bridge://crashed` and, when auto-restart is on, schedules a restart
+// with exponential backoff, giving up after `maxRestarts` crashes in a row.
+// A bridge that stayed up for a while starts over with a full budget.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::config::get_desktop_data_dir;
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+use crate::poller::StatusCache;
+use crate::service::{self, AppState, StartLock};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+// Running this long counts as recovered
+const STABLE_AFTER: Duration = Duration::from_secs(10 * 60);
+
+pub(crate) const CRASHED_EVENT: &str = "bridge://crashed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisorSettings {
+    auto_restart: bool,
+    max_restarts: u32,
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+}
+
+impl Default for SupervisorSettings {
+    fn default() -> Self {
+        Self {
+            auto_restart: false,
+            max_restarts: 5,
+            initial_backoff_secs: 5,
+            max_backoff_secs: 300,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BridgeCrashed {
+    exit_code: Option<i32>,
+    message: String,
+    restarts: u32,
+    // None when auto-restart is off or the cap was reached
+    restart_in_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisorStatus {
+    restarts: u32,
+    max_restarts: u32,
+    restart_in_secs: Option<u64>,
+}
+
+fn get_settings_path() -> PathBuf {
+    get_desktop_data_dir().join("supervisor.json")
+}
+
+fn load_settings() -> SupervisorSettings {
+    fs::read_to_string(get_settings_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn backoff(settings: &SupervisorSettings, restarts: u32) -> Duration {
+    let secs = settings
+        .initial_backoff_secs
+        .max(1)
+        .saturating_mul(1 << restarts.min(16))
+        .min(settings.max_backoff_secs.max(1));
+    Duration::from_secs(secs)
+}
+
+// Notice a bridge that exited without being asked to; returns whether it had.
+// Called on every tick and by the wake handler.
+pub(crate) fn check_exit(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    let crash = {
+        let Ok(mut service) = state.lock() else {
+            return false;
+        };
+        if !service.is_running {
+            return false;
+        }
+        // The exited child is left in place so the crash report still finds it
+        let Some(Ok(Some(status))) = service.process.as_mut().map(|c| c.try_wait()) else {
+            return false;
+        };
+        service.is_running = false;
+
+        if status.success() {
+            service.logs.info(i18n::tr("service.exited", &[("status", &status.to_string())]));
+            None
+        } else {
+            let message = i18n::tr("service.crashed", &[("status", &status.to_string())]);
+            service.logs.error(message.clone());
+            service.crashed = true;
+            let settings = load_settings();
+            if service.started_at.is_some_and(|t| t.elapsed() >= STABLE_AFTER) {
+                service.restarts = 0;
+            }
+
+            let delay = if !settings.auto_restart {
+                None
+            } else if service.restarts >= settings.max_restarts {
+                service.logs.error(i18n::tr("service.restart_limit", &[("max", &settings.max_restarts.to_string())]));
+                None
+            } else {
+                let delay = backoff(&settings, service.restarts);
+                service.restarts += 1;
+                let attempt = service.restarts.to_string();
+                service.logs.warn(i18n::tr(
+                    "service.restarting",
+                    &[
+                        ("seconds", &delay.as_secs().to_string()),
+                        ("attempt", &attempt),
+                        ("max", &settings.max_restarts.to_string()),
+                    ],
+                ));
+                Some(delay)
+            };
+            service.restart_at = delay.map(|d| Instant::now() + d);
+            Some(BridgeCrashed {
+                exit_code: status.code(),
+                message,
+                restarts: service.restarts,
+                restart_in_secs: delay.map(|d| d.as_secs()),
+            })
+        }
+    };
+
+    if let Some(crash) = crash {
+        notifications::record(app, NotificationCategory::Bridge, &i18n::t("notify.bridge_crashed"), &crash.message);
+        let _ = app.emit(CRASHED_EVENT, crash);
+    }
+    app.state::<StatusCache>().request_refresh();
+    true
+}
+
+// Take the due restart, if any; a start or stop in the meantime cancels it
+fn take_due_restart(app: &AppHandle) -> Option<u32> {
+    let state = app.state::<AppState>();
+    let mut service = state.lock().ok()?;
+    if service.restart_at.is_some_and(|t| t <= Instant::now()) {
+        service.restart_at = None;
+        Some(service.restarts)
+    } else {
+        None
+    }
+}
+
+pub(crate) async fn run(app: AppHandle) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+        check_exit(&app);
+
+        let Some(restarts) = take_due_restart(&app) else {
+            continue;
+        };
+        let state = app.state::<AppState>();
+        // A failed start leaves an exited child behind, which the next tick
+        // counts as another crash
+        let _ = service::launch(&app, &state, &app.state::<StatusCache>(), &app.state::<StartLock>()).await;
+        if let Ok(mut service) = state.lock() {
+            service.restarts = restarts;
+        };
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_supervisor_settings() -> SupervisorSettings {
+    load_settings()
+}
+
+#[tauri::command]
+pub(crate) fn set_supervisor_settings(settings: SupervisorSettings) -> Result<bool, String> {
+    if settings.max_backoff_secs < settings.initial_backoff_secs {
+        return Err("Maximum backoff must not be shorter than the initial backoff".to_string());
+    }
+    let path = get_settings_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save supervisor settings: {}", e))?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn get_supervisor_status(state: State<'_, AppState>) -> Result<SupervisorStatus, String> {
+    let service = state.lock().map_err(|e| e.to_string())?;
+    Ok(SupervisorStatus {
+        restarts: service.restarts,
+        max_restarts: load_settings().max_restarts,
+        restart_in_secs: service
+            .restart_at
+            .map(|t| t.saturating_duration_since(Instant::now()).as_secs()),
+    })
+}
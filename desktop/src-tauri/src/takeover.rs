@@ -0,0 +1,111 @@
+// Human takeover of a conversation.
+//
+// Taking over a session tells the bridge to stop running the agent for that
+// chat; incoming messages are held for the operator, who replies by hand until
+// the session is released back to the agent.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+use crate::templates::send_session_message;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Takeover {
+    session_id: String,
+    chat_key: Option<String>,
+    started_at: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TakeoverResponse {
+    chat_key: Option<String>,
+}
+
+// Sessions currently handled by a human, keyed by session id
+#[derive(Default)]
+pub(crate) struct Takeovers(Mutex<HashMap<String, Takeover>>);
+
+async fn set_takeover(api: &BridgeApiClient, session_id: &str, active: bool) -> Result<TakeoverResponse, String> {
+    let path = format!("/sessions/{}/takeover", session_id);
+    let result = if active { api.post(&path, None).await } else { api.delete(&path).await };
+    match result {
+        Ok(response) => Ok(response.json().await.unwrap_or_default()),
+        Err(ApiError::NotFound(_)) => Err(format!("Session '{}' not found", session_id)),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version doesn't support takeover".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn request_takeover(
+    app: AppHandle,
+    api: State<'_, BridgeApiClient>,
+    takeovers: State<'_, Takeovers>,
+    session_id: String,
+) -> Result<Takeover, String> {
+    let response = set_takeover(&api, &session_id, true).await?;
+    let takeover = Takeover {
+        session_id: session_id.clone(),
+        chat_key: response.chat_key,
+        started_at: chrono::Local::now().to_rfc3339(),
+    };
+    takeovers
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(session_id, takeover.clone());
+
+    notifications::notify(
+        &app,
+        NotificationCategory::Takeover,
+        &i18n::t("takeover.title"),
+        &i18n::tr(
+            "takeover.body",
+            &[("chat", takeover.chat_key.as_deref().unwrap_or(&takeover.session_id))],
+        ),
+    );
+
+    Ok(takeover)
+}
+
+#[tauri::command]
+pub(crate) async fn release_takeover(
+    api: State<'_, BridgeApiClient>,
+    takeovers: State<'_, Takeovers>,
+    session_id: String,
+) -> Result<bool, String> {
+    set_takeover(&api, &session_id, false).await?;
+    takeovers.0.lock().map_err(|e| e.to_string())?.remove(&session_id);
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn list_takeovers(takeovers: State<'_, Takeovers>) -> Result<Vec<Takeover>, String> {
+    let takeovers = takeovers.0.lock().map_err(|e| e.to_string())?;
+    Ok(takeovers.values().cloned().collect())
+}
+
+// Reply as the operator while a session is taken over
+#[tauri::command]
+pub(crate) async fn send_takeover_reply(
+    api: State<'_, BridgeApiClient>,
+    takeovers: State<'_, Takeovers>,
+    session_id: String,
+    text: String,
+) -> Result<bool, String> {
+    if text.trim().is_empty() {
+        return Err("Reply text cannot be empty".to_string());
+    }
+    if !takeovers.0.lock().map_err(|e| e.to_string())?.contains_key(&session_id) {
+        return Err(format!("Session '{}' is not taken over", session_id));
+    }
+    send_session_message(&api, &session_id, &text).await?;
+    Ok(true)
+}
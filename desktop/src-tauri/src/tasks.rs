@@ -0,0 +1,182 @@
+// Registry for long-running, cancellable operations.
+//
+// Commands that may take a while start a task and return its id right away.
+// The task reports progress on `task://progress` and its outcome on
+// `task://finished`; `cancel_task` trips its cancellation token, which drops
+// the task's future at the next await point (child processes spawned with
+// `kill_on_drop` go with it).
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio_util::sync::CancellationToken;
+
+pub(crate) const TASK_PROGRESS_EVENT: &str = "task://progress";
+pub(crate) const TASK_FINISHED_EVENT: &str = "task://finished";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    id: String,
+    kind: String,
+    label: String,
+    started_at: String,
+    // 0.0 to 1.0 when the task can tell
+    progress: Option<f64>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskOutcome {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskFinished {
+    id: String,
+    kind: String,
+    outcome: TaskOutcome,
+    result: Option<serde_json::Value>,
+    error: Option<String>,
+}
+
+struct TaskEntry {
+    info: TaskInfo,
+    token: CancellationToken,
+}
+
+// Managed state
+#[derive(Default)]
+pub(crate) struct TaskRegistry(Mutex<HashMap<String, TaskEntry>>);
+
+// Given to the task body for reporting progress and checking for cancellation
+#[derive(Clone)]
+pub(crate) struct TaskHandle {
+    id: String,
+    app: AppHandle,
+    token: CancellationToken,
+}
+
+impl TaskHandle {
+    pub(crate) fn progress(&self, progress: Option<f64>, message: impl Into<String>) {
+        let message = message.into();
+        let registry = self.app.state::<TaskRegistry>();
+        let info = registry.0.lock().ok().and_then(|mut tasks| {
+            let entry = tasks.get_mut(&self.id)?;
+            entry.info.progress = progress.map(|p| p.clamp(0.0, 1.0));
+            entry.info.message = Some(message);
+            Some(entry.info.clone())
+        });
+        if let Some(info) = info {
+            let _ = self.app.emit(TASK_PROGRESS_EVENT, info);
+        }
+    }
+
+    // For blocking work, which can't be dropped mid-way like a future
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+// Run `body` in the background and return the new task's id
+pub(crate) fn spawn_task<F, Fut>(app: &AppHandle, kind: &str, label: impl Into<String>, body: F) -> Result<String, String>
+where
+    F: FnOnce(TaskHandle) -> Fut,
+    Fut: Future<Output = Result<serde_json::Value, String>> + Send + 'static,
+{
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let token = CancellationToken::new();
+
+    let id = {
+        let registry = app.state::<TaskRegistry>();
+        let mut tasks = registry.0.lock().map_err(|e| e.to_string())?;
+        let mut id = format!("task-{}", millis);
+        // Several tasks started in the same millisecond
+        let mut n = 1;
+        while tasks.contains_key(&id) {
+            id = format!("task-{}-{}", millis, n);
+            n += 1;
+        }
+        tasks.insert(
+            id.clone(),
+            TaskEntry {
+                info: TaskInfo {
+                    id: id.clone(),
+                    kind: kind.to_string(),
+                    label: label.into(),
+                    started_at: chrono::Local::now().to_rfc3339(),
+                    progress: None,
+                    message: None,
+                },
+                token: token.clone(),
+            },
+        );
+        id
+    };
+
+    let future = body(TaskHandle {
+        id: id.clone(),
+        app: app.clone(),
+        token: token.clone(),
+    });
+    let app = app.clone();
+    let task_id = id.clone();
+    let kind = kind.to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let result = tokio::select! {
+            _ = token.cancelled() => None,
+            result = future => Some(result),
+        };
+
+        if let Ok(mut tasks) = app.state::<TaskRegistry>().0.lock() {
+            tasks.remove(&task_id);
+        }
+
+        let (outcome, result, error) = match result {
+            Some(Ok(value)) => (TaskOutcome::Succeeded, Some(value), None),
+            Some(Err(e)) => (TaskOutcome::Failed, None, Some(e)),
+            None => (TaskOutcome::Cancelled, None, None),
+        };
+        let _ = app.emit(
+            TASK_FINISHED_EVENT,
+            TaskFinished {
+                id: task_id,
+                kind,
+                outcome,
+                result,
+                error,
+            },
+        );
+    });
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub(crate) fn list_tasks(registry: State<'_, TaskRegistry>) -> Result<Vec<TaskInfo>, String> {
+    let tasks = registry.0.lock().map_err(|e| e.to_string())?;
+    let mut list: Vec<TaskInfo> = tasks.values().map(|t| t.info.clone()).collect();
+    list.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    Ok(list)
+}
+
+// Returns false when the task already finished
+#[tauri::command]
+pub(crate) fn cancel_task(registry: State<'_, TaskRegistry>, id: String) -> Result<bool, String> {
+    let tasks = registry.0.lock().map_err(|e| e.to_string())?;
+    match tasks.get(&id) {
+        Some(task) => {
+            task.token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
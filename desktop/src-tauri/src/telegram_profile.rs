@@ -0,0 +1,146 @@
+// Telegram bot profile.
+//
+// Sets a bot's display name, about text and command menu through the Bot API
+// instead of a round of /setname, /setabouttext and /setcommands with
+// @BotFather. The about text is used for both the profile and the
+// description shown before a user's first /start.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::State;
+
+use crate::config::ConfigStore;
+use crate::discord_commands::BRIDGE_COMMANDS;
+use crate::groups::bot_section;
+use crate::telegram_webhook::TELEGRAM_API;
+use crate::{http, keychain};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// Bot API limits
+const MAX_NAME_LEN: usize = 64;
+const MAX_ABOUT_LEN: usize = 120;
+const MAX_COMMAND_LEN: usize = 32;
+const MAX_COMMAND_DESCRIPTION_LEN: usize = 256;
+const MAX_COMMANDS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotCommand {
+    command: String,
+    description: String,
+}
+
+fn bot_token(config: &serde_json::Value, bot_id: Option<&str>) -> Result<String, String> {
+    let section = bot_section(config, "telegram", bot_id).ok_or(match bot_id {
+        Some(id) => format!("Telegram bot '{}' not found", id),
+        None => "Telegram is not configured".to_string(),
+    })?;
+    section
+        .get("botToken")
+        .and_then(|t| t.as_str())
+        .map(keychain::resolve)
+        .filter(|t| !t.is_empty())
+        .ok_or("Telegram bot has no token".to_string())
+}
+
+// The bridge's chat commands, for a menu that matches what the bot answers to
+fn bridge_commands() -> Vec<BotCommand> {
+    BRIDGE_COMMANDS
+        .iter()
+        .map(|(command, description, _)| BotCommand {
+            command: command.to_string(),
+            description: description.to_string(),
+        })
+        .collect()
+}
+
+fn validate(name: Option<&str>, description: Option<&str>, commands: &[BotCommand]) -> Result<(), String> {
+    if name.is_some_and(|n| n.chars().count() > MAX_NAME_LEN) {
+        return Err(format!("Bot name can be at most {} characters", MAX_NAME_LEN));
+    }
+    if description.is_some_and(|d| d.chars().count() > MAX_ABOUT_LEN) {
+        return Err(format!("About text can be at most {} characters", MAX_ABOUT_LEN));
+    }
+    if commands.len() > MAX_COMMANDS {
+        return Err(format!("Telegram allows at most {} commands", MAX_COMMANDS));
+    }
+    for command in commands {
+        let valid_chars = command
+            .command
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if command.command.is_empty() || command.command.len() > MAX_COMMAND_LEN || !valid_chars {
+            return Err(format!(
+                "'{}' is not a valid command; use 1-{} characters of a-z, 0-9 and _",
+                command.command, MAX_COMMAND_LEN
+            ));
+        }
+        let length = command.description.chars().count();
+        if length == 0 || length > MAX_COMMAND_DESCRIPTION_LEN {
+            return Err(format!(
+                "Description of /{} must be 1-{} characters",
+                command.command, MAX_COMMAND_DESCRIPTION_LEN
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn call(client: &reqwest::Client, token: &str, method: &str, body: serde_json::Value) -> Result<(), String> {
+    let response = client
+        .post(format!("{}/bot{}/{}", TELEGRAM_API, token, method))
+        .json(&body)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Telegram: {}", e.without_url()))?;
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    if body["ok"].as_bool() == Some(true) {
+        return Ok(());
+    }
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::NOT_FOUND => {
+            Err("Telegram rejected the bot token".to_string())
+        }
+        // setMyName is rate limited to a few changes a day
+        reqwest::StatusCode::TOO_MANY_REQUESTS => Err(format!(
+            "Telegram is rate limiting {}; try again in {} seconds",
+            method,
+            body["parameters"]["retry_after"].as_u64().unwrap_or(60)
+        )),
+        status => Err(format!(
+            "Telegram returned {} for {}: {}",
+            status,
+            method,
+            body["description"].as_str().unwrap_or_default()
+        )),
+    }
+}
+
+// Fields left out stay as they are; without `commands` the menu is set to the
+// bridge's own commands
+#[tauri::command]
+pub(crate) async fn configure_telegram_bot_profile(
+    store: State<'_, ConfigStore>,
+    bot_id: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    commands: Option<Vec<BotCommand>>,
+) -> Result<bool, String> {
+    let commands = commands.unwrap_or_else(bridge_commands);
+    validate(name.as_deref(), description.as_deref(), &commands)?;
+
+    let config = store.read()?.ok_or("Config file not found")?;
+    let token = bot_token(&config, bot_id.as_deref())?;
+    let client = http::client();
+
+    if let Some(name) = name {
+        call(&client, &token, "setMyName", serde_json::json!({ "name": name })).await?;
+    }
+    if let Some(description) = description {
+        call(&client, &token, "setMyShortDescription", serde_json::json!({ "short_description": description })).await?;
+        call(&client, &token, "setMyDescription", serde_json::json!({ "description": description })).await?;
+    }
+    call(&client, &token, "setMyCommands", serde_json::json!({ "commands": commands })).await?;
+    Ok(true)
+}
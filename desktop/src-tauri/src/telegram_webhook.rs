@@ -0,0 +1,382 @@
+// Telegram webhook mode.
+//
+// By default the bridge long-polls Telegram. Webhook mode needs a public HTTPS
+// URL that forwards to the bridge's webhook port, configured in
+// `channels.telegram.webhook`; the bridge registers each bot at
+// <url>/telegram/<bot id>. Users without one can let the desktop run a
+// cloudflared quick tunnel, which is restarted if it dies and whose (changing)
+// public URL is written back into the config.
+
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::Notify;
+
+use crate::config::ConfigStore;
+use crate::groups::bot_section;
+use crate::service::get_extended_path;
+use crate::{http, keychain};
+
+pub(crate) const TELEGRAM_API: &str = "https://api.telegram.org";
+const TUNNEL_RESTART_DELAY: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelKind {
+    #[default]
+    None,
+    Cloudflared,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSettings {
+    enabled: bool,
+    // Public HTTPS URL Telegram posts updates to
+    #[serde(default)]
+    url: String,
+    // Sent back by Telegram in X-Telegram-Bot-Api-Secret-Token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret_token: Option<String>,
+    // Local port the bridge's webhook server listens on
+    port: u16,
+    #[serde(default)]
+    tunnel: TunnelKind,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            secret_token: None,
+            port: 38793,
+            tunnel: TunnelKind::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelStatus {
+    running: bool,
+    public_url: Option<String>,
+    last_error: Option<String>,
+}
+
+// Managed state for the supervised cloudflared process
+#[derive(Default)]
+pub(crate) struct TunnelState {
+    status: Mutex<TunnelStatus>,
+    stop: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotWebhookCheck {
+    bot_id: Option<String>,
+    registered_url: Option<String>,
+    matches: bool,
+    pending_updates: u64,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookTestResult {
+    url: String,
+    reachable: bool,
+    http_status: Option<u16>,
+    error: Option<String>,
+    bots: Vec<BotWebhookCheck>,
+}
+
+fn read_settings(config: &serde_json::Value) -> WebhookSettings {
+    config
+        .get("channels")
+        .and_then(|c| c.get("telegram"))
+        .and_then(|t| t.get("webhook"))
+        .and_then(|w| serde_json::from_value(w.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn write_settings(store: &ConfigStore, settings: &WebhookSettings) -> Result<(), String> {
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let telegram = config
+        .get_mut("channels")
+        .and_then(|c| c.get_mut("telegram"))
+        .and_then(|t| t.as_object_mut())
+        .ok_or("Telegram is not configured")?;
+    telegram.insert("webhook".to_string(), serde_json::to_value(settings).map_err(|e| e.to_string())?);
+    Ok(store.write(&config)?)
+}
+
+fn validate(settings: &WebhookSettings) -> Result<(), String> {
+    if let Some(ref secret) = settings.secret_token {
+        // Telegram's rules for secret_token
+        let valid_chars = secret.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if secret.is_empty() || secret.len() > 256 || !valid_chars {
+            return Err("Secret token must be 1-256 characters of A-Z, a-z, 0-9, _ and -".to_string());
+        }
+    }
+    if settings.enabled && settings.tunnel == TunnelKind::None && !settings.url.starts_with("https://") {
+        return Err("Webhook URL must start with https://".to_string());
+    }
+    if settings.port == 0 {
+        return Err("Webhook port cannot be 0".to_string());
+    }
+    Ok(())
+}
+
+// (bot id, token) for the legacy single bot and every entry in `bots`
+fn telegram_tokens(config: &serde_json::Value) -> Vec<(Option<String>, String)> {
+    let mut tokens = Vec::new();
+    if let Some(token) = bot_section(config, "telegram", None)
+        .and_then(|t| t.get("botToken"))
+        .and_then(|t| t.as_str())
+        .map(keychain::resolve)
+        .filter(|t| !t.is_empty())
+    {
+        tokens.push((None, token));
+    }
+    let bots = config
+        .get("channels")
+        .and_then(|c| c.get("telegram"))
+        .and_then(|t| t.get("bots"))
+        .and_then(|b| b.as_array());
+    for bot in bots.into_iter().flatten() {
+        if let (Some(id), Some(token)) = (
+            bot.get("id").and_then(|v| v.as_str()),
+            bot.get("botToken").and_then(|v| v.as_str()),
+        ) {
+            tokens.push((Some(id.to_string()), keychain::resolve(token)));
+        }
+    }
+    tokens
+}
+
+// Where the bridge asks Telegram to deliver a bot's updates; "default" is the legacy single bot
+fn bot_url(settings: &WebhookSettings, bot_id: Option<&str>) -> Result<String, String> {
+    let mut url = reqwest::Url::parse(&settings.url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+    url.path_segments_mut()
+        .map_err(|_| "Invalid webhook URL".to_string())?
+        .pop_if_empty()
+        .extend(["telegram", bot_id.unwrap_or("default")]);
+    Ok(url.to_string())
+}
+
+fn set_status(app: &AppHandle, update: impl FnOnce(&mut TunnelStatus)) {
+    if let Ok(mut status) = app.state::<TunnelState>().status.lock() {
+        update(&mut status);
+    }
+}
+
+// cloudflared prints the assigned quick tunnel URL to stderr
+fn find_tunnel_url(line: &str) -> Option<String> {
+    let start = line.find("https://")?;
+    let url: String = line[start..].chars().take_while(|c| !c.is_whitespace() && *c != '|').collect();
+    url.contains(".trycloudflare.com").then_some(url)
+}
+
+async fn supervise_tunnel(app: AppHandle, port: u16, stop: Arc<Notify>) {
+    loop {
+        let spawned = Command::new("cloudflared")
+            .args(["tunnel", "--no-autoupdate", "--url"])
+            .arg(format!("http://127.0.0.1:{}", port))
+            .env("PATH", get_extended_path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                set_status(&app, |s| {
+                    s.running = false;
+                    s.last_error = Some(format!("Failed to run cloudflared (is it installed?): {}", e));
+                });
+                return;
+            }
+        };
+
+        let mut lines = child.stderr.take().map(|stderr| BufReader::new(stderr).lines());
+        let stopped = loop {
+            tokio::select! {
+                _ = stop.notified() => break true,
+                status = child.wait() => {
+                    let message = status.map(|s| format!("cloudflared exited ({})", s)).unwrap_or_else(|e| e.to_string());
+                    set_status(&app, |s| {
+                        s.public_url = None;
+                        s.last_error = Some(message);
+                    });
+                    break false;
+                }
+                Ok(Some(line)) = async { match lines.as_mut() { Some(l) => l.next_line().await, None => std::future::pending().await } } => {
+                    if let Some(url) = find_tunnel_url(&line) {
+                        let store = app.state::<ConfigStore>();
+                        let result = store.read().map_err(String::from).and_then(|config| {
+                            let mut settings = config.as_ref().map(read_settings).unwrap_or_default();
+                            settings.url = url.clone();
+                            write_settings(&store, &settings)
+                        });
+                        set_status(&app, |s| {
+                            s.public_url = Some(url);
+                            s.last_error = result.err();
+                        });
+                    }
+                }
+            }
+        };
+
+        if stopped {
+            let _ = child.kill().await;
+            set_status(&app, |s| {
+                s.running = false;
+                s.public_url = None;
+            });
+            return;
+        }
+
+        tokio::select! {
+            _ = stop.notified() => {
+                set_status(&app, |s| s.running = false);
+                return;
+            }
+            _ = tokio::time::sleep(TUNNEL_RESTART_DELAY) => {}
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) fn get_webhook_settings(store: State<'_, ConfigStore>) -> Result<WebhookSettings, String> {
+    Ok(store.read()?.map(|c| read_settings(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn set_webhook_settings(store: State<'_, ConfigStore>, settings: WebhookSettings) -> Result<bool, String> {
+    validate(&settings)?;
+    write_settings(&store, &settings)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) fn start_tunnel(
+    app: AppHandle,
+    store: State<'_, ConfigStore>,
+    tunnel: State<'_, TunnelState>,
+) -> Result<TunnelStatus, String> {
+    let settings = store.read()?.map(|c| read_settings(&c)).unwrap_or_default();
+    if settings.tunnel != TunnelKind::Cloudflared {
+        return Err("No tunnel is configured for the Telegram webhook".to_string());
+    }
+
+    let mut status = tunnel.status.lock().map_err(|e| e.to_string())?;
+    if !status.running {
+        *status = TunnelStatus {
+            running: true,
+            ..Default::default()
+        };
+        tauri::async_runtime::spawn(supervise_tunnel(app.clone(), settings.port, Arc::clone(&tunnel.stop)));
+    }
+    Ok(status.clone())
+}
+
+#[tauri::command]
+pub(crate) fn stop_tunnel(tunnel: State<'_, TunnelState>) -> Result<bool, String> {
+    let running = tunnel.status.lock().map_err(|e| e.to_string())?.running;
+    if running {
+        // notify_one stores a permit, so the supervisor sees it even mid-restart
+        tunnel.stop.notify_one();
+    }
+    Ok(running)
+}
+
+#[tauri::command]
+pub(crate) fn get_tunnel_status(tunnel: State<'_, TunnelState>) -> Result<TunnelStatus, String> {
+    Ok(tunnel.status.lock().map_err(|e| e.to_string())?.clone())
+}
+
+#[tauri::command]
+pub(crate) async fn test_webhook(store: State<'_, ConfigStore>) -> Result<WebhookTestResult, String> {
+    let config = store.read()?.ok_or("Config file not found")?;
+    let settings = read_settings(&config);
+    if settings.url.is_empty() {
+        return Err("No webhook URL is configured".to_string());
+    }
+
+    let client = http::client();
+    let tokens = telegram_tokens(&config);
+    let first_bot = tokens.first().ok_or("No Telegram bot is configured")?;
+
+    // Deliver an empty update to the first bot the way Telegram would
+    let mut request = client
+        .post(bot_url(&settings, first_bot.0.as_deref())?)
+        .json(&serde_json::json!({ "update_id": 0 }))
+        .timeout(REQUEST_TIMEOUT);
+    if let Some(ref secret) = settings.secret_token {
+        request = request.header("X-Telegram-Bot-Api-Secret-Token", secret);
+    }
+    let (reachable, http_status, error) = match request.send().await {
+        Ok(response) => (
+            response.status().is_success(),
+            Some(response.status().as_u16()),
+            (!response.status().is_success()).then(|| format!("Webhook returned {}", response.status())),
+        ),
+        Err(e) => (false, None, Some(format!("Failed to reach webhook: {}", e))),
+    };
+
+    // Ask Telegram what it has registered and whether its deliveries fail
+    let mut bots = Vec::new();
+    for (bot_id, token) in tokens {
+        let expected_url = bot_url(&settings, bot_id.as_deref())?;
+        // The request URL carries the token, so errors are stripped of it
+        let info = client
+            .get(format!("{}/bot{}/getWebhookInfo", TELEGRAM_API, token))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| e.without_url().to_string());
+        let info: Result<serde_json::Value, String> = match info {
+            Ok(response) => response.json().await.map_err(|e| e.without_url().to_string()),
+            Err(e) => Err(e),
+        };
+        let check = match info {
+            Ok(body) => {
+                let result = &body["result"];
+                let registered_url = result["url"].as_str().filter(|u| !u.is_empty()).map(String::from);
+                BotWebhookCheck {
+                    matches: registered_url.as_deref() == Some(expected_url.as_str()),
+                    registered_url,
+                    pending_updates: result["pending_update_count"].as_u64().unwrap_or(0),
+                    last_error: result["last_error_message"]
+                        .as_str()
+                        .or(body["description"].as_str())
+                        .map(String::from),
+                    bot_id,
+                }
+            }
+            Err(e) => BotWebhookCheck {
+                bot_id,
+                registered_url: None,
+                matches: false,
+                pending_updates: 0,
+                last_error: Some(e),
+            },
+        };
+        bots.push(check);
+    }
+
+    Ok(WebhookTestResult {
+        url: settings.url,
+        reachable,
+        http_status,
+        error,
+        bots,
+    })
+}
@@ -0,0 +1,151 @@
+// Canned operator replies.
+//
+// Templates are short messages with `{{variable}}` placeholders ("agent is being
+// restarted, back in {{minutes}} minutes") kept in the desktop's data dir and
+// sent into a session through the Control API.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::State;
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::config::get_desktop_data_dir;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageTemplate {
+    id: String,
+    name: String,
+    text: String,
+}
+
+fn get_templates_path() -> PathBuf {
+    get_desktop_data_dir().join("templates.json")
+}
+
+fn load_templates() -> Result<Vec<MessageTemplate>, String> {
+    let path = get_templates_path();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read templates: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse templates: {}", e))
+}
+
+fn save_templates(templates: &[MessageTemplate]) -> Result<(), String> {
+    let path = get_templates_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(templates).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write templates: {}", e))
+}
+
+// Replace `{{name}}` placeholders, failing on any placeholder without a value
+pub(crate) fn render_template(text: &str, vars: &HashMap<String, String>) -> Result<String, String> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(open) = rest.find("{{") {
+        rendered.push_str(&rest[..open]);
+        let after_open = &rest[open + 2..];
+        let Some(close) = after_open.find("}}") else {
+            return Err("Unclosed '{{' in template".to_string());
+        };
+        let name = after_open[..close].trim();
+        let value = vars.get(name).ok_or(format!("Missing value for '{{{{{}}}}}'", name))?;
+        rendered.push_str(value);
+        rest = &after_open[close + 2..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+// Names of the `{{name}}` placeholders in `text`, in order of first use
+pub(crate) fn template_variables(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find("{{") {
+        let after_open = &rest[open + 2..];
+        let Some(close) = after_open.find("}}") else {
+            break;
+        };
+        let name = after_open[..close].trim().to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+        rest = &after_open[close + 2..];
+    }
+
+    names
+}
+
+// Send a plain text message into an existing session via the bridge
+pub(crate) async fn send_session_message(api: &BridgeApiClient, session_id: &str, text: &str) -> Result<(), String> {
+    let body = serde_json::json!({ "text": text });
+    match api.post(&format!("/sessions/{}/messages", session_id), Some(&body)).await {
+        Ok(_) => Ok(()),
+        Err(ApiError::NotFound(_)) => Err(format!("Session '{}' not found", session_id)),
+        Err(ApiError::Unsupported(_)) => Err("This bridge version can't send messages into sessions".to_string()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub(crate) fn list_templates() -> Result<Vec<MessageTemplate>, String> {
+    load_templates()
+}
+
+#[tauri::command]
+pub(crate) fn add_template(name: String, text: String) -> Result<MessageTemplate, String> {
+    if name.trim().is_empty() || text.trim().is_empty() {
+        return Err("Template name and text are required".to_string());
+    }
+
+    let mut templates = load_templates()?;
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+    let template = MessageTemplate {
+        id: format!("tpl-{}", millis),
+        name,
+        text,
+    };
+    templates.push(template.clone());
+    save_templates(&templates)?;
+
+    Ok(template)
+}
+
+#[tauri::command]
+pub(crate) fn remove_template(id: String) -> Result<bool, String> {
+    let mut templates = load_templates()?;
+    let original_len = templates.len();
+    templates.retain(|t| t.id != id);
+    if templates.len() == original_len {
+        return Err(format!("Template '{}' not found", id));
+    }
+    save_templates(&templates)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn send_template(
+    api: State<'_, BridgeApiClient>,
+    session_id: String,
+    template_id: String,
+    vars: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let template = load_templates()?
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or(format!("Template '{}' not found", template_id))?;
+
+    let text = render_template(&template.text, &vars.unwrap_or_default())?;
+    send_session_message(&api, &session_id, &text).await?;
+
+    Ok(text)
+}
@@ -0,0 +1,132 @@
+// Reconcile an agent's tool lists with its workspace's Claude Code settings.
+//
+// Claude Code keeps per-project `permissions.allow` / `permissions.deny` lists
+// in `<workspace>/.claude/settings.json`, which drift from the agent's
+// `allowedTools` / `disallowedTools` in config.json. Without a direction this
+// only reports the differences; with one, the chosen side overwrites the other.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::config::{expand_home, ConfigStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDirection {
+    // Copy the project's settings into the agent config
+    ProjectToAgent,
+    // Write the agent's lists into the project's settings
+    AgentToProject,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolListDiff {
+    only_in_project: Vec<String>,
+    only_in_agent: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSyncReport {
+    settings_path: String,
+    settings_exists: bool,
+    allow: ToolListDiff,
+    deny: ToolListDiff,
+    in_sync: bool,
+    applied: Option<SyncDirection>,
+}
+
+fn string_list(value: Option<&Value>) -> BTreeSet<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|t| t.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn diff(project: &BTreeSet<String>, agent: &BTreeSet<String>) -> ToolListDiff {
+    ToolListDiff {
+        only_in_project: project.difference(agent).cloned().collect(),
+        only_in_agent: agent.difference(project).cloned().collect(),
+    }
+}
+
+fn read_settings(path: &PathBuf) -> Result<Value, String> {
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+#[tauri::command]
+pub(crate) fn sync_agent_tools(
+    store: State<'_, ConfigStore>,
+    id: String,
+    direction: Option<SyncDirection>,
+) -> Result<ToolSyncReport, String> {
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    let agent = config
+        .get_mut("agents")
+        .and_then(|a| a.get_mut("list"))
+        .and_then(|l| l.as_array_mut())
+        .and_then(|list| list.iter_mut().find(|a| a.get("id").and_then(|v| v.as_str()) == Some(id.as_str())))
+        .ok_or(format!("Agent '{}' not found", id))?;
+
+    let workspace = agent.get("workspace").and_then(|w| w.as_str()).unwrap_or_default();
+    let settings_path = expand_home(workspace).join(".claude").join("settings.json");
+    let settings_exists = settings_path.exists();
+    let mut settings = read_settings(&settings_path)?;
+
+    let permissions = settings.get("permissions");
+    let project_allow = string_list(permissions.and_then(|p| p.get("allow")));
+    let project_deny = string_list(permissions.and_then(|p| p.get("deny")));
+    let agent_allow = string_list(agent.get("allowedTools"));
+    let agent_deny = string_list(agent.get("disallowedTools"));
+
+    let allow = diff(&project_allow, &agent_allow);
+    let deny = diff(&project_deny, &agent_deny);
+    let in_sync = project_allow == agent_allow && project_deny == agent_deny;
+
+    let applied = match direction {
+        Some(_) if in_sync => None,
+        Some(SyncDirection::ProjectToAgent) => {
+            let agent = agent.as_object_mut().ok_or("Agent entry is not an object")?;
+            agent.insert("allowedTools".to_string(), serde_json::json!(project_allow));
+            agent.insert("disallowedTools".to_string(), serde_json::json!(project_deny));
+            store.write(&config)?;
+            direction
+        }
+        Some(SyncDirection::AgentToProject) => {
+            let root = settings.as_object_mut().ok_or("Project settings are not a JSON object")?;
+            let permissions = root
+                .entry("permissions")
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .ok_or("Project permissions are not a JSON object")?;
+            permissions.insert("allow".to_string(), serde_json::json!(agent_allow));
+            permissions.insert("deny".to_string(), serde_json::json!(agent_deny));
+
+            if let Some(dir) = settings_path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create .claude dir: {}", e))?;
+            }
+            let content = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+            fs::write(&settings_path, content).map_err(|e| format!("Failed to write project settings: {}", e))?;
+            direction
+        }
+        None => None,
+    };
+
+    Ok(ToolSyncReport {
+        settings_path: settings_path.to_string_lossy().to_string(),
+        settings_exists,
+        allow,
+        deny,
+        in_sync,
+        applied,
+    })
+}
@@ -0,0 +1,105 @@
+// Shared helpers for Claude Code's on-disk transcripts.
+//
+// Claude Code keeps one JSONL file per session under
+// ~/.claude/projects/<escaped-cwd>/, appending a line per event. Subsystems that
+// mine these files (usage rollups, history search) read them incrementally and
+// attribute sessions to agents by matching the recorded cwd against workspaces.
+
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+pub(crate) fn get_projects_dir() -> PathBuf {
+    let home = dirs::home_dir().expect("Could not find home directory");
+    home.join(".claude").join("projects")
+}
+
+pub(crate) fn transcript_files() -> Vec<PathBuf> {
+    let pattern = get_projects_dir().join("*").join("*.jsonl");
+    glob::glob(pattern.to_string_lossy().as_ref())
+        .map(|paths| paths.filter_map(Result::ok).collect())
+        .unwrap_or_default()
+}
+
+// Transcript of one Claude Code session, by its session id
+pub(crate) fn transcript_file(session_id: &str) -> Option<PathBuf> {
+    let name = format!("{}.jsonl", session_id);
+    transcript_files()
+        .into_iter()
+        .find(|path| path.file_name().is_some_and(|n| n.to_string_lossy() == name))
+}
+
+// Read the complete lines appended to `path` since `offset`, advancing it past
+// them. A trailing partial line is left for the next call.
+pub(crate) fn read_appended_lines(path: &Path, offset: &mut u64) -> Vec<String> {
+    let Ok(mut file) = fs::File::open(path) else {
+        return vec![];
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if len < *offset {
+        // File was truncated or replaced; start over
+        *offset = 0;
+    }
+    if len == *offset || file.seek(SeekFrom::Start(*offset)).is_err() {
+        return vec![];
+    }
+
+    let mut buf = Vec::new();
+    if file.read_to_end(&mut buf).is_err() {
+        return vec![];
+    }
+    let Some(last_newline) = buf.iter().rposition(|b| *b == b'\n') else {
+        return vec![];
+    };
+    *offset += last_newline as u64 + 1;
+
+    String::from_utf8_lossy(&buf[..last_newline])
+        .lines()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+// (agent id, workspace) pairs from config, used to attribute sessions by cwd
+pub(crate) fn agent_workspaces(config: &serde_json::Value) -> Vec<(String, String)> {
+    config
+        .get("agents")
+        .and_then(|a| a.get("list"))
+        .and_then(|l| l.as_array())
+        .map(|agents| {
+            agents
+                .iter()
+                .filter_map(|a| {
+                    let id = a.get("id")?.as_str()?.to_string();
+                    let workspace = a.get("workspace")?.as_str()?.to_string();
+                    Some((id, workspace))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Longest workspace that contains `cwd` wins, so nested workspaces resolve correctly
+pub(crate) fn agent_for_cwd(workspaces: &[(String, String)], cwd: &str) -> Option<String> {
+    workspaces
+        .iter()
+        .filter(|(_, workspace)| Path::new(cwd).starts_with(workspace))
+        .max_by_key(|(_, workspace)| workspace.len())
+        .map(|(id, _)| id.clone())
+}
+
+// Plain text of a transcript message: either a string or an array of blocks,
+// of which only text blocks are kept (tool calls and results are skipped)
+pub(crate) fn message_text(message: &serde_json::Value) -> Option<String> {
+    match message.get("content")? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(blocks) => {
+            let text: Vec<&str> = blocks
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect();
+            (!text.is_empty()).then(|| text.join("\n"))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,350 @@
+// Menu bar tray icon.
+//
+// The app lives in the tray: left-clicking the icon toggles the window right
+// below it, and the menu has Show and Quit. Pending pairing requests are
+// listed above those, each with Approve and Deny, and the menu is rebuilt
+// whenever the poller sees them change. The icon itself reflects the bridge
+// at a glance: a colored dot for starting, running or needing attention (a
+// crash, or a channel that lost its connection), a blue badge while pairing
+// requests wait, and the tooltip and title carry the active session count.
+// Linux tray hosts (GNOME's
+// AppIndicator extension, KDE's status notifier) only ever open the menu and
+// don't say where the icon is, so there Show puts the window in the top-right
+// corner, where their panels keep the tray, and it stays up like a normal
+// window instead of hiding when it loses focus.
+
+use std::sync::Mutex;
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    App, AppHandle, Manager, PhysicalPosition, Rect, Runtime, WebviewWindow,
+};
+
+use crate::{ccb_updates, i18n};
+use crate::notifications::{self, NotificationCategory};
+use crate::pairing::{self, channel_label};
+use crate::pairing_policy::user_label;
+use crate::poller::StatusCache;
+use crate::service::AppState;
+
+const TRAY_ID: &str = "main";
+// Between the tray icon (or the screen edge) and the window
+const GAP: i32 = 4;
+// Below a top panel when the icon's position is unknown
+const PANEL_HEIGHT: i32 = 32;
+// Pairing requests listed in the menu; the rest are in the window
+const MAX_MENU_PAIRINGS: usize = 10;
+const APPROVE_PREFIX: &str = "pairing-approve:";
+const DENY_PREFIX: &str = "pairing-deny:";
+
+const GREEN: [u8; 3] = [52, 199, 89];
+const AMBER: [u8; 3] = [255, 159, 10];
+const RED: [u8; 3] = [255, 59, 48];
+const BLUE: [u8; 3] = [10, 132, 255];
+const PURPLE: [u8; 3] = [175, 82, 222];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BridgeState {
+    Stopped,
+    Starting,
+    Running,
+    // Crashed, or running with a channel that isn't connected
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TrayStatus {
+    state: BridgeState,
+    pending_pairings: usize,
+    active_sessions: u32,
+    // Newer ccb release, if any
+    ccb_update: Option<String>,
+    locale: String,
+}
+
+// What the tray shows now, so unchanged polls don't redraw it
+static SHOWN: Mutex<Option<TrayStatus>> = Mutex::new(None);
+
+// Whether the window should hide when it loses focus, as menu bar popovers
+// do; Linux desktops report focus changes too unreliably for that
+pub(crate) fn hides_on_blur() -> bool {
+    !cfg!(target_os = "linux")
+}
+
+fn build_tray_menu<R: Runtime, M: Manager<R>>(app: &M) -> tauri::Result<Menu<R>> {
+    let menu = Menu::new(app)?;
+
+    let pairings = app.try_state::<StatusCache>().map(|cache| cache.pairings()).unwrap_or_default();
+    if !pairings.is_empty() {
+        let count = pairings.len().to_string();
+        menu.append(&MenuItem::with_id(app, "pairings", i18n::tr("tray.pairings", &[("count", &count)]), false, None::<&str>)?)?;
+        for request in pairings.iter().take(MAX_MENU_PAIRINGS) {
+            let label = i18n::tr(
+                "tray.pairing",
+                &[("user", &user_label(request)), ("channel", &channel_label(&request.user_info.channel))],
+            );
+            let approve = MenuItem::with_id(app, format!("{}{}", APPROVE_PREFIX, request.code), i18n::t("tray.approve"), true, None::<&str>)?;
+            let deny = MenuItem::with_id(app, format!("{}{}", DENY_PREFIX, request.code), i18n::t("tray.deny"), true, None::<&str>)?;
+            menu.append(&Submenu::with_items(app, label, true, &[&approve, &deny])?)?;
+        }
+        if pairings.len() > MAX_MENU_PAIRINGS {
+            let more = (pairings.len() - MAX_MENU_PAIRINGS).to_string();
+            menu.append(&MenuItem::with_id(app, "more-pairings", i18n::tr("tray.more_pairings", &[("count", &more)]), true, None::<&str>)?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    let quit = MenuItem::with_id(app, "quit", i18n::t("tray.quit"), true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", i18n::t("tray.show"), true, None::<&str>)?;
+    menu.append_items(&[&show, &quit])?;
+    Ok(menu)
+}
+
+// Rebuild the tray menu, e.g. after the locale or the pending pairings change
+pub(crate) fn refresh_tray_menu(app: &AppHandle) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(build_tray_menu(app)?))?;
+    }
+    Ok(())
+}
+
+// Approve or deny from the menu; problems are reported as notifications,
+// since the window may well be closed
+fn decide_pairing(app: &AppHandle, code: String, approve: bool) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let user = app
+            .state::<StatusCache>()
+            .pairings()
+            .iter()
+            .find(|p| p.code == code)
+            .map(user_label)
+            .unwrap_or_else(|| code.clone());
+        let result = if approve {
+            pairing::approve_pairing(app.clone(), app.state(), app.state(), app.state(), code, None).await
+        } else {
+            pairing::deny_pairing(app.state(), app.state(), code).await
+        };
+        let message = match result {
+            Ok(true) => None,
+            Ok(false) => Some(i18n::tr("tray.pairing_gone", &[("user", &user)])),
+            Err(e) => {
+                let key = if approve { "tray.approve_failed" } else { "tray.deny_failed" };
+                Some(i18n::tr(key, &[("user", &user), ("error", &e.to_string())]))
+            }
+        };
+        if let Some(message) = message {
+            notifications::notify(&app, NotificationCategory::Pairing, &i18n::t("tray.pairing_title"), &message);
+        }
+        let _ = refresh_tray_menu(&app);
+    });
+}
+
+fn current_status(app: &AppHandle) -> TrayStatus {
+    let cache = app.state::<StatusCache>();
+    let status = cache.status();
+    let (spawned, crashed) = app
+        .state::<AppState>()
+        .lock()
+        .map(|s| (s.is_running || s.restart_at.is_some(), s.crashed))
+        .unwrap_or_default();
+    let state = match status {
+        Some(ref status) if status.channels.iter().any(|c| c.enabled && !c.connected) => BridgeState::Error,
+        Some(_) => BridgeState::Running,
+        None if spawned => BridgeState::Starting,
+        None if crashed => BridgeState::Error,
+        None => BridgeState::Stopped,
+    };
+    TrayStatus {
+        state,
+        pending_pairings: cache.pairings().len(),
+        active_sessions: status.map(|s| s.sessions.active).unwrap_or_default(),
+        ccb_update: ccb_updates::update_available(),
+        locale: i18n::current_locale(),
+    }
+}
+
+// A filled circle of `color` centered at (cx, cy), with a transparent ring
+// around it so it stands apart from the icon
+fn draw_dot(rgba: &mut [u8], width: u32, height: u32, center: (f32, f32), radius: f32, color: [u8; 3]) {
+    let ring = radius * 1.35;
+    for y in 0..height {
+        for x in 0..width {
+            let distance = ((x as f32 + 0.5 - center.0).powi(2) + (y as f32 + 0.5 - center.1).powi(2)).sqrt();
+            if distance > ring {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            if distance <= radius {
+                rgba[i..i + 3].copy_from_slice(&color);
+                rgba[i + 3] = 255;
+            } else {
+                rgba[i + 3] = 0;
+            }
+        }
+    }
+}
+
+// The app icon with the status dot in the bottom-right corner, the pairing
+// badge in the top-right one and the update badge in the top-left one
+fn status_icon(base: &Image<'_>, status: &TrayStatus) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let radius = width.min(height) as f32 * 0.2;
+    let color = match status.state {
+        BridgeState::Stopped => None,
+        BridgeState::Starting => Some(AMBER),
+        BridgeState::Running => Some(GREEN),
+        BridgeState::Error => Some(RED),
+    };
+    if let Some(color) = color {
+        draw_dot(&mut rgba, width, height, (width as f32 - radius, height as f32 - radius), radius, color);
+    }
+    if status.pending_pairings > 0 {
+        draw_dot(&mut rgba, width, height, (width as f32 - radius, radius), radius, BLUE);
+    }
+    if status.ccb_update.is_some() {
+        draw_dot(&mut rgba, width, height, (radius, radius), radius, PURPLE);
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+fn tooltip(status: &TrayStatus) -> String {
+    let mut lines = vec![match status.state {
+        BridgeState::Stopped => i18n::t("tray.status_stopped"),
+        BridgeState::Starting => i18n::t("tray.status_starting"),
+        BridgeState::Running => i18n::tr("tray.status_running", &[("sessions", &status.active_sessions.to_string())]),
+        BridgeState::Error => i18n::t("tray.status_error"),
+    }];
+    if status.pending_pairings > 0 {
+        lines.push(i18n::tr("tray.pairings", &[("count", &status.pending_pairings.to_string())]));
+    }
+    if let Some(ref version) = status.ccb_update {
+        lines.push(i18n::tr("tray.ccb_update", &[("version", version)]));
+    }
+    lines.join("\n")
+}
+
+// Bring the icon, tooltip and title in line with the bridge; called by the
+// poller on every round
+pub(crate) fn update_tray_status(app: &AppHandle) {
+    let status = current_status(app);
+    let Ok(mut shown) = SHOWN.lock() else {
+        return;
+    };
+    if shown.as_ref() == Some(&status) {
+        return;
+    }
+    let (Some(tray), Some(base)) = (app.tray_by_id(TRAY_ID), app.default_window_icon()) else {
+        return;
+    };
+    let plain = status.state == BridgeState::Stopped && status.pending_pairings == 0 && status.ccb_update.is_none();
+    let _ = tray.set_icon(Some(status_icon(base, &status)));
+    // Template icons are drawn in one color, which would hide the dots
+    let _ = tray.set_icon_as_template(plain && cfg!(target_os = "macos"));
+    let _ = tray.set_tooltip(Some(tooltip(&status)));
+    // Shown next to the icon in the menu bar
+    let title = (status.active_sessions > 0).then(|| status.active_sessions.to_string());
+    let _ = tray.set_title(title);
+    *shown = Some(status);
+}
+
+// Where the window goes: centered below the tray icon, or in the top-right
+// corner without one, and kept on the screen either way
+fn window_position(window: &WebviewWindow, tray: Option<Rect>) -> Option<PhysicalPosition<i32>> {
+    let window_size = window.outer_size().unwrap_or(tauri::PhysicalSize { width: 320, height: 480 });
+    let (width, height) = (window_size.width as i32, window_size.height as i32);
+    let monitor = window.current_monitor().ok().flatten().or_else(|| window.primary_monitor().ok().flatten())?;
+    let scale = monitor.scale_factor();
+    let (left, top) = (monitor.position().x, monitor.position().y);
+    let (right, bottom) = (left + monitor.size().width as i32, top + monitor.size().height as i32);
+
+    // Extract tray rect position and size; Linux reports an empty one
+    let tray = tray.and_then(|rect| {
+        let position = rect.position.to_physical::<i32>(scale);
+        let size = rect.size.to_physical::<i32>(scale);
+        (size.width > 0 && size.height > 0).then_some((position.x, position.y, size.width, size.height))
+    });
+    let (x, y) = match tray {
+        // Calculate position: center horizontally under tray, with small gap below;
+        // above it for a tray at the bottom of the screen (Windows, KDE)
+        Some((tray_x, tray_y, tray_w, tray_h)) => {
+            let x = tray_x + (tray_w / 2) - (width / 2);
+            let below = tray_y + tray_h + GAP;
+            let y = if below + height > bottom { tray_y - height - GAP } else { below };
+            (x, y)
+        }
+        None => (right - width - GAP, top + (PANEL_HEIGHT as f64 * scale) as i32 + GAP),
+    };
+    Some(PhysicalPosition::new(
+        // A window about as wide as the monitor would make min exceed max
+        x.clamp(left + GAP, (right - width - GAP).max(left + GAP)),
+        y.clamp(top, (bottom - height).max(top)),
+    ))
+}
+
+fn show_window(window: &WebviewWindow, tray: Option<Rect>) {
+    if let Some(position) = window_position(window, tray) {
+        let _ = window.set_position(position);
+    }
+    let _ = window.show();
+    let _ = window.set_focus();
+}
+
+pub(crate) fn create(app: &App) -> tauri::Result<()> {
+    let menu = build_tray_menu(app)?;
+
+    // Tray icon using the default window icon; only macOS recolors template
+    // icons to match the menu bar, elsewhere they'd stay black on dark panels
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(app.default_window_icon().unwrap().clone())
+        .icon_as_template(cfg!(target_os = "macos"))
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "quit" => {
+                app.exit(0);
+            }
+            "show" | "more-pairings" => {
+                if let Some(window) = app.get_webview_window("main") {
+                    // Linux has no click to position by; elsewhere the window
+                    // stays where the last click put it
+                    if cfg!(target_os = "linux") {
+                        show_window(&window, None);
+                    } else {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+            }
+            id => {
+                if let Some(code) = id.strip_prefix(APPROVE_PREFIX) {
+                    decide_pairing(app, code.to_string(), true);
+                } else if let Some(code) = id.strip_prefix(DENY_PREFIX) {
+                    decide_pairing(app, code.to_string(), false);
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                rect,
+                ..
+            } = event
+            {
+                let app = tray.app_handle();
+                if let Some(window) = app.get_webview_window("main") {
+                    // Toggle window visibility
+                    if window.is_visible().unwrap_or(false) {
+                        let _ = window.hide();
+                    } else {
+                        show_window(&window, Some(rect));
+                    }
+                }
+            }
+        })
+        .build(app)?;
+    Ok(())
+}
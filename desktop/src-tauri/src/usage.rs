@@ -0,0 +1,360 @@
+// Token usage and cost rollups.
+//
+// Claude Code appends every assistant turn, including its token usage, to JSONL
+// transcripts under ~/.claude/projects. We scan those files incrementally
+// (remembering how far each one was read), fold new turns into daily rollups per
+// session and model, and persist the rollups so charts don't depend on the
+// transcripts sticking around.
+
+use chrono::{DateTime, Duration as ChronoDuration, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::config::{get_desktop_data_dir, ConfigStore};
+use crate::transcripts::{agent_for_cwd, agent_workspaces, read_appended_lines, transcript_files};
+
+// Serializes scans so concurrent dashboard requests don't double count
+static SCAN_LOCK: Mutex<()> = Mutex::new(());
+
+// Sessions listed individually in the dashboard, by cost
+const TOP_SESSIONS: usize = 20;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageRange {
+    Today,
+    Week,
+    Month,
+    All,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileCursor {
+    offset: u64,
+    // Claude Code writes one line per content block, all repeating the same
+    // message id and usage; remember the last id so a turn is counted once
+    last_message_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DailyRollup {
+    date: String,
+    agent_id: Option<String>,
+    session_id: String,
+    model: String,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    messages: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageStore {
+    files: HashMap<String, FileCursor>,
+    rollups: Vec<DailyRollup>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_creation_tokens: u64,
+    cache_read_tokens: u64,
+    messages: u64,
+    cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, rollup: &DailyRollup) {
+        self.input_tokens += rollup.input_tokens;
+        self.output_tokens += rollup.output_tokens;
+        self.cache_creation_tokens += rollup.cache_creation_tokens;
+        self.cache_read_tokens += rollup.cache_read_tokens;
+        self.messages += rollup.messages;
+        self.cost_usd += estimate_cost(rollup);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayUsage {
+    date: String,
+    totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentUsage {
+    agent_id: Option<String>,
+    totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    session_id: String,
+    agent_id: Option<String>,
+    totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageDashboard {
+    range: UsageRange,
+    totals: UsageTotals,
+    by_day: Vec<DayUsage>,
+    by_agent: Vec<AgentUsage>,
+    top_sessions: Vec<SessionUsage>,
+}
+
+// Estimated USD per million tokens as (input, output). Cache writes bill at
+// 1.25x the input rate and cache reads at 0.1x.
+fn model_pricing(model: &str) -> (f64, f64) {
+    if model.contains("opus") {
+        (15.0, 75.0)
+    } else if model.contains("haiku") {
+        (0.8, 4.0)
+    } else {
+        (3.0, 15.0)
+    }
+}
+
+fn estimate_cost(rollup: &DailyRollup) -> f64 {
+    let (input, output) = model_pricing(&rollup.model);
+    (rollup.input_tokens as f64 * input
+        + rollup.cache_creation_tokens as f64 * input * 1.25
+        + rollup.cache_read_tokens as f64 * input * 0.1
+        + rollup.output_tokens as f64 * output)
+        / 1_000_000.0
+}
+
+fn get_usage_store_path() -> PathBuf {
+    get_desktop_data_dir().join("usage.json")
+}
+
+fn load_store() -> UsageStore {
+    fs::read_to_string(get_usage_store_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(store: &UsageStore) -> Result<(), String> {
+    let path = get_usage_store_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create data dir: {}", e))?;
+    }
+    let content = serde_json::to_string(store).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write usage store: {}", e))
+}
+
+fn scan(workspaces: &[(String, String)]) -> Result<UsageStore, String> {
+    let _guard = SCAN_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load_store();
+
+    let mut index: HashMap<(String, String, String), usize> = store
+        .rollups
+        .iter()
+        .enumerate()
+        .map(|(i, r)| ((r.date.clone(), r.session_id.clone(), r.model.clone()), i))
+        .collect();
+
+    for path in transcript_files() {
+        let key = path.to_string_lossy().to_string();
+        let mut cursor = store.files.get(&key).cloned().unwrap_or_default();
+
+        for line in read_appended_lines(&path, &mut cursor.offset) {
+            let Ok(entry) = serde_json::from_str::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if entry.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+                continue;
+            }
+            let Some(message) = entry.get("message") else {
+                continue;
+            };
+            let Some(usage) = message.get("usage") else {
+                continue;
+            };
+
+            let message_id = message.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
+            if message_id.is_some() && message_id == cursor.last_message_id {
+                continue;
+            }
+            cursor.last_message_id = message_id;
+
+            let Some(date) = entry
+                .get("timestamp")
+                .and_then(|t| t.as_str())
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Local).format("%Y-%m-%d").to_string())
+            else {
+                continue;
+            };
+            let session_id = entry.get("sessionId").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+            let model = message.get("model").and_then(|v| v.as_str()).unwrap_or("unknown").to_string();
+            let tokens = |field: &str| usage.get(field).and_then(|v| v.as_u64()).unwrap_or(0);
+
+            let i = *index
+                .entry((date.clone(), session_id.clone(), model.clone()))
+                .or_insert_with(|| {
+                    let agent_id = entry
+                        .get("cwd")
+                        .and_then(|v| v.as_str())
+                        .and_then(|cwd| agent_for_cwd(workspaces, cwd));
+                    store.rollups.push(DailyRollup {
+                        date,
+                        agent_id,
+                        session_id,
+                        model,
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        cache_creation_tokens: 0,
+                        cache_read_tokens: 0,
+                        messages: 0,
+                    });
+                    store.rollups.len() - 1
+                });
+
+            let rollup = &mut store.rollups[i];
+            rollup.input_tokens += tokens("input_tokens");
+            rollup.output_tokens += tokens("output_tokens");
+            rollup.cache_creation_tokens += tokens("cache_creation_input_tokens");
+            rollup.cache_read_tokens += tokens("cache_read_input_tokens");
+            rollup.messages += 1;
+        }
+
+        store.files.insert(key, cursor);
+    }
+
+    save_store(&store)?;
+    Ok(store)
+}
+
+fn range_start(range: UsageRange) -> Option<String> {
+    let days = match range {
+        UsageRange::Today => 1,
+        UsageRange::Week => 7,
+        UsageRange::Month => 30,
+        UsageRange::All => return None,
+    };
+    let start = Local::now().date_naive() - ChronoDuration::days(days - 1);
+    Some(start.format("%Y-%m-%d").to_string())
+}
+
+fn build_dashboard(store: &UsageStore, range: UsageRange) -> UsageDashboard {
+    let start = range_start(range);
+    let rollups = store
+        .rollups
+        .iter()
+        .filter(|r| start.as_ref().map(|s| r.date >= *s).unwrap_or(true));
+
+    let mut totals = UsageTotals::default();
+    let mut by_day: HashMap<String, UsageTotals> = HashMap::new();
+    let mut by_agent: HashMap<Option<String>, UsageTotals> = HashMap::new();
+    let mut by_session: HashMap<String, (Option<String>, UsageTotals)> = HashMap::new();
+
+    for rollup in rollups {
+        totals.add(rollup);
+        by_day.entry(rollup.date.clone()).or_default().add(rollup);
+        by_agent.entry(rollup.agent_id.clone()).or_default().add(rollup);
+        by_session
+            .entry(rollup.session_id.clone())
+            .or_insert_with(|| (rollup.agent_id.clone(), UsageTotals::default()))
+            .1
+            .add(rollup);
+    }
+
+    let mut by_day: Vec<DayUsage> = by_day
+        .into_iter()
+        .map(|(date, totals)| DayUsage { date, totals })
+        .collect();
+    by_day.sort_by(|a, b| a.date.cmp(&b.date));
+
+    let mut by_agent: Vec<AgentUsage> = by_agent
+        .into_iter()
+        .map(|(agent_id, totals)| AgentUsage { agent_id, totals })
+        .collect();
+    by_agent.sort_by(|a, b| b.totals.cost_usd.total_cmp(&a.totals.cost_usd));
+
+    let mut top_sessions: Vec<SessionUsage> = by_session
+        .into_iter()
+        .map(|(session_id, (agent_id, totals))| SessionUsage { session_id, agent_id, totals })
+        .collect();
+    top_sessions.sort_by(|a, b| b.totals.cost_usd.total_cmp(&a.totals.cost_usd));
+    top_sessions.truncate(TOP_SESSIONS);
+
+    UsageDashboard {
+        range,
+        totals,
+        by_day,
+        by_agent,
+        top_sessions,
+    }
+}
+
+// (date, messages, estimated cost) per day from the last scan, oldest first
+pub(crate) fn recent_daily_usage() -> Vec<(String, u64, f64)> {
+    build_dashboard(&load_store(), UsageRange::Month)
+        .by_day
+        .into_iter()
+        .map(|d| (d.date, d.totals.messages, d.totals.cost_usd))
+        .collect()
+}
+
+// Estimated spend per session and for today, for enforcing budgets
+pub(crate) struct CostSnapshot {
+    pub(crate) today_usd: f64,
+    // Sessions with usage today
+    pub(crate) today_sessions: Vec<String>,
+    // Session id -> (agent id, all-time cost)
+    pub(crate) sessions: HashMap<String, (Option<String>, f64)>,
+}
+
+// Picks up new turns first, like the dashboard
+pub(crate) fn cost_snapshot(workspaces: &[(String, String)]) -> Result<CostSnapshot, String> {
+    let store = scan(workspaces)?;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let mut snapshot = CostSnapshot {
+        today_usd: 0.0,
+        today_sessions: vec![],
+        sessions: HashMap::new(),
+    };
+    for rollup in &store.rollups {
+        let cost = estimate_cost(rollup);
+        snapshot
+            .sessions
+            .entry(rollup.session_id.clone())
+            .or_insert_with(|| (rollup.agent_id.clone(), 0.0))
+            .1 += cost;
+        if rollup.date == today {
+            snapshot.today_usd += cost;
+            if !snapshot.today_sessions.contains(&rollup.session_id) {
+                snapshot.today_sessions.push(rollup.session_id.clone());
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub(crate) async fn get_usage_dashboard(
+    config: State<'_, ConfigStore>,
+    range: UsageRange,
+) -> Result<UsageDashboard, String> {
+    let workspaces = config.read()?.map(|c| agent_workspaces(&c)).unwrap_or_default();
+
+    let store = tauri::async_runtime::spawn_blocking(move || scan(&workspaces))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(build_dashboard(&store, range))
+}
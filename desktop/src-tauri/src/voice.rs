@@ -0,0 +1,192 @@
+// Voice note transcription settings.
+//
+// The bridge transcribes incoming voice messages before handing them to the
+// agent, either with a local Whisper install or OpenAI's transcription API. The
+// settings live in the `voice` section of config.json; `test_transcription`
+// runs a sample file through the same pipeline so users can check their setup.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tauri::State;
+use tokio::process::Command;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::http;
+use crate::service::get_extended_path;
+
+const OPENAI_TRANSCRIPTION_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+const TRANSCRIPTION_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptionProvider {
+    Local,
+    Openai,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceSettings {
+    enabled: bool,
+    provider: TranscriptionProvider,
+    // Whisper model: "base", "small", ... for local, "whisper-1" for the API
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+    // ISO-639-1 code; None lets Whisper detect the language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: TranscriptionProvider::Local,
+            model: "base".to_string(),
+            api_key: None,
+            language: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionResult {
+    text: String,
+    provider: TranscriptionProvider,
+    duration_ms: u64,
+}
+
+fn read_settings(config: &serde_json::Value) -> VoiceSettings {
+    config
+        .get("voice")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+fn validate(settings: &VoiceSettings) -> Result<(), String> {
+    if settings.model.trim().is_empty() {
+        return Err("Whisper model cannot be empty".to_string());
+    }
+    if let Some(ref language) = settings.language {
+        if language.len() != 2 || !language.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(format!("Invalid language code '{}', expected e.g. 'en'", language));
+        }
+    }
+    if settings.provider == TranscriptionProvider::Openai
+        && settings.api_key.as_deref().map(str::trim).unwrap_or("").is_empty()
+    {
+        return Err("An OpenAI API key is required for API transcription".to_string());
+    }
+    Ok(())
+}
+
+async fn transcribe_local(settings: &VoiceSettings, path: &Path) -> Result<String, String> {
+    let output_dir = std::env::temp_dir().join("ccb-transcription-test");
+    std::fs::create_dir_all(&output_dir).map_err(|e| e.to_string())?;
+
+    let mut command = Command::new("whisper");
+    command
+        .arg(path)
+        .args(["--model", &settings.model, "--output_format", "txt"])
+        .arg("--output_dir")
+        .arg(&output_dir)
+        .env("PATH", get_extended_path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if let Some(ref language) = settings.language {
+        command.args(["--language", language]);
+    }
+
+    let output = tokio::time::timeout(TRANSCRIPTION_TIMEOUT, command.output())
+        .await
+        .map_err(|_| "Transcription timed out".to_string())?
+        .map_err(|e| format!("Failed to run whisper (is it installed?): {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("whisper failed: {}", stderr.trim()));
+    }
+
+    let stem = path.file_stem().unwrap_or_default();
+    let transcript = output_dir.join(stem).with_extension("txt");
+    let text = std::fs::read_to_string(&transcript).map_err(|e| format!("Failed to read transcript: {}", e))?;
+    let _ = std::fs::remove_file(&transcript);
+    Ok(text.trim().to_string())
+}
+
+async fn transcribe_openai(settings: &VoiceSettings, path: &Path) -> Result<String, String> {
+    let bytes = tokio::fs::read(path).await.map_err(|e| format!("Failed to read audio file: {}", e))?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    let mut form = reqwest::multipart::Form::new()
+        .text("model", settings.model.clone())
+        .part("file", reqwest::multipart::Part::bytes(bytes).file_name(file_name));
+    if let Some(ref language) = settings.language {
+        form = form.text("language", language.clone());
+    }
+
+    let response = http::client()
+        .post(OPENAI_TRANSCRIPTION_URL)
+        .bearer_auth(settings.api_key.as_deref().unwrap_or_default())
+        .multipart(form)
+        .timeout(TRANSCRIPTION_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = response.status();
+    let body: serde_json::Value = response.json().await.map_err(|e| e.to_string())?;
+    if !status.is_success() {
+        let message = body
+            .get("error")
+            .and_then(|e| e.get("message"))
+            .and_then(|m| m.as_str())
+            .unwrap_or("unknown error");
+        return Err(format!("Transcription API returned {}: {}", status, message));
+    }
+
+    Ok(body.get("text").and_then(|t| t.as_str()).unwrap_or_default().trim().to_string())
+}
+
+#[tauri::command]
+pub(crate) fn get_voice_settings(store: State<'_, ConfigStore>) -> Result<VoiceSettings, String> {
+    Ok(store.read()?.map(|c| read_settings(&c)).unwrap_or_default())
+}
+
+#[tauri::command]
+pub(crate) fn set_voice_settings(store: State<'_, ConfigStore>, settings: VoiceSettings) -> Result<bool, String> {
+    validate(&settings)?;
+    let mut config = store.read()?.ok_or("Config file not found")?;
+    config["voice"] = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    store.write(&config)?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub(crate) async fn test_transcription(
+    store: State<'_, ConfigStore>,
+    path: String,
+) -> Result<TranscriptionResult, String> {
+    let settings = store.read()?.map(|c| read_settings(&c)).unwrap_or_default();
+    validate(&settings)?;
+
+    let path = expand_home(&path);
+    if !path.is_file() {
+        return Err(format!("Audio file {} not found", path.display()));
+    }
+
+    let started = Instant::now();
+    let text = match settings.provider {
+        TranscriptionProvider::Local => transcribe_local(&settings, &path).await?,
+        TranscriptionProvider::Openai => transcribe_openai(&settings, &path).await?,
+    };
+
+    Ok(TranscriptionResult {
+        text,
+        provider: settings.provider,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,173 @@
+// WhatsApp channel.
+//
+// WhatsApp has no bot tokens: the bridge links itself to a phone as a linked
+// device, which means scanning a QR code the bridge generates. The channel is
+// configured under `channels.whatsapp` like the others, the QR code comes from
+// the Control API, and the link's progress (waiting for a scan, connecting,
+// connected) is emitted as `whatsapp://link-state` so the UI can follow along
+// instead of someone watching the terminal. Bridges with `/events` push those
+// changes; for the rest the status is polled while a QR code is on screen.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::api_client::{ApiError, BridgeApiClient};
+use crate::config::ConfigStore;
+use crate::error::{CommandResult, Error};
+use crate::{config_validation, events, i18n};
+
+pub(crate) const LINK_STATE_EVENT: &str = "whatsapp://link-state";
+
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// A QR code is only valid for a minute or so; give up watching after a few
+const WATCH_FOR: Duration = Duration::from_secs(3 * 60);
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhatsAppConfig {
+    enabled: bool,
+    #[serde(default = "default_dm_policy")]
+    dm_policy: String,
+    #[serde(default)]
+    allow_from: Vec<String>,
+    // Route every chat to this agent instead of the bindings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    agent_id: Option<String>,
+    // Where the bridge keeps the linked-device credentials
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    session_dir: Option<String>,
+}
+
+fn default_dm_policy() -> String {
+    "pairing".to_string()
+}
+
+impl Default for WhatsAppConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dm_policy: default_dm_policy(),
+            allow_from: vec![],
+            agent_id: None,
+            session_dir: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhatsAppQr {
+    // The text the QR code encodes, for rendering in the UI
+    qr: String,
+    // PNG data URL, when the bridge renders one itself
+    #[serde(default)]
+    image: Option<String>,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+// "qr", "connecting", "connected" or "disconnected", plus whatever detail the
+// bridge adds (e.g. the linked phone number)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkState {
+    state: String,
+    #[serde(flatten)]
+    detail: serde_json::Map<String, serde_json::Value>,
+}
+
+fn unsupported() -> Error {
+    Error::Bridge("This bridge version doesn't support WhatsApp".to_string())
+}
+
+// Forwarded from the bridge's event stream
+pub(crate) fn emit_link_state(app: &AppHandle, payload: serde_json::Value) {
+    let _ = app.emit(LINK_STATE_EVENT, payload);
+}
+
+// Poll the link status until it's connected or the QR code has long expired
+async fn watch_link(app: AppHandle, api: BridgeApiClient) {
+    let until = Instant::now() + WATCH_FOR;
+    let mut last: Option<LinkState> = None;
+    while Instant::now() < until && !events::is_streaming() {
+        if let Ok(state) = api.get::<LinkState>("/whatsapp/status").await {
+            if last.as_ref() != Some(&state) {
+                let _ = app.emit(LINK_STATE_EVENT, &state);
+            }
+            let connected = state.state == "connected";
+            last = Some(state);
+            if connected {
+                break;
+            }
+        }
+        tokio::time::sleep(STATUS_POLL_INTERVAL).await;
+    }
+    WATCHING.store(false, Ordering::Relaxed);
+}
+
+#[tauri::command]
+pub(crate) fn get_whatsapp_config(store: State<'_, ConfigStore>) -> CommandResult<WhatsAppConfig> {
+    let section = store
+        .read()?
+        .and_then(|c| c.get("channels").and_then(|ch| ch.get("whatsapp")).cloned());
+    match section {
+        Some(section) => serde_json::from_value(section)
+            .map_err(|e| Error::Config(format!("Invalid WhatsApp config: {}", e))),
+        None => Ok(WhatsAppConfig::default()),
+    }
+}
+
+// Takes effect when the bridge restarts
+#[tauri::command]
+pub(crate) fn save_whatsapp_config(store: State<'_, ConfigStore>, whatsapp: WhatsAppConfig) -> CommandResult<bool> {
+    let mut config = store.read()?.ok_or_else(|| Error::Config(i18n::t("config.not_found")))?;
+    let channels = config
+        .as_object_mut()
+        .ok_or_else(|| Error::Config(i18n::t("config.invalid")))?
+        .entry("channels")
+        .or_insert_with(|| serde_json::json!({}));
+    let channels = channels.as_object_mut().ok_or_else(|| Error::Config(i18n::t("config.invalid")))?;
+    channels.insert(
+        "whatsapp".to_string(),
+        serde_json::to_value(&whatsapp).map_err(|e| e.to_string())?,
+    );
+
+    config_validation::ensure_valid_in(&config, &["channels.whatsapp".to_string()])?;
+    store.write(&config)?;
+    Ok(true)
+}
+
+// The current login QR code; link-state events follow until the phone is
+// linked
+#[tauri::command]
+pub(crate) async fn get_whatsapp_qr(app: AppHandle, api: State<'_, BridgeApiClient>) -> CommandResult<WhatsAppQr> {
+    let qr = match api.get::<WhatsAppQr>("/whatsapp/qr").await {
+        Ok(qr) => qr,
+        Err(ApiError::Unsupported(_)) => return Err(unsupported()),
+        Err(ApiError::NotFound(_)) => {
+            return Err(Error::Bridge(
+                "No QR code available: WhatsApp is already linked or not enabled".to_string(),
+            ))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !events::is_streaming() && !WATCHING.swap(true, Ordering::Relaxed) {
+        let api = app.state::<BridgeApiClient>().inner().clone();
+        tauri::async_runtime::spawn(watch_link(app, api));
+    }
+    Ok(qr)
+}
+
+#[tauri::command]
+pub(crate) async fn get_whatsapp_status(api: State<'_, BridgeApiClient>) -> CommandResult<LinkState> {
+    match api.get::<LinkState>("/whatsapp/status").await {
+        Ok(state) => Ok(state),
+        Err(ApiError::Unsupported(_)) => Err(unsupported()),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,324 @@
+// Disk usage of agent workspaces.
+//
+// Agents install dependencies and run builds in their workspaces, and Claude
+// Code keeps every session's transcript, so both grow unnoticed. Each
+// workspace and the transcript store are sized concurrently, noting
+// artifacts that can be regenerated (node_modules, build output) and
+// transcripts that haven't been touched in a while; the biggest of those are
+// flagged. `clean_workspace_artifacts` lists them for one agent on a dry run
+// and then removes only the ones the user confirmed. Home and root folders
+// are never scanned, and artifacts are only looked for a few levels deep.
+
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::State;
+use tokio::task::JoinSet;
+
+use crate::config::{expand_home, ConfigStore};
+use crate::transcripts::{agent_workspaces, get_projects_dir};
+
+// Transcripts not written to for this long count as old
+const OLD_TRANSCRIPT_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+// How many of the biggest cleanable items are flagged
+const LARGEST_COUNT: usize = 10;
+// Below this many levels, directories are sized but not searched for artifacts
+const MAX_ARTIFACT_DEPTH: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    // node_modules
+    Dependencies,
+    // target, dist, .next, __pycache__ and the like
+    Build,
+    // Old Claude Code transcripts of the workspace's sessions
+    Transcripts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskArtifact {
+    agent_id: String,
+    kind: ArtifactKind,
+    // The directory; for transcripts, the project folder they're in
+    path: String,
+    bytes: u64,
+    #[serde(skip)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDiskUsage {
+    agent_id: String,
+    workspace: String,
+    // Everything under the workspace, artifacts included
+    bytes: u64,
+    // All of the workspace's transcripts, old or not
+    transcript_bytes: u64,
+    // What cleaning would free
+    cleanable_bytes: u64,
+    artifacts: Vec<DiskArtifact>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsageReport {
+    workspaces: Vec<WorkspaceDiskUsage>,
+    // ~/.claude/projects as a whole, including sessions outside any workspace
+    transcript_store_bytes: u64,
+    // Biggest cleanable items across all workspaces, largest first
+    largest: Vec<DiskArtifact>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactCleanup {
+    dry_run: bool,
+    removed: Vec<String>,
+    freed_bytes: u64,
+}
+
+fn artifact_kind(parent: &Path, name: &OsStr) -> Option<ArtifactKind> {
+    match name.to_str()? {
+        "node_modules" => Some(ArtifactKind::Dependencies),
+        "target" if parent.join("Cargo.toml").is_file() => Some(ArtifactKind::Build),
+        "dist" | "build" | "out" if parent.join("package.json").is_file() => Some(ArtifactKind::Build),
+        ".next" | ".nuxt" | ".turbo" | ".parcel-cache" | "__pycache__" | ".pytest_cache" => Some(ArtifactKind::Build),
+        _ => None,
+    }
+}
+
+// Size of everything under `dir`; symlinks aren't followed
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+// Size of `dir`, noting artifact directories on the way
+fn scan(dir: &Path, depth: usize, skip: &Path, artifacts: &mut Vec<(PathBuf, ArtifactKind, u64)>) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut total = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let path = entry.path();
+        if !metadata.is_dir() {
+            total += metadata.len();
+            continue;
+        }
+        if path == skip {
+            continue;
+        }
+        if depth >= MAX_ARTIFACT_DEPTH {
+            total += dir_size(&path);
+            continue;
+        }
+        match artifact_kind(dir, &entry.file_name()) {
+            Some(kind) => {
+                let size = dir_size(&path);
+                artifacts.push((path, kind, size));
+                total += size;
+            }
+            None => total += scan(&path, depth + 1, skip, artifacts),
+        }
+    }
+    total
+}
+
+// Claude Code names a project's folder after its cwd, with every character
+// other than a letter or digit replaced by '-'
+fn project_folder_name(workspace: &Path) -> String {
+    workspace
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+// Transcript files of sessions run in the workspace itself, with their size
+// and whether they're old. Sessions in subfolders have folders of their own,
+// which can't be told apart from those of sibling folders with a '-' in the
+// name, so they're left out.
+fn workspace_transcripts(workspace: &Path) -> (PathBuf, Vec<(PathBuf, u64, bool)>) {
+    let folder = get_projects_dir().join(project_folder_name(workspace));
+    let cutoff = SystemTime::now() - OLD_TRANSCRIPT_AGE;
+    let Ok(files) = fs::read_dir(&folder) else {
+        return (folder, vec![]);
+    };
+    let mut found = Vec::new();
+    for file in files.filter_map(|e| e.ok()) {
+        let path = file.path();
+        let Ok(metadata) = file.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() || path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let old = metadata.modified().is_ok_and(|modified| modified < cutoff);
+        found.push((path, metadata.len(), old));
+    }
+    (folder, found)
+}
+
+// The home folder, the root or anything above home: scanning would take
+// ages and cleaning would reach into unrelated projects
+fn too_broad(path: &Path) -> bool {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    path.parent().is_none() || dirs::home_dir().is_some_and(|home| home.starts_with(&path))
+}
+
+fn workspace_usage(agent_id: String, workspace: String) -> WorkspaceDiskUsage {
+    let mut usage = WorkspaceDiskUsage {
+        agent_id: agent_id.clone(),
+        workspace: workspace.clone(),
+        ..Default::default()
+    };
+    let path = expand_home(&workspace);
+    if !path.is_dir() {
+        usage.error = Some("Workspace directory does not exist".to_string());
+        return usage;
+    }
+    if too_broad(&path) {
+        usage.error = Some("Workspace is the home folder or above it, so it isn't scanned".to_string());
+        return usage;
+    }
+
+    let mut found = Vec::new();
+    usage.bytes = scan(&path, 0, &get_projects_dir(), &mut found);
+    usage.artifacts = found
+        .into_iter()
+        .map(|(path, kind, bytes)| DiskArtifact {
+            agent_id: agent_id.clone(),
+            kind,
+            path: path.to_string_lossy().to_string(),
+            bytes,
+            files: vec![path],
+        })
+        .collect();
+
+    // One entry for all of the old transcripts
+    let (folder, transcripts) = workspace_transcripts(&path);
+    let mut old = DiskArtifact {
+        agent_id: agent_id.clone(),
+        kind: ArtifactKind::Transcripts,
+        path: folder.to_string_lossy().to_string(),
+        bytes: 0,
+        files: vec![],
+    };
+    for (file, bytes, is_old) in transcripts {
+        usage.transcript_bytes += bytes;
+        if is_old {
+            old.bytes += bytes;
+            old.files.push(file);
+        }
+    }
+    if !old.files.is_empty() {
+        usage.artifacts.push(old);
+    }
+    usage.artifacts.sort_by_key(|a| std::cmp::Reverse(a.bytes));
+    usage.cleanable_bytes = usage.artifacts.iter().map(|a| a.bytes).sum();
+    usage
+}
+
+#[tauri::command]
+pub(crate) async fn get_workspace_disk_usage(store: State<'_, ConfigStore>) -> Result<DiskUsageReport, String> {
+    let workspaces = store.read()?.map(|c| agent_workspaces(&c)).unwrap_or_default();
+
+    let store_size = tokio::task::spawn_blocking(|| dir_size(&get_projects_dir()));
+    let mut tasks = JoinSet::new();
+    for (index, (agent_id, workspace)) in workspaces.into_iter().enumerate() {
+        tasks.spawn_blocking(move || (index, workspace_usage(agent_id, workspace)));
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            results.push(result);
+        }
+    }
+    // Keep the agents' config order
+    results.sort_by_key(|(index, _)| *index);
+    let workspaces: Vec<WorkspaceDiskUsage> = results.into_iter().map(|(_, usage)| usage).collect();
+
+    let mut largest: Vec<DiskArtifact> = workspaces.iter().flat_map(|w| w.artifacts.iter().cloned()).collect();
+    largest.sort_by_key(|a| std::cmp::Reverse(a.bytes));
+    // Agents sharing a workspace would list the same artifact twice
+    let mut seen = Vec::new();
+    largest.retain(|a| {
+        let new = !seen.contains(&a.path);
+        seen.push(a.path.clone());
+        new
+    });
+    largest.truncate(LARGEST_COUNT);
+
+    Ok(DiskUsageReport {
+        workspaces,
+        transcript_store_bytes: store_size.await.map_err(|e| e.to_string())?,
+        largest,
+    })
+}
+
+// With `dry_run`, list the agent's regenerable artifacts and old transcripts;
+// otherwise remove those of `paths`, the ones the user confirmed from that list
+#[tauri::command]
+pub(crate) async fn clean_workspace_artifacts(
+    store: State<'_, ConfigStore>,
+    id: String,
+    dry_run: bool,
+    paths: Option<Vec<String>>,
+) -> Result<ArtifactCleanup, String> {
+    if !dry_run && paths.as_ref().is_none_or(|p| p.is_empty()) {
+        return Err("Choose what to remove from a dry run first".to_string());
+    }
+    let workspaces = store.read()?.map(|c| agent_workspaces(&c)).unwrap_or_default();
+    let (agent_id, workspace) = workspaces
+        .into_iter()
+        .find(|(agent_id, _)| *agent_id == id)
+        .ok_or_else(|| format!("Agent '{}' not found", id))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // Sized afresh, so only what's found now is removed
+        let usage = workspace_usage(agent_id, workspace);
+        if let Some(error) = usage.error {
+            return Err(error);
+        }
+        let mut cleanup = ArtifactCleanup {
+            dry_run,
+            removed: Vec::new(),
+            freed_bytes: 0,
+        };
+        // Only what the user confirmed, and only if it's still an artifact
+        let confirmed = |artifact: &DiskArtifact| paths.as_ref().is_none_or(|p| p.contains(&artifact.path));
+        for artifact in usage.artifacts.into_iter().filter(|a| confirmed(a)) {
+            let removed = dry_run
+                || match artifact.kind {
+                    ArtifactKind::Transcripts => artifact.files.iter().all(|file| fs::remove_file(file).is_ok()),
+                    _ => fs::remove_dir_all(&artifact.path).is_ok(),
+                };
+            if removed {
+                cleanup.freed_bytes += artifact.bytes;
+                cleanup.removed.push(artifact.path);
+            }
+        }
+        Ok(cleanup)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,221 @@
+// Provisioning workspaces for new agents.
+//
+// Instead of pointing a new agent at an existing folder, the user can have one
+// created: an empty git repo, a clone of a URL, or a copy of another folder.
+// Provisioning runs as a cancellable task; a half-built destination is
+// removed again if it fails or is cancelled.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::AppHandle;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command;
+
+use crate::config::expand_home;
+use crate::service::get_extended_path;
+use crate::tasks::{spawn_task, TaskHandle};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum WorkspaceTemplate {
+    EmptyGit,
+    Clone {
+        url: String,
+        #[serde(default)]
+        branch: Option<String>,
+    },
+    Copy {
+        source: String,
+    },
+}
+
+// Removes the destination when dropped unless disarmed, which also covers a
+// task future being dropped on cancellation
+struct Cleanup {
+    path: PathBuf,
+    armed: bool,
+}
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+fn git(args: &[&str], dir: &Path) -> Command {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .current_dir(dir)
+        .env("PATH", get_extended_path())
+        // Never block on credential or editor prompts
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    command
+}
+
+// "Receiving objects:  45% (450/1000)" -> 0.45
+fn clone_progress(line: &str) -> Option<(f64, &str)> {
+    let (phase, rest) = line.split_once(':')?;
+    let percent: f64 = rest.trim_start().split('%').next()?.trim().parse().ok()?;
+    Some((percent / 100.0, phase.trim()))
+}
+
+async fn clone_repo(task: &TaskHandle, url: &str, branch: Option<&str>, dest: &Path) -> Result<(), String> {
+    let parent = dest.parent().ok_or("Destination has no parent folder")?;
+    let dest_str = dest.to_string_lossy().to_string();
+    let mut args = vec!["clone", "--progress"];
+    if let Some(branch) = branch {
+        args.extend(["--branch", branch]);
+    }
+    args.extend(["--", url, &dest_str]);
+
+    let mut child = git(&args, parent)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // git rewrites its progress line with \r, so read raw chunks
+    let mut stderr = child.stderr.take().ok_or("Failed to read git output")?;
+    let mut output = String::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = stderr.read(&mut buf).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        output.push_str(&String::from_utf8_lossy(&buf[..n]));
+        if let Some((progress, phase)) = output.rsplit(['\r', '\n']).find_map(clone_progress) {
+            // Receiving is most of the work; resolving deltas is the tail
+            let overall = if phase.starts_with("Resolving") { 0.9 + progress * 0.1 } else { progress * 0.9 };
+            task.progress(Some(overall), phase.to_string());
+        }
+    }
+
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+    if !status.success() {
+        let last = output.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or_default();
+        return Err(format!("git clone failed: {}", last.trim()));
+    }
+    Ok(())
+}
+
+fn count_entries(dir: &Path) -> usize {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| match e.file_type() {
+                    Ok(t) if t.is_dir() => 1 + count_entries(&e.path()),
+                    _ => 1,
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn copy_tree(task: &TaskHandle, source: &Path, dest: &Path, copied: &mut usize, total: usize) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let entries = fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    for entry in entries.flatten() {
+        if task.is_cancelled() {
+            return Err("Cancelled".to_string());
+        }
+        let from = entry.path();
+        let to = dest.join(entry.file_name());
+        let file_type = entry.file_type().map_err(|e| e.to_string())?;
+
+        if file_type.is_dir() {
+            copy_tree(task, &from, &to, copied, total)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            {
+                let target = fs::read_link(&from).map_err(|e| e.to_string())?;
+                std::os::unix::fs::symlink(target, &to)
+                    .map_err(|e| format!("Failed to link {}: {}", to.display(), e))?;
+            }
+        } else {
+            fs::copy(&from, &to).map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+        }
+
+        *copied += 1;
+        if copied.is_multiple_of(200) || *copied == total {
+            task.progress(Some(*copied as f64 / total.max(1) as f64), format!("Copied {} of {} items", copied, total));
+        }
+    }
+    Ok(())
+}
+
+async fn provision(task: TaskHandle, template: WorkspaceTemplate, dest: PathBuf) -> Result<serde_json::Value, String> {
+    let mut cleanup = Cleanup {
+        path: dest.clone(),
+        armed: true,
+    };
+
+    match template {
+        WorkspaceTemplate::EmptyGit => {
+            fs::create_dir_all(&dest).map_err(|e| format!("Failed to create workspace: {}", e))?;
+            task.progress(Some(0.5), "Initializing git repository");
+            let output = git(&["init"], &dest).output().await.map_err(|e| format!("Failed to run git: {}", e))?;
+            if !output.status.success() {
+                return Err(format!("git init failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+            }
+        }
+        WorkspaceTemplate::Clone { url, branch } => {
+            task.progress(Some(0.0), format!("Cloning {}", url));
+            clone_repo(&task, &url, branch.as_deref(), &dest).await?;
+        }
+        WorkspaceTemplate::Copy { source } => {
+            let source = expand_home(&source);
+            if !source.is_dir() {
+                return Err(format!("Source folder not found: {}", source.display()));
+            }
+            if dest.starts_with(&source) {
+                return Err("Cannot copy a folder into itself".to_string());
+            }
+            let copy_task = task.clone();
+            let copy_dest = dest.clone();
+            tokio::task::spawn_blocking(move || {
+                let total = count_entries(&source);
+                copy_task.progress(Some(0.0), format!("Copying {} items", total));
+                copy_tree(&copy_task, &source, &copy_dest, &mut 0, total)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+        }
+    }
+
+    cleanup.armed = false;
+    task.progress(Some(1.0), "Workspace ready");
+    Ok(serde_json::json!({ "workspace": dest.to_string_lossy() }))
+}
+
+// Returns the id of the provisioning task; its result carries the workspace path
+#[tauri::command]
+pub(crate) fn create_workspace_from_template(
+    app: AppHandle,
+    template: WorkspaceTemplate,
+    dest: String,
+) -> Result<String, String> {
+    let dest = expand_home(&dest);
+    if !dest.is_absolute() {
+        return Err("Destination must be an absolute path".to_string());
+    }
+    // Only fill in an empty folder, never overwrite one
+    let occupied = fs::read_dir(&dest).map(|mut d| d.next().is_some()).unwrap_or(false);
+    if occupied || dest.is_file() {
+        return Err(format!("{} already exists and is not empty", dest.display()));
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let label = format!("Create workspace {}", dest.display());
+    spawn_task(&app, "workspace", label, move |task| provision(task, template, dest))
+}
@@ -0,0 +1,144 @@
+// Checking a workspace before an agent is pointed at it.
+//
+// A mistyped workspace used to surface only as a failure inside the bridge
+// once a chat reached the agent. The agent editor checks the folder as soon
+// as it's picked or typed: that it exists and is a directory the bridge can
+// read and write, whether it's a git repo and on which branch, and roughly
+// how big it is. The size walk is capped so a huge tree can't stall the form.
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::expand_home;
+use crate::git_status::run_git;
+
+// Where the size walk gives up and reports what it counted so far
+const MAX_SIZE_ENTRIES: u64 = 100_000;
+const MAX_SIZE_TIME: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceCheck {
+    // With `~` expanded
+    path: String,
+    exists: bool,
+    is_dir: bool,
+    readable: bool,
+    writable: bool,
+    is_repo: bool,
+    // None when detached
+    branch: Option<String>,
+    size_bytes: u64,
+    file_count: u64,
+    // The walk hit its limit, so the size is a lower bound
+    size_estimated: bool,
+    // Why the folder can't be used; None when it can
+    error: Option<String>,
+    // Usable, but worth a second look
+    warnings: Vec<String>,
+}
+
+// Asks the OS rather than writing a probe file, so checking leaves the folder untouched
+#[cfg(unix)]
+fn is_writable(dir: &Path) -> bool {
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(path) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+        return false;
+    };
+    unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 }
+}
+
+#[cfg(not(unix))]
+fn is_writable(dir: &Path) -> bool {
+    fs::metadata(dir).is_ok_and(|m| !m.permissions().readonly())
+}
+
+// (bytes, files, stopped early); symlinks are counted but not followed
+fn estimate_size(dir: &Path) -> (u64, u64, bool) {
+    let started = Instant::now();
+    let (mut bytes, mut files, mut entries) = (0, 0, 0);
+    let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(children) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for child in children.flatten() {
+            entries += 1;
+            if entries > MAX_SIZE_ENTRIES || started.elapsed() > MAX_SIZE_TIME {
+                return (bytes, files, true);
+            }
+            let Ok(metadata) = child.path().symlink_metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(child.path());
+            } else {
+                bytes += metadata.len();
+                files += 1;
+            }
+        }
+    }
+    (bytes, files, false)
+}
+
+fn check_folder(path: PathBuf) -> WorkspaceCheck {
+    let mut check = WorkspaceCheck {
+        path: path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let Ok(metadata) = fs::metadata(&path) else {
+        check.error = Some(format!("{} does not exist", check.path));
+        return check;
+    };
+    check.exists = true;
+    check.is_dir = metadata.is_dir();
+    if !check.is_dir {
+        check.error = Some(format!("{} is a file, not a folder", check.path));
+        return check;
+    }
+    check.readable = fs::read_dir(&path).is_ok();
+    check.writable = check.readable && is_writable(&path);
+    if !check.readable {
+        check.error = Some(format!("{} can't be read", check.path));
+        return check;
+    }
+    if !check.writable {
+        check.warnings.push("The folder is read-only, so the agent won't be able to edit files in it".to_string());
+    }
+    if dirs::home_dir().is_some_and(|home| home == path) || path.parent().is_none() {
+        check.warnings.push("The agent would have access to everything under this folder; a project folder is safer".to_string());
+    }
+
+    (check.size_bytes, check.file_count, check.size_estimated) = estimate_size(&path);
+    check
+}
+
+#[tauri::command]
+pub(crate) async fn validate_workspace(path: String) -> Result<WorkspaceCheck, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("A workspace folder is required".to_string());
+    }
+    let resolved = expand_home(path);
+    let folder = resolved.clone();
+    let mut check = tauri::async_runtime::spawn_blocking(move || check_folder(folder))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !check.readable {
+        return Ok(check);
+    }
+
+    match run_git(&resolved, &["rev-parse", "--is-inside-work-tree"]).await {
+        Ok(output) => check.is_repo = output.trim() == "true",
+        // Also what a missing git looks like; either way there's no repo to report
+        Err(_) => return Ok(check),
+    }
+    // Works on a repo without commits too; fails when detached
+    if let Ok(output) = run_git(&resolved, &["symbolic-ref", "--short", "-q", "HEAD"]).await {
+        check.branch = Some(output.trim().to_string()).filter(|b| !b.is_empty());
+    }
+    Ok(check)
+}
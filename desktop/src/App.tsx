@@ -1,5 +1,6 @@
 import { useEffect, useState, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 
 interface BotInfo {
@@ -24,6 +25,40 @@ interface BridgeStatus {
   pairings: { pending: number };
 }
 
+interface CachedStatus {
+  status: BridgeStatus | null;
+  fetchedAt: string | null;
+  isStale: boolean;
+}
+
+interface LogEntry {
+  timestamp: string;
+  level: "debug" | "info" | "warn" | "error";
+  source: "bridge" | "desktop";
+  message: string;
+  repeats?: number;
+}
+
+// Lines kept in the log view
+const MAX_LOG_LINES = 1000;
+
+function logLine(entry: LogEntry): string {
+  return entry.repeats ? `${entry.message} (repeated ${entry.repeats} times)` : entry.message;
+}
+
+// A streamed entry with repeats is the previous line again, with its new count
+function appendLogEntries(lines: string[], entries: LogEntry[]): string[] {
+  const next = [...lines];
+  for (const entry of entries) {
+    if (entry.repeats && next.length > 0) {
+      next[next.length - 1] = logLine(entry);
+    } else {
+      next.push(logLine(entry));
+    }
+  }
+  return next.slice(-MAX_LOG_LINES);
+}
+
 interface PairingRequest {
   code: string;
   chatKey: string;
@@ -44,6 +79,7 @@ interface BotConfig {
   id: string;
   token: string;
   agentId?: string;
+  enabled?: boolean;
 }
 
 interface PluginConfig {
@@ -132,7 +168,7 @@ function App() {
 
   const fetchStatus = useCallback(async () => {
     try {
-      const result = await invoke<BridgeStatus | null>("get_status");
+      const { status: result } = await invoke<CachedStatus>("get_status");
       setStatus(result);
       if (result) {
         setNeedsSetup(false);
@@ -189,27 +225,54 @@ function App() {
     }
   }, [loadExistingConfig]);
 
-  const fetchLogs = useCallback(async () => {
-    try {
-      const result = await invoke<string[]>("get_logs");
-      setLogs(result);
-    } catch (error) {
-      console.error("Failed to fetch logs:", error);
-    }
+  useEffect(() => {
+    // Lines streamed before the snapshot arrives go after it; a reset in that
+    // time means the snapshot is already out of date
+    let early: LogEntry[] | null = [];
+    let snapshotCleared = false;
+    const unlistenLine = listen<LogEntry[]>("log://line", event => {
+      if (early) {
+        early.push(...event.payload);
+      } else {
+        setLogs(prev => appendLogEntries(prev, event.payload));
+      }
+    });
+    const unlistenReset = listen("log://reset", () => {
+      if (early) {
+        early = [];
+        snapshotCleared = true;
+      }
+      setLogs([]);
+    });
+    invoke<LogEntry[]>("subscribe_logs", { filter: null })
+      .then(entries => {
+        const streamed = early ?? [];
+        early = null;
+        setLogs(appendLogEntries(snapshotCleared ? [] : entries.map(logLine), streamed));
+      })
+      .catch(error => {
+        const streamed = early ?? [];
+        early = null;
+        setLogs(prev => appendLogEntries(prev, streamed));
+        console.error("Failed to subscribe to logs:", error);
+      });
+    return () => {
+      unlistenLine.then(stop => stop());
+      unlistenReset.then(stop => stop());
+      invoke("unsubscribe_logs").catch(() => {});
+    };
   }, []);
 
   useEffect(() => {
     checkConfig();
     fetchStatus();
     fetchPairings();
-    fetchLogs();
     const interval = setInterval(() => {
       fetchStatus();
       fetchPairings();
-      fetchLogs();
     }, 2000);
     return () => clearInterval(interval);
-  }, [fetchStatus, fetchPairings, fetchLogs, checkConfig]);
+  }, [fetchStatus, fetchPairings, checkConfig]);
 
   const handleStart = async () => {
     if (needsSetup) {
@@ -223,7 +286,6 @@ function App() {
       await invoke("start_service");
       for (let i = 0; i < 10; i++) {
         await new Promise(r => setTimeout(r, 500));
-        await fetchLogs();
         await fetchStatus();
       }
     } catch (error) {
@@ -261,7 +323,14 @@ function App() {
   };
 
   const handleApprove = async (code: string) => {
-    await invoke("approve_pairing", { code });
+    try {
+      await invoke("approve_pairing", { code });
+    } catch (e) {
+      // Sensitive agents need the app lock PIN while one is set
+      if (!(await invoke<boolean>("get_app_lock_status"))) throw e;
+      const pin = window.prompt(String(e));
+      if (pin) await invoke("approve_pairing", { code, pin });
+    }
     fetchPairings();
   };
 
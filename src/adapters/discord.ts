@@ -7,6 +7,7 @@ import {
   GatewayIntentBits,
   Partials,
   Message,
+  ChatInputCommandInteraction,
   TextChannel,
   DMChannel,
   NewsChannel,
@@ -82,6 +83,13 @@ export class DiscordAdapter extends BaseAdapter {
       await this.handleMessage(message);
     });
 
+    // Handle slash commands registered from the desktop app
+    this.client.on("interactionCreate", async (interaction) => {
+      if (interaction.isChatInputCommand()) {
+        await this.handleSlashCommand(interaction);
+      }
+    });
+
     // Handle errors
     this.client.on("error", (error) => {
       this.emitError(error);
@@ -183,21 +191,88 @@ export class DiscordAdapter extends BaseAdapter {
     await this.emitMessage(incomingMessage);
   }
 
+  /**
+   * Run a slash command like the same command typed as a message
+   */
+  private async handleSlashCommand(interaction: ChatInputCommandInteraction): Promise<void> {
+    if (interaction.user.bot) return;
+
+    const isDM = !interaction.guildId;
+    const chatKey = this.chatKeyFor(interaction.user.id, isDM ? undefined : interaction.channelId);
+
+    // Each bridge command takes at most one free-form string argument
+    const argument = interaction.options.data[0]?.value;
+    const args = typeof argument === "string" ? argument.trim().split(/\s+/).filter(Boolean) : [];
+    const command = interaction.commandName.toLowerCase();
+
+    const userInfo: UserInfo = {
+      id: interaction.user.id,
+      username: interaction.user.username,
+      displayName: interaction.user.displayName || interaction.user.username,
+      channel: "discord",
+    };
+
+    // Discord wants an answer within three seconds; handlers can take longer
+    try {
+      await interaction.deferReply();
+    } catch (error) {
+      this.emitError(error instanceof Error ? error : new Error(String(error)));
+      return;
+    }
+
+    let replied = false;
+    const commandCtx: CommandContext = {
+      command,
+      args,
+      message: {
+        chatKey,
+        channel: "discord",
+        userId: interaction.user.id,
+        text: ["/" + command, ...args].join(" "),
+        userInfo,
+        isGroup: !isDM,
+        groupId: interaction.guildId ?? undefined,
+        timestamp: interaction.createdAt,
+      },
+      reply: async (text: string) => {
+        for (const chunk of this.splitMessage(text, DISCORD_MAX_MESSAGE_LENGTH)) {
+          if (replied) {
+            await interaction.followUp(chunk);
+          } else {
+            await interaction.editReply(chunk);
+            replied = true;
+          }
+        }
+      },
+    };
+
+    await this.emitCommand(commandCtx);
+
+    // Don't leave "thinking..." behind when nothing answered
+    if (!replied) {
+      await interaction.deleteReply().catch(() => undefined);
+    }
+  }
+
   private buildChatKey(message: Message): string {
+    return this.chatKeyFor(message.author.id, message.guild ? message.channelId : undefined);
+  }
+
+  private chatKeyFor(userId: string, guildChannelId?: string): string {
     // Multi-bot format: discord:botId:channel:channelId or discord:botId:userId
     // Single-bot format: discord:channel:channelId or discord:userId
     if (this.botId) {
-      if (message.guild) {
-        return `discord:${this.botId}:channel:${message.channelId}`;
+      if (guildChannelId) {
+        return `discord:${this.botId}:channel:${guildChannelId}`;
       }
-      return `discord:${this.botId}:${message.author.id}`;
+      return `discord:${this.botId}:${userId}`;
     }
 
     // Backward compatible format
-    if (message.guild) {
-      return `discord:channel:${message.channelId}`;
+    if (guildChannelId) {
+      return `discord:channel:${guildChannelId}`;
     }
-    return `discord:${message.author.id}`;
+    return `discord:${userId}`;
   }
 
   private extractChannelId(chatKey: string): string {
@@ -0,0 +1,64 @@
+/**
+ * Telegram webhook server - receives updates for every Telegram bot on one
+ * local port instead of long polling, each bot at /telegram/<botId>. A tunnel
+ * or reverse proxy forwards the public webhook URL to it.
+ */
+
+import Fastify, { FastifyInstance, FastifyReply, FastifyRequest } from "fastify";
+import { webhookCallback, type Bot } from "grammy";
+import type { TelegramWebhookConfig } from "../core/types.js";
+
+type UpdateHandler = (req: FastifyRequest, reply: FastifyReply) => Promise<unknown>;
+
+export function telegramWebhookPath(botId: string): string {
+  return `/telegram/${encodeURIComponent(botId)}`;
+}
+
+export class TelegramWebhookServer {
+  private app: FastifyInstance;
+  private config: TelegramWebhookConfig;
+  private handlers: Map<string, UpdateHandler> = new Map();
+  private listening?: Promise<string>;
+
+  constructor(config: TelegramWebhookConfig) {
+    this.config = config;
+    this.app = Fastify({ logger: false });
+
+    this.app.post("/telegram/:botId", async (req, reply) => {
+      const { botId } = req.params as { botId: string };
+      const handler = this.handlers.get(botId);
+      if (!handler) {
+        return reply.code(404).send({ error: "Unknown bot" });
+      }
+      return handler(req, reply);
+    });
+  }
+
+  /**
+   * Route a bot's updates here and point Telegram at its URL
+   */
+  async add(botId: string, bot: Bot): Promise<void> {
+    // grammY checks X-Telegram-Bot-Api-Secret-Token when a secret is set
+    this.handlers.set(botId, webhookCallback(bot, "fastify", { secretToken: this.config.secretToken }));
+    this.listening ??= this.app.listen({ host: "127.0.0.1", port: this.config.port });
+    await this.listening;
+
+    const url = this.config.url.replace(/\/+$/, "") + telegramWebhookPath(botId);
+    await bot.api.setWebhook(url, { secret_token: this.config.secretToken });
+  }
+
+  /**
+   * Stop routing a bot's updates; Telegram keeps them queued until it's back
+   */
+  remove(botId: string): void {
+    this.handlers.delete(botId);
+  }
+
+  async stop(): Promise<void> {
+    this.handlers.clear();
+    if (this.listening) {
+      await this.app.close();
+      this.listening = undefined;
+    }
+  }
+}
@@ -4,6 +4,7 @@
 
 import { Bot, Context } from "grammy";
 import { BaseAdapter } from "./base.js";
+import type { TelegramWebhookServer } from "./telegram-webhook.js";
 import type { TelegramConfig, TelegramBotConfig, IncomingMessage, UserInfo, CommandContext, DmPolicy } from "../core/types.js";
 
 const TELEGRAM_MAX_MESSAGE_LENGTH = 4096;
@@ -22,8 +23,9 @@ export class TelegramAdapter extends BaseAdapter {
   private botInfo: { username?: string } = {};
   private botId?: string; // For multi-bot chatKey format
   private agentId?: string; // Direct agent binding
+  private webhookServer?: TelegramWebhookServer; // Set in webhook mode; long polling otherwise
 
-  constructor(config: TelegramConfig | TelegramAdapterOptions) {
+  constructor(config: TelegramConfig | TelegramAdapterOptions, webhookServer?: TelegramWebhookServer) {
     // Handle both TelegramConfig (backward compat) and TelegramAdapterOptions (multi-bot)
     const baseConfig = {
       enabled: true,
@@ -40,6 +42,7 @@ export class TelegramAdapter extends BaseAdapter {
     this.bot = new Bot(botToken);
     this.botId = "botId" in config ? config.botId : undefined;
     this.agentId = "agentId" in config ? config.agentId : undefined;
+    this.webhookServer = webhookServer;
   }
 
   /**
@@ -71,6 +74,11 @@ export class TelegramAdapter extends BaseAdapter {
       this.emitError(err.error instanceof Error ? err.error : new Error(String(err.error)));
     });
 
+    if (this.webhookServer) {
+      await this.webhookServer.add(this.webhookBotId(), this.bot);
+      return;
+    }
+
     // Start polling - use a promise that resolves when onStart fires
     // because bot.start() is a long-running operation that only resolves when stopped
     await new Promise<void>((resolve) => {
@@ -83,9 +91,17 @@ export class TelegramAdapter extends BaseAdapter {
   }
 
   async stop(): Promise<void> {
+    if (this.webhookServer) {
+      this.webhookServer.remove(this.webhookBotId());
+      return;
+    }
     await this.bot.stop();
   }
 
+  private webhookBotId(): string {
+    return this.botId ?? "default";
+  }
+
   async send(chatKey: string, text: string, options?: { replyTo?: string }): Promise<void> {
     const chatId = this.extractChatId(chatKey);
     const chunks = this.splitMessage(text, TELEGRAM_MAX_MESSAGE_LENGTH);
@@ -206,13 +222,13 @@ export class TelegramAdapter extends BaseAdapter {
   /**
    * Create a TelegramAdapter from a TelegramBotConfig (for multi-bot mode)
    */
-  static fromBotConfig(botConfig: TelegramBotConfig): TelegramAdapter {
+  static fromBotConfig(botConfig: TelegramBotConfig, webhookServer?: TelegramWebhookServer): TelegramAdapter {
     return new TelegramAdapter({
       botId: botConfig.id,
       botToken: botConfig.botToken,
       agentId: botConfig.agentId,
       dmPolicy: botConfig.dmPolicy,
       allowFrom: botConfig.allowFrom,
-    });
+    }, webhookServer);
   }
 }
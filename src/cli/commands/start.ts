@@ -16,10 +16,11 @@ import { createSettingsCommands } from "../../commands/handlers/settings.js";
 import { createControlCommands } from "../../commands/handlers/control.js";
 import { createDiscoveryCommands } from "../../commands/handlers/discovery.js";
 import { TelegramAdapter } from "../../adapters/telegram.js";
+import { TelegramWebhookServer } from "../../adapters/telegram-webhook.js";
 import { DiscordAdapter } from "../../adapters/discord.js";
 import { createWebhookServer } from "../../webhooks/server.js";
 import { MessageLogger } from "../../core/logger.js";
-import { createControlAPI } from "../../core/control-api.js";
+import { createControlAPI, controlApiPortFromEnv } from "../../core/control-api.js";
 import type { Adapter, IncomingMessage, CommandContext, BridgeConfig, ChannelConfig, ChannelType, DmPolicy } from "../../core/types.js";
 
 // Extended adapter interface for multi-bot support
@@ -99,6 +100,7 @@ export async function runStart(options: StartOptions): Promise<void> {
 
   // Initialize Control API for desktop app
   const controlAPI = createControlAPI({
+    port: controlApiPortFromEnv(),
     config,
     db,
     pairingManager,
@@ -121,15 +123,23 @@ export async function runStart(options: StartOptions): Promise<void> {
   }
 
   // Telegram - support both single-bot and multi-bot modes
+  let telegramWebhook: TelegramWebhookServer | undefined;
   if (config.channels.telegram?.enabled) {
     const telegramConfig = config.channels.telegram;
+    if (telegramConfig.webhook?.enabled && telegramConfig.webhook.url) {
+      telegramWebhook = new TelegramWebhookServer(telegramConfig.webhook);
+    }
 
     if (telegramConfig.bots && telegramConfig.bots.length > 0) {
       // Multi-bot mode
       for (const botConfig of telegramConfig.bots) {
+        if (botConfig.enabled === false) {
+          console.log(chalk.gray(`  Telegram (${botConfig.id}): disabled`));
+          continue;
+        }
         spinner.start(`Connecting to Telegram (${botConfig.id})...`);
         try {
-          const telegram = TelegramAdapter.fromBotConfig(botConfig);
+          const telegram = TelegramAdapter.fromBotConfig(botConfig, telegramWebhook);
           const adapterConfig: AdapterBotConfig = {
             dmPolicy: botConfig.dmPolicy ?? telegramConfig.dmPolicy,
             allowFrom: botConfig.allowFrom ?? telegramConfig.allowFrom,
@@ -155,7 +165,7 @@ export async function runStart(options: StartOptions): Promise<void> {
       // Single-bot mode (backward compat)
       spinner.start("Connecting to Telegram...");
       try {
-        const telegram = new TelegramAdapter(telegramConfig);
+        const telegram = new TelegramAdapter(telegramConfig, telegramWebhook);
         setupAdapter(telegram, config, sessionManager, pairingManager, allowlistManager, commandParser, undefined, logger);
         await telegram.start();
         adapters.set("telegram", telegram);
@@ -179,6 +189,10 @@ export async function runStart(options: StartOptions): Promise<void> {
     if (discordConfig.bots && discordConfig.bots.length > 0) {
       // Multi-bot mode
       for (const botConfig of discordConfig.bots) {
+        if (botConfig.enabled === false) {
+          console.log(chalk.gray(`  Discord (${botConfig.id}): disabled`));
+          continue;
+        }
         spinner.start(`Connecting to Discord (${botConfig.id})...`);
         try {
           const discord = DiscordAdapter.fromBotConfig(botConfig);
@@ -265,10 +279,13 @@ export async function runStart(options: StartOptions): Promise<void> {
       }
     }
 
-    // Stop webhook server
+    // Stop webhook servers
     if (webhookServer) {
       await webhookServer.stop();
     }
+    if (telegramWebhook) {
+      await telegramWebhook.stop();
+    }
 
     // Close logger
     if (logger) {
@@ -53,6 +53,7 @@ const baseChannelConfigSchema = z.object({
 const telegramBotConfigSchema = z.object({
   id: z.string().min(1),
   botToken: z.string().min(1),
+  enabled: z.boolean().optional(), // false switches the bot off without removing its token
   agentId: z.string().optional(), // Direct agent binding - all messages from this bot go to this agent
   dmPolicy: dmPolicySchema.optional(),
   allowFrom: z.array(z.string()).optional(),
@@ -62,15 +63,30 @@ const discordBotConfigSchema = z.object({
   id: z.string().min(1),
   token: z.string().min(1),
   applicationId: z.string().optional(),
+  enabled: z.boolean().optional(), // false switches the bot off without removing its token
   agentId: z.string().optional(), // Direct agent binding
   dmPolicy: dmPolicySchema.optional(),
   allowFrom: z.array(z.string()).optional(),
 });
 
+// Webhook mode - Telegram posts updates to `url` instead of being long-polled
+const telegramWebhookSchema = z.object({
+  enabled: z.boolean().default(false),
+  url: z.string().default(""),
+  secretToken: z.string().regex(/^[A-Za-z0-9_-]{1,256}$/).optional(),
+  port: z.number().int().min(1).max(65535).default(38793),
+  tunnel: z.string().optional(), // Run by the desktop app, not the bridge
+}).refine(
+  // Empty until the desktop's tunnel reports its URL
+  (data) => !data.enabled || data.url === "" || data.url.startsWith("https://"),
+  { message: "Webhook mode needs a public https:// URL", path: ["url"] }
+);
+
 // Telegram channel config - supports both single bot (backward compat) and multi-bot
 const telegramConfigSchema = baseChannelConfigSchema.extend({
   botToken: z.string().optional(), // Single bot (backward compat)
   bots: z.array(telegramBotConfigSchema).optional(), // Multi-bot support
+  webhook: telegramWebhookSchema.optional(),
 }).refine(
   (data) => !data.enabled || (data.botToken && data.botToken.length > 0) || (data.bots && data.bots.length > 0),
   { message: "Either botToken or bots array is required when Telegram is enabled", path: ["botToken"] }
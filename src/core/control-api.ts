@@ -1,7 +1,8 @@
 /**
  * Control API - HTTP server for desktop app communication
  *
- * Runs on localhost:38792 when bridge starts
+ * Runs on localhost:38792 when bridge starts, or on the port in
+ * CCB_CONTROL_API_PORT, which the desktop app sets for a custom port
  */
 
 import Fastify, { FastifyInstance } from "fastify";
@@ -271,6 +272,19 @@ export class ControlAPI {
   }
 }
 
+export function controlApiPortFromEnv(): number | undefined {
+  const value = process.env.CCB_CONTROL_API_PORT;
+  if (!value) {
+    return undefined;
+  }
+  const port = Number(value);
+  if (!Number.isInteger(port) || port < 1 || port > 65535) {
+    console.error(`[CCB] Ignoring CCB_CONTROL_API_PORT=${value}: not a port number`);
+    return undefined;
+  }
+  return port;
+}
+
 export function createControlAPI(options: ControlAPIOptions): ControlAPI {
   return new ControlAPI(options);
 }
@@ -0,0 +1,53 @@
+/**
+ * Permission webhook - asks an outside approver, such as the desktop app,
+ * before the SDK runs a tool the agent's permission mode doesn't allow on its
+ * own. Enabled by CCB_PERMISSION_WEBHOOK_URL and CCB_PERMISSION_WEBHOOK_TOKEN;
+ * anything but an explicit "allow" from the approver is a deny.
+ */
+
+import type { CanUseTool, PermissionResult } from "@anthropic-ai/claude-agent-sdk";
+
+interface WebhookReply {
+  behavior?: string;
+  updatedInput?: Record<string, unknown>;
+  message?: string;
+}
+
+export function permissionWebhookFromEnv(agentId: string): CanUseTool | undefined {
+  const url = process.env.CCB_PERMISSION_WEBHOOK_URL;
+  const token = process.env.CCB_PERMISSION_WEBHOOK_TOKEN;
+  if (!url || !token) {
+    return undefined;
+  }
+
+  return async (toolName, input, options): Promise<PermissionResult> => {
+    try {
+      const response = await fetch(url, {
+        method: "POST",
+        headers: {
+          "Content-Type": "application/json",
+          Authorization: `Bearer ${token}`,
+        },
+        body: JSON.stringify({
+          agentId,
+          toolName,
+          input,
+          reason: options.decisionReason,
+        }),
+        signal: options.signal,
+      });
+      if (!response.ok) {
+        return { behavior: "deny", message: `Permission webhook returned ${response.status}` };
+      }
+      const reply = (await response.json()) as WebhookReply;
+      if (reply.behavior === "allow") {
+        return { behavior: "allow", updatedInput: reply.updatedInput ?? input };
+      }
+      return { behavior: "deny", message: reply.message || "Denied by the operator" };
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      console.error(`[CCB] Permission webhook failed: ${message}`);
+      return { behavior: "deny", message: `Permission webhook failed: ${message}` };
+    }
+  };
+}
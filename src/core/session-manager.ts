@@ -6,11 +6,13 @@ import {
   query,
   type SDKMessage,
   type SDKAssistantMessage,
+  type SDKUserMessage,
   type Options,
 } from "@anthropic-ai/claude-agent-sdk";
 import { BridgeDatabase } from "../db/sqlite.js";
 import { Router } from "./router.js";
 import { getInstalledPlugins } from "./plugins.js";
+import { permissionWebhookFromEnv } from "./permission-webhook.js";
 import type { AgentConfig, BridgeConfig, IncomingMessage, SessionInfo } from "./types.js";
 
 export interface SendMessageOptions {
@@ -140,6 +142,8 @@ export class SessionManager {
       `User info: ${message.userInfo.displayName || message.userInfo.username || message.userId} via ${message.channel}`
     );
 
+    // Ends the prompt stream once the turn is over
+    let finishInput: () => void = () => {};
     try {
       // Build query options
       const queryOptions: Options = {
@@ -214,9 +218,31 @@ export class SessionManager {
         (queryOptions as Record<string, unknown>).agents = agent.subagents;
       }
 
+      // Ask the permission webhook, if one is configured, about tools the
+      // permission mode doesn't allow on its own
+      const canUseTool = permissionWebhookFromEnv(agent.id);
+      let prompt: string | AsyncIterable<SDKUserMessage> = message.text;
+      if (canUseTool) {
+        queryOptions.canUseTool = canUseTool;
+        // Permission answers travel over the SDK's input stream, so it has
+        // to stay open until the turn is over
+        const finished = new Promise<void>((resolve) => {
+          finishInput = resolve;
+        });
+        prompt = (async function* () {
+          yield {
+            type: "user",
+            message: { role: "user", content: message.text },
+            parent_tool_use_id: null,
+            session_id: resumeSessionId ?? "",
+          } as SDKUserMessage;
+          await finished;
+        })();
+      }
+
       // Use the query function with streaming
       const response = query({
-        prompt: message.text,
+        prompt,
         options: queryOptions,
       });
 
@@ -232,6 +258,7 @@ export class SessionManager {
         // Extract session ID from result
         if (event.type === "result") {
           sessionId = event.session_id;
+          finishInput();
         }
       }
 
@@ -244,6 +271,8 @@ export class SessionManager {
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error);
       yield { type: "error", error: errorMessage };
+    } finally {
+      finishInput();
     }
   }
 
@@ -52,6 +52,7 @@ export interface ChannelConfig {
 export interface TelegramBotConfig {
   id: string;
   botToken: string;
+  enabled?: boolean; // false switches the bot off
   agentId?: string; // Direct agent binding
   dmPolicy?: DmPolicy;
   allowFrom?: string[];
@@ -61,14 +62,23 @@ export interface DiscordBotConfig {
   id: string;
   token: string;
   applicationId?: string;
+  enabled?: boolean; // false switches the bot off
   agentId?: string; // Direct agent binding
   dmPolicy?: DmPolicy;
   allowFrom?: string[];
 }
 
+export interface TelegramWebhookConfig {
+  enabled: boolean;
+  url: string; // Public HTTPS base URL; each bot gets /telegram/<botId> under it
+  secretToken?: string; // Telegram sends it back in X-Telegram-Bot-Api-Secret-Token
+  port: number; // Local port the webhook server listens on
+}
+
 export interface TelegramConfig extends ChannelConfig {
   botToken?: string; // Single bot (backward compat)
   bots?: TelegramBotConfig[]; // Multi-bot support
+  webhook?: TelegramWebhookConfig; // Receive updates by webhook instead of long polling
 }
 
 export interface DiscordConfig extends ChannelConfig {